@@ -0,0 +1,192 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::Float;
+
+/// A complex number `re + im·i`.
+///
+/// Only the arithmetic the crate needs, such as for the complex roots of a
+/// [`Polynomial`](super::Polynomial).
+///
+/// ```
+/// use mathslib::generals::Complex;
+///
+/// let z = Complex::new(3.0, 4.0);
+/// assert_eq!(z.norm(), 5.0);
+/// assert_eq!(z * z.conj(), Complex::real(25.0));
+/// assert_eq!(Complex::real(1.0) / Complex::new(0.0, 1.0), Complex::new(0.0, -1.0));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Complex<T> {
+    /// Real part.
+    pub re: T,
+    /// Imaginary part.
+    pub im: T,
+}
+
+impl<T: Float> Complex<T> {
+    /// The number `re + im·i`.
+    pub fn new(re: T, im: T) -> Self {
+        Complex { re, im }
+    }
+
+    /// The real number `re`.
+    pub fn real(re: T) -> Self {
+        Complex { re, im: T::ZERO }
+    }
+
+    /// The number with modulus `radius` and argument `angle`.
+    pub fn from_polar(radius: T, angle: T) -> Self {
+        Complex::new(radius * angle.cos(), radius * angle.sin())
+    }
+
+    /// The complex conjugate `re - im·i`.
+    pub fn conj(self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+
+    /// The modulus `|z|`, without undue overflow.
+    pub fn norm(self) -> T {
+        self.re.hypot(self.im)
+    }
+
+    /// The argument, in `(-π, π]`.
+    pub fn arg(self) -> T {
+        self.im.atan2(self.re)
+    }
+
+    /// The principal square root, with a nonnegative real part.
+    pub fn sqrt(self) -> Self {
+        if self.im == T::ZERO {
+            return if self.re >= T::ZERO {
+                Complex::real(self.re.sqrt())
+            } else {
+                Complex::new(T::ZERO, (-self.re).sqrt().copysign(self.im))
+            };
+        }
+        // Of the two parts, compute the larger from the modulus and the
+        // other by division, which avoids cancellation.
+        let half = T::from_f64(0.5);
+        let t = ((self.re.abs() + self.norm()) * half).sqrt();
+        if self.re >= T::ZERO {
+            Complex::new(t, self.im / (t + t))
+        } else {
+            Complex::new(self.im.abs() / (t + t), t.copysign(self.im))
+        }
+    }
+
+    /// Whether both parts are finite.
+    pub fn is_finite(self) -> bool {
+        self.re.is_finite() && self.im.is_finite()
+    }
+}
+
+impl<T: Float> Add for Complex<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl<T: Float> Sub for Complex<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl<T: Float> Mul for Complex<T> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl<T: Float> Div for Complex<T> {
+    type Output = Self;
+
+    /// Smith's algorithm, which avoids overflow in `|other|²`.
+    fn div(self, other: Self) -> Self {
+        if other.re.abs() >= other.im.abs() {
+            let ratio = other.im / other.re;
+            let denominator = other.re + other.im * ratio;
+            Complex::new(
+                (self.re + self.im * ratio) / denominator,
+                (self.im - self.re * ratio) / denominator,
+            )
+        } else {
+            let ratio = other.re / other.im;
+            let denominator = other.re * ratio + other.im;
+            Complex::new(
+                (self.re * ratio + self.im) / denominator,
+                (self.im * ratio - self.re) / denominator,
+            )
+        }
+    }
+}
+
+impl<T: Float> Mul<T> for Complex<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Complex::new(self.re * factor, self.im * factor)
+    }
+}
+
+impl<T: Float> Add<T> for Complex<T> {
+    type Output = Self;
+
+    fn add(self, other: T) -> Self {
+        Complex::new(self.re + other, self.im)
+    }
+}
+
+impl<T: Float> Neg for Complex<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+impl<T: Float> fmt::Display for Complex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.im >= T::ZERO || self.im.is_nan() {
+            write!(f, "{}+{}i", self.re, self.im)
+        } else {
+            write!(f, "{}-{}i", self.re, -self.im)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_and_square_roots() {
+        let (a, b) = (Complex::new(1.5, -2.0), Complex::new(-0.5, 3.0));
+        assert_eq!(a + b, Complex::new(1.0, 1.0));
+        assert_eq!(a - b, Complex::new(2.0, -5.0));
+        assert_eq!(a * b, Complex::new(5.25, 5.5));
+        let q = (a * b) / b;
+        assert!((q - a).norm() < 1e-15);
+        // Smith's division survives moduli whose square overflows.
+        let big = Complex::new(1e300, 1e300);
+        assert_eq!(big / big, Complex::real(1.0));
+
+        for z in [a, b, Complex::real(-4.0), Complex::new(-1e-20, 1e-30)] {
+            let root = z.sqrt();
+            assert!(root.re >= 0.0);
+            assert!((root * root - z).norm() <= 1e-15 * z.norm());
+        }
+        assert_eq!(Complex::real(-4.0).sqrt(), Complex::new(0.0, 2.0));
+        assert_eq!(format!("{a}"), "1.5-2i");
+    }
+}
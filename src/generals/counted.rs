@@ -0,0 +1,116 @@
+use std::cell::Cell;
+use std::fmt;
+
+use super::Float;
+
+/// Wrapper around an objective that counts its calls and keeps track of the
+/// arguments and values seen.
+///
+/// Optimizers take any `Fn(T) -> T`, so a `CountedFn` is passed as a closure
+/// calling [`CountedFn::call`]. This makes it easy to compare the cost of
+/// methods on a given problem:
+///
+/// ```
+/// use mathslib::generals::CountedFn;
+/// use mathslib::optimize::scalar::{bound_gr_minimize, bracket_gr_minimize};
+///
+/// let f = CountedFn::new(|x: f64| (x - 0.7).powi(2));
+/// bracket_gr_minimize(|x| f.call(x), 0.0, 0.5, 2.0, 1e-8, 200).unwrap();
+/// let bracketed = f.calls();
+/// f.reset();
+/// bound_gr_minimize(|x| f.call(x), 0.0, 2.0, 1e-8, 200).unwrap();
+/// assert!(f.calls() > 0 && bracketed > 0);
+/// assert!((f.best().unwrap().0 - 0.7).abs() < 1e-6);
+/// ```
+pub struct CountedFn<T, F> {
+    func: F,
+    calls: Cell<usize>,
+    range: Cell<Option<(T, T)>>,
+    best: Cell<Option<(T, T)>>,
+}
+
+impl<T: Float, F: Fn(T) -> T> CountedFn<T, F> {
+    /// Wraps `func` with no calls recorded yet.
+    pub fn new(func: F) -> Self {
+        CountedFn {
+            func,
+            calls: Cell::new(0),
+            range: Cell::new(None),
+            best: Cell::new(None),
+        }
+    }
+
+    /// Evaluates the wrapped function at `x` and records the call.
+    pub fn call(&self, x: T) -> T {
+        let fx = (self.func)(x);
+        self.calls.set(self.calls.get() + 1);
+        if !x.is_nan() {
+            let range = self
+                .range
+                .get()
+                .map_or((x, x), |(lo, hi)| (lo.min(x), hi.max(x)));
+            self.range.set(Some(range));
+        }
+        if !fx.is_nan() && self.best.get().is_none_or(|(_, best)| fx < best) {
+            self.best.set(Some((x, fx)));
+        }
+        fx
+    }
+
+    /// Number of calls so far.
+    pub fn calls(&self) -> usize {
+        self.calls.get()
+    }
+
+    /// Smallest and largest argument seen, if any.
+    pub fn arg_range(&self) -> Option<(T, T)> {
+        self.range.get()
+    }
+
+    /// Argument with the lowest value seen, and that value. NaN values are
+    /// never the best.
+    pub fn best(&self) -> Option<(T, T)> {
+        self.best.get()
+    }
+
+    /// Forgets all recorded calls.
+    pub fn reset(&self) {
+        self.calls.set(0);
+        self.range.set(None);
+        self.best.set(None);
+    }
+
+    /// Returns the wrapped function.
+    pub fn into_inner(self) -> F {
+        self.func
+    }
+}
+
+impl<T: Float, F> fmt::Debug for CountedFn<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CountedFn")
+            .field("calls", &self.calls.get())
+            .field("range", &self.range.get())
+            .field("best", &self.best.get())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_calls_range_and_best() {
+        let f = CountedFn::new(|x: f64| if x > 5.0 { f64::NAN } else { (x - 1.0).abs() });
+        assert_eq!((f.calls(), f.arg_range(), f.best()), (0, None, None));
+        for x in [3.0, -2.0, 1.5, 6.0, 0.75] {
+            f.call(x);
+        }
+        assert_eq!(f.calls(), 5);
+        assert_eq!(f.arg_range(), Some((-2.0, 6.0)));
+        assert_eq!(f.best(), Some((0.75, 0.25)));
+        f.reset();
+        assert_eq!((f.calls(), f.best()), (0, None));
+    }
+}
@@ -0,0 +1,258 @@
+//! Finite difference approximations of derivatives.
+//!
+//! Functions of several variables take their point as a slice, so the
+//! dimension is only known at run time.
+
+use super::Float;
+
+/// Central difference approximation of `func'(x)` with step `h`.
+pub fn first_derivative<T, F>(func: F, x: T, h: T) -> T
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    (func(x + h) - func(x - h)) / (T::from_f64(2.0) * h)
+}
+
+/// Central difference approximation of `func''(x)` with step `h`.
+pub fn second_derivative<T, F>(func: F, x: T, h: T) -> T
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    (func(x + h) - T::from_f64(2.0) * func(x) + func(x - h)) / (h * h)
+}
+
+/// Central difference approximation of the partial derivative of `func` along
+/// `x[i]`, with step `h`.
+///
+/// # Panics
+///
+/// Panics if `i` is out of bounds for `x`.
+pub fn partial_derivative<T, F>(func: F, x: &[T], i: usize, h: T) -> T
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+{
+    let mut point = x.to_vec();
+    point[i] = x[i] + h;
+    let forward = func(&point);
+    point[i] = x[i] - h;
+    (forward - func(&point)) / (T::from_f64(2.0) * h)
+}
+
+/// Central difference approximation of the gradient of `func` at `x`, with
+/// step `h` along every coordinate. Costs `2n` evaluations.
+pub fn gradient<T, F>(func: F, x: &[T], h: T) -> Vec<T>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+{
+    let mut point = x.to_vec();
+    (0..x.len())
+        .map(|i| {
+            point[i] = x[i] + h;
+            let forward = func(&point);
+            point[i] = x[i] - h;
+            let backward = func(&point);
+            point[i] = x[i];
+            (forward - backward) / (T::from_f64(2.0) * h)
+        })
+        .collect()
+}
+
+/// A finite difference scheme for functions of several variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difference {
+    /// `(f(x + h·eⱼ) - f(x)) / h`: `n + 1` evaluations, error `O(h)`.
+    Forward,
+    /// `(f(x + h·eⱼ) - f(x - h·eⱼ)) / 2h`: `2n` evaluations, error `O(h²)`.
+    #[default]
+    Central,
+}
+
+/// Finite difference approximation of the Jacobian of the vector function
+/// `func` at `x`, with step `h` along every coordinate, as a vector of rows:
+/// row `i` is the gradient of the `i`-th component.
+///
+/// Forward differences suit steps around `√ε` times the scale of `x`, central
+/// ones steps around `∛ε` times it.
+pub fn jacobian_fd<T, F>(func: F, x: &[T], h: T, difference: Difference) -> Vec<Vec<T>>
+where
+    T: Float,
+    F: Fn(&[T]) -> Vec<T>,
+{
+    difference_jacobian(func, x, None, |_| h, difference)
+}
+
+/// [`jacobian_fd`] with the step `step(j)` along `x[j]`, reusing the value
+/// `fx` of `func` at `x` if known.
+pub(crate) fn difference_jacobian<T, F, S>(
+    func: F,
+    x: &[T],
+    fx: Option<&[T]>,
+    step: S,
+    difference: Difference,
+) -> Vec<Vec<T>>
+where
+    T: Float,
+    F: Fn(&[T]) -> Vec<T>,
+    S: Fn(usize) -> T,
+{
+    // The value at x, which forward differences share.
+    let center = match difference {
+        Difference::Forward => Some(fx.map_or_else(|| func(x), <[T]>::to_vec)),
+        Difference::Central => None,
+    };
+    let mut point = x.to_vec();
+    // Column j holds the difference of func along x[j].
+    let columns: Vec<Vec<T>> = (0..x.len())
+        .map(|j| {
+            let h = step(j);
+            point[j] = x[j] + h;
+            let forward = func(&point);
+            let (backward, width) = match &center {
+                Some(center) => (center.clone(), h),
+                None => {
+                    point[j] = x[j] - h;
+                    (func(&point), T::from_f64(2.0) * h)
+                }
+            };
+            point[j] = x[j];
+            forward
+                .iter()
+                .zip(&backward)
+                .map(|(&f, &b)| (f - b) / width)
+                .collect()
+        })
+        .collect();
+    let m = columns
+        .first()
+        .map_or_else(|| fx.map_or(0, <[T]>::len), Vec::len);
+    (0..m)
+        .map(|i| columns.iter().map(|column| column[i]).collect())
+        .collect()
+}
+
+/// Which entries of a Hessian [`hessian_fd`] approximates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HessianPattern {
+    /// Every entry: `2n² + 1` evaluations.
+    #[default]
+    Full,
+    /// The diagonal only, leaving the other entries zero: `2n + 1`
+    /// evaluations. Enough for the curvature along each coordinate.
+    Diagonal,
+}
+
+/// Central difference approximation of the Hessian of `func` at `x`, with
+/// step `h` along every coordinate, as a vector of rows.
+///
+/// Diagonal entries use `(f(x + h·eᵢ) - 2f(x) + f(x - h·eᵢ)) / h²` and the
+/// others the four point stencil on `x ± h·eᵢ ± h·eⱼ`, which is the same for
+/// `(i, j)` and `(j, i)`, so the result is exactly symmetric. The error is
+/// `O(h²)` plus rounding of order `ε·|f| / h²`, so steps around `∜ε` times the
+/// scale of `x` work best.
+pub fn hessian_fd<T, F>(func: F, x: &[T], h: T, pattern: HessianPattern) -> Vec<Vec<T>>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+{
+    let n = x.len();
+    let two = T::from_f64(2.0);
+    let fx = func(x);
+    let mut point = x.to_vec();
+    let mut hessian = vec![vec![T::ZERO; n]; n];
+    for i in 0..n {
+        point[i] = x[i] + h;
+        let forward = func(&point);
+        point[i] = x[i] - h;
+        let backward = func(&point);
+        point[i] = x[i];
+        hessian[i][i] = (forward - two * fx + backward) / (h * h);
+        if pattern == HessianPattern::Diagonal {
+            continue;
+        }
+        for j in 0..i {
+            let mut corner = |si: T, sj: T| {
+                point[i] = x[i] + si * h;
+                point[j] = x[j] + sj * h;
+                let value = func(&point);
+                point[i] = x[i];
+                point[j] = x[j];
+                value
+            };
+            let (one, minus) = (T::ONE, -T::ONE);
+            let mixed = (corner(one, one) - corner(one, minus) - corner(minus, one)
+                + corner(minus, minus))
+                / (T::from_f64(4.0) * h * h);
+            hessian[i][j] = mixed;
+            hessian[j][i] = mixed;
+        }
+    }
+    hessian
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derivatives_of_cubic() {
+        let f = |x: f64| x.powi(3) - 2.0 * x;
+        assert!((first_derivative(f, 2.0, 1e-5) - 10.0).abs() < 1e-8);
+        assert!((second_derivative(f, 2.0, 1e-4) - 12.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn gradient_of_a_runtime_sized_quadratic() {
+        // f(x) = Σ i·xᵢ², with as many coordinates as the data has.
+        let f = |x: &[f64]| x.iter().enumerate().map(|(i, xi)| i as f64 * xi * xi).sum();
+        for n in [1, 3, 6] {
+            let x: Vec<f64> = (0..n).map(|i| 1.0 - 0.5 * i as f64).collect();
+            let g = gradient(f, &x, 1e-5);
+            assert_eq!(g.len(), n);
+            for (i, (gi, xi)) in g.iter().zip(&x).enumerate() {
+                assert!((gi - 2.0 * i as f64 * xi).abs() < 1e-8);
+                assert_eq!(*gi, partial_derivative(f, &x, i, 1e-5));
+            }
+        }
+    }
+
+    #[test]
+    fn jacobian_of_polar_coordinates() {
+        let polar = |p: &[f64]| vec![p[0] * p[1].cos(), p[0] * p[1].sin()];
+        let p = [2.0, 0.6];
+        let exact = [
+            [0.6f64.cos(), -2.0 * 0.6f64.sin()],
+            [0.6f64.sin(), 2.0 * 0.6f64.cos()],
+        ];
+        let central = jacobian_fd(polar, &p, 1e-5, Difference::Central);
+        let forward = jacobian_fd(polar, &p, 1e-7, Difference::Forward);
+        assert_eq!(central.len(), 2);
+        for (i, row) in exact.iter().enumerate() {
+            for (j, e) in row.iter().enumerate() {
+                assert!((central[i][j] - e).abs() < 1e-9);
+                assert!((forward[i][j] - e).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn hessian_of_a_coupled_quartic() {
+        // f = x⁴ + x²y + 3y², with Hessian [[12x² + 2y, 2x], [2x, 6]].
+        let f = |p: &[f64]| p[0].powi(4) + p[0] * p[0] * p[1] + 3.0 * p[1] * p[1];
+        let p = [0.5, -1.0];
+        let exact = [[1.0, 1.0], [1.0, 6.0]];
+        let full = hessian_fd(f, &p, 1e-4, HessianPattern::Full);
+        let diagonal = hessian_fd(f, &p, 1e-4, HessianPattern::Diagonal);
+        for i in 0..2 {
+            for j in 0..2 {
+                assert!((full[i][j] - exact[i][j]).abs() < 1e-6, "{full:?}");
+                assert_eq!(full[i][j], full[j][i]);
+            }
+            assert_eq!(diagonal[i][i], full[i][i]);
+        }
+        assert_eq!((diagonal[0][1], diagonal[1][0]), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,62 @@
+use std::error::Error;
+use std::fmt;
+
+/// Errors reported by the factorizations in [`crate::generals::linalg`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinalgError {
+    /// The columns of the matrix are numerically linearly dependent: only
+    /// `rank` of them are independent, so the solution is not unique.
+    RankDeficient { rank: usize },
+    /// The matrix is singular: elimination met a zero pivot.
+    Singular,
+    /// The matrix is so ill-conditioned, with a 1-norm condition number
+    /// estimated at `condition`, that the result would carry no accurate
+    /// digits.
+    IllConditioned { condition: f64 },
+    /// An iterative method that needs a diagonally dominant matrix was given
+    /// one that is not.
+    NotDiagonallyDominant,
+    /// An iterative method that divides by the diagonal met a zero on row
+    /// `row`.
+    ZeroDiagonal { row: usize },
+    /// A method for symmetric positive definite matrices found the matrix
+    /// not to be one.
+    NotPositiveDefinite,
+    /// An iterative method broke down: a quantity it divides by vanished or
+    /// became non-finite.
+    Breakdown,
+    /// An iterative method that must converge to give any result did not
+    /// within `iterations` iterations.
+    NoConvergence { iterations: usize },
+}
+
+impl fmt::Display for LinalgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinalgError::RankDeficient { rank } => {
+                write!(f, "matrix is rank deficient, with rank {rank}")
+            }
+            LinalgError::Singular => write!(f, "matrix is singular"),
+            LinalgError::IllConditioned { condition } => {
+                write!(
+                    f,
+                    "matrix is ill-conditioned, with condition number {condition:e}"
+                )
+            }
+            LinalgError::NotDiagonallyDominant => write!(f, "matrix is not diagonally dominant"),
+            LinalgError::ZeroDiagonal { row } => {
+                write!(f, "matrix has a zero diagonal entry on row {row}")
+            }
+            LinalgError::NotPositiveDefinite => write!(f, "matrix is not positive definite"),
+            LinalgError::Breakdown => write!(f, "iterative method broke down"),
+            LinalgError::NoConvergence { iterations } => {
+                write!(
+                    f,
+                    "iterative method did not converge in {iterations} iterations"
+                )
+            }
+        }
+    }
+}
+
+impl Error for LinalgError {}
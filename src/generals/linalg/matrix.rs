@@ -0,0 +1,464 @@
+use std::ops::{Add, Index, IndexMut, Mul, Neg, Range, Sub};
+
+use super::{dot, LinalgError, Lu, Vector};
+use crate::generals::Float;
+
+/// A dense matrix, stored row-major.
+///
+/// Entries are indexed by `(row, column)` pairs. Products with matrices,
+/// [`Vector`]s and slices are available both as methods and as operators on
+/// references; mismatched dimensions panic.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::{Matrix, Vector};
+///
+/// let a = Matrix::from_rows(&[vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]);
+/// assert_eq!(a.shape(), (3, 2));
+/// assert_eq!(a[(2, 1)], 6.0);
+/// assert_eq!(a.transpose().row(1), &[2.0, 4.0, 6.0]);
+///
+/// let x = Vector::from(vec![1.0, -1.0]);
+/// assert_eq!(&a * &x, Vector::from(vec![-1.0, -1.0, -1.0]));
+/// let gram = &a.transpose() * &a;
+/// assert_eq!(gram, Matrix::from_rows(&[vec![35.0, 44.0], vec![44.0, 56.0]]));
+/// assert_eq!(a.submatrix(1..3, 0..1).column(0), Vector::from(vec![3.0, 5.0]));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Float> Matrix<T> {
+    /// The `rows × cols` matrix with the entries `data`, row after row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` does not have `rows · cols` entries.
+    pub fn new(rows: usize, cols: usize, data: Vec<T>) -> Self {
+        assert_eq!(
+            data.len(),
+            rows * cols,
+            "data must have rows · cols entries"
+        );
+        Matrix { rows, cols, data }
+    }
+
+    /// The `rows × cols` matrix of zeros.
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Matrix::new(rows, cols, vec![T::ZERO; rows * cols])
+    }
+
+    /// The `n × n` identity matrix.
+    pub fn identity(n: usize) -> Self {
+        Matrix::from_fn(n, n, |i, j| if i == j { T::ONE } else { T::ZERO })
+    }
+
+    /// The square matrix with `diagonal` on its diagonal and zeros elsewhere.
+    pub fn from_diagonal(diagonal: &[T]) -> Self {
+        let n = diagonal.len();
+        Matrix::from_fn(n, n, |i, j| if i == j { diagonal[i] } else { T::ZERO })
+    }
+
+    /// The `rows × cols` matrix with entries `f(i, j)`.
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let data = (0..rows)
+            .flat_map(|i| (0..cols).map(move |j| (i, j)))
+            .map(|(i, j)| f(i, j))
+            .collect();
+        Matrix { rows, cols, data }
+    }
+
+    /// The matrix with the given rows, as the crate's Jacobians and Hessians
+    /// are returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows have different lengths.
+    pub fn from_rows(rows: &[Vec<T>]) -> Self {
+        let cols = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == cols),
+            "rows must have the same length"
+        );
+        Matrix::new(rows.len(), cols, rows.concat())
+    }
+
+    /// The rows, as separate vectors.
+    pub fn to_rows(&self) -> Vec<Vec<T>> {
+        (0..self.rows).map(|i| self.row(i).to_vec()).collect()
+    }
+
+    /// Number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// `(rows, cols)`.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Whether the matrix has as many rows as columns.
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    /// The entries, row after row.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Row `i`.
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// Row `i`, mutably.
+    pub fn row_mut(&mut self, i: usize) -> &mut [T] {
+        &mut self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// Column `j`, copied.
+    pub fn column(&self, j: usize) -> Vector<T> {
+        assert!(j < self.cols, "column index out of bounds");
+        (0..self.rows)
+            .map(|i| self.data[i * self.cols + j])
+            .collect()
+    }
+
+    /// The diagonal entries.
+    pub fn diagonal(&self) -> Vector<T> {
+        (0..self.rows.min(self.cols))
+            .map(|i| self.data[i * self.cols + i])
+            .collect()
+    }
+
+    /// The block of the given `rows` and `cols`, copied.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a range reaches past the matrix.
+    pub fn submatrix(&self, rows: Range<usize>, cols: Range<usize>) -> Self {
+        assert!(
+            rows.end <= self.rows && cols.end <= self.cols,
+            "block reaches past the matrix"
+        );
+        Matrix::from_fn(rows.len(), cols.len(), |i, j| {
+            self[(rows.start + i, cols.start + j)]
+        })
+    }
+
+    /// The transpose.
+    pub fn transpose(&self) -> Self {
+        Matrix::from_fn(self.cols, self.rows, |i, j| self[(j, i)])
+    }
+
+    /// The product with the vector `x`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` does not have one entry per column.
+    pub fn mul_vector(&self, x: &[T]) -> Vector<T> {
+        assert_eq!(x.len(), self.cols, "x must have one entry per column");
+        (0..self.rows).map(|i| dot(self.row(i), x)).collect()
+    }
+
+    /// The product `Aᵀ·y` of the transpose with the vector `y`, without
+    /// forming the transpose.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` does not have one entry per row.
+    pub fn transpose_mul_vector(&self, y: &[T]) -> Vector<T> {
+        assert_eq!(y.len(), self.rows, "y must have one entry per row");
+        let mut product = Vector::zeros(self.cols);
+        for (i, &yi) in y.iter().enumerate() {
+            for (p, &a) in product.iter_mut().zip(self.row(i)) {
+                *p += a * yi;
+            }
+        }
+        product
+    }
+
+    /// The matrix product with `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` does not have as many rows as this has columns.
+    pub fn mul_matrix(&self, other: &Matrix<T>) -> Self {
+        assert_eq!(
+            self.cols, other.rows,
+            "inner dimensions of a product must agree"
+        );
+        let mut product = Matrix::zeros(self.rows, other.cols);
+        for i in 0..self.rows {
+            for (k, &a) in self.row(i).iter().enumerate() {
+                if a == T::ZERO {
+                    continue;
+                }
+                let target = i * other.cols;
+                for (p, &b) in product.data[target..target + other.cols]
+                    .iter_mut()
+                    .zip(other.row(k))
+                {
+                    *p += a * b;
+                }
+            }
+        }
+        product
+    }
+
+    /// The matrix with every entry multiplied by `factor`.
+    pub fn scale(&self, factor: T) -> Self {
+        self.map(|a| a * factor)
+    }
+
+    /// The matrix with `f` applied to every entry.
+    pub fn map(&self, f: impl Fn(T) -> T) -> Self {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|&a| f(a)).collect(),
+        }
+    }
+
+    /// The Frobenius norm, `sqrt(Σ aᵢⱼ²)`.
+    pub fn frobenius_norm(&self) -> T {
+        dot(&self.data, &self.data).sqrt()
+    }
+
+    /// The 1-norm, the largest absolute column sum.
+    pub fn norm_1(&self) -> T {
+        (0..self.cols)
+            .map(|j| (0..self.rows).map(|i| self[(i, j)].abs()).sum::<T>())
+            .fold(T::ZERO, T::max)
+    }
+
+    /// The ∞-norm, the largest absolute row sum.
+    pub fn norm_inf(&self) -> T {
+        (0..self.rows)
+            .map(|i| self.row(i).iter().map(|a| a.abs()).sum::<T>())
+            .fold(T::ZERO, T::max)
+    }
+
+    /// The inverse, through an [`Lu`] factorization.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LinalgError::Singular`] for a singular matrix and
+    /// [`LinalgError::IllConditioned`] for one so close to singular that the
+    /// inverse would be meaningless.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathslib::generals::linalg::{LinalgError, Matrix};
+    ///
+    /// let a: Matrix<f64> = Matrix::from_rows(&[vec![4.0, 7.0], vec![2.0, 6.0]]);
+    /// let inverse = a.inverse().unwrap();
+    /// assert!((&(&a * &inverse) - &Matrix::identity(2)).frobenius_norm() < 1e-15);
+    /// assert_eq!(a.det(), 10.0);
+    /// assert!((a.condition_estimate() - 14.3).abs() < 1e-12);
+    ///
+    /// let singular: Matrix<f64> = Matrix::from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]);
+    /// assert_eq!(singular.inverse(), Err(LinalgError::Singular));
+    /// ```
+    pub fn inverse(&self) -> Result<Self, LinalgError> {
+        Lu::new(self).inverse()
+    }
+
+    /// The determinant, through an [`Lu`] factorization.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn det(&self) -> T {
+        Lu::new(self).det()
+    }
+
+    /// An estimate of the 1-norm condition number `‖A‖₁·‖A⁻¹‖₁`, infinite
+    /// for a singular matrix. See [`Lu::condition_estimate`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn condition_estimate(&self) -> T {
+        Lu::new(self).condition_estimate()
+    }
+
+    fn zip_with(&self, other: &Matrix<T>, f: impl Fn(T, T) -> T) -> Self {
+        assert_eq!(
+            self.shape(),
+            other.shape(),
+            "matrices must have the same shape"
+        );
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&other.data)
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+impl<T: Float> Add for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, other: &Matrix<T>) -> Matrix<T> {
+        self.zip_with(other, |a, b| a + b)
+    }
+}
+
+impl<T: Float> Sub for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn sub(self, other: &Matrix<T>) -> Matrix<T> {
+        self.zip_with(other, |a, b| a - b)
+    }
+}
+
+impl<T: Float> Neg for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn neg(self) -> Matrix<T> {
+        self.map(|a| -a)
+    }
+}
+
+impl<T: Float> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, other: &Matrix<T>) -> Matrix<T> {
+        self.mul_matrix(other)
+    }
+}
+
+impl<T: Float> Mul<&Vector<T>> for &Matrix<T> {
+    type Output = Vector<T>;
+
+    fn mul(self, x: &Vector<T>) -> Vector<T> {
+        self.mul_vector(x)
+    }
+}
+
+impl<T: Float> Mul<&[T]> for &Matrix<T> {
+    type Output = Vector<T>;
+
+    fn mul(self, x: &[T]) -> Vector<T> {
+        self.mul_vector(x)
+    }
+}
+
+impl<T: Float> Mul<T> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, factor: T) -> Matrix<T> {
+        self.scale(factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generals::linalg::Norm;
+
+    fn sample() -> Matrix<f64> {
+        Matrix::from_rows(&[vec![1.0, -2.0, 3.0], vec![0.0, 4.0, -1.0]])
+    }
+
+    #[test]
+    fn products_agree_with_their_definitions() {
+        let a = sample();
+        let b = Matrix::from_fn(3, 2, |i, j| (i + 2 * j) as f64 - 1.5);
+        let ab = &a * &b;
+        for i in 0..2 {
+            for j in 0..2 {
+                assert_eq!(
+                    ab[(i, j)],
+                    a.row(i)
+                        .iter()
+                        .zip(b.column(j).iter())
+                        .map(|(x, y)| x * y)
+                        .sum::<f64>()
+                );
+            }
+        }
+        assert_eq!(&Matrix::identity(2) * &a, a);
+        assert_eq!(&a * &Matrix::identity(3), a);
+        let x = [0.5, 1.0, -2.0];
+        assert_eq!(&a * &x[..], Vector::from(vec![-7.5, 6.0]));
+        assert_eq!(
+            a.transpose_mul_vector(&[1.0, 2.0]),
+            a.transpose().mul_vector(&[1.0, 2.0])
+        );
+        assert_eq!(
+            (&ab.transpose() - &(&b.transpose() * &a.transpose())).frobenius_norm(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn norms_slices_and_elementwise_operations() {
+        let mut a = sample();
+        assert_eq!(a.norm_1(), 6.0);
+        assert_eq!(a.norm_inf(), 6.0);
+        assert_eq!(a.frobenius_norm(), 31f64.sqrt());
+        assert_eq!(a.diagonal(), Vector::from(vec![1.0, 4.0]));
+        assert_eq!(
+            a.submatrix(0..2, 1..3),
+            Matrix::new(2, 2, vec![-2.0, 3.0, 4.0, -1.0])
+        );
+        assert_eq!((&a + &a), &a * 2.0);
+        assert!((&a - &a).as_slice().iter().all(|&x| x == 0.0));
+        a[(1, 0)] = 7.0;
+        a.row_mut(0)[0] = -1.0;
+        assert_eq!(
+            a.to_rows(),
+            vec![vec![-1.0, -2.0, 3.0], vec![7.0, 4.0, -1.0]]
+        );
+        assert_eq!(a.column(0).norm(Norm::Infinity), 7.0);
+        assert_eq!(
+            Matrix::from_diagonal(&[2.0, 3.0]).diagonal().into_vec(),
+            vec![2.0, 3.0]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "inner dimensions")]
+    fn mismatched_products_panic() {
+        let _ = &sample() * &sample();
+    }
+}
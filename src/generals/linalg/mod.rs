@@ -0,0 +1,125 @@
+//! Vectors and matrices: the dense [`Matrix`] and [`Vector`] types, their
+//! [`Lu`] and [`Qr`] factorizations, the [`BandedMatrix`] and sparse
+//! [`CsrMatrix`] types, and small helpers on slices.
+
+mod banded;
+mod csr;
+mod error;
+mod lu;
+mod matrix;
+mod qr;
+mod vector;
+
+pub use banded::{BandedLu, BandedMatrix};
+pub use csr::CsrMatrix;
+pub use error::LinalgError;
+pub use lu::Lu;
+pub use matrix::Matrix;
+pub use qr::Qr;
+pub use vector::Vector;
+
+use super::Float;
+
+/// Dot product of `a` and `b`, which must have the same length.
+pub fn dot<T: Float>(a: &[T], b: &[T]) -> T {
+    debug_assert_eq!(a.len(), b.len());
+    a.iter().zip(b).map(|(&x, &y)| x * y).sum()
+}
+
+/// Euclidean norm of `a`.
+pub fn norm<T: Float>(a: &[T]) -> T {
+    dot(a, a).sqrt()
+}
+
+/// A vector norm.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Norm {
+    /// Euclidean norm, `sqrt(Σ vᵢ²)`.
+    #[default]
+    L2,
+    /// Largest absolute component, `max |vᵢ|`.
+    Infinity,
+    /// Sum of absolute components, `Σ |vᵢ|`.
+    L1,
+}
+
+impl Norm {
+    /// This norm of `v`.
+    pub fn of<T: Float>(self, v: &[T]) -> T {
+        match self {
+            Norm::L2 => norm(v),
+            Norm::Infinity => v.iter().fold(T::ZERO, |max, x| max.max(x.abs())),
+            Norm::L1 => v.iter().map(|x| x.abs()).sum(),
+        }
+    }
+}
+
+/// `x + alpha * d` as a new vector.
+pub fn add_scaled<T: Float>(x: &[T], alpha: T, d: &[T]) -> Vec<T> {
+    debug_assert_eq!(x.len(), d.len());
+    x.iter().zip(d).map(|(&xi, &di)| xi + alpha * di).collect()
+}
+
+/// Cholesky factor `L` of the symmetric positive definite `n × n` matrix `a`,
+/// both stored row-major, such that `a = L·Lᵀ`. Only the lower triangle of `a`
+/// is read. Returns `None` if `a` is not positive definite.
+pub(crate) fn cholesky<T: Float>(a: &[T], n: usize) -> Option<Vec<T>> {
+    debug_assert_eq!(a.len(), n * n);
+    let mut l = vec![T::ZERO; n * n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum = dot(&l[i * n..i * n + j], &l[j * n..j * n + j]);
+            if i == j {
+                let pivot = a[i * n + i] - sum;
+                if pivot <= T::ZERO || pivot.is_nan() {
+                    return None;
+                }
+                l[i * n + i] = pivot.sqrt();
+            } else {
+                l[i * n + j] = (a[i * n + j] - sum) / l[j * n + j];
+            }
+        }
+    }
+    Some(l)
+}
+
+/// Solves `L·Lᵀ·x = b` for the Cholesky factor `l` returned by [`cholesky`].
+pub(crate) fn cholesky_solve<T: Float>(l: &[T], n: usize, b: &[T]) -> Vec<T> {
+    debug_assert_eq!(b.len(), n);
+    let mut y = b.to_vec();
+    for i in 0..n {
+        y[i] = (y[i] - dot(&l[i * n..i * n + i], &y[..i])) / l[i * n + i];
+    }
+    for i in (0..n).rev() {
+        let sum = (i + 1..n).map(|k| l[k * n + i] * y[k]).sum::<T>();
+        y[i] = (y[i] - sum) / l[i * n + i];
+    }
+    y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vector_helpers() {
+        assert_eq!(dot(&[1.0, 2.0, 3.0], &[4.0, -5.0, 6.0]), 12.0);
+        assert_eq!(norm(&[3.0, 4.0]), 5.0);
+        assert_eq!(add_scaled(&[1.0, 1.0], 0.5, &[2.0, -4.0]), vec![2.0, -1.0]);
+        assert_eq!(Norm::L2.of(&[3.0, -4.0]), 5.0);
+        assert_eq!(Norm::Infinity.of(&[3.0, -4.0]), 4.0);
+        assert_eq!(Norm::L1.of(&[3.0, -4.0]), 7.0);
+    }
+
+    #[test]
+    fn cholesky_solves_positive_definite_systems() {
+        let a = [4.0, 2.0, 0.4, 2.0, 5.0, 1.0, 0.4, 1.0, 3.0];
+        let l = cholesky(&a, 3).unwrap();
+        let x = cholesky_solve(&l, 3, &[1.0, 2.0, 3.0]);
+        for i in 0..3 {
+            let row = dot(&a[3 * i..3 * i + 3], &x);
+            assert!((row - (i + 1) as f64).abs() < 1e-12);
+        }
+        assert_eq!(cholesky(&[1.0, 2.0, 2.0, 1.0], 2), None);
+    }
+}
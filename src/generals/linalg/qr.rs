@@ -0,0 +1,342 @@
+use super::{dot, norm, LinalgError, Matrix, Vector};
+use crate::generals::Float;
+
+/// Householder QR factorization of a `rows × cols` matrix `a`, optionally
+/// with column pivoting.
+///
+/// Factors `a·P = Q·R` with `Q` orthogonal, `R` upper trapezoidal and `P`
+/// the permutation moving column `permutation()[k]` of `a` to position `k`;
+/// without pivoting `P` is the identity. Pivoting brings the column of
+/// largest remaining norm forward at every step, so the diagonal of `R`
+/// decreases in magnitude and reveals the [`rank`](Qr::rank) reliably, at the
+/// price of a little bookkeeping.
+///
+/// `Q` is kept as the product of its Householder reflectors, in the storage
+/// of `a`, so factoring costs `O(rows·cols²)` and solving applies `Qᵀ`
+/// without forming it. [`q`](Qr::q) and [`r`](Qr::r) give the thin factors,
+/// `rows × k` and `k × cols` for `k = min(rows, cols)`, and
+/// [`full_q`](Qr::full_q) the whole `rows × rows` orthogonal matrix.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::{Matrix, Qr};
+///
+/// // Fit a line c0 + c1·t through four points.
+/// let a: Matrix<f64> =
+///     Matrix::from_rows(&[vec![1.0, 0.0], vec![1.0, 1.0], vec![1.0, 2.0], vec![1.0, 3.0]]);
+/// let qr = Qr::new(&a);
+/// let c = qr.solve_least_squares(&[1.0, 3.0, 5.0, 8.0]).unwrap();
+/// assert!((c[0] - 0.8).abs() < 1e-12 && (c[1] - 2.3).abs() < 1e-12);
+///
+/// let reconstructed = &qr.q() * &qr.r();
+/// assert!((&reconstructed - &a).frobenius_norm() < 1e-12);
+/// assert_eq!(qr.full_q().cols(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Qr<T> {
+    /// `R` on and above the diagonal, row-major, and below it the reflector
+    /// vectors, whose leading entries are one and not stored.
+    pub(crate) factors: Vec<T>,
+    /// Scale `τ` of each reflector `I - τ·v·vᵀ`, zero for the identity.
+    pub(crate) tau: Vec<T>,
+    pub(crate) permutation: Vec<usize>,
+    pub(crate) rows: usize,
+    pub(crate) cols: usize,
+}
+
+impl<T: Float> Qr<T> {
+    /// The factorization of `a` without pivoting.
+    pub fn new(a: &Matrix<T>) -> Self {
+        Qr::factor(a.as_slice(), a.rows(), a.cols(), false)
+    }
+
+    /// The factorization of `a` with column pivoting.
+    pub fn with_pivoting(a: &Matrix<T>) -> Self {
+        Qr::factor(a.as_slice(), a.rows(), a.cols(), true)
+    }
+
+    /// The factorization of the `rows × cols` matrix `a`, stored row-major.
+    pub(crate) fn factor(a: &[T], rows: usize, cols: usize, pivoting: bool) -> Self {
+        debug_assert_eq!(a.len(), rows * cols);
+        let mut r = a.to_vec();
+        let mut tau = vec![T::ZERO; rows.min(cols)];
+        let mut permutation: Vec<usize> = (0..cols).collect();
+        for k in 0..rows.min(cols) {
+            if pivoting {
+                let column_norm =
+                    |r: &[T], j: usize| (k..rows).map(|i| r[i * cols + j].powi(2)).sum::<T>();
+                let pivot = (k + 1..cols).fold(k, |best, j| {
+                    if column_norm(&r, j) > column_norm(&r, best) {
+                        j
+                    } else {
+                        best
+                    }
+                });
+                if pivot != k {
+                    permutation.swap(k, pivot);
+                    for i in 0..rows {
+                        r.swap(i * cols + k, i * cols + pivot);
+                    }
+                }
+            }
+            let x: Vec<T> = (k..rows).map(|i| r[i * cols + k]).collect();
+            let length = norm(&x);
+            if length == T::ZERO {
+                continue;
+            }
+            let alpha = if x[0] > T::ZERO { -length } else { length };
+            // v = (x - alpha·e₁) / (x₀ - alpha), so that v₀ = 1.
+            let lead = x[0] - alpha;
+            let mut v: Vec<T> = x.iter().map(|&xi| xi / lead).collect();
+            v[0] = T::ONE;
+            tau[k] = T::from_f64(2.0) / dot(&v, &v);
+            // R ← H·R for H = I - τvvᵀ acting on rows k...
+            for j in k + 1..cols {
+                let s = tau[k] * (k..rows).map(|i| v[i - k] * r[i * cols + j]).sum::<T>();
+                for i in k..rows {
+                    r[i * cols + j] -= s * v[i - k];
+                }
+            }
+            r[k * cols + k] = alpha;
+            for i in k + 1..rows {
+                r[i * cols + k] = v[i - k];
+            }
+        }
+        Qr {
+            factors: r,
+            tau,
+            permutation,
+            rows,
+            cols,
+        }
+    }
+
+    /// Applies the reflector `k` to `y` in place.
+    fn reflect(&self, k: usize, y: &mut [T]) {
+        let tau = self.tau[k];
+        if tau == T::ZERO {
+            return;
+        }
+        let v = |i: usize| {
+            if i == k {
+                T::ONE
+            } else {
+                self.factors[i * self.cols + k]
+            }
+        };
+        let s = tau * (k..self.rows).map(|i| v(i) * y[i]).sum::<T>();
+        for (i, yi) in y.iter_mut().enumerate().skip(k) {
+            *yi -= s * v(i);
+        }
+    }
+
+    /// `Qᵀ·y`, in place.
+    pub(crate) fn apply_qt(&self, y: &mut [T]) {
+        debug_assert_eq!(y.len(), self.rows);
+        for k in 0..self.tau.len() {
+            self.reflect(k, y);
+        }
+    }
+
+    /// `Q·y`, in place.
+    pub(crate) fn apply_q(&self, y: &mut [T]) {
+        debug_assert_eq!(y.len(), self.rows);
+        for k in (0..self.tau.len()).rev() {
+            self.reflect(k, y);
+        }
+    }
+
+    /// The first `columns` columns of `Q`, as a `rows × columns` matrix.
+    fn q_columns(&self, columns: usize) -> Matrix<T> {
+        let mut q = Matrix::zeros(self.rows, columns);
+        for j in 0..columns {
+            for (i, qij) in self.q_column(j).into_iter().enumerate() {
+                q[(i, j)] = qij;
+            }
+        }
+        q
+    }
+
+    /// The thin orthogonal factor `Q`, `rows × min(rows, cols)`, whose
+    /// columns are orthonormal.
+    pub fn q(&self) -> Matrix<T> {
+        self.q_columns(self.rows.min(self.cols))
+    }
+
+    /// The full orthogonal factor `Q`, `rows × rows`, whose columns past the
+    /// first `min(rows, cols)` complete those of [`q`](Self::q) to a basis.
+    pub fn full_q(&self) -> Matrix<T> {
+        self.q_columns(self.rows)
+    }
+
+    /// The upper trapezoidal factor `R`, `min(rows, cols) × cols`.
+    pub fn r(&self) -> Matrix<T> {
+        Matrix::from_fn(self.rows.min(self.cols), self.cols, |i, j| {
+            if i <= j {
+                self.factors[i * self.cols + j]
+            } else {
+                T::ZERO
+            }
+        })
+    }
+
+    /// Where the columns of `R` come from: column `k` of `R` belongs to
+    /// column `permutation()[k]` of the factored matrix.
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    /// Numerical rank: the number of diagonal entries of `R` above
+    /// `max(rows, cols)·ε` relative to the largest. Only reliable with
+    /// pivoting.
+    pub fn rank(&self) -> usize {
+        let size = self.rows.min(self.cols);
+        let diagonal = (0..size).map(|k| self.factors[k * self.cols + k].abs());
+        let tolerance = T::from_usize(self.rows.max(self.cols))
+            * T::EPSILON
+            * diagonal.clone().fold(T::ZERO, T::max);
+        diagonal.filter(|&d| d > tolerance).count()
+    }
+
+    /// Column `j` of the full `Q`.
+    pub(crate) fn q_column(&self, j: usize) -> Vec<T> {
+        let mut column = vec![T::ZERO; self.rows];
+        column[j] = T::ONE;
+        self.apply_q(&mut column);
+        column
+    }
+
+    /// The `x` minimizing `‖a·x - b‖`, or the solution of `a·x = b` for a
+    /// square `a`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LinalgError::RankDeficient`] if the columns of `a` are
+    /// linearly dependent, so that the minimizer is not unique.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `b` does not have one entry per row.
+    pub fn solve_least_squares(&self, b: &[T]) -> Result<Vector<T>, LinalgError> {
+        assert_eq!(b.len(), self.rows, "b must have one entry per row");
+        let rank = self.rank();
+        if rank < self.cols {
+            return Err(LinalgError::RankDeficient { rank });
+        }
+        Ok(self.solve(b).into())
+    }
+
+    /// The basic least-squares solution of `a·x = b`: it minimizes `‖a·x - b‖`
+    /// using only the first [`rank`](Self::rank) pivot columns, with the
+    /// other entries of `x` zero. For a square nonsingular `a`, the solution.
+    /// Requires pivoting when `a` may be rank deficient.
+    pub(crate) fn solve(&self, b: &[T]) -> Vec<T> {
+        debug_assert_eq!(b.len(), self.rows);
+        let rank = self.rank();
+        let mut y = b.to_vec();
+        self.apply_qt(&mut y);
+        y.truncate(rank);
+        for k in (0..rank).rev() {
+            let sum = (k + 1..rank)
+                .map(|j| self.factors[k * self.cols + j] * y[j])
+                .sum::<T>();
+            y[k] = (y[k] - sum) / self.factors[k * self.cols + k];
+        }
+        let mut x = vec![T::ZERO; self.cols];
+        for (k, yk) in y.into_iter().enumerate() {
+            x[self.permutation[k]] = yk;
+        }
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generals::linalg::Norm;
+
+    #[test]
+    fn pivoted_qr_reconstructs_and_finds_the_rank() {
+        // 4 × 3 with the last column the sum of the first two.
+        let a = [1.0, 2.0, 3.0, 4.0, 0.0, 4.0, -1.0, 5.0, 4.0, 2.0, 2.0, 4.0];
+        let qr = Qr::factor(&a, 4, 3, true);
+        assert_eq!(qr.rank(), 2);
+        let (q, r) = (qr.q(), qr.r());
+        assert_eq!((q.rows(), q.cols(), r.rows(), r.cols()), (4, 3, 3, 3));
+        let product = &q * &r;
+        for i in 0..4 {
+            for (k, &j) in qr.permutation.iter().enumerate() {
+                assert!((product[(i, k)] - a[i * 3 + j]).abs() < 1e-12);
+            }
+        }
+        let full = qr.full_q();
+        assert!((&(&full.transpose() * &full) - &Matrix::identity(4)).frobenius_norm() < 1e-12);
+        for j in 0..3 {
+            for i in 0..4 {
+                assert_eq!(full[(i, j)], q[(i, j)]);
+            }
+        }
+        assert!(r[(1, 0)] == 0.0 && r[(2, 0)] == 0.0 && r[(2, 1)] == 0.0);
+    }
+
+    #[test]
+    fn applies_q_without_forming_it() {
+        let a = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0, 5.0, 3.0];
+        let qr = Qr::factor(&a, 5, 2, false);
+        let b = [1.0, -2.0, 0.5, 3.0, 2.0];
+        let mut y = b.to_vec();
+        qr.apply_qt(&mut y);
+        let expected = qr.full_q().transpose().mul_vector(&b);
+        assert!(y
+            .iter()
+            .zip(expected.iter())
+            .all(|(yi, ei)| (yi - ei).abs() < 1e-12));
+        qr.apply_q(&mut y);
+        assert!(y.iter().zip(b).all(|(yi, bi)| (yi - bi).abs() < 1e-12));
+    }
+
+    #[test]
+    fn pivoted_qr_solves_square_and_rank_deficient_systems() {
+        let a = [2.0, 1.0, -1.0, -3.0, -1.0, 2.0, -2.0, 1.0, 2.0];
+        let x = Qr::factor(&a, 3, 3, true).solve(&[8.0, -11.0, -3.0]);
+        for (xi, expected) in x.iter().zip([2.0, 3.0, -1.0]) {
+            assert!((xi - expected).abs() < 1e-12);
+        }
+        // Columns 1 and 2 of a are equal, and b is not in its range: the
+        // least-squares solution uses one of them.
+        let a = [1.0, 1.0, 1.0, 1.0, 2.0, 2.0];
+        let qr = Qr::factor(&a, 3, 2, true);
+        let x = qr.solve(&[1.0, 2.0, 2.0]);
+        assert!(x.iter().filter(|&&xi| xi == 0.0).count() == 1);
+        assert!((x[0] + x[1] - 7.0 / 6.0).abs() < 1e-12);
+        assert_eq!(
+            qr.solve_least_squares(&[1.0, 2.0, 2.0]),
+            Err(LinalgError::RankDeficient { rank: 1 })
+        );
+    }
+
+    #[test]
+    fn unpivoted_qr_keeps_the_column_order() {
+        let a = Matrix::from_rows(&[
+            vec![0.0, 3.0, 1.0],
+            vec![0.0, 4.0, -2.0],
+            vec![1.0, 0.0, 5.0],
+        ]);
+        let qr = Qr::new(&a);
+        assert_eq!(qr.permutation(), &[0, 1, 2]);
+        let (q, r) = (qr.q(), qr.r());
+        assert!((&(&q * &r) - &a).frobenius_norm() < 1e-12);
+        assert!((&(&q.transpose() * &q) - &Matrix::identity(3)).frobenius_norm() < 1e-12);
+        assert!(r[(1, 0)] == 0.0 && r[(2, 0)] == 0.0 && r[(2, 1)] == 0.0);
+        assert_eq!(qr.rank(), 3);
+
+        let pivoted = Qr::with_pivoting(&a);
+        assert_eq!(pivoted.permutation()[0], 2);
+        let b = [1.0, 2.0, 3.0];
+        let x = qr.solve_least_squares(&b).unwrap();
+        let y = pivoted.solve_least_squares(&b).unwrap();
+        assert!((&x - &y).norm(Norm::Infinity) < 1e-12);
+        assert!((&(&a * &x) - &Vector::from(b.to_vec())).norm(Norm::Infinity) < 1e-12);
+    }
+}
@@ -0,0 +1,157 @@
+use std::ops::{Add, Deref, DerefMut, Mul, Neg, Sub};
+
+use super::{dot, Norm};
+use crate::generals::Float;
+
+/// A dense column vector.
+///
+/// A thin wrapper around a `Vec<T>` that dereferences to a slice, so it can
+/// be indexed, sliced, iterated and passed wherever the crate takes `&[T]`,
+/// with vector arithmetic on top.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::{Norm, Vector};
+///
+/// let u = Vector::from(vec![3.0, 0.0, 4.0]);
+/// let v = Vector::from(vec![1.0, 2.0, 2.0]);
+/// assert_eq!(u.norm(Norm::L2), 5.0);
+/// assert_eq!(u.dot(&v), 11.0);
+/// assert_eq!(&u + &v, Vector::from(vec![4.0, 2.0, 6.0]));
+/// assert_eq!(&u * 2.0, Vector::from(vec![6.0, 0.0, 8.0]));
+/// assert_eq!(u[1..], [0.0, 4.0]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Vector<T> {
+    data: Vec<T>,
+}
+
+impl<T: Float> Vector<T> {
+    /// The vector of `len` zeros.
+    pub fn zeros(len: usize) -> Self {
+        Vector {
+            data: vec![T::ZERO; len],
+        }
+    }
+
+    /// The vector with entries `f(i)` for `i` in `0..len`.
+    pub fn from_fn(len: usize, f: impl FnMut(usize) -> T) -> Self {
+        Vector {
+            data: (0..len).map(f).collect(),
+        }
+    }
+
+    /// The dot product with `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths differ.
+    pub fn dot(&self, other: &[T]) -> T {
+        assert_eq!(self.len(), other.len(), "vectors must have the same length");
+        dot(&self.data, other)
+    }
+
+    /// This vector's length in `norm`.
+    pub fn norm(&self, norm: Norm) -> T {
+        norm.of(&self.data)
+    }
+
+    /// The entries as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// The entries as a `Vec`.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+}
+
+impl<T> From<Vec<T>> for Vector<T> {
+    fn from(data: Vec<T>) -> Self {
+        Vector { data }
+    }
+}
+
+impl<T: Copy> From<&[T]> for Vector<T> {
+    fn from(data: &[T]) -> Self {
+        Vector {
+            data: data.to_vec(),
+        }
+    }
+}
+
+impl<T> From<Vector<T>> for Vec<T> {
+    fn from(vector: Vector<T>) -> Self {
+        vector.data
+    }
+}
+
+impl<T> FromIterator<T> for Vector<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Vector {
+            data: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Deref for Vector<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T> DerefMut for Vector<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+}
+
+impl<T: Float> Add for &Vector<T> {
+    type Output = Vector<T>;
+
+    /// # Panics
+    ///
+    /// Panics if the lengths differ.
+    fn add(self, other: &Vector<T>) -> Vector<T> {
+        assert_eq!(self.len(), other.len(), "vectors must have the same length");
+        self.iter()
+            .zip(other.iter())
+            .map(|(&a, &b)| a + b)
+            .collect()
+    }
+}
+
+impl<T: Float> Sub for &Vector<T> {
+    type Output = Vector<T>;
+
+    /// # Panics
+    ///
+    /// Panics if the lengths differ.
+    fn sub(self, other: &Vector<T>) -> Vector<T> {
+        assert_eq!(self.len(), other.len(), "vectors must have the same length");
+        self.iter()
+            .zip(other.iter())
+            .map(|(&a, &b)| a - b)
+            .collect()
+    }
+}
+
+impl<T: Float> Mul<T> for &Vector<T> {
+    type Output = Vector<T>;
+
+    fn mul(self, factor: T) -> Vector<T> {
+        self.iter().map(|&a| a * factor).collect()
+    }
+}
+
+impl<T: Float> Neg for &Vector<T> {
+    type Output = Vector<T>;
+
+    fn neg(self) -> Vector<T> {
+        self.iter().map(|&a| -a).collect()
+    }
+}
@@ -0,0 +1,14 @@
+//! General purpose building blocks shared by the rest of the crate.
+
+mod complex;
+mod counted;
+pub mod differential_methods;
+mod float;
+pub mod linalg;
+mod polynomial;
+pub mod random;
+
+pub use complex::Complex;
+pub use counted::CountedFn;
+pub use float::Float;
+pub use polynomial::Polynomial;
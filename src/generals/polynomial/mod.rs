@@ -0,0 +1,317 @@
+mod roots;
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use super::Float;
+
+/// A polynomial `c₀ + c₁x + … + cₙxⁿ` with real coefficients.
+///
+/// Coefficients are stored from the constant term up, without trailing
+/// zeros, so two equal polynomials compare equal and the last coefficient is
+/// the leading one. The zero polynomial has no coefficients.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::Polynomial;
+///
+/// // (x - 1)(x + 2) = x² + x - 2
+/// let p = Polynomial::from_roots(&[1.0, -2.0]);
+/// assert_eq!(p.coefficients(), &[-2.0, 1.0, 1.0]);
+/// assert_eq!(p.eval(3.0), 10.0);
+/// assert_eq!(p.derivative(), Polynomial::new(vec![1.0, 2.0]));
+///
+/// let q = &p * &Polynomial::new(vec![0.0, 1.0]);
+/// assert_eq!(q.degree(), Some(3));
+/// assert_eq!((&q - &q).degree(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial<T> {
+    coefficients: Vec<T>,
+}
+
+impl<T: Float> Polynomial<T> {
+    /// The polynomial with `coefficients`, constant term first. Trailing
+    /// zeros are dropped.
+    pub fn new(mut coefficients: Vec<T>) -> Self {
+        while coefficients.last() == Some(&T::ZERO) {
+            coefficients.pop();
+        }
+        Polynomial { coefficients }
+    }
+
+    /// The zero polynomial.
+    pub fn zero() -> Self {
+        Polynomial {
+            coefficients: Vec::new(),
+        }
+    }
+
+    /// The constant polynomial `value`.
+    pub fn constant(value: T) -> Self {
+        Polynomial::new(vec![value])
+    }
+
+    /// The monic polynomial `(x - r₁)(x - r₂)…` with the given `roots`,
+    /// repeated roots counted as often as they appear.
+    pub fn from_roots(roots: &[T]) -> Self {
+        let mut coefficients = Vec::with_capacity(roots.len() + 1);
+        coefficients.push(T::ONE);
+        for &root in roots {
+            // Multiply by (x - root) in place, from the top down.
+            coefficients.push(T::ZERO);
+            for k in (1..coefficients.len()).rev() {
+                coefficients[k] = coefficients[k - 1] - root * coefficients[k];
+            }
+            coefficients[0] = -root * coefficients[0];
+        }
+        Polynomial::new(coefficients)
+    }
+
+    /// The coefficients, constant term first and leading term last.
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
+    /// The degree, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    /// Whether this is the zero polynomial.
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// The coefficient of `xⁿ` with the highest `n`, or zero for the zero
+    /// polynomial.
+    pub fn leading_coefficient(&self) -> T {
+        self.coefficients.last().copied().unwrap_or(T::ZERO)
+    }
+
+    /// The value at `x`, by Horner's rule.
+    pub fn eval(&self, x: T) -> T {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(T::ZERO, |value, &c| value * x + c)
+    }
+
+    /// The value and first derivative at `x`, by Horner's rule on both.
+    pub fn eval_with_derivative(&self, x: T) -> (T, T) {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold((T::ZERO, T::ZERO), |(value, slope), &c| {
+                (value * x + c, slope * x + value)
+            })
+    }
+
+    /// The derivative.
+    pub fn derivative(&self) -> Self {
+        Polynomial::new(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(k, &c)| c * T::from_usize(k))
+                .collect(),
+        )
+    }
+
+    /// The antiderivative that takes the value `constant` at zero.
+    pub fn integral(&self, constant: T) -> Self {
+        let mut coefficients = Vec::with_capacity(self.coefficients.len() + 1);
+        coefficients.push(constant);
+        coefficients.extend(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .map(|(k, &c)| c / T::from_usize(k + 1)),
+        );
+        Polynomial::new(coefficients)
+    }
+
+    /// The companion matrix, as rows: the `n × n` matrix whose
+    /// characteristic polynomial is this one divided by its leading
+    /// coefficient, so that its eigenvalues are the roots.
+    ///
+    /// Ones sit on the subdiagonal and the last column holds `-cₖ/cₙ`. It is
+    /// upper Hessenberg, ready for the QR algorithm, which
+    /// [`companion_roots`](crate::solve::polynomial::companion_roots) applies.
+    /// Empty for constants and the zero polynomial.
+    ///
+    /// ```
+    /// use mathslib::generals::Polynomial;
+    ///
+    /// // x² - 3x + 2
+    /// let p = Polynomial::new(vec![2.0, -3.0, 1.0]);
+    /// assert_eq!(p.companion_matrix(), vec![vec![0.0, -2.0], vec![1.0, 3.0]]);
+    /// ```
+    pub fn companion_matrix(&self) -> Vec<Vec<T>> {
+        let n = self.degree().unwrap_or(0);
+        let lead = self.leading_coefficient();
+        (0..n)
+            .map(|i| {
+                let mut row = vec![T::ZERO; n];
+                if i > 0 {
+                    row[i - 1] = T::ONE;
+                }
+                row[n - 1] = -self.coefficients[i] / lead;
+                row
+            })
+            .collect()
+    }
+
+    /// The polynomial with every coefficient multiplied by `factor`.
+    pub fn scale(&self, factor: T) -> Self {
+        Polynomial::new(self.coefficients.iter().map(|&c| c * factor).collect())
+    }
+}
+
+impl<T: Float> Add for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn add(self, other: &Polynomial<T>) -> Polynomial<T> {
+        let (long, short) = if self.coefficients.len() >= other.coefficients.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut coefficients = long.coefficients.clone();
+        for (c, &d) in coefficients.iter_mut().zip(&short.coefficients) {
+            *c += d;
+        }
+        Polynomial::new(coefficients)
+    }
+}
+
+impl<T: Float> Sub for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn sub(self, other: &Polynomial<T>) -> Polynomial<T> {
+        self + &-other
+    }
+}
+
+impl<T: Float> Mul for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn mul(self, other: &Polynomial<T>) -> Polynomial<T> {
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::zero();
+        }
+        let mut coefficients =
+            vec![T::ZERO; self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, &a) in self.coefficients.iter().enumerate() {
+            for (j, &b) in other.coefficients.iter().enumerate() {
+                coefficients[i + j] += a * b;
+            }
+        }
+        Polynomial::new(coefficients)
+    }
+}
+
+impl<T: Float> Neg for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn neg(self) -> Polynomial<T> {
+        Polynomial {
+            coefficients: self.coefficients.iter().map(|&c| -c).collect(),
+        }
+    }
+}
+
+macro_rules! impl_by_value {
+    ($trait:ident, $method:ident) => {
+        impl<T: Float> $trait for Polynomial<T> {
+            type Output = Polynomial<T>;
+
+            fn $method(self, other: Polynomial<T>) -> Polynomial<T> {
+                (&self).$method(&other)
+            }
+        }
+    };
+}
+
+impl_by_value!(Add, add);
+impl_by_value!(Sub, sub);
+impl_by_value!(Mul, mul);
+
+impl<T: Float> Neg for Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn neg(self) -> Polynomial<T> {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_from_roots_and_trims_zeros() {
+        let p = Polynomial::from_roots(&[1.0, 2.0, 3.0]);
+        assert_eq!(p.coefficients(), &[-6.0, 11.0, -6.0, 1.0]);
+        assert!([1.0, 2.0, 3.0].iter().all(|&r| p.eval(r) == 0.0));
+        assert_eq!(Polynomial::new(vec![1.0, 0.0, 0.0]).degree(), Some(0));
+        assert_eq!(
+            Polynomial::<f64>::from_roots(&[]),
+            Polynomial::constant(1.0)
+        );
+        assert!(Polynomial::new(vec![0.0, 0.0]).is_zero());
+        assert_eq!(Polynomial::<f64>::zero().eval(2.0), 0.0);
+    }
+
+    #[test]
+    fn arithmetic_agrees_with_evaluation() {
+        let p = Polynomial::new(vec![1.0, -2.0, 0.5]);
+        let q = Polynomial::new(vec![3.0, 0.0, 0.0, -1.0]);
+        for x in [-1.5, 0.0, 0.25, 2.0] {
+            assert_eq!((&p + &q).eval(x), p.eval(x) + q.eval(x));
+            assert_eq!((&p - &q).eval(x), p.eval(x) - q.eval(x));
+            assert!(((&p * &q).eval(x) - p.eval(x) * q.eval(x)).abs() < 1e-12);
+        }
+        // Leading terms that cancel lower the degree.
+        let r = Polynomial::new(vec![0.0, 1.0, 0.5]);
+        assert_eq!((p.clone() - r).coefficients(), &[1.0, -3.0]);
+        assert_eq!(p.scale(2.0), Polynomial::new(vec![2.0, -4.0, 1.0]));
+        assert!((&p * &Polynomial::zero()).is_zero());
+    }
+
+    #[test]
+    fn differentiates_and_integrates() {
+        let p = Polynomial::new(vec![1.0, -2.0, 0.5, 4.0]);
+        assert_eq!(p.derivative(), Polynomial::new(vec![-2.0, 1.0, 12.0]));
+        assert_eq!(p.derivative().integral(1.0), p);
+        assert_eq!(
+            p.eval_with_derivative(2.0),
+            (p.eval(2.0), p.derivative().eval(2.0))
+        );
+        assert!(Polynomial::constant(3.0).derivative().is_zero());
+    }
+
+    #[test]
+    fn companion_matrix_has_the_polynomial_as_characteristic() {
+        // det(x·I - C) of the 3 × 3 companion matrix, by cofactors.
+        let p = Polynomial::new(vec![6.0, -1.0, 4.0, 2.0]);
+        let c = p.companion_matrix();
+        let characteristic = |x: f64| {
+            let m: Vec<Vec<f64>> = (0..3)
+                .map(|i| {
+                    (0..3)
+                        .map(|j| if i == j { x } else { 0.0 } - c[i][j])
+                        .collect()
+                })
+                .collect();
+            m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+                - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+                + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+        };
+        for x in [-2.0, 0.5, 3.0] {
+            assert!((characteristic(x) - p.eval(x) / 2.0).abs() < 1e-12);
+        }
+        assert!(Polynomial::constant(1.0).companion_matrix().is_empty());
+    }
+}
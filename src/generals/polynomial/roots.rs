@@ -0,0 +1,205 @@
+use super::Polynomial;
+use crate::generals::{Complex, Float};
+
+/// Iteration limit of the simultaneous iteration; it usually needs a few
+/// dozen.
+const MAX_ITERATIONS: usize = 500;
+
+impl<T: Float> Polynomial<T> {
+    /// All complex roots, repeated as often as their multiplicity, ordered
+    /// by real part and then imaginary part.
+    ///
+    /// Roots at zero are split off exactly; the others are found together
+    /// by the Aberth–Ehrlich iteration, which converges cubically to simple
+    /// roots, and then polished with a Newton step on the polynomial
+    /// itself. Simple roots come out to about machine precision relative to
+    /// their size; a root of multiplicity `m` only to about `ε^(1/m)`, as
+    /// any method must, so a real double root may come out as a pair of
+    /// complex conjugates with tiny imaginary parts. The zero polynomial and
+    /// the nonzero constants have no roots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathslib::generals::Polynomial;
+    ///
+    /// // x³ - x² + x - 1 = (x - 1)(x² + 1)
+    /// let p = Polynomial::new(vec![-1.0, 1.0, -1.0, 1.0]);
+    /// let roots = p.roots();
+    /// assert_eq!(roots.len(), 3);
+    /// // ±i, then 1.
+    /// assert!((roots[0].im * roots[1].im + 1.0f64).abs() < 1e-14);
+    /// assert!((roots[2].re - 1.0f64).abs() < 1e-14 && roots[2].im.abs() < 1e-14);
+    /// assert_eq!(p.real_roots(1e-12).len(), 1);
+    /// ```
+    pub fn roots(&self) -> Vec<Complex<T>> {
+        let zeros = self
+            .coefficients
+            .iter()
+            .take_while(|&&c| c == T::ZERO)
+            .count();
+        let mut roots = vec![Complex::default(); zeros];
+        roots.extend(
+            aberth(&self.coefficients[zeros..])
+                .into_iter()
+                .map(|z| polish(&self.coefficients, z)),
+        );
+        roots.sort_by(|a, b| {
+            (a.re, a.im)
+                .partial_cmp(&(b.re, b.im))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        roots
+    }
+
+    /// The real parts of the [`roots`](Self::roots) whose imaginary part is
+    /// at most `tol` in magnitude, in increasing order.
+    pub fn real_roots(&self, tol: T) -> Vec<T> {
+        self.roots()
+            .into_iter()
+            .filter(|z| z.im.abs() <= tol)
+            .map(|z| z.re)
+            .collect()
+    }
+}
+
+/// The value and derivative at `z` of the polynomial with real
+/// `coefficients`, by Horner's rule.
+fn eval<T: Float>(coefficients: &[T], z: Complex<T>) -> (Complex<T>, Complex<T>) {
+    coefficients
+        .iter()
+        .rev()
+        .fold((Complex::default(), Complex::default()), |(p, dp), &c| {
+            (p * z + c, dp * z + p)
+        })
+}
+
+/// The roots of the polynomial with `coefficients`, whose constant term is
+/// not zero, by the Aberth–Ehrlich iteration in Gauss–Seidel form.
+fn aberth<T: Float>(coefficients: &[T]) -> Vec<Complex<T>> {
+    let n = coefficients.len().saturating_sub(1);
+    if n == 0 {
+        return Vec::new();
+    }
+    let lead = coefficients[n];
+    if n == 1 {
+        return vec![Complex::real(-coefficients[0] / lead)];
+    }
+
+    // Start on a circle whose radius is the geometric mean of the root
+    // moduli, turned off the real axis so that the starting points are not
+    // symmetric about it.
+    let radius = (coefficients[0] / lead)
+        .abs()
+        .powf(T::ONE / T::from_usize(n));
+    let turn = T::from_f64(std::f64::consts::TAU) / T::from_usize(n);
+    let offset = T::from_f64(0.4);
+    let mut z: Vec<Complex<T>> = (0..n)
+        .map(|k| Complex::from_polar(radius, turn * T::from_usize(k) + offset))
+        .collect();
+    let mut done = vec![false; n];
+    let tolerance = T::from_f64(4.0) * T::EPSILON;
+    for _ in 0..MAX_ITERATIONS {
+        for i in 0..n {
+            if done[i] {
+                continue;
+            }
+            let (p, dp) = eval(coefficients, z[i]);
+            if p == Complex::default() {
+                done[i] = true;
+                continue;
+            }
+            let repulsion = (0..n)
+                .filter(|&j| j != i)
+                .fold(Complex::default(), |sum, j| {
+                    sum + Complex::real(T::ONE) / (z[i] - z[j])
+                });
+            let step = Complex::real(T::ONE) / (dp / p - repulsion);
+            if !step.is_finite() {
+                continue;
+            }
+            z[i] = z[i] - step;
+            done[i] = step.norm() <= tolerance * z[i].norm();
+        }
+        if done.iter().all(|&d| d) {
+            break;
+        }
+    }
+    z
+}
+
+/// `z` after a Newton step on the polynomial with `coefficients`, if that
+/// lowers its modulus there.
+fn polish<T: Float>(coefficients: &[T], z: Complex<T>) -> Complex<T> {
+    let (p, dp) = eval(coefficients, z);
+    let newton = z - p / dp;
+    if newton.is_finite() && eval(coefficients, newton).0.norm() < p.norm() {
+        newton
+    } else {
+        z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_real_complex_and_zero_roots() {
+        // x²(x - 3)(x + 0.5)(x² + 2x + 5), with roots 0, 0, -0.5, 3, -1 ± 2i.
+        let p =
+            &Polynomial::from_roots(&[0.0, 0.0, 3.0, -0.5]) * &Polynomial::new(vec![5.0, 2.0, 1.0]);
+        let roots = p.roots();
+        let expected = [
+            Complex::new(-1.0, -2.0),
+            Complex::new(-1.0, 2.0),
+            Complex::real(-0.5),
+            Complex::real(0.0),
+            Complex::real(0.0),
+            Complex::real(3.0),
+        ];
+        assert_eq!(roots.len(), expected.len());
+        for (root, expected) in roots.iter().zip(&expected) {
+            assert!((*root - *expected).norm() < 1e-13, "{roots:?}");
+        }
+        assert_eq!(roots[3], Complex::real(0.0));
+        let real = p.real_roots(1e-12);
+        assert_eq!(real.len(), 4);
+        assert!((real[3] - 3.0).abs() < 1e-13);
+
+        assert!(Polynomial::constant(2.0).roots().is_empty());
+        assert!(Polynomial::<f64>::zero().roots().is_empty());
+        assert_eq!(
+            Polynomial::new(vec![1.0, 4.0]).roots(),
+            vec![Complex::real(-0.25)]
+        );
+    }
+
+    #[test]
+    fn copes_with_high_degree_and_clustered_roots() {
+        // The 20th roots of unity.
+        let mut coefficients = vec![0.0; 21];
+        coefficients[0] = -1.0;
+        coefficients[20] = 1.0;
+        let unity = Polynomial::new(coefficients);
+        let roots = unity.roots();
+        assert_eq!(roots.len(), 20);
+        assert!(roots.iter().all(|z| (z.norm() - 1.0).abs() < 1e-14));
+        assert_eq!(unity.real_roots(1e-12).len(), 2);
+
+        // Wilkinson's polynomial of degree 10 has well separated but
+        // sensitive roots.
+        let roots: Vec<f64> = (1..=10).map(f64::from).collect();
+        let found = Polynomial::from_roots(&roots).real_roots(1e-6);
+        assert_eq!(found.len(), 10);
+        for (found, expected) in found.iter().zip(&roots) {
+            assert!((found - expected).abs() < 1e-8, "{found} vs {expected}");
+        }
+
+        // A double root is only found to about the square root of ε.
+        let double = Polynomial::from_roots(&[1.0, 1.0, 2.0]);
+        let roots = double.roots();
+        assert!((roots[0].re - 1.0).abs() < 1e-7 && (roots[1].re - 1.0).abs() < 1e-7);
+        assert!((roots[2] - Complex::real(2.0)).norm() < 1e-14);
+    }
+}
@@ -0,0 +1,115 @@
+//! Seedable pseudo random numbers for the stochastic algorithms in the crate.
+
+use super::Float;
+
+/// A small, fast pseudo random number generator (xoshiro256**).
+///
+/// Sequences are fully determined by the seed, so stochastic algorithms are
+/// reproducible. Not suitable for cryptography.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    /// Creates a generator from `seed`, expanded with SplitMix64.
+    pub fn new(seed: u64) -> Self {
+        let mut x = seed;
+        let mut state = [0; 4];
+        for word in &mut state {
+            x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            *word = z ^ (z >> 31);
+        }
+        Rng { state }
+    }
+
+    /// Next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// Uniform sample from `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Uniform sample from `[low, high)`.
+    pub fn uniform<T: Float>(&mut self, low: T, high: T) -> T {
+        low + (high - low) * T::from_f64(self.next_f64())
+    }
+
+    /// Sample from the standard normal distribution (Box-Muller).
+    pub fn normal<T: Float>(&mut self) -> T {
+        let u = 1.0 - self.next_f64();
+        let v = self.next_f64();
+        T::from_f64((-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos())
+    }
+
+    /// Uniform sample from `0..n`, with `n > 0`.
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    /// Puts `items` in a uniformly random order (Fisher-Yates).
+    pub fn shuffle<U>(&mut self, items: &mut [U]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let mut c = Rng::new(43);
+        let xs: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        assert_eq!(xs, (0..5).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(xs, (0..5).map(|_| c.next_u64()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn uniform_stays_in_range_and_covers_it() {
+        let mut rng = Rng::new(7);
+        let samples: Vec<f64> = (0..10_000).map(|_| rng.uniform(-2.0, 3.0)).collect();
+        assert!(samples.iter().all(|&x| (-2.0..3.0).contains(&x)));
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        assert!((mean - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn normal_has_unit_variance() {
+        let mut rng = Rng::new(9);
+        let samples: Vec<f64> = (0..20_000).map(|_| rng.normal()).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance =
+            samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        assert!(mean.abs() < 0.03 && (variance - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn shuffle_permutes() {
+        let mut rng = Rng::new(5);
+        let mut items: Vec<usize> = (0..50).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+    }
+}
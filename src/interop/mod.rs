@@ -0,0 +1,11 @@
+//! Adapters for other numerics crates, each behind the feature of the same
+//! name.
+//!
+//! The optimizers work on slices and vectors of rows; the adapters wrap
+//! closures written against another crate's types and convert matrices, so
+//! data needs no manual copying in and out.
+
+#[cfg(feature = "nalgebra")]
+pub mod nalgebra;
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
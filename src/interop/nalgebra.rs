@@ -0,0 +1,136 @@
+//! [`nalgebra`] adapters, behind the `nalgebra` feature.
+//!
+//! [`objective`], [`vector_function`] and [`matrix_function`] turn closures
+//! over [`DVectorView`] into the slice-based closures the optimizers take,
+//! without copying the point. Results come back as `Vec`s, which
+//! [`DVector::from_vec`] takes over without copying; matrices of rows, such as
+//! Jacobians and Hessians, convert with [`from_rows`] and [`to_rows`].
+//!
+//! ```
+//! use mathslib::interop::nalgebra::{from_rows, matrix_function, objective, vector_function};
+//! use mathslib::optimize::multivariate::newton_minimize_with_hessian;
+//! use mathslib::optimize::line_search::Backtracking;
+//! use nalgebra::{dmatrix, dvector, DMatrix, DVector, DVectorView};
+//!
+//! // ½xᵀAx - bᵀx, minimized where Ax = b.
+//! let a = dmatrix![4.0, 1.0; 1.0, 3.0];
+//! let b = dvector![1.0, 2.0];
+//! let f = |x: DVectorView<f64>| 0.5 * x.dot(&(&a * x)) - b.dot(&x);
+//! let grad = |x: DVectorView<f64>| &a * x - &b;
+//! let hessian = |_: DVectorView<f64>| a.clone();
+//! let result = newton_minimize_with_hessian(
+//!     objective(f),
+//!     vector_function(grad),
+//!     matrix_function(hessian),
+//!     &[0.0, 0.0],
+//!     Backtracking::default(),
+//!     1e-12,
+//!     20,
+//! )
+//! .unwrap();
+//! let x = DVector::from_vec(result.x);
+//! assert!((&a * x - &b).norm() < 1e-12);
+//! assert_eq!(from_rows(&[vec![4.0, 1.0], vec![1.0, 3.0]]), a);
+//! ```
+
+use nalgebra::{DMatrix, DVector, DVectorView, Scalar};
+
+use crate::generals::Float;
+
+/// A scalar function of slices, from one of vector views.
+pub fn objective<T, F>(func: F) -> impl Fn(&[T]) -> T
+where
+    T: Float + Scalar,
+    F: Fn(DVectorView<T>) -> T,
+{
+    move |x| func(DVectorView::from_slice(x, x.len()))
+}
+
+/// A vector function of slices, such as a gradient, residuals or
+/// constraints, from one of vector views.
+pub fn vector_function<T, F>(func: F) -> impl Fn(&[T]) -> Vec<T>
+where
+    T: Float + Scalar,
+    F: Fn(DVectorView<T>) -> DVector<T>,
+{
+    move |x| func(DVectorView::from_slice(x, x.len())).data.into()
+}
+
+/// A matrix function of slices returning rows, such as a Jacobian or a
+/// Hessian, from one of vector views.
+pub fn matrix_function<T, F>(func: F) -> impl Fn(&[T]) -> Vec<Vec<T>>
+where
+    T: Float + Scalar,
+    F: Fn(DVectorView<T>) -> DMatrix<T>,
+{
+    move |x| to_rows(&func(DVectorView::from_slice(x, x.len())))
+}
+
+/// The rows of `matrix`.
+pub fn to_rows<T: Float + Scalar>(matrix: &DMatrix<T>) -> Vec<Vec<T>> {
+    matrix
+        .row_iter()
+        .map(|row| row.iter().copied().collect())
+        .collect()
+}
+
+/// The matrix with rows `rows`.
+///
+/// # Panics
+///
+/// Panics if the rows do not all have the same length.
+pub fn from_rows<T: Float + Scalar>(rows: &[Vec<T>]) -> DMatrix<T> {
+    let columns = rows.first().map_or(0, Vec::len);
+    assert!(
+        rows.iter().all(|row| row.len() == columns),
+        "rows must all have the same length"
+    );
+    DMatrix::from_fn(rows.len(), columns, |i, j| rows[i][j])
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::dmatrix;
+
+    use super::*;
+    use crate::optimize::least_squares::levenberg_marquardt_with_jacobian;
+    use crate::optimize::ConvergenceCriteria;
+
+    #[test]
+    fn rows_round_trip() {
+        let matrix = dmatrix![1.0, 2.0, 3.0; 4.0, 5.0, 6.0];
+        let rows = to_rows(&matrix);
+        assert_eq!(rows, vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+        assert_eq!(from_rows(&rows), matrix);
+        assert_eq!(from_rows::<f64>(&[]), DMatrix::zeros(0, 0));
+    }
+
+    #[test]
+    fn fits_with_vector_residuals_and_jacobian() {
+        let t = DVector::from_fn(9, |i, _| 0.25 * i as f64);
+        let y = t.map(|t| 2.0 * (0.5 * t).exp());
+        let residuals = |p: DVectorView<f64>| t.map(|t| p[0] * (p[1] * t).exp()) - &y;
+        let jacobian = |p: DVectorView<f64>| {
+            DMatrix::from_fn(t.len(), 2, |i, j| {
+                let e = (p[1] * t[i]).exp();
+                if j == 0 {
+                    e
+                } else {
+                    p[0] * t[i] * e
+                }
+            })
+        };
+        let result = levenberg_marquardt_with_jacobian(
+            vector_function(residuals),
+            matrix_function(jacobian),
+            &[1.0, 0.0],
+            ConvergenceCriteria::Gradient(1e-12),
+            100,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!((result.x[0] - 2.0).abs() < 1e-9 && (result.x[1] - 0.5).abs() < 1e-9);
+        let x = DVector::from_vec(result.x);
+        assert_eq!(from_rows(&result.jacobian), jacobian(x.as_view()));
+    }
+}
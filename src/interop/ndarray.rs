@@ -0,0 +1,137 @@
+//! [`ndarray`] adapters, behind the `ndarray` feature.
+//!
+//! [`objective`], [`vector_function`] and [`matrix_function`] turn closures
+//! over [`ArrayView1`] into the slice-based closures the optimizers take,
+//! without copying the point. Results come back as `Vec`s, which
+//! [`Array1::from`] takes over without copying; matrices of rows, such as
+//! Jacobians, convert with [`from_rows`] and [`to_rows`].
+//!
+//! ```
+//! use mathslib::interop::ndarray::{objective, vector_function};
+//! use mathslib::optimize::line_search::StrongWolfe;
+//! use mathslib::optimize::multivariate::lbfgs_minimize;
+//! use ndarray::{array, Array1, ArrayView1};
+//!
+//! let target = array![1.0, -2.0, 0.5];
+//! let f = |x: ArrayView1<f64>| (&x - &target).mapv(|d| d * d).sum();
+//! let grad = |x: ArrayView1<f64>| 2.0 * (&x - &target);
+//! let x0 = Array1::<f64>::zeros(3);
+//! let result = lbfgs_minimize(
+//!     objective(f),
+//!     vector_function(grad),
+//!     x0.as_slice().unwrap(),
+//!     5,
+//!     StrongWolfe::default(),
+//!     1e-10,
+//!     100,
+//! )
+//! .unwrap();
+//! let x = Array1::from(result.x);
+//! assert!((x - target).iter().all(|d| d.abs() < 1e-8));
+//! ```
+
+use ::ndarray::{Array1, Array2, ArrayView1, ArrayView2};
+
+use crate::generals::Float;
+
+/// A scalar function of slices, from one of array views.
+pub fn objective<T, F>(func: F) -> impl Fn(&[T]) -> T
+where
+    T: Float,
+    F: Fn(ArrayView1<T>) -> T,
+{
+    move |x| func(ArrayView1::from(x))
+}
+
+/// A vector function of slices, such as a gradient, residuals or
+/// constraints, from one of array views.
+pub fn vector_function<T, F>(func: F) -> impl Fn(&[T]) -> Vec<T>
+where
+    T: Float,
+    F: Fn(ArrayView1<T>) -> Array1<T>,
+{
+    move |x| func(ArrayView1::from(x)).to_vec()
+}
+
+/// A matrix function of slices returning rows, such as a Jacobian or a
+/// Hessian, from one of array views.
+pub fn matrix_function<T, F>(func: F) -> impl Fn(&[T]) -> Vec<Vec<T>>
+where
+    T: Float,
+    F: Fn(ArrayView1<T>) -> Array2<T>,
+{
+    move |x| to_rows(func(ArrayView1::from(x)).view())
+}
+
+/// The rows of `matrix`.
+pub fn to_rows<T: Float>(matrix: ArrayView2<T>) -> Vec<Vec<T>> {
+    matrix.rows().into_iter().map(|row| row.to_vec()).collect()
+}
+
+/// The matrix with rows `rows`.
+///
+/// # Panics
+///
+/// Panics if the rows do not all have the same length.
+pub fn from_rows<T: Float>(rows: &[Vec<T>]) -> Array2<T> {
+    let columns = rows.first().map_or(0, Vec::len);
+    assert!(
+        rows.iter().all(|row| row.len() == columns),
+        "rows must all have the same length"
+    );
+    Array2::from_shape_fn((rows.len(), columns), |(i, j)| rows[i][j])
+}
+
+#[cfg(test)]
+mod tests {
+    use ::ndarray::{array, Array2};
+
+    use super::*;
+    use crate::optimize::least_squares::levenberg_marquardt_with_jacobian;
+    use crate::optimize::ConvergenceCriteria;
+
+    #[test]
+    fn rows_round_trip() {
+        let matrix = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+        let rows = to_rows(matrix.view());
+        assert_eq!(rows, vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+        assert_eq!(from_rows(&rows), matrix);
+        // Views with strides come out in logical order.
+        assert_eq!(
+            to_rows(matrix.t()),
+            vec![vec![1.0, 4.0], vec![2.0, 5.0], vec![3.0, 6.0]]
+        );
+        assert_eq!(from_rows::<f64>(&[]), Array2::zeros((0, 0)));
+    }
+
+    #[test]
+    fn fits_with_array_residuals_and_jacobian() {
+        let t = Array1::linspace(0.0, 2.0, 9);
+        let y = t.mapv(|t: f64| 2.0 * (0.5 * t).exp());
+        let residuals = |p: ArrayView1<f64>| t.mapv(|t| p[0] * (p[1] * t).exp()) - &y;
+        let jacobian = |p: ArrayView1<f64>| {
+            Array2::from_shape_fn((t.len(), 2), |(i, j)| {
+                let e = (p[1] * t[i]).exp();
+                if j == 0 {
+                    e
+                } else {
+                    p[0] * t[i] * e
+                }
+            })
+        };
+        let result = levenberg_marquardt_with_jacobian(
+            vector_function(residuals),
+            matrix_function(jacobian),
+            &[1.0, 0.0],
+            ConvergenceCriteria::Gradient(1e-12),
+            100,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!((result.x[0] - 2.0).abs() < 1e-9 && (result.x[1] - 0.5).abs() < 1e-9);
+        assert_eq!(
+            from_rows(&result.jacobian),
+            jacobian(ArrayView1::from(&result.x))
+        );
+    }
+}
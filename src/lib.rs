@@ -1,3 +1,9 @@
+pub mod generals;
+#[cfg(any(feature = "nalgebra", feature = "ndarray"))]
+pub mod interop;
+pub mod optimize;
+pub mod solve;
+
 pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
@@ -0,0 +1,128 @@
+use std::cell::Cell;
+
+use super::OptimizeError;
+use crate::generals::Float;
+
+/// Limits on how much work an optimizer may do.
+///
+/// Optimizers accept anything convertible into a budget, and a bare count
+/// converts to an iteration limit with no cap on objective evaluations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    /// Maximum number of iterations.
+    pub max_iter: usize,
+    /// Maximum number of objective evaluations, if capped.
+    ///
+    /// The cap is checked between iterations, so the iteration that reaches it
+    /// may overshoot by the few evaluations it needs.
+    pub max_fevals: Option<usize>,
+}
+
+impl Budget {
+    /// A budget of `max_iter` iterations and unlimited evaluations.
+    pub fn new(max_iter: usize) -> Self {
+        Budget {
+            max_iter,
+            max_fevals: None,
+        }
+    }
+
+    /// Caps the number of objective evaluations at `max_fevals`.
+    pub fn with_max_fevals(mut self, max_fevals: usize) -> Self {
+        self.max_fevals = Some(max_fevals);
+        self
+    }
+
+    /// Whether another iteration may start after `iterations` iterations that
+    /// used `fevals` evaluations.
+    pub(crate) fn allows(&self, iterations: usize, fevals: usize) -> bool {
+        iterations < self.max_iter && self.max_fevals.is_none_or(|max| fevals < max)
+    }
+}
+
+impl From<usize> for Budget {
+    fn from(max_iter: usize) -> Self {
+        Budget::new(max_iter)
+    }
+}
+
+/// Objective evaluations made during a run: how many there were and where the
+/// objective first returned a non-finite value, if it did.
+#[derive(Debug, Default)]
+pub(crate) struct Evaluations {
+    count: Cell<usize>,
+    non_finite: Cell<Option<f64>>,
+}
+
+impl Evaluations {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of evaluations so far.
+    pub(crate) fn get(&self) -> usize {
+        self.count.get()
+    }
+
+    /// Whether every value so far was finite, so the run may continue.
+    pub(crate) fn all_finite(&self) -> bool {
+        self.non_finite.get().is_none()
+    }
+
+    /// Replaces `outcome` with [`OptimizeError::NonFiniteObjective`] if a
+    /// non-finite value was seen, since the run's comparisons are then
+    /// meaningless.
+    pub(crate) fn verify<R>(&self, outcome: Result<R, OptimizeError>) -> Result<R, OptimizeError> {
+        match self.non_finite.get() {
+            Some(x) => Err(OptimizeError::NonFiniteObjective { x }),
+            None => outcome,
+        }
+    }
+}
+
+/// Wraps `func` so that every call is recorded in `evaluations`.
+pub(crate) fn counted<'a, T, F>(func: F, evaluations: &'a Evaluations) -> impl Fn(T) -> T + 'a
+where
+    T: Float,
+    F: Fn(T) -> T + 'a,
+{
+    move |x| {
+        evaluations.count.set(evaluations.count.get() + 1);
+        let fx = func(x);
+        if !fx.is_finite() && evaluations.all_finite() {
+            evaluations.non_finite.set(Some(x.to_f64()));
+        }
+        fx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_limits_iterations_and_evaluations() {
+        let budget = Budget::from(10).with_max_fevals(25);
+        assert!(budget.allows(9, 24));
+        assert!(!budget.allows(10, 0));
+        assert!(!budget.allows(3, 25));
+        assert!(Budget::new(10).allows(9, usize::MAX));
+    }
+
+    #[test]
+    fn evaluations_record_first_non_finite_point() {
+        let evaluations = Evaluations::new();
+        let f = counted(|x: f64| 1.0 / x, &evaluations);
+        f(2.0);
+        assert!(evaluations.all_finite());
+        assert_eq!(evaluations.verify(Ok(1)), Ok(1));
+        f(0.0);
+        f(-0.0);
+        assert_eq!(evaluations.get(), 3);
+        assert!(!evaluations.all_finite());
+        assert_eq!(
+            evaluations.verify(Ok(1)),
+            Err(OptimizeError::NonFiniteObjective { x: 0.0 })
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use std::cell::Cell;
+
+use super::ConstrainedResult;
+use crate::generals::Float;
+use crate::optimize::multivariate::{forward_difference_jacobian, MultivariateResult};
+use crate::optimize::OptimizeError;
+
+/// How [`barrier_minimize`] lowers the barrier parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Barrier<T> {
+    /// Barrier parameter `µ` of the first round.
+    pub initial_mu: T,
+    /// Factor by which `µ` shrinks after each round, in `(0, 1)`.
+    pub decrease: T,
+    /// Bound on `m·µ`, for `m` constraints, at which to stop.
+    pub tolerance: T,
+    /// Maximum number of rounds.
+    pub max_rounds: usize,
+}
+
+impl<T: Float> Default for Barrier<T> {
+    /// `µ` from 1 down by factors of 10 for at most 50 rounds, until
+    /// `m·µ ≤ 1e-8`.
+    fn default() -> Self {
+        Barrier {
+            initial_mu: T::ONE,
+            decrease: T::from_f64(0.1),
+            tolerance: T::from_f64(1e-8),
+            max_rounds: 50,
+        }
+    }
+}
+
+impl<T: Float> Barrier<T> {
+    /// Starts with the barrier parameter `initial_mu`.
+    pub fn with_initial_mu(mut self, initial_mu: T) -> Self {
+        self.initial_mu = initial_mu;
+        self
+    }
+
+    /// Multiplies `µ` by `decrease` after each round.
+    pub fn with_decrease(mut self, decrease: T) -> Self {
+        self.decrease = decrease;
+        self
+    }
+
+    /// Stops once `m·µ ≤ tolerance`.
+    pub fn with_tolerance(mut self, tolerance: T) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Stops after `max_rounds` rounds.
+    pub fn with_max_rounds(mut self, max_rounds: usize) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+}
+
+/// Minimizes `func`, whose gradient is `grad`, subject to
+/// `inequalities(x) ≤ 0` with a log barrier interior point method, starting
+/// from the strictly feasible point `x0`.
+///
+/// Each round minimizes the barrier objective `f(x) - µ·Σ ln(-gᵢ(x))` with
+/// `inner`, starting from the result of the previous round, and then
+/// multiplies `µ` by `settings.decrease` until `m·µ ≤ settings.tolerance`.
+/// `inner` receives the barrier objective, its gradient and the starting
+/// point, as in [`penalty_minimize`](super::penalty_minimize). The barrier
+/// objective is infinite outside the feasible region, which line searches
+/// treat as too long a step, so the iterates stay strictly feasible
+/// throughout. For a convex problem the objective at the minimizer of a round
+/// exceeds the constrained minimum by at most `m·µ`.
+///
+/// The constraint gradients are taken by forward differences, costing `n + 1`
+/// constraint evaluations per barrier gradient and limiting its accuracy to
+/// about `√ε` times the multipliers `µ / |gᵢ|`, so inner gradient criteria
+/// should not be much tighter. The constraints themselves need to be finite
+/// just outside the feasible region.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::InfeasibleStart`] if some `gᵢ(x0) ≥ 0` or is NaN,
+/// and the errors of `inner`.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::constrained::{barrier_minimize, Barrier};
+/// use mathslib::optimize::line_search::Backtracking;
+/// use mathslib::optimize::multivariate::lbfgs_minimize;
+/// use mathslib::optimize::ConvergenceCriteria::Gradient;
+///
+/// // Minimize x + y on the unit disk.
+/// let result = barrier_minimize(
+///     |x: &[f64]| x[0] + x[1],
+///     |_: &[f64]| vec![1.0, 1.0],
+///     |x: &[f64]| vec![x[0] * x[0] + x[1] * x[1] - 1.0],
+///     &[0.0, 0.0],
+///     Barrier::default(),
+///     |f, g, x| lbfgs_minimize(f, g, x, 10, Backtracking::default(), Gradient(1e-6), 1000),
+/// )
+/// .unwrap();
+/// assert!(result.converged);
+/// let corner = -0.5f64.sqrt();
+/// assert!((result.x[0] - corner).abs() < 1e-6 && (result.x[1] - corner).abs() < 1e-6);
+/// ```
+pub fn barrier_minimize<T, F, G, I, S>(
+    func: F,
+    grad: G,
+    inequalities: I,
+    x0: &[T],
+    settings: Barrier<T>,
+    mut inner: S,
+) -> Result<ConstrainedResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    I: Fn(&[T]) -> Vec<T>,
+    S: FnMut(
+        &dyn Fn(&[T]) -> T,
+        &dyn Fn(&[T]) -> Vec<T>,
+        &[T],
+    ) -> Result<MultivariateResult<T>, OptimizeError>,
+{
+    let cevals = Cell::new(0);
+    let constraints = |x: &[T]| {
+        cevals.set(cevals.get() + 1);
+        inequalities(x)
+    };
+    let g0 = constraints(x0);
+    if g0.iter().any(|&g| g >= T::ZERO || g.is_nan()) {
+        return Err(OptimizeError::InfeasibleStart);
+    }
+
+    let m = T::from_usize(g0.len());
+    let mut x = x0.to_vec();
+    let mut mu = settings.initial_mu;
+    let (mut fevals, mut gevals, mut iterations) = (0, 0, 0);
+    let mut last_mu = mu;
+    let mut inner_converged = false;
+    while iterations < settings.max_rounds {
+        last_mu = mu;
+        let barrier = |x: &[T]| {
+            let g = constraints(x);
+            if g.iter().any(|&gi| gi >= T::ZERO || gi.is_nan()) {
+                return T::INFINITY;
+            }
+            func(x) - mu * g.iter().fold(T::ZERO, |sum, &gi| sum + (-gi).ln())
+        };
+        let barrier_gradient = |x: &[T]| {
+            let g = constraints(x);
+            let jacobian = forward_difference_jacobian(constraints, x, &g);
+            let mut gradient = grad(x);
+            for (row, &gi) in jacobian.iter().zip(&g) {
+                for (dj, &rj) in gradient.iter_mut().zip(row) {
+                    *dj -= mu * rj / gi;
+                }
+            }
+            gradient
+        };
+        let result = inner(&barrier, &barrier_gradient, &x)?;
+        x = result.x;
+        fevals += result.fevals;
+        gevals += result.gevals;
+        inner_converged = result.converged;
+        iterations += 1;
+        if m * mu <= settings.tolerance {
+            break;
+        }
+        mu *= settings.decrease;
+    }
+
+    let g = constraints(&x);
+    // Multiplier estimates µ / |gᵢ| exceed |gᵢ| for the active constraints.
+    let active_set = (0..g.len()).filter(|&i| g[i] * g[i] < last_mu).collect();
+    Ok(ConstrainedResult {
+        fx: func(&x),
+        x,
+        equality_violations: Vec::new(),
+        inequality_violations: g.into_iter().map(|g| g.max(T::ZERO)).collect(),
+        active_set,
+        iterations,
+        fevals: fevals + 1,
+        gevals,
+        cevals: cevals.get(),
+        converged: inner_converged && m * mu <= settings.tolerance,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::line_search::StrongWolfe;
+    use crate::optimize::multivariate::lbfgs_minimize;
+    use crate::optimize::ConvergenceCriteria::Gradient;
+
+    #[test]
+    fn stays_feasible_on_a_box() {
+        // Unconstrained minimum at (2, 0.5); x ≤ 1 is active.
+        let result = barrier_minimize(
+            |x: &[f64]| (x[0] - 2.0).powi(2) + (x[1] - 0.5).powi(2),
+            |x: &[f64]| vec![2.0 * (x[0] - 2.0), 2.0 * (x[1] - 0.5)],
+            |x: &[f64]| vec![x[0] - 1.0, -x[0], x[1] - 1.0, -x[1]],
+            &[0.5, 0.9],
+            Barrier::default(),
+            |f, g, x| lbfgs_minimize(f, g, x, 10, StrongWolfe::default(), Gradient(1e-6), 1000),
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!(result.x[0] < 1.0 && (result.x[0] - 1.0).abs() < 1e-7);
+        assert!((result.x[1] - 0.5).abs() < 1e-7);
+        assert_eq!(result.max_violation(), 0.0);
+        assert_eq!(result.active_set, vec![0]);
+    }
+
+    #[test]
+    fn rejects_an_infeasible_start() {
+        let result = barrier_minimize(
+            |x: &[f64]| x[0],
+            |_: &[f64]| vec![1.0],
+            |x: &[f64]| vec![-x[0]],
+            &[0.0],
+            Barrier::default(),
+            |f, g, x| lbfgs_minimize(f, g, x, 10, StrongWolfe::default(), Gradient(1e-6), 1000),
+        );
+        assert_eq!(result, Err(OptimizeError::InfeasibleStart));
+    }
+}
@@ -0,0 +1,291 @@
+use std::cell::Cell;
+
+use crate::generals::Float;
+use crate::optimize::multivariate::forward_difference_jacobian;
+
+/// Whether a [`Constraint`] requires its values to be zero or at most zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintKind {
+    /// `c(x) = 0`.
+    Equality,
+    /// `c(x) ≤ 0`.
+    Inequality,
+}
+
+type VectorFunction<'a, T> = Box<dyn Fn(&[T]) -> Vec<T> + 'a>;
+type MatrixFunction<'a, T> = Box<dyn Fn(&[T]) -> Vec<Vec<T>> + 'a>;
+
+/// A block of nonlinear constraints `c(x) = 0` or `c(x) ≤ 0`, with `c`
+/// returning one entry per constraint, as taken by
+/// [`penalty_minimize_with_constraints`] and [`sqp_minimize_with_constraints`].
+///
+/// The Jacobian of `c` is taken by forward differences unless one is given
+/// with [`Constraint::with_jacobian`], and a constraint is satisfied to the
+/// tolerance of the solver unless it has its own, set with
+/// [`Constraint::with_tolerance`]; this suits constraints in different units.
+///
+/// [`penalty_minimize_with_constraints`]: super::penalty_minimize_with_constraints
+/// [`sqp_minimize_with_constraints`]: super::sqp_minimize_with_constraints
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::constrained::Constraint;
+///
+/// // The unit circle, with its gradient.
+/// let circle = Constraint::equality(|x: &[f64]| vec![x[0] * x[0] + x[1] * x[1] - 1.0])
+///     .with_jacobian(|x: &[f64]| vec![vec![2.0 * x[0], 2.0 * x[1]]]);
+/// // x ≥ 0 and y ≥ 0, to within 1e-10.
+/// let quadrant = Constraint::inequality(|x: &[f64]| vec![-x[0], -x[1]]).with_tolerance(1e-10);
+/// assert_eq!(circle.values(&[1.0, 1.0]), vec![1.0]);
+/// assert_eq!(quadrant.tolerance(), Some(1e-10));
+/// ```
+pub struct Constraint<'a, T> {
+    kind: ConstraintKind,
+    function: VectorFunction<'a, T>,
+    jacobian: Option<MatrixFunction<'a, T>>,
+    tolerance: Option<T>,
+}
+
+impl<'a, T: Float> Constraint<'a, T> {
+    /// The constraints `function(x) = 0`.
+    pub fn equality<C>(function: C) -> Self
+    where
+        C: Fn(&[T]) -> Vec<T> + 'a,
+    {
+        Constraint::new(ConstraintKind::Equality, function)
+    }
+
+    /// The constraints `function(x) ≤ 0`.
+    pub fn inequality<C>(function: C) -> Self
+    where
+        C: Fn(&[T]) -> Vec<T> + 'a,
+    {
+        Constraint::new(ConstraintKind::Inequality, function)
+    }
+
+    /// The constraints `function(x) = 0` or `function(x) ≤ 0`, by `kind`.
+    pub fn new<C>(kind: ConstraintKind, function: C) -> Self
+    where
+        C: Fn(&[T]) -> Vec<T> + 'a,
+    {
+        Constraint {
+            kind,
+            function: Box::new(function),
+            jacobian: None,
+            tolerance: None,
+        }
+    }
+
+    /// Uses `jacobian`, which returns the gradient of each constraint as a
+    /// row, instead of forward differences.
+    pub fn with_jacobian<J>(mut self, jacobian: J) -> Self
+    where
+        J: Fn(&[T]) -> Vec<Vec<T>> + 'a,
+    {
+        self.jacobian = Some(Box::new(jacobian));
+        self
+    }
+
+    /// Accepts violations up to `tolerance`, whatever the solver's tolerance.
+    pub fn with_tolerance(mut self, tolerance: T) -> Self {
+        self.tolerance = Some(tolerance);
+        self
+    }
+
+    /// Whether these are equality or inequality constraints.
+    pub fn kind(&self) -> ConstraintKind {
+        self.kind
+    }
+
+    /// The tolerance set with [`Constraint::with_tolerance`], if any.
+    pub fn tolerance(&self) -> Option<T> {
+        self.tolerance
+    }
+
+    /// The constraint values at `x`.
+    pub fn values(&self, x: &[T]) -> Vec<T> {
+        (self.function)(x)
+    }
+
+    /// The constraint gradients at `x`, as rows, from the Jacobian if one was
+    /// given and from forward differences otherwise.
+    pub fn jacobian(&self, x: &[T]) -> Vec<Vec<T>> {
+        match &self.jacobian {
+            Some(jacobian) => jacobian(x),
+            None => forward_difference_jacobian(&self.function, x, &self.values(x)),
+        }
+    }
+}
+
+/// A list of [`Constraint`]s flattened into one vector function, with the
+/// rows of the equality constraints first, that counts its evaluations.
+pub(crate) struct ConstraintSet<'c, 'a, T> {
+    // Equality blocks first, each with its number of rows.
+    blocks: Vec<(&'c Constraint<'a, T>, usize)>,
+    equalities: usize,
+    cevals: Cell<usize>,
+}
+
+impl<'c, 'a, T: Float> ConstraintSet<'c, 'a, T> {
+    /// The set of `constraints`, sized by their values at `x0`, which are
+    /// returned as well.
+    pub(crate) fn new(constraints: &'c [Constraint<'a, T>], x0: &[T]) -> (Self, Vec<T>) {
+        let ordered = constraints
+            .iter()
+            .filter(|c| c.kind == ConstraintKind::Equality)
+            .chain(
+                constraints
+                    .iter()
+                    .filter(|c| c.kind == ConstraintKind::Inequality),
+            );
+        let mut values = Vec::new();
+        let mut blocks = Vec::with_capacity(constraints.len());
+        let mut equalities = 0;
+        for constraint in ordered {
+            let v = constraint.values(x0);
+            if constraint.kind == ConstraintKind::Equality {
+                equalities += v.len();
+            }
+            blocks.push((constraint, v.len()));
+            values.extend(v);
+        }
+        let set = ConstraintSet {
+            blocks,
+            equalities,
+            cevals: Cell::new(1),
+        };
+        (set, values)
+    }
+
+    /// Number of equality rows, which come first.
+    pub(crate) fn equalities(&self) -> usize {
+        self.equalities
+    }
+
+    /// Number of evaluations of the whole set so far.
+    pub(crate) fn cevals(&self) -> usize {
+        self.cevals.get()
+    }
+
+    /// All constraint values at `x`.
+    pub(crate) fn values(&self, x: &[T]) -> Vec<T> {
+        self.cevals.set(self.cevals.get() + 1);
+        self.blocks
+            .iter()
+            .flat_map(|(constraint, _)| constraint.values(x))
+            .collect()
+    }
+
+    /// Jacobian at `x` of all constraints, whose values there are `c`. The
+    /// blocks without an analytic Jacobian are differenced together, costing
+    /// `n` evaluations of them, which count as evaluations of the set.
+    pub(crate) fn jacobian(&self, x: &[T], c: &[T]) -> Vec<Vec<T>> {
+        let mut rows = vec![Vec::new(); c.len()];
+        let mut differenced = Vec::new();
+        let mut start = 0;
+        for &(constraint, size) in &self.blocks {
+            match &constraint.jacobian {
+                Some(jacobian) => {
+                    for (row, gradient) in rows[start..start + size].iter_mut().zip(jacobian(x)) {
+                        *row = gradient;
+                    }
+                }
+                None => differenced.push((constraint, start..start + size)),
+            }
+            start += size;
+        }
+        if !differenced.is_empty() {
+            let base: Vec<T> = differenced
+                .iter()
+                .flat_map(|(_, range)| c[range.clone()].to_vec())
+                .collect();
+            let function = |x: &[T]| {
+                self.cevals.set(self.cevals.get() + 1);
+                differenced
+                    .iter()
+                    .flat_map(|(constraint, _)| constraint.values(x))
+                    .collect()
+            };
+            let mut gradients = forward_difference_jacobian(function, x, &base).into_iter();
+            for (_, range) in &differenced {
+                for row in &mut rows[range.clone()] {
+                    *row = gradients.next().unwrap_or_default();
+                }
+            }
+        }
+        rows
+    }
+
+    /// Tolerance of each row, `default` unless its constraint has its own.
+    pub(crate) fn tolerances(&self, default: T) -> Vec<T> {
+        self.blocks
+            .iter()
+            .flat_map(|&(constraint, size)| {
+                std::iter::repeat_n(constraint.tolerance.unwrap_or(default), size)
+            })
+            .collect()
+    }
+
+    /// Violation of each row for the values `c`: `|cᵢ|` for the equality
+    /// rows and `max(0, cᵢ)` for the inequality rows.
+    pub(crate) fn violations(&self, c: &[T]) -> Vec<T> {
+        let (eq, ineq) = c.split_at(self.equalities);
+        eq.iter()
+            .map(|h| h.abs())
+            .chain(ineq.iter().map(|&g| g.max(T::ZERO)))
+            .collect()
+    }
+
+    /// Whether every row of `c` is violated by at most its tolerance.
+    pub(crate) fn holds(&self, c: &[T], tolerances: &[T]) -> bool {
+        self.violations(c)
+            .iter()
+            .zip(tolerances)
+            .all(|(&v, &tol)| v <= tol)
+    }
+
+    /// Indices among the inequality rows of those within their tolerance of
+    /// the bound or beyond it.
+    pub(crate) fn active(&self, c: &[T], tolerances: &[T]) -> Vec<usize> {
+        c[self.equalities..]
+            .iter()
+            .zip(&tolerances[self.equalities..])
+            .enumerate()
+            .filter(|(_, (&g, &tol))| g >= -tol)
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flattens_equalities_first() {
+        let constraints = [
+            Constraint::inequality(|x: &[f64]| vec![x[0] - 1.0, -x[1]]).with_tolerance(0.5),
+            Constraint::equality(|x: &[f64]| vec![x[0] * x[1] - 2.0])
+                .with_jacobian(|x: &[f64]| vec![vec![x[1], x[0]]]),
+        ];
+        let (set, c) = ConstraintSet::new(&constraints, &[1.2, 3.0]);
+        assert_eq!(set.equalities(), 1);
+        assert_eq!(c.len(), 3);
+        assert!((c[0] - 1.6).abs() < 1e-12 && (c[1] - 0.2).abs() < 1e-12);
+        assert_eq!(c[2], -3.0);
+
+        let jacobian = set.jacobian(&[1.2, 3.0], &c);
+        assert_eq!(jacobian[0], vec![3.0, 1.2]);
+        assert!((jacobian[1][0] - 1.0).abs() < 1e-7 && jacobian[1][1].abs() < 1e-7);
+        assert!(jacobian[2][0].abs() < 1e-7 && (jacobian[2][1] + 1.0).abs() < 1e-7);
+        // One evaluation for the values and two for the differences.
+        assert_eq!(set.cevals(), 3);
+
+        let tolerances = set.tolerances(1e-6);
+        assert_eq!(tolerances, vec![1e-6, 0.5, 0.5]);
+        assert!(!set.holds(&c, &tolerances));
+        assert!(set.holds(&[0.0, 0.4, -3.0], &tolerances));
+        assert_eq!(set.active(&[0.0, 0.1, -0.4], &tolerances), vec![0, 1]);
+    }
+}
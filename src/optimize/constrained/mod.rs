@@ -0,0 +1,65 @@
+//! Minimization subject to constraints.
+//!
+//! Constraints are given as vector functions of `x`: equality constraints
+//! `h(x) = 0` and inequality constraints `g(x) ≤ 0`, each returning one entry
+//! per constraint. A problem without constraints of one kind passes
+//! `|_: &[f64]| vec![]` for them. The `_with_constraints` variants take a
+//! list of [`Constraint`]s instead, each of which may carry an analytic
+//! Jacobian and its own tolerance. Quadratic programs, with a quadratic
+//! objective and linear constraints, are solved directly by [`qp_minimize`],
+//! and linear equality constraints alone can be eliminated with
+//! [`null_space_minimize`], which hands the reduced problem to any
+//! unconstrained optimizer.
+
+mod barrier;
+mod constraint;
+mod null_space;
+mod penalty;
+mod qp;
+mod sqp;
+
+pub use barrier::{barrier_minimize, Barrier};
+pub use constraint::{Constraint, ConstraintKind};
+pub use null_space::{null_space_minimize, NullSpace};
+pub use penalty::{penalty_minimize, penalty_minimize_with_constraints, Penalty};
+pub use qp::{qp_minimize, QpResult};
+pub use sqp::{sqp_minimize, sqp_minimize_with_constraints};
+
+/// Outcome of a constrained minimization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstrainedResult<T> {
+    /// Best point found.
+    pub x: Vec<T>,
+    /// Objective value at `x`.
+    pub fx: T,
+    /// Values of the equality constraints at `x`, which should be zero.
+    pub equality_violations: Vec<T>,
+    /// Amounts by which the inequality constraints at `x` are exceeded, zero
+    /// for those that hold.
+    pub inequality_violations: Vec<T>,
+    /// Indices into `inequality_violations` of the inequality constraints
+    /// active at `x`, those holding with equality to within the tolerance.
+    pub active_set: Vec<usize>,
+    /// Number of outer iterations performed.
+    pub iterations: usize,
+    /// Number of objective evaluations performed.
+    pub fevals: usize,
+    /// Number of gradient evaluations performed.
+    pub gevals: usize,
+    /// Number of evaluations of the constraint functions performed, counting
+    /// an evaluation of both kinds once.
+    pub cevals: usize,
+    /// Whether the constraints hold to the requested tolerance and the last
+    /// inner minimization converged.
+    pub converged: bool,
+}
+
+impl<T: crate::generals::Float> ConstrainedResult<T> {
+    /// Largest violation of any constraint, in absolute value.
+    pub fn max_violation(&self) -> T {
+        self.equality_violations
+            .iter()
+            .chain(&self.inequality_violations)
+            .fold(T::ZERO, |max, v| max.max(v.abs()))
+    }
+}
@@ -0,0 +1,317 @@
+use super::ConstrainedResult;
+use crate::generals::linalg::{add_scaled, dot, norm, Qr};
+use crate::generals::Float;
+use crate::optimize::multivariate::MultivariateResult;
+use crate::optimize::OptimizeError;
+
+/// The solutions of the linear equations `a·x = b`, parameterized as
+/// `x = origin + Z·z` by an orthonormal basis `Z` of the null space of `a`.
+///
+/// Any function of `x` becomes an unconstrained function of the reduced
+/// variables `z`, with [`NullSpace::expand`] mapping them back, and its
+/// gradient becomes `Zᵀ·∇f`. Redundant equations are allowed; the basis comes
+/// from a QR factorization with column pivoting of `aᵀ`, whose numerical rank
+/// decides how many equations are independent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NullSpace<T> {
+    origin: Vec<T>,
+    basis: Vec<Vec<T>>,
+}
+
+impl<T: Float> NullSpace<T> {
+    /// The solutions of `a·x = b`, with `a` given as a vector of rows,
+    /// parameterized around the solution closest to `x0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptimizeError::Infeasible`] if the equations are
+    /// inconsistent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` have different lengths or a row of `a` does not
+    /// have one entry per coordinate of `x0`.
+    pub fn new(a: &[Vec<T>], b: &[T], x0: &[T]) -> Result<Self, OptimizeError> {
+        let (m, n) = (a.len(), x0.len());
+        assert_eq!(m, b.len(), "a and b must have as many rows");
+        assert!(
+            a.iter().all(|row| row.len() == n),
+            "rows of a must have one entry per coordinate"
+        );
+        // aᵀ·P = Q·R, so a·x = b reads Rᵀ·Qᵀ·x = Pᵀ·b.
+        let transposed: Vec<T> = (0..n)
+            .flat_map(|j| a.iter().map(move |row| row[j]))
+            .collect();
+        let qr = Qr::factor(&transposed, n, m, true);
+        let rank = qr.rank();
+
+        // The closest solution moves x0 within the range of the first `rank`
+        // columns of Q, by y solving the leading triangle of Rᵀ.
+        let residual: Vec<T> = qr
+            .permutation
+            .iter()
+            .map(|&i| b[i] - dot(&a[i], x0))
+            .collect();
+        let mut y = vec![T::ZERO; rank];
+        for k in 0..rank {
+            let sum = (0..k).map(|j| qr.factors[j * m + k] * y[j]).sum::<T>();
+            y[k] = (residual[k] - sum) / qr.factors[k * m + k];
+        }
+        let mut origin = x0.to_vec();
+        for (k, &yk) in y.iter().enumerate() {
+            origin = add_scaled(&origin, yk, &qr.q_column(k));
+        }
+
+        let scale = norm(b) + norm(&a.concat()) * norm(&origin);
+        let inconsistency = norm(
+            &a.iter()
+                .zip(b)
+                .map(|(row, &bi)| dot(row, &origin) - bi)
+                .collect::<Vec<T>>(),
+        );
+        if inconsistency > T::EPSILON.sqrt() * scale || inconsistency.is_nan() {
+            return Err(OptimizeError::Infeasible);
+        }
+        Ok(NullSpace {
+            origin,
+            basis: (rank..n).map(|j| qr.q_column(j)).collect(),
+        })
+    }
+
+    /// The solution that the reduced variables `z = 0` stand for.
+    pub fn origin(&self) -> &[T] {
+        &self.origin
+    }
+
+    /// Number of reduced variables, the dimension of the null space.
+    pub fn dimension(&self) -> usize {
+        self.basis.len()
+    }
+
+    /// The point `origin + Z·z` for the reduced variables `z`.
+    pub fn expand(&self, z: &[T]) -> Vec<T> {
+        debug_assert_eq!(z.len(), self.dimension());
+        self.basis
+            .iter()
+            .zip(z)
+            .fold(self.origin.clone(), |x, (column, &zi)| {
+                add_scaled(&x, zi, column)
+            })
+    }
+
+    /// The reduced variables `Zᵀ·(x - origin)` of `x`, which
+    /// [`NullSpace::expand`] maps back to `x` if it solves the equations and
+    /// to its projection onto the solutions otherwise.
+    pub fn reduce(&self, x: &[T]) -> Vec<T> {
+        self.reduce_gradient(&add_scaled(x, -T::ONE, &self.origin))
+    }
+
+    /// The gradient `Zᵀ·g` with respect to the reduced variables of a
+    /// function whose gradient with respect to `x` is `g`.
+    pub fn reduce_gradient(&self, g: &[T]) -> Vec<T> {
+        self.basis.iter().map(|column| dot(column, g)).collect()
+    }
+}
+
+/// Minimizes `func`, whose gradient is `grad`, subject to the linear
+/// equations `a·x = b`, with `a` given as a vector of rows, by running `inner`
+/// on the problem reduced to the null space of `a`.
+///
+/// The solutions are parameterized as described for [`NullSpace`], around the
+/// one closest to `x0`, and `inner` receives the reduced objective, its
+/// gradient and the starting point `z = 0`. It may run any unconstrained
+/// optimizer, for example
+/// `|f, g, z| lbfgs_minimize(f, g, z, 10, StrongWolfe::default(), Gradient(1e-8), 1000)`.
+/// Every point it evaluates satisfies the equations up to rounding, and the
+/// reduced problem is as well conditioned as the original one restricted to
+/// the solutions, unlike the penalized objectives of [`penalty_minimize`].
+/// Each objective and gradient evaluation costs an extra `O(n·(n - r))` for
+/// `r` independent equations.
+///
+/// The result reports the residuals `a·x - b` as `equality_violations` and
+/// the iterations and evaluations of `inner`; `cevals` is zero. If the
+/// equations leave a single solution, it is returned without calling
+/// `inner`.
+///
+/// [`penalty_minimize`]: super::penalty_minimize
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::Infeasible`] if the equations are inconsistent,
+/// and the errors of `inner`.
+///
+/// # Panics
+///
+/// Panics if `a` and `b` have different lengths or a row of `a` does not have
+/// one entry per coordinate of `x0`.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::constrained::null_space_minimize;
+/// use mathslib::optimize::line_search::StrongWolfe;
+/// use mathslib::optimize::multivariate::lbfgs_minimize;
+/// use mathslib::optimize::ConvergenceCriteria::Gradient;
+///
+/// // Rosenbrock's function along the plane x + y + z = 3.
+/// let f = |x: &[f64]| {
+///     (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2) + (x[2] - 1.0).powi(2)
+/// };
+/// let grad = |x: &[f64]| {
+///     vec![
+///         -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+///         200.0 * (x[1] - x[0] * x[0]),
+///         2.0 * (x[2] - 1.0),
+///     ]
+/// };
+/// let result = null_space_minimize(f, grad, &[vec![1.0, 1.0, 1.0]], &[3.0], &[0.0; 3], |f, g, z| {
+///     lbfgs_minimize(f, g, z, 10, StrongWolfe::default(), Gradient(1e-10), 1000)
+/// })
+/// .unwrap();
+/// assert!(result.converged);
+/// assert!(result.x.iter().all(|xi| (xi - 1.0f64).abs() < 1e-6));
+/// assert!(result.max_violation() < 1e-12);
+/// ```
+pub fn null_space_minimize<T, F, G, S>(
+    func: F,
+    grad: G,
+    a: &[Vec<T>],
+    b: &[T],
+    x0: &[T],
+    inner: S,
+) -> Result<ConstrainedResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    S: FnOnce(
+        &dyn Fn(&[T]) -> T,
+        &dyn Fn(&[T]) -> Vec<T>,
+        &[T],
+    ) -> Result<MultivariateResult<T>, OptimizeError>,
+{
+    let space = NullSpace::new(a, b, x0)?;
+    let (x, iterations, fevals, gevals, converged) = if space.dimension() == 0 {
+        (space.origin().to_vec(), 0, 0, 0, true)
+    } else {
+        let reduced = |z: &[T]| func(&space.expand(z));
+        let reduced_gradient = |z: &[T]| space.reduce_gradient(&grad(&space.expand(z)));
+        let result = inner(
+            &reduced,
+            &reduced_gradient,
+            &vec![T::ZERO; space.dimension()],
+        )?;
+        (
+            space.expand(&result.x),
+            result.iterations,
+            result.fevals,
+            result.gevals,
+            result.converged,
+        )
+    };
+    let equality_violations = a
+        .iter()
+        .zip(b)
+        .map(|(row, &bi)| dot(row, &x) - bi)
+        .collect();
+    Ok(ConstrainedResult {
+        fx: func(&x),
+        x,
+        equality_violations,
+        inequality_violations: vec![],
+        active_set: vec![],
+        iterations,
+        fevals: fevals + 1,
+        gevals,
+        cevals: 0,
+        converged,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::constrained::qp_minimize;
+    use crate::optimize::line_search::StrongWolfe;
+    use crate::optimize::multivariate::{lbfgs_minimize, trust_region_minimize, TrustRegion};
+    use crate::optimize::ConvergenceCriteria::Gradient;
+
+    #[test]
+    fn parameterizes_redundant_equations() {
+        // The second equation is twice the first, leaving the line
+        // x + y = 1, z = 2 in three dimensions.
+        let a = [
+            vec![1.0, 1.0, 0.0],
+            vec![2.0, 2.0, 0.0],
+            vec![0.0, 0.0, 1.0],
+        ];
+        let space = NullSpace::new(&a, &[1.0, 2.0, 2.0], &[3.0, 0.0, 0.0]).unwrap();
+        assert_eq!(space.dimension(), 1);
+        let origin = space.origin();
+        assert!((origin[0] - 2.0).abs() < 1e-12 && (origin[1] + 1.0).abs() < 1e-12);
+        assert!((origin[2] - 2.0).abs() < 1e-12);
+        for z in [-3.0, 0.5, 10.0] {
+            let x = space.expand(&[z]);
+            assert!((x[0] + x[1] - 1.0).abs() < 1e-12 && (x[2] - 2.0).abs() < 1e-12);
+            assert!((space.reduce(&x)[0] - z).abs() < 1e-12);
+        }
+        assert_eq!(
+            NullSpace::new(&a, &[1.0, 3.0, 2.0], &[0.0; 3]),
+            Err(OptimizeError::Infeasible)
+        );
+    }
+
+    #[test]
+    fn matches_the_quadratic_program() {
+        // ½xᵀQx + cᵀx subject to two equations in four variables.
+        let q = [
+            vec![4.0, 1.0, 0.0, 0.0],
+            vec![1.0, 3.0, 0.5, 0.0],
+            vec![0.0, 0.5, 2.0, 0.2],
+            vec![0.0, 0.0, 0.2, 1.0],
+        ];
+        let c = [1.0, -2.0, 0.5, 3.0];
+        let a = [vec![1.0, 1.0, 1.0, 1.0], vec![1.0, -1.0, 2.0, 0.0]];
+        let b = [2.0, -1.0];
+        let f = |x: &[f64]| {
+            q.iter()
+                .zip(x)
+                .map(|(row, &xi)| 0.5 * xi * dot(row, x))
+                .sum::<f64>()
+                + dot(&c, x)
+        };
+        let grad = |x: &[f64]| -> Vec<f64> {
+            q.iter()
+                .zip(&c)
+                .map(|(row, &ci)| dot(row, x) + ci)
+                .collect()
+        };
+        let expected = qp_minimize(&q, &c, &a, &b, &[], &[]).unwrap();
+        let result = null_space_minimize(f, grad, &a, &b, &[5.0; 4], |f, g, z| {
+            trust_region_minimize(f, g, z, TrustRegion::default(), Gradient(1e-10), 100)
+        })
+        .unwrap();
+        assert!(result.converged);
+        for (xi, ei) in result.x.iter().zip(&expected.x) {
+            assert!((xi - ei).abs() < 1e-8, "{:?} vs {:?}", result.x, expected.x);
+        }
+        assert!(result.max_violation() < 1e-12);
+        assert!(result.inequality_violations.is_empty());
+    }
+
+    #[test]
+    fn a_single_solution_needs_no_inner_run() {
+        let result = null_space_minimize(
+            |x: &[f64]| x[0] * x[1],
+            |x: &[f64]| vec![x[1], x[0]],
+            &[vec![1.0, 1.0], vec![1.0, -1.0]],
+            &[3.0, 1.0],
+            &[0.0, 0.0],
+            |f, g, z| lbfgs_minimize(f, g, z, 5, StrongWolfe::default(), Gradient(1e-8), 100),
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!((result.x[0] - 2.0).abs() < 1e-12 && (result.x[1] - 1.0).abs() < 1e-12);
+        assert_eq!((result.iterations, result.fevals), (0, 1));
+    }
+}
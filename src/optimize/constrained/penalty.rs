@@ -0,0 +1,308 @@
+use super::constraint::ConstraintSet;
+use super::{ConstrainedResult, Constraint};
+use crate::generals::linalg::dot;
+use crate::generals::Float;
+use crate::optimize::multivariate::{non_finite_at, MultivariateResult};
+use crate::optimize::OptimizeError;
+
+/// How [`penalty_minimize`] raises the penalty weight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Penalty<T> {
+    /// Penalty weight of the first round.
+    pub initial_weight: T,
+    /// Factor by which the weight grows after each round.
+    pub growth: T,
+    /// Largest constraint violation accepted as feasible.
+    pub tolerance: T,
+    /// Maximum number of rounds.
+    pub max_rounds: usize,
+}
+
+impl<T: Float> Default for Penalty<T> {
+    /// Weights from 10 up by factors of 10 for at most 20 rounds, until no
+    /// constraint is violated by more than `1e-6`.
+    fn default() -> Self {
+        Penalty {
+            initial_weight: T::from_f64(10.0),
+            growth: T::from_f64(10.0),
+            tolerance: T::from_f64(1e-6),
+            max_rounds: 20,
+        }
+    }
+}
+
+impl<T: Float> Penalty<T> {
+    /// Starts with the penalty weight `initial_weight`.
+    pub fn with_initial_weight(mut self, initial_weight: T) -> Self {
+        self.initial_weight = initial_weight;
+        self
+    }
+
+    /// Multiplies the weight by `growth` after each round.
+    pub fn with_growth(mut self, growth: T) -> Self {
+        self.growth = growth;
+        self
+    }
+
+    /// Accepts constraint violations up to `tolerance`.
+    pub fn with_tolerance(mut self, tolerance: T) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Stops after `max_rounds` rounds.
+    pub fn with_max_rounds(mut self, max_rounds: usize) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+}
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` subject to
+/// `equalities(x) = 0` and `inequalities(x) ≤ 0` with the quadratic penalty
+/// method.
+///
+/// Each round minimizes the penalized objective
+/// `f(x) + ½μ·(Σ hᵢ(x)² + Σ max(0, gᵢ(x))²)` with `inner`, starting from the
+/// result of the previous round, and then multiplies the weight `μ` by
+/// `settings.growth` until no constraint is violated by more than
+/// `settings.tolerance`. `inner` receives the penalized objective, its
+/// gradient and the starting point, and may run any unconstrained optimizer,
+/// for example
+/// `|f, g, x| lbfgs_minimize(f, g, x, 10, StrongWolfe::default(), Gradient(1e-8), 1000)`.
+///
+/// The minimizers of the penalized objective approach the constrained minimum
+/// from the infeasible side, with violations falling roughly like `1 / μ`, so
+/// tight tolerances need large weights and make the inner problems poorly
+/// conditioned; a gradient criterion suits the inner runs better than an x
+/// criterion, which line searches may stall before meeting. The constraint
+/// gradients are taken by forward differences, costing `n + 1` constraint
+/// evaluations per penalized gradient; [`penalty_minimize_with_constraints`]
+/// accepts analytic Jacobians and per-constraint tolerances.
+///
+/// # Errors
+///
+/// Returns the errors of `inner`, and [`OptimizeError::NonFiniteAtPoint`] if
+/// a constraint is NaN or infinite at `x0`.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::constrained::{penalty_minimize, Penalty};
+/// use mathslib::optimize::line_search::StrongWolfe;
+/// use mathslib::optimize::multivariate::lbfgs_minimize;
+/// use mathslib::optimize::ConvergenceCriteria::Gradient;
+///
+/// // The point of the line x + y = 1 closest to (2, 1).
+/// let result = penalty_minimize(
+///     |x: &[f64]| (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2),
+///     |x: &[f64]| vec![2.0 * (x[0] - 2.0), 2.0 * (x[1] - 1.0)],
+///     |x: &[f64]| vec![x[0] + x[1] - 1.0],
+///     |_: &[f64]| vec![],
+///     &[0.0, 0.0],
+///     Penalty::default(),
+///     |f, g, x| lbfgs_minimize(f, g, x, 10, StrongWolfe::default(), Gradient(1e-8), 1000),
+/// )
+/// .unwrap();
+/// assert!(result.converged);
+/// assert!((result.x[0] - 1.0).abs() < 1e-5 && result.x[1].abs() < 1e-5);
+/// ```
+pub fn penalty_minimize<T, F, G, E, I, S>(
+    func: F,
+    grad: G,
+    equalities: E,
+    inequalities: I,
+    x0: &[T],
+    settings: Penalty<T>,
+    inner: S,
+) -> Result<ConstrainedResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    E: Fn(&[T]) -> Vec<T>,
+    I: Fn(&[T]) -> Vec<T>,
+    S: FnMut(
+        &dyn Fn(&[T]) -> T,
+        &dyn Fn(&[T]) -> Vec<T>,
+        &[T],
+    ) -> Result<MultivariateResult<T>, OptimizeError>,
+{
+    let constraints = [
+        Constraint::equality(equalities),
+        Constraint::inequality(inequalities),
+    ];
+    penalty_minimize_with_constraints(func, grad, &constraints, x0, settings, inner)
+}
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` subject to
+/// `constraints` with the quadratic penalty method of [`penalty_minimize`].
+///
+/// Rounds continue until every constraint is violated by at most its own
+/// tolerance, or `settings.tolerance` for those without one, and the penalty
+/// gradient uses the constraints' Jacobians where given. The result lists the
+/// equality rows of `constraints` in order, then the inequality rows, and
+/// reports the inequalities within their tolerance of the bound as active.
+///
+/// # Errors
+///
+/// Returns the errors of `inner`, and [`OptimizeError::NonFiniteAtPoint`] if
+/// a constraint is NaN or infinite at `x0`.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::constrained::{penalty_minimize_with_constraints, Constraint, Penalty};
+/// use mathslib::optimize::line_search::StrongWolfe;
+/// use mathslib::optimize::multivariate::lbfgs_minimize;
+/// use mathslib::optimize::ConvergenceCriteria::Gradient;
+///
+/// // The point of the line x + y = 1 closest to (2, 1), with y ≥ 0.2.
+/// let constraints = [
+///     Constraint::equality(|x: &[f64]| vec![x[0] + x[1] - 1.0])
+///         .with_jacobian(|_: &[f64]| vec![vec![1.0, 1.0]]),
+///     Constraint::inequality(|x: &[f64]| vec![0.2 - x[1]]),
+/// ];
+/// let result = penalty_minimize_with_constraints(
+///     |x: &[f64]| (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2),
+///     |x: &[f64]| vec![2.0 * (x[0] - 2.0), 2.0 * (x[1] - 1.0)],
+///     &constraints,
+///     &[0.0, 0.0],
+///     Penalty::default(),
+///     |f, g, x| lbfgs_minimize(f, g, x, 10, StrongWolfe::default(), Gradient(1e-8), 1000),
+/// )
+/// .unwrap();
+/// assert!(result.converged);
+/// assert!((result.x[0] - 0.8).abs() < 1e-5 && (result.x[1] - 0.2).abs() < 1e-5);
+/// assert_eq!(result.active_set, vec![0]);
+/// ```
+pub fn penalty_minimize_with_constraints<T, F, G, S>(
+    func: F,
+    grad: G,
+    constraints: &[Constraint<'_, T>],
+    x0: &[T],
+    settings: Penalty<T>,
+    mut inner: S,
+) -> Result<ConstrainedResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    S: FnMut(
+        &dyn Fn(&[T]) -> T,
+        &dyn Fn(&[T]) -> Vec<T>,
+        &[T],
+    ) -> Result<MultivariateResult<T>, OptimizeError>,
+{
+    let (set, c0) = ConstraintSet::new(constraints, x0);
+    if c0.iter().any(|v| !v.is_finite()) {
+        return Err(non_finite_at(x0));
+    }
+    let tolerances = set.tolerances(settings.tolerance);
+    // Equality values followed by the positive parts of the inequalities.
+    let signed_violations = |c: &[T]| -> Vec<T> {
+        let mut v = c.to_vec();
+        for g in &mut v[set.equalities()..] {
+            *g = g.max(T::ZERO);
+        }
+        v
+    };
+
+    let half = T::from_f64(0.5);
+    let mut x = x0.to_vec();
+    let mut c = c0;
+    let mut weight = settings.initial_weight;
+    let (mut fevals, mut gevals, mut iterations) = (0, 0, 0);
+    let mut inner_converged = false;
+    while iterations < settings.max_rounds {
+        let penalized = |x: &[T]| {
+            let v = signed_violations(&set.values(x));
+            func(x) + half * weight * dot(&v, &v)
+        };
+        let penalized_gradient = |x: &[T]| {
+            let c = set.values(x);
+            let jacobian = set.jacobian(x, &c);
+            let mut g = grad(x);
+            for (row, &vi) in jacobian.iter().zip(&signed_violations(&c)) {
+                for (gj, &dj) in g.iter_mut().zip(row) {
+                    *gj += weight * vi * dj;
+                }
+            }
+            g
+        };
+        let result = inner(&penalized, &penalized_gradient, &x)?;
+        x = result.x;
+        fevals += result.fevals;
+        gevals += result.gevals;
+        inner_converged = result.converged;
+        iterations += 1;
+        c = set.values(&x);
+        if set.holds(&c, &tolerances) {
+            break;
+        }
+        weight *= settings.growth;
+    }
+
+    let active_set = set.active(&c, &tolerances);
+    let converged = inner_converged && set.holds(&c, &tolerances);
+    let inequality_violations = signed_violations(&c).split_off(set.equalities());
+    c.truncate(set.equalities());
+    Ok(ConstrainedResult {
+        fx: func(&x),
+        x,
+        equality_violations: c,
+        inequality_violations,
+        active_set,
+        iterations,
+        fevals: fevals + 1,
+        gevals,
+        cevals: set.cevals(),
+        converged,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::line_search::StrongWolfe;
+    use crate::optimize::multivariate::lbfgs_minimize;
+    use crate::optimize::ConvergenceCriteria::Gradient;
+
+    #[test]
+    fn respects_an_active_inequality() {
+        // Closest point to the origin with x + y ≥ 1, and y ≤ 2 inactive.
+        let result = penalty_minimize(
+            |x: &[f64]| x[0] * x[0] + x[1] * x[1],
+            |x: &[f64]| vec![2.0 * x[0], 2.0 * x[1]],
+            |_: &[f64]| vec![],
+            |x: &[f64]| vec![1.0 - x[0] - x[1], x[1] - 2.0],
+            &[3.0, -1.0],
+            Penalty::default().with_tolerance(1e-7),
+            |f, g, x| lbfgs_minimize(f, g, x, 10, StrongWolfe::default(), Gradient(1e-8), 1000),
+        )
+        .unwrap();
+        assert!(result.converged, "{result:?}");
+        assert!((result.x[0] - 0.5).abs() < 1e-6 && (result.x[1] - 0.5).abs() < 1e-6);
+        assert_eq!(result.inequality_violations.len(), 2);
+        assert_eq!(result.inequality_violations[1], 0.0);
+        assert!(result.max_violation() <= 1e-7);
+        assert!(result.iterations > 1);
+    }
+
+    #[test]
+    fn reports_remaining_violations() {
+        let result = penalty_minimize(
+            |x: &[f64]| x[0] * x[0],
+            |x: &[f64]| vec![2.0 * x[0]],
+            |x: &[f64]| vec![x[0] - 1.0],
+            |_: &[f64]| vec![],
+            &[0.0],
+            Penalty::default().with_max_rounds(1),
+            |f, g, x| lbfgs_minimize(f, g, x, 10, StrongWolfe::default(), Gradient(1e-8), 1000),
+        )
+        .unwrap();
+        assert!(!result.converged);
+        // Minimizer of x² + 5(x - 1)².
+        assert!((result.x[0] - 5.0 / 6.0).abs() < 1e-6);
+        assert!((result.equality_violations[0] + 1.0 / 6.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,341 @@
+use crate::generals::linalg::{add_scaled, cholesky, cholesky_solve, dot};
+use crate::generals::Float;
+use crate::optimize::OptimizeError;
+
+/// Outcome of [`qp_minimize`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QpResult<T> {
+    /// Minimizer.
+    pub x: Vec<T>,
+    /// Objective value `½xᵀQx + cᵀx` at `x`.
+    pub fx: T,
+    /// Lagrange multipliers of the equality constraints.
+    pub equality_multipliers: Vec<T>,
+    /// Lagrange multipliers of the inequality constraints, nonnegative and
+    /// zero for inactive constraints.
+    pub inequality_multipliers: Vec<T>,
+    /// Number of steps of the active set method.
+    pub iterations: usize,
+}
+
+/// Minimizes the convex quadratic `½xᵀQx + cᵀx` subject to
+/// `a_eq·x = b_eq` and `a_in·x ≤ b_in`, with the matrices given as vectors of
+/// rows.
+///
+/// The dual active set method of Goldfarb and Idnani starts from the
+/// unconstrained minimum and adds violated constraints one at a time,
+/// dropping active inequalities whose multipliers would turn negative, until
+/// all constraints hold. No feasible starting point is needed, and
+/// infeasibility is detected on the way. The multipliers satisfy
+/// `Qx + c + a_eqᵀ·λ_eq + a_inᵀ·λ_in = 0`. Each step refactors the active
+/// constraints, costing `O(n³)`, which suits problems of up to a few hundred
+/// variables.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NotPositiveDefinite`] unless `Q` is positive
+/// definite; a semidefinite `Q` can be regularized by adding a small multiple
+/// of the identity. Returns [`OptimizeError::Infeasible`] if the constraints
+/// cannot all be satisfied.
+///
+/// # Panics
+///
+/// Panics if the dimensions of `q`, `a_eq`, `b_eq`, `a_in` or `b_in` do not
+/// match `c`.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::constrained::qp_minimize;
+///
+/// // Closest point to (1, 2) with x + y ≤ 1.
+/// let q = [vec![1.0f64, 0.0], vec![0.0, 1.0]];
+/// let result = qp_minimize(&q, &[-1.0, -2.0], &[], &[], &[vec![1.0, 1.0]], &[1.0]).unwrap();
+/// assert!(result.x[0].abs() < 1e-12 && (result.x[1] - 1.0).abs() < 1e-12);
+/// assert!((result.inequality_multipliers[0] - 1.0).abs() < 1e-12);
+/// ```
+pub fn qp_minimize<T: Float>(
+    q: &[Vec<T>],
+    c: &[T],
+    a_eq: &[Vec<T>],
+    b_eq: &[T],
+    a_in: &[Vec<T>],
+    b_in: &[T],
+) -> Result<QpResult<T>, OptimizeError> {
+    let n = c.len();
+    assert!(
+        q.len() == n && q.iter().all(|row| row.len() == n),
+        "q must be n × n"
+    );
+    assert!(
+        a_eq.len() == b_eq.len() && a_in.len() == b_in.len(),
+        "constraint matrices and right-hand sides must have as many rows"
+    );
+    assert!(
+        a_eq.iter().chain(a_in).all(|row| row.len() == n),
+        "constraint rows must have n entries"
+    );
+    let constraints = LinearConstraints {
+        rows: a_eq.iter().chain(a_in).cloned().collect(),
+        rhs: b_eq.iter().chain(b_in).copied().collect(),
+        equalities: a_eq.len(),
+    };
+    let flat = q.concat();
+    let solution = dual_active_set(&flat, c, &constraints)?;
+    let qx: Vec<T> = q.iter().map(|row| dot(row, &solution.x)).collect();
+    let fx = T::from_f64(0.5) * dot(&solution.x, &qx) + dot(c, &solution.x);
+    let mut equality_multipliers = solution.multipliers;
+    let inequality_multipliers = equality_multipliers.split_off(a_eq.len());
+    Ok(QpResult {
+        x: solution.x,
+        fx,
+        equality_multipliers,
+        inequality_multipliers,
+        iterations: solution.iterations,
+    })
+}
+
+/// Linear constraints `aᵢ·x = bᵢ` for the first `equalities` rows and
+/// `aᵢ·x ≤ bᵢ` for the rest.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LinearConstraints<T> {
+    pub(crate) rows: Vec<Vec<T>>,
+    pub(crate) rhs: Vec<T>,
+    pub(crate) equalities: usize,
+}
+
+/// Solution of a quadratic program and its Lagrange multipliers, one per
+/// constraint, with `∇f(x) + Σ λᵢ·aᵢ = 0` and `λᵢ ≥ 0` for inequalities.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct QpSolution<T> {
+    pub(crate) x: Vec<T>,
+    pub(crate) multipliers: Vec<T>,
+    pub(crate) iterations: usize,
+}
+
+/// Minimizes `½xᵀQx + cᵀx` subject to `constraints` for a positive definite
+/// row-major `n × n` matrix `q`, with the dual active set method of Goldfarb
+/// and Idnani.
+///
+/// Starting from the unconstrained minimum, the method adds one violated
+/// constraint at a time, dropping active inequalities whose multipliers would
+/// turn negative on the way, so the iterates stay optimal for the constraints
+/// in the active set and no feasible starting point is needed. All
+/// equalities are added first and never dropped. Each iteration refactors
+/// the active set from scratch, which is fine for the small problems this
+/// serves. Rounding errors with nearly dependent constraints could make the
+/// method cycle, so it gives up with [`OptimizeError::Infeasible`] after
+/// `10·(n + m) + 100` steps.
+pub(crate) fn dual_active_set<T: Float>(
+    q: &[T],
+    c: &[T],
+    constraints: &LinearConstraints<T>,
+) -> Result<QpSolution<T>, OptimizeError> {
+    let n = c.len();
+    let m = constraints.rows.len();
+    let max_iter = 10 * (n + m) + 100;
+    let l = cholesky(q, n).ok_or(OptimizeError::NotPositiveDefinite)?;
+    let solve = |v: &[T]| cholesky_solve(&l, n, v);
+    let minus_c: Vec<T> = c.iter().map(|&ci| -ci).collect();
+    let mut x = solve(&minus_c);
+
+    // In the working form every constraint reads nᵢ·x ≥ βᵢ, with the normal nᵢ
+    // of an inequality being -aᵢ; equalities keep whichever sign makes them
+    // violated when they are added, recorded in `flipped`.
+    let mut flipped = vec![false; m];
+    let slack = |i: usize, flip: bool, x: &[T]| {
+        let s = constraints.rhs[i] - dot(&constraints.rows[i], x);
+        if flip {
+            -s
+        } else {
+            s
+        }
+    };
+    let tolerance = |i: usize, x: &[T]| {
+        let scale = constraints.rows[i]
+            .iter()
+            .zip(x)
+            .fold(constraints.rhs[i].abs(), |sum, (&a, &xi)| {
+                sum + (a * xi).abs()
+            });
+        T::from_f64(1e4) * T::EPSILON * (T::ONE + scale)
+    };
+    let normal = |i: usize, flip: bool| -> Vec<T> {
+        constraints.rows[i]
+            .iter()
+            .map(|&a| if flip { a } else { -a })
+            .collect()
+    };
+
+    let mut active: Vec<usize> = Vec::new();
+    let mut u: Vec<T> = Vec::new();
+    // Equalities that were added, or found redundant.
+    let mut done = vec![false; m];
+    let mut iterations = 0;
+    loop {
+        // Next constraint to add: any pending equality, else the most violated
+        // inequality.
+        let p = (0..constraints.equalities).find(|&i| !done[i]).or_else(|| {
+            (constraints.equalities..m)
+                .filter(|i| !active.contains(i))
+                .map(|i| (i, slack(i, false, &x)))
+                .filter(|&(i, s)| s < -tolerance(i, &x))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|(i, _)| i)
+        });
+        let Some(p) = p else {
+            break;
+        };
+        if p < constraints.equalities && slack(p, false, &x) > T::ZERO {
+            flipped[p] = true;
+        }
+        let n_p = normal(p, flipped[p]);
+        let mut u_p = T::ZERO;
+        loop {
+            iterations += 1;
+            if iterations > max_iter {
+                return Err(OptimizeError::Infeasible);
+            }
+            // z is the primal step direction in the null space of the active
+            // normals and r the rate at which their multipliers fall.
+            let normals: Vec<Vec<T>> = active.iter().map(|&i| normal(i, flipped[i])).collect();
+            let g_inv_normals: Vec<Vec<T>> = normals.iter().map(|v| solve(v)).collect();
+            let g_inv_np = solve(&n_p);
+            let k = active.len();
+            let r = if k == 0 {
+                Vec::new()
+            } else {
+                let mut gram = vec![T::ZERO; k * k];
+                for (i, w) in g_inv_normals.iter().enumerate() {
+                    for (j, v) in normals.iter().enumerate() {
+                        gram[i * k + j] = dot(w, v);
+                    }
+                }
+                let gram_l = cholesky(&gram, k).ok_or(OptimizeError::NotPositiveDefinite)?;
+                let rhs: Vec<T> = g_inv_normals.iter().map(|w| dot(w, &n_p)).collect();
+                cholesky_solve(&gram_l, k, &rhs)
+            };
+            let z = r
+                .iter()
+                .zip(&g_inv_normals)
+                .fold(g_inv_np.clone(), |z, (&rj, w)| add_scaled(&z, -rj, w));
+
+            let partial = active
+                .iter()
+                .enumerate()
+                .filter(|&(j, &i)| i >= constraints.equalities && r[j] > T::ZERO)
+                .map(|(j, _)| (j, u[j] / r[j]))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+            let zn = dot(&z, &n_p);
+            let s_p = slack(p, flipped[p], &x);
+            let full = if zn > T::from_f64(1e3) * T::EPSILON * dot(&g_inv_np, &n_p) {
+                Some(-s_p / zn)
+            } else {
+                None
+            };
+
+            let t = match (partial, full) {
+                (None, None) => {
+                    if p < constraints.equalities && s_p.abs() <= tolerance(p, &x) {
+                        // Redundant with the equalities already active.
+                        done[p] = true;
+                        break;
+                    }
+                    return Err(OptimizeError::Infeasible);
+                }
+                (Some((_, t1)), Some(t2)) => t1.min(t2),
+                (Some((_, t1)), None) => t1,
+                (None, Some(t2)) => t2,
+            };
+            for (uj, &rj) in u.iter_mut().zip(&r) {
+                *uj -= t * rj;
+            }
+            u_p += t;
+            if full.is_some() {
+                x = add_scaled(&x, t, &z);
+            }
+            match (partial, full) {
+                (Some((j, t1)), Some(t2)) if t1 < t2 => {
+                    active.remove(j);
+                    u.remove(j);
+                }
+                (Some((j, _)), None) => {
+                    active.remove(j);
+                    u.remove(j);
+                }
+                _ => {
+                    active.push(p);
+                    u.push(u_p);
+                    done[p] = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    let mut multipliers = vec![T::ZERO; m];
+    for (&i, &ui) in active.iter().zip(&u) {
+        multipliers[i] = if flipped[i] { -ui } else { ui };
+    }
+    Ok(QpSolution {
+        x,
+        multipliers,
+        iterations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_kkt_conditions() {
+        // min ½(x² + y²) - x - 2y with x + y ≤ 1, x - y = 0.2 and y ≥ 0.
+        let constraints = LinearConstraints {
+            rows: vec![vec![1.0, -1.0], vec![1.0, 1.0], vec![0.0, -1.0]],
+            rhs: vec![0.2, 1.0, 0.0],
+            equalities: 1,
+        };
+        let q = [1.0, 0.0, 0.0, 1.0];
+        let c = [-1.0, -2.0];
+        let solution = dual_active_set(&q, &c, &constraints).unwrap();
+        assert!((solution.x[0] - 0.6).abs() < 1e-12 && (solution.x[1] - 0.4).abs() < 1e-12);
+        // Stationarity: Qx + c + Σ λᵢ·aᵢ = 0.
+        let lambda = &solution.multipliers;
+        for (j, (&xj, &cj)) in solution.x.iter().zip(&c).enumerate() {
+            let residual = xj
+                + cj
+                + (0..3)
+                    .map(|i| lambda[i] * constraints.rows[i][j])
+                    .sum::<f64>();
+            assert!(residual.abs() < 1e-12);
+        }
+        assert!(lambda[1] > 0.0 && lambda[2] == 0.0);
+    }
+
+    #[test]
+    fn handles_redundant_equalities() {
+        // x + y = 2 twice and 2x + 2y = 4, minimizing x² + 2y².
+        let q = [vec![2.0, 0.0], vec![0.0, 4.0]];
+        let a_eq = [vec![1.0, 1.0], vec![1.0, 1.0], vec![2.0, 2.0]];
+        let result = qp_minimize(&q, &[0.0, 0.0], &a_eq, &[2.0, 2.0, 4.0], &[], &[]).unwrap();
+        assert!((result.x[0] - 4.0 / 3.0).abs() < 1e-12);
+        assert!((result.x[1] - 2.0 / 3.0).abs() < 1e-12);
+        assert!((result.fx - 8.0 / 3.0).abs() < 1e-12);
+        let conflicting = qp_minimize(&q, &[0.0, 0.0], &a_eq, &[2.0, 2.0, 5.0], &[], &[]);
+        assert_eq!(conflicting, Err(OptimizeError::Infeasible));
+    }
+
+    #[test]
+    fn detects_infeasibility() {
+        let constraints = LinearConstraints {
+            rows: vec![vec![1.0], vec![-1.0]],
+            rhs: vec![-1.0, -1.0],
+            equalities: 0,
+        };
+        let result = dual_active_set(&[1.0], &[0.0], &constraints);
+        assert_eq!(result, Err(OptimizeError::Infeasible));
+        let indefinite = dual_active_set(&[-1.0], &[0.0], &constraints);
+        assert_eq!(indefinite, Err(OptimizeError::NotPositiveDefinite));
+    }
+}
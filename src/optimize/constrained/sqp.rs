@@ -0,0 +1,340 @@
+use super::constraint::ConstraintSet;
+use super::qp::{dual_active_set, LinearConstraints};
+use super::{ConstrainedResult, Constraint};
+use crate::generals::linalg::{add_scaled, dot, Norm};
+use crate::generals::Float;
+use crate::optimize::multivariate::{evaluate, non_finite_at};
+use crate::optimize::{Budget, OptimizeError};
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` subject to
+/// `equalities(x) = 0` and `inequalities(x) ≤ 0` by sequential quadratic
+/// programming.
+///
+/// Each iteration solves the quadratic program of minimizing the quadratic
+/// model `∇f·p + ½pᵀBp` subject to the constraints linearized at `x`, as
+/// [`qp_minimize`](super::qp_minimize) does, and takes the step `p` with a
+/// backtracking line search on the `ℓ₁` merit function `f + ν·(Σ |hᵢ| + Σ max(0, gᵢ))`. The
+/// weight `ν` is kept above the Lagrange multipliers of the subproblem so that
+/// `p` lowers the merit function. `B` approximates the Hessian of the
+/// Lagrangian by damped BFGS updates (Powell's), which keep it positive
+/// definite. `x0` need not be feasible.
+///
+/// The search converges when the gradient of the Lagrangian and every
+/// constraint violation are at most `tolerance` in absolute value, and stops
+/// with `converged == false` if the line search cannot lower the merit
+/// function. Constraint Jacobians are taken by forward differences, costing
+/// `n + 1` constraint evaluations each, which limits the attainable
+/// `tolerance` to about `√ε` times the size of the multipliers.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::Infeasible`] if the linearized constraints at an
+/// iterate cannot be satisfied, which can happen far from a feasible point or
+/// with more equalities than variables, and
+/// [`OptimizeError::NonFiniteAtPoint`] if the objective, gradient or
+/// constraints are NaN or infinite at `x0` or an accepted point.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::constrained::sqp_minimize;
+///
+/// // The point of the unit circle closest to (2, 1) with y ≤ 0.3.
+/// let result = sqp_minimize(
+///     |x: &[f64]| (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2),
+///     |x: &[f64]| vec![2.0 * (x[0] - 2.0), 2.0 * (x[1] - 1.0)],
+///     |x: &[f64]| vec![x[0] * x[0] + x[1] * x[1] - 1.0],
+///     |x: &[f64]| vec![x[1] - 0.3],
+///     &[1.0, 0.0],
+///     1e-8,
+///     100,
+/// )
+/// .unwrap();
+/// assert!(result.converged);
+/// assert!((result.x[0] - 0.91f64.sqrt()).abs() < 1e-6 && (result.x[1] - 0.3).abs() < 1e-6);
+/// ```
+pub fn sqp_minimize<T, F, G, E, I>(
+    func: F,
+    grad: G,
+    equalities: E,
+    inequalities: I,
+    x0: &[T],
+    tolerance: T,
+    budget: impl Into<Budget>,
+) -> Result<ConstrainedResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    E: Fn(&[T]) -> Vec<T>,
+    I: Fn(&[T]) -> Vec<T>,
+{
+    let constraints = [
+        Constraint::equality(equalities),
+        Constraint::inequality(inequalities),
+    ];
+    sqp_minimize_with_constraints(func, grad, &constraints, x0, tolerance, budget)
+}
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` subject to
+/// `constraints` by the sequential quadratic programming method of
+/// [`sqp_minimize`].
+///
+/// The constraints' Jacobians are used where given, which lifts the accuracy
+/// limit of differencing, and each constraint must hold to its own tolerance,
+/// or `tolerance` for those without one; the gradient of the Lagrangian must
+/// be at most `tolerance` in any case. The result lists the equality rows of
+/// `constraints` in order, then the inequality rows, and reports the
+/// inequalities within their tolerance of the bound as active.
+///
+/// # Errors
+///
+/// As for [`sqp_minimize`].
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::constrained::{sqp_minimize_with_constraints, Constraint};
+///
+/// // The point of the unit circle closest to (2, 1) with y ≤ 0.3.
+/// let constraints = [
+///     Constraint::equality(|x: &[f64]| vec![x[0] * x[0] + x[1] * x[1] - 1.0])
+///         .with_jacobian(|x: &[f64]| vec![vec![2.0 * x[0], 2.0 * x[1]]]),
+///     Constraint::inequality(|x: &[f64]| vec![x[1] - 0.3])
+///         .with_jacobian(|_: &[f64]| vec![vec![0.0, 1.0]]),
+/// ];
+/// let result = sqp_minimize_with_constraints(
+///     |x: &[f64]| (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2),
+///     |x: &[f64]| vec![2.0 * (x[0] - 2.0), 2.0 * (x[1] - 1.0)],
+///     &constraints,
+///     &[1.0, 0.0],
+///     1e-12,
+///     100,
+/// )
+/// .unwrap();
+/// assert!(result.converged);
+/// assert!((result.x[0] - 0.91f64.sqrt()).abs() < 1e-12 && (result.x[1] - 0.3).abs() < 1e-12);
+/// assert_eq!(result.active_set, vec![0]);
+/// ```
+pub fn sqp_minimize_with_constraints<T, F, G>(
+    func: F,
+    grad: G,
+    constraints: &[Constraint<'_, T>],
+    x0: &[T],
+    tolerance: T,
+    budget: impl Into<Budget>,
+) -> Result<ConstrainedResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    let budget = budget.into();
+    let n = x0.len();
+    // Equality values followed by inequality values.
+    let (set, mut c) = ConstraintSet::new(constraints, x0);
+    let m_eq = set.equalities();
+    let tolerances = set.tolerances(tolerance);
+    let violation = |c: &[T]| set.violations(c).into_iter().sum::<T>();
+    let lagrangian_gradient = |gradient: &[T], jacobian: &[Vec<T>], lambda: &[T]| {
+        jacobian
+            .iter()
+            .zip(lambda)
+            .fold(gradient.to_vec(), |g, (row, &l)| add_scaled(&g, l, row))
+    };
+
+    let mut x = x0.to_vec();
+    let (mut fx, mut gradient) = evaluate(&func, &grad, &x)?;
+    if c.iter().any(|ci| !ci.is_finite()) {
+        return Err(non_finite_at(&x));
+    }
+    let mut jacobian = set.jacobian(&x, &c);
+    let mut b: Vec<T> = (0..n * n)
+        .map(|k| if k % (n + 1) == 0 { T::ONE } else { T::ZERO })
+        .collect();
+    let mut nu = T::ZERO;
+    let (mut fevals, mut gevals) = (1, 1);
+    let mut iterations = 0;
+    let mut converged = false;
+    while budget.allows(iterations, fevals) {
+        let subproblem = LinearConstraints {
+            rows: jacobian.clone(),
+            rhs: c.iter().map(|&ci| -ci).collect(),
+            equalities: m_eq,
+        };
+        let qp = dual_active_set(&b, &gradient, &subproblem)?;
+        let (p, lambda) = (qp.x, qp.multipliers);
+        let stationarity = Norm::Infinity.of(&lagrangian_gradient(&gradient, &jacobian, &lambda));
+        if stationarity <= tolerance && set.holds(&c, &tolerances) {
+            converged = true;
+            break;
+        }
+        iterations += 1;
+
+        nu = nu.max(T::from_f64(1.1) * Norm::Infinity.of(&lambda));
+        let merit = fx + nu * violation(&c);
+        let slope = dot(&gradient, &p) - nu * violation(&c);
+        if slope >= T::ZERO || slope.is_nan() {
+            break;
+        }
+        let mut alpha = T::ONE;
+        let mut accepted = None;
+        for _ in 0..40 {
+            let trial = add_scaled(&x, alpha, &p);
+            let f_trial = func(&trial);
+            fevals += 1;
+            let c_trial = set.values(&trial);
+            let merit_trial = f_trial + nu * violation(&c_trial);
+            if merit_trial.is_finite() && merit_trial <= merit + T::from_f64(1e-4) * alpha * slope {
+                accepted = Some((trial, f_trial, c_trial));
+                break;
+            }
+            alpha *= T::from_f64(0.5);
+        }
+        let Some((trial, f_trial, c_trial)) = accepted else {
+            break;
+        };
+        let new_gradient = grad(&trial);
+        gevals += 1;
+        if new_gradient.iter().any(|g| !g.is_finite()) {
+            return Err(non_finite_at(&trial));
+        }
+        let new_jacobian = set.jacobian(&trial, &c_trial);
+
+        let s: Vec<T> = p.iter().map(|&pi| alpha * pi).collect();
+        let y = add_scaled(
+            &lagrangian_gradient(&new_gradient, &new_jacobian, &lambda),
+            -T::ONE,
+            &lagrangian_gradient(&gradient, &jacobian, &lambda),
+        );
+        damped_bfgs_update(&mut b, n, &s, &y);
+        (x, fx, gradient, c, jacobian) = (trial, f_trial, new_gradient, c_trial, new_jacobian);
+    }
+
+    let active_set = set.active(&c, &tolerances);
+    let inequality_violations = c[m_eq..].iter().map(|&g| g.max(T::ZERO)).collect();
+    c.truncate(m_eq);
+    Ok(ConstrainedResult {
+        x,
+        fx,
+        equality_violations: c,
+        inequality_violations,
+        active_set,
+        iterations,
+        fevals,
+        gevals,
+        cevals: set.cevals(),
+        converged,
+    })
+}
+
+/// Powell's damped BFGS update of the row-major `n × n` matrix `b` for the step
+/// `s` and gradient change `y`, which keeps `b` positive definite even when
+/// `sᵀy ≤ 0`.
+fn damped_bfgs_update<T: Float>(b: &mut [T], n: usize, s: &[T], y: &[T]) {
+    let bs: Vec<T> = (0..n).map(|i| dot(&b[i * n..(i + 1) * n], s)).collect();
+    let sbs = dot(s, &bs);
+    let sy = dot(s, y);
+    if sbs <= T::ZERO || !sbs.is_finite() {
+        return;
+    }
+    let theta = if sy >= T::from_f64(0.2) * sbs {
+        T::ONE
+    } else {
+        T::from_f64(0.8) * sbs / (sbs - sy)
+    };
+    let r: Vec<T> = y
+        .iter()
+        .zip(&bs)
+        .map(|(&yi, &bsi)| theta * yi + (T::ONE - theta) * bsi)
+        .collect();
+    let sr = dot(s, &r);
+    for i in 0..n {
+        for j in 0..n {
+            b[i * n + j] += r[i] * r[j] / sr - bs[i] * bs[j] / sbs;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_an_equality_constrained_problem() {
+        // Example 18.2 of Nocedal & Wright.
+        let f = |x: &[f64]| {
+            x.iter().product::<f64>().exp() - 0.5 * (x[0].powi(3) + x[1].powi(3) + 1.0).powi(2)
+        };
+        let grad = |x: &[f64]| {
+            let e = x.iter().product::<f64>().exp();
+            let cubic = x[0].powi(3) + x[1].powi(3) + 1.0;
+            (0..5)
+                .map(|i| {
+                    let others: f64 = (0..5).filter(|&j| j != i).map(|j| x[j]).product();
+                    let from_cubic = if i < 2 {
+                        3.0 * x[i] * x[i] * cubic
+                    } else {
+                        0.0
+                    };
+                    e * others - from_cubic
+                })
+                .collect()
+        };
+        let equalities = |x: &[f64]| {
+            vec![
+                x.iter().map(|xi| xi * xi).sum::<f64>() - 10.0,
+                x[1] * x[2] - 5.0 * x[3] * x[4],
+                x[0].powi(3) + x[1].powi(3) + 1.0,
+            ]
+        };
+        let result = sqp_minimize(
+            f,
+            grad,
+            equalities,
+            |_: &[f64]| vec![],
+            &[-1.71, 1.59, 1.82, -0.763, -0.763],
+            1e-7,
+            100,
+        )
+        .unwrap();
+        assert!(result.converged);
+        let expected = [-1.71714, 1.59571, 1.82725, -0.763643, -0.763643];
+        for (x, e) in result.x.iter().zip(expected) {
+            assert!((x - e).abs() < 1e-5, "{:?}", result.x);
+        }
+        assert!(result.max_violation() <= 1e-7);
+    }
+
+    #[test]
+    fn keeps_rosenbrock_inside_a_disk() {
+        let result = sqp_minimize(
+            |x: &[f64]| (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2),
+            |x: &[f64]| {
+                vec![
+                    -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+                    200.0 * (x[1] - x[0] * x[0]),
+                ]
+            },
+            |_: &[f64]| vec![],
+            |x: &[f64]| vec![x[0] * x[0] + x[1] * x[1] - 1.0, -x[0]],
+            &[0.0, 0.0],
+            1e-7,
+            200,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!((result.x[0] - 0.786415).abs() < 1e-5 && (result.x[1] - 0.617698).abs() < 1e-5);
+        assert_eq!(result.inequality_violations.len(), 2);
+    }
+
+    #[test]
+    fn damped_update_stays_positive_definite() {
+        let mut b = vec![1.0, 0.0, 0.0, 1.0];
+        damped_bfgs_update(&mut b, 2, &[1.0, 0.0], &[-1.0, 0.5]);
+        assert!(crate::generals::linalg::cholesky(&b, 2).is_some());
+        // The secant condition holds for the damped gradient change.
+        let bs = [b[0], b[2]];
+        assert!((bs[0] - 0.2).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::generals::Float;
+
+/// What an optimizer should treat as "converged".
+///
+/// Optimizers accept anything convertible into a criterion. A bare number
+/// converts to [`ConvergenceCriteria::AbsoluteX`] and a pair `(atol, rtol)` to
+/// [`ConvergenceCriteria::MixedX`]. The default is a mixed x tolerance that
+/// works for minimizers of any magnitude, including zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConvergenceCriteria<T> {
+    /// The uncertainty in the minimizer (the last step, or the width of the
+    /// remaining interval for interval methods) is below the threshold.
+    AbsoluteX(T),
+    /// As [`ConvergenceCriteria::AbsoluteX`], relative to `|x|`.
+    RelativeX(T),
+    /// The uncertainty in the minimizer is below `atol + rtol * |x|`, so the
+    /// tolerance is relative for large `|x|` and absolute near zero.
+    MixedX { atol: T, rtol: T },
+    /// The best objective value improved by less than the threshold.
+    AbsoluteF(T),
+    /// As [`ConvergenceCriteria::AbsoluteF`], relative to `|f(x)|`.
+    RelativeF(T),
+    /// The magnitude of the (estimated) derivative at the best point is below
+    /// the threshold.
+    Gradient(T),
+}
+
+impl<T> From<T> for ConvergenceCriteria<T> {
+    fn from(tolerance: T) -> Self {
+        ConvergenceCriteria::AbsoluteX(tolerance)
+    }
+}
+
+impl<T> From<(T, T)> for ConvergenceCriteria<T> {
+    fn from((atol, rtol): (T, T)) -> Self {
+        ConvergenceCriteria::MixedX { atol, rtol }
+    }
+}
+
+impl<T: Float> Default for ConvergenceCriteria<T> {
+    /// [`ConvergenceCriteria::MixedX`] with `rtol` the square root of machine
+    /// precision, the best relative accuracy a minimizer can be located to, and
+    /// `atol` its two-thirds power, the resolution of finite difference
+    /// derivatives.
+    fn default() -> Self {
+        ConvergenceCriteria::MixedX {
+            atol: T::EPSILON.powf(T::from_f64(2.0 / 3.0)),
+            rtol: T::EPSILON.sqrt(),
+        }
+    }
+}
+
+/// Snapshot of an iteration, used to test a [`ConvergenceCriteria`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Progress<T> {
+    /// Best point.
+    pub x: T,
+    /// Uncertainty in `x`.
+    pub dx: T,
+    /// Objective value at `x`.
+    pub fx: T,
+    /// Improvement of the best objective value, if the best point moved.
+    pub df: Option<T>,
+    /// Derivative estimate at `x`.
+    pub slope: T,
+}
+
+impl<T: Float> ConvergenceCriteria<T> {
+    /// Whether `progress` satisfies the criterion.
+    pub(crate) fn is_met(&self, progress: &Progress<T>) -> bool {
+        match *self {
+            ConvergenceCriteria::AbsoluteX(tol) => progress.dx < tol,
+            ConvergenceCriteria::RelativeX(tol) => progress.dx < tol * progress.x.abs(),
+            ConvergenceCriteria::MixedX { atol, rtol } => {
+                progress.dx < atol + rtol * progress.x.abs()
+            }
+            ConvergenceCriteria::AbsoluteF(tol) => progress.df.is_some_and(|df| df.abs() < tol),
+            ConvergenceCriteria::RelativeF(tol) => progress
+                .df
+                .is_some_and(|df| df.abs() < tol * progress.fx.abs()),
+            ConvergenceCriteria::Gradient(tol) => progress.slope.abs() < tol,
+        }
+    }
+
+    /// Whether the criterion looks at objective values, so methods that do not
+    /// otherwise evaluate the objective at their iterates need to.
+    pub(crate) fn uses_f(&self) -> bool {
+        matches!(
+            self,
+            ConvergenceCriteria::AbsoluteF(_) | ConvergenceCriteria::RelativeF(_)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(df: Option<f64>) -> Progress<f64> {
+        Progress {
+            x: 100.0,
+            dx: 0.5,
+            fx: -20.0,
+            df,
+            slope: 1e-3,
+        }
+    }
+
+    #[test]
+    fn criteria_check_the_right_quantity() {
+        let p = progress(Some(0.1));
+        assert!(!ConvergenceCriteria::AbsoluteX(0.1).is_met(&p));
+        assert!(ConvergenceCriteria::RelativeX(0.01).is_met(&p));
+        assert!(ConvergenceCriteria::from((0.1, 0.005)).is_met(&p));
+        assert!(!ConvergenceCriteria::from((0.1, 0.003)).is_met(&p));
+        assert!(ConvergenceCriteria::AbsoluteF(0.2).is_met(&p));
+        assert!(!ConvergenceCriteria::RelativeF(0.001).is_met(&p));
+        assert!(ConvergenceCriteria::Gradient(0.01).is_met(&p));
+    }
+
+    #[test]
+    fn f_criteria_need_an_improvement() {
+        assert!(!ConvergenceCriteria::AbsoluteF(1.0).is_met(&progress(None)));
+    }
+
+    #[test]
+    fn default_is_mixed_and_works_at_zero() {
+        let criteria = ConvergenceCriteria::<f64>::default();
+        let at_zero = Progress {
+            x: 0.0,
+            dx: 1e-12,
+            ..progress(None)
+        };
+        assert!(criteria.is_met(&at_zero));
+        let large = Progress {
+            x: 1e6,
+            dx: 1e-3,
+            ..progress(None)
+        };
+        assert!(criteria.is_met(&large));
+        assert!(!criteria.is_met(&Progress { dx: 1.0, ..large }));
+    }
+
+    #[test]
+    fn bare_number_is_absolute_x() {
+        assert_eq!(
+            ConvergenceCriteria::from(1e-6),
+            ConvergenceCriteria::AbsoluteX(1e-6)
+        );
+    }
+}
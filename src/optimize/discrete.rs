@@ -0,0 +1,235 @@
+//! Minimization of functions of a single integer variable, such as grid sizes
+//! or batch counts.
+
+use crate::generals::Float;
+use crate::optimize::OptimizeError;
+
+/// Outcome of an integer minimization.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiscreteResult<T> {
+    /// Best integer found.
+    pub x: i64,
+    /// Objective value at `x`.
+    pub fx: T,
+    /// Number of objective evaluations performed.
+    pub fevals: usize,
+}
+
+/// Minimizes a unimodal `func` over the integers in `[a, b]` by Fibonacci
+/// search.
+///
+/// Like ternary search, each iteration compares two interior points and drops
+/// the part of the interval beyond the worse one, but the points sit at
+/// Fibonacci offsets so that the better one is reused by the next iteration.
+/// Each iteration therefore costs a single evaluation, and about
+/// `1.44·log2(b - a)` evaluations are needed in total. No point is evaluated
+/// twice. The result is exact for strictly unimodal objectives; on plateaus the
+/// search moves right on ties.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteObjective`] if `func` is NaN or infinite
+/// at an evaluated point.
+pub fn fibonacci_minimize_int<T, F>(
+    func: F,
+    a: i64,
+    b: i64,
+) -> Result<DiscreteResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(i64) -> T,
+{
+    let (a, b) = if a < b { (a, b) } else { (b, a) };
+    let mut fevals = 0;
+    // Points right of `b` pad the interval to a Fibonacci length and count as
+    // infinitely high without being evaluated.
+    let mut eval = |n: i128| {
+        if n > i128::from(b) {
+            return Ok(T::INFINITY);
+        }
+        let n = n as i64;
+        fevals += 1;
+        let fx = func(n);
+        if fx.is_finite() {
+            Ok(fx)
+        } else {
+            Err(OptimizeError::NonFiniteObjective { x: n as f64 })
+        }
+    };
+
+    // The open interval (lo, lo + fib[k]) holds every candidate.
+    let mut fib: Vec<i128> = vec![1, 1];
+    while fib[fib.len() - 1] < i128::from(b) - i128::from(a) + 2 {
+        fib.push(fib[fib.len() - 1] + fib[fib.len() - 2]);
+    }
+    let mut k = fib.len() - 1;
+    let mut lo = i128::from(a) - 1;
+    let mut inner = None;
+    while k >= 4 {
+        let (x1, x2) = (lo + fib[k - 2], lo + fib[k - 1]);
+        let (f1, f2) = match inner {
+            Some((x, fx)) if x == x1 => (fx, eval(x2)?),
+            Some((x, fx)) if x == x2 => (eval(x1)?, fx),
+            _ => (eval(x1)?, eval(x2)?),
+        };
+        if f1 < f2 {
+            inner = Some((x1, f1));
+        } else {
+            lo = x1;
+            inner = Some((x2, f2));
+        }
+        k -= 1;
+    }
+
+    let mut best: Option<(i128, T)> = None;
+    for x in lo + 1..lo + fib[k] {
+        let fx = match inner {
+            Some((xi, fi)) if xi == x => fi,
+            _ => eval(x)?,
+        };
+        if best.is_none_or(|(_, fb)| fx < fb) {
+            best = Some((x, fx));
+        }
+    }
+    let (x, fx) = best.expect("the final interval holds a candidate");
+    Ok(DiscreteResult {
+        x: x as i64,
+        fx,
+        fevals,
+    })
+}
+
+/// Minimizes a unimodal `func` over the integers in `[a, b]` by ternary
+/// search.
+///
+/// Runs until at most three candidates remain and then compares them directly,
+/// so the result is exact for strictly unimodal objectives. Unlike
+/// [`fibonacci_minimize_int`] it evaluates two fresh points per iteration.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteObjective`] if `func` is NaN or infinite
+/// at an evaluated point.
+pub fn ternary_minimize_int<T, F>(
+    func: F,
+    a: i64,
+    b: i64,
+) -> Result<DiscreteResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(i64) -> T,
+{
+    let mut fevals = 0;
+    let mut func = |n: i64| {
+        fevals += 1;
+        let fx = func(n);
+        if fx.is_finite() {
+            Ok(fx)
+        } else {
+            Err(OptimizeError::NonFiniteObjective { x: n as f64 })
+        }
+    };
+    // Widths and offsets in i128, since `b - a` overflows i64 on wide ranges.
+    let (mut a, mut b) = if a < b { (a, b) } else { (b, a) };
+    while i128::from(b) - i128::from(a) > 2 {
+        let third = ((i128::from(b) - i128::from(a)) / 3) as i64;
+        let m1 = a + third;
+        let m2 = b - third;
+        if func(m1)? < func(m2)? {
+            b = m2 - 1;
+        } else {
+            a = m1 + 1;
+        }
+    }
+    let mut best = (a, func(a)?);
+    for x in a + 1..=b {
+        let fx = func(x)?;
+        if fx < best.1 {
+            best = (x, fx);
+        }
+    }
+    let (x, fx) = best;
+    Ok(DiscreteResult { x, fx, fevals })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn integer_ternary_search() {
+        let calls = RefCell::new(0);
+        for target in -7..=7 {
+            *calls.borrow_mut() = 0;
+            let f = |n: i64| {
+                *calls.borrow_mut() += 1;
+                ((n - target) as f64).abs()
+            };
+            let result = ternary_minimize_int(f, -10, 10).unwrap();
+            assert_eq!((result.x, result.fx), (target, 0.0));
+            assert_eq!(result.fevals, *calls.borrow());
+        }
+    }
+
+    #[test]
+    fn ternary_search_handles_extreme_bounds() {
+        let result =
+            ternary_minimize_int(|n: i64| (n as f64 - 5.0).abs(), i64::MIN, i64::MAX).unwrap();
+        assert_eq!((result.x, result.fx), (5, 0.0));
+    }
+
+    #[test]
+    fn integer_search_reports_non_finite_values() {
+        let f = |n: i64| {
+            if n == 3 {
+                f64::NAN
+            } else {
+                (n as f64 - 3.0).abs()
+            }
+        };
+        assert_eq!(
+            ternary_minimize_int(f, 0, 9),
+            Err(OptimizeError::NonFiniteObjective { x: 3.0 })
+        );
+        assert_eq!(
+            fibonacci_minimize_int(f, 0, 9),
+            Err(OptimizeError::NonFiniteObjective { x: 3.0 })
+        );
+    }
+
+    #[test]
+    fn fibonacci_search_is_exact_on_every_interval() {
+        for (a, b) in [(0, 0), (0, 1), (-3, 4), (5, 17), (-100, 60)] {
+            for target in a - 2..=b + 2 {
+                let f = |n: i64| ((n - target) as f64).powi(2);
+                let result = fibonacci_minimize_int(f, a, b).unwrap();
+                assert_eq!(result.x, target.clamp(a, b), "[{a}, {b}] target {target}");
+                assert_eq!(result.fx, f(result.x));
+            }
+        }
+    }
+
+    #[test]
+    fn fibonacci_search_never_repeats_evaluations() {
+        let seen = RefCell::new(Vec::new());
+        let f = |n: i64| {
+            seen.borrow_mut().push(n);
+            (n as f64 - 123_456.0).abs()
+        };
+        let result = fibonacci_minimize_int(f, 0, 1_000_000).unwrap();
+        assert_eq!(result.x, 123_456);
+        let mut seen = seen.into_inner();
+        assert_eq!(result.fevals, seen.len());
+        assert!(result.fevals <= 32);
+        seen.sort_unstable();
+        seen.dedup();
+        assert_eq!(result.fevals, seen.len());
+    }
+
+    #[test]
+    fn fibonacci_search_handles_extreme_bounds() {
+        let result = fibonacci_minimize_int(|n: i64| (n as f64).abs(), i64::MIN, i64::MAX).unwrap();
+        assert_eq!(result.x, 0);
+    }
+}
@@ -0,0 +1,110 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+/// Errors reported by the optimizers in [`crate::optimize`].
+#[derive(Debug, Clone)]
+pub enum OptimizeError {
+    /// The middle point of a bracket does not lie strictly between the outer points.
+    InvalidBracket,
+    /// No starting bracket was given to a [`crate::optimize::scalar::ScalarMinimizer`].
+    MissingBracket,
+    /// The middle point of a bracket is not lower than both outer points.
+    BracketNotADip,
+    /// A new point coincides with a point already in the bracket.
+    DupeBoundForBracket,
+    /// Parabolic interpolation could not propose a point that shrinks the bracket.
+    DeadEnd,
+    /// A derivative used as a divisor evaluated to zero.
+    ZeroDerivative,
+    /// A line search direction does not point downhill.
+    NotADescentDirection,
+    /// A line search found no acceptable step length.
+    LineSearchFailed,
+    /// The objective returned NaN or an infinite value at `x`.
+    NonFiniteObjective { x: f64 },
+    /// The objective or its gradient returned NaN or an infinite value at the
+    /// point `x` of a multivariate problem.
+    NonFiniteAtPoint { x: Vec<f64> },
+    /// The starting point of an interior point method does not satisfy the
+    /// inequality constraints strictly.
+    InfeasibleStart,
+    /// The constraints of a problem cannot all be satisfied.
+    Infeasible,
+    /// The objective decreases without bound over the feasible region.
+    Unbounded,
+    /// A matrix that must be positive definite, such as the Hessian of a
+    /// quadratic program, is not.
+    NotPositiveDefinite,
+    /// The objective function itself failed; see [`crate::optimize::fallible`].
+    ObjectiveError(Arc<dyn Error + Send + Sync>),
+}
+
+impl PartialEq for OptimizeError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (OptimizeError::ObjectiveError(a), OptimizeError::ObjectiveError(b)) => {
+                Arc::ptr_eq(a, b)
+            }
+            (
+                OptimizeError::NonFiniteObjective { x: a },
+                OptimizeError::NonFiniteObjective { x: b },
+            ) => a == b || (a.is_nan() && b.is_nan()),
+            (
+                OptimizeError::NonFiniteAtPoint { x: a },
+                OptimizeError::NonFiniteAtPoint { x: b },
+            ) => a == b,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl fmt::Display for OptimizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptimizeError::InvalidBracket => {
+                write!(f, "bracket middle point is not between the outer points")
+            }
+            OptimizeError::MissingBracket => write!(f, "no starting bracket was given"),
+            OptimizeError::BracketNotADip => {
+                write!(f, "bracket middle point is not below both outer points")
+            }
+            OptimizeError::DupeBoundForBracket => {
+                write!(f, "new point coincides with an existing bracket point")
+            }
+            OptimizeError::DeadEnd => {
+                write!(
+                    f,
+                    "parabolic interpolation stalled without shrinking the bracket"
+                )
+            }
+            OptimizeError::ZeroDerivative => write!(f, "derivative evaluated to zero"),
+            OptimizeError::NotADescentDirection => {
+                write!(f, "search direction is not a descent direction")
+            }
+            OptimizeError::LineSearchFailed => write!(f, "line search found no acceptable step"),
+            OptimizeError::NonFiniteObjective { x } => {
+                write!(f, "objective value is not finite at x = {x}")
+            }
+            OptimizeError::NonFiniteAtPoint { x } => {
+                write!(f, "objective or gradient is not finite at x = {x:?}")
+            }
+            OptimizeError::InfeasibleStart => {
+                write!(f, "starting point is not strictly feasible")
+            }
+            OptimizeError::Infeasible => write!(f, "constraints cannot all be satisfied"),
+            OptimizeError::Unbounded => write!(f, "objective is unbounded"),
+            OptimizeError::NotPositiveDefinite => write!(f, "matrix is not positive definite"),
+            OptimizeError::ObjectiveError(error) => write!(f, "objective function failed: {error}"),
+        }
+    }
+}
+
+impl Error for OptimizeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            OptimizeError::ObjectiveError(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
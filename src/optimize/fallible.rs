@@ -0,0 +1,128 @@
+use std::cell::RefCell;
+use std::error::Error;
+use std::sync::Arc;
+
+use super::scalar::ScalarResult;
+use super::OptimizeError;
+use crate::generals::Float;
+
+/// Result types an optimizer run can produce, flattened by [`fallible`].
+pub trait Outcome {
+    type Output;
+
+    fn into_result(self) -> Result<Self::Output, OptimizeError>;
+}
+
+impl<T> Outcome for ScalarResult<T> {
+    type Output = ScalarResult<T>;
+
+    fn into_result(self) -> Result<ScalarResult<T>, OptimizeError> {
+        Ok(self)
+    }
+}
+
+impl<R> Outcome for Result<R, OptimizeError> {
+    type Output = R;
+
+    fn into_result(self) -> Result<R, OptimizeError> {
+        self
+    }
+}
+
+/// Runs an optimizer on an objective that can fail.
+///
+/// `run` receives an infallible view of `func` and should call the optimizer
+/// with it. The first error returned by `func` is reported as
+/// [`OptimizeError::ObjectiveError`], whatever the optimizer returned; after it
+/// the view yields NaN without calling `func` again.
+///
+/// ```
+/// use mathslib::optimize::{fallible, scalar::brent_minimize};
+///
+/// let table = [4.0, 1.0, 0.5, 2.0];
+/// let lookup = |x: f64| {
+///     table
+///         .get(x.round() as usize)
+///         .copied()
+///         .ok_or_else(|| std::fmt::Error)
+/// };
+/// let result = fallible(lookup, |f| brent_minimize(f, 0.0, 2.0, 3.0, 0.1, 50));
+/// assert!(result.is_ok());
+/// let result = fallible(lookup, |f| brent_minimize(f, 0.0, 2.0, 9.0, 0.1, 50));
+/// assert!(result.is_err());
+/// ```
+pub fn fallible<T, E, F, Run, R>(func: F, run: Run) -> Result<R::Output, OptimizeError>
+where
+    T: Float,
+    E: Error + Send + Sync + 'static,
+    F: Fn(T) -> Result<T, E>,
+    Run: FnOnce(&dyn Fn(T) -> T) -> R,
+    R: Outcome,
+{
+    let failure: RefCell<Option<E>> = RefCell::new(None);
+    let view = |x: T| {
+        if failure.borrow().is_some() {
+            return T::NAN;
+        }
+        match func(x) {
+            Ok(value) => value,
+            Err(error) => {
+                *failure.borrow_mut() = Some(error);
+                T::NAN
+            }
+        }
+    };
+    let outcome = run(&view);
+    match failure.into_inner() {
+        Some(error) => Err(OptimizeError::ObjectiveError(Arc::new(error))),
+        None => outcome.into_result(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::scalar::{bound_gr_minimize, brent_minimize};
+    use std::fmt;
+
+    #[derive(Debug, PartialEq)]
+    struct OutOfDomain(f64);
+
+    impl fmt::Display for OutOfDomain {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{} is outside the domain", self.0)
+        }
+    }
+
+    impl Error for OutOfDomain {}
+
+    fn log_objective(x: f64) -> Result<f64, OutOfDomain> {
+        if x > 0.0 {
+            Ok(x - x.ln())
+        } else {
+            Err(OutOfDomain(x))
+        }
+    }
+
+    #[test]
+    fn successful_objective_passes_result_through() {
+        let result =
+            fallible(log_objective, |f| bound_gr_minimize(f, 0.1, 3.0, 1e-8, 100)).unwrap();
+        assert!((result.x - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn objective_error_is_propagated() {
+        let error = fallible(log_objective, |f| {
+            brent_minimize(f, -1.0, 0.5, 3.0, 1e-8, 100)
+        })
+        .unwrap_err();
+        let OptimizeError::ObjectiveError(source) = &error else {
+            panic!("unexpected error {error:?}");
+        };
+        assert_eq!(
+            source.downcast_ref::<OutOfDomain>(),
+            Some(&OutOfDomain(-1.0))
+        );
+    }
+}
@@ -0,0 +1,510 @@
+//! Genetic algorithms: populations of candidate solutions improved by
+//! selection, crossover and mutation.
+//!
+//! The operators are traits, so any genome type can be evolved by supplying
+//! operators for it. Real-coded genomes, `Vec<T>`, and permutations,
+//! `Vec<usize>`, come with default operators. A run can be checkpointed and
+//! continued later from its [`Population`].
+
+mod operators;
+
+pub use operators::{
+    BlendCrossover, Crossover, GaussianMutation, Mutation, OrderCrossover, Selection, SwapMutation,
+    Tournament,
+};
+
+use std::thread;
+
+use crate::generals::random::Rng;
+use crate::generals::Float;
+
+/// A genetic algorithm with selection `S`, crossover `C` and mutation `M`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneticAlgorithm<S, C, M> {
+    /// How parents are picked.
+    pub selection: S,
+    /// How parents are combined.
+    pub crossover: C,
+    /// How children are perturbed.
+    pub mutation: M,
+    /// Number of individuals per generation.
+    pub population_size: usize,
+    /// Number of generations bred after the one a run starts from.
+    pub generations: usize,
+    /// Probability that two parents are crossed rather than copied.
+    pub crossover_rate: f64,
+    /// Number of the fittest individuals carried over unchanged.
+    pub elitism: usize,
+    /// Seed of the random generator.
+    pub seed: u64,
+}
+
+/// Outcome of [`GeneticAlgorithm::minimize`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneticResult<G, T> {
+    /// Fittest genome found.
+    pub best: G,
+    /// Fitness of `best`.
+    pub fitness: T,
+    /// Best fitness of every generation, starting with the initial one.
+    pub history: Vec<T>,
+    /// Number of fitness evaluations performed.
+    pub evaluations: usize,
+}
+
+impl<S, C, M> GeneticAlgorithm<S, C, M> {
+    /// A genetic algorithm with the given operators, evolving 50 individuals
+    /// for 100 generations, crossing 90% of the parents and keeping the single
+    /// fittest individual, seeded with 0.
+    pub fn new(selection: S, crossover: C, mutation: M) -> Self {
+        GeneticAlgorithm {
+            selection,
+            crossover,
+            mutation,
+            population_size: 50,
+            generations: 100,
+            crossover_rate: 0.9,
+            elitism: 1,
+            seed: 0,
+        }
+    }
+
+    /// Evolves `population_size` individuals per generation.
+    pub fn with_population_size(mut self, population_size: usize) -> Self {
+        self.population_size = population_size;
+        self
+    }
+
+    /// Breeds `generations` generations.
+    pub fn with_generations(mut self, generations: usize) -> Self {
+        self.generations = generations;
+        self
+    }
+
+    /// Crosses parents with probability `crossover_rate`.
+    pub fn with_crossover_rate(mut self, crossover_rate: f64) -> Self {
+        self.crossover_rate = crossover_rate;
+        self
+    }
+
+    /// Carries the `elitism` fittest individuals over unchanged.
+    pub fn with_elitism(mut self, elitism: usize) -> Self {
+        self.elitism = elitism;
+        self
+    }
+
+    /// Seeds the random generator with `seed`.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The initial population of a run: `population_size` genomes drawn by
+    /// `random_genome`, with the random generator seeded by `seed`.
+    pub fn populate<G, R>(&self, mut random_genome: R) -> Population<G>
+    where
+        R: FnMut(&mut Rng) -> G,
+    {
+        let mut rng = Rng::new(self.seed);
+        let genomes = (0..self.population_size.max(1))
+            .map(|_| random_genome(&mut rng))
+            .collect();
+        Population { genomes, rng }
+    }
+
+    /// Minimizes `fitness` over genomes of type `G`, starting from a
+    /// population drawn by `random_genome`.
+    ///
+    /// Every generation, parents picked by the selection are crossed with
+    /// probability `crossover_rate`, or else copied, and their children are
+    /// mutated, until the new generation is full; the `elitism` fittest
+    /// individuals of the old generation join it unchanged. Runs are
+    /// reproducible for a given seed. NaN fitness values count as infinitely
+    /// bad. See [`minimize_parallel`](Self::minimize_parallel) to evaluate the
+    /// fitness on several threads, and [`resume`](Self::resume) to continue a
+    /// run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathslib::optimize::genetic::{
+    ///     uniform_genome, BlendCrossover, GaussianMutation, GeneticAlgorithm, Tournament,
+    /// };
+    ///
+    /// let (lower, upper) = ([-5.0, -5.0], [5.0, 5.0]);
+    /// let ga = GeneticAlgorithm::new(
+    ///     Tournament::default(),
+    ///     BlendCrossover::default(),
+    ///     GaussianMutation::new(0.2, 0.1).with_bounds(&lower, &upper),
+    /// );
+    /// let result = ga.minimize(
+    ///     |x: &Vec<f64>| (x[0] - 1.0).powi(2) + (x[1] + 2.0).powi(2),
+    ///     uniform_genome(&lower, &upper),
+    /// );
+    /// assert!(result.fitness < 1e-4);
+    /// ```
+    pub fn minimize<G, T, F, R>(&self, fitness: F, random_genome: R) -> GeneticResult<G, T>
+    where
+        G: Clone,
+        T: Float,
+        F: Fn(&G) -> T,
+        R: FnMut(&mut Rng) -> G,
+        S: Selection<T>,
+        C: Crossover<G>,
+        M: Mutation<G>,
+    {
+        self.resume(fitness, &mut self.populate(random_genome))
+    }
+
+    /// Like [`minimize`](Self::minimize), but evaluates the fitness of each
+    /// generation on as many threads as the machine runs in parallel.
+    ///
+    /// Only the evaluations are spread over the threads; breeding stays on
+    /// the calling thread, so the result is the same as that of
+    /// [`minimize`](Self::minimize) for the same seed, whatever the number of
+    /// threads. This pays off when the fitness is expensive to evaluate.
+    pub fn minimize_parallel<G, T, F, R>(&self, fitness: F, random_genome: R) -> GeneticResult<G, T>
+    where
+        G: Clone + Sync,
+        T: Float + Send,
+        F: Fn(&G) -> T + Sync,
+        R: FnMut(&mut Rng) -> G,
+        S: Selection<T>,
+        C: Crossover<G>,
+        M: Mutation<G>,
+    {
+        self.resume_parallel(fitness, &mut self.populate(random_genome))
+    }
+
+    /// Continues the run whose current generation is `population`, breeding
+    /// `generations` more generations and updating `population` as it goes.
+    ///
+    /// The population is scored again first, which costs one evaluation per
+    /// individual but lets the run continue on a changed `fitness`. On the same
+    /// `fitness`, resuming the population a run ended with breeds the same
+    /// generations as one longer run would have. The history and evaluations
+    /// reported are those of this run only; `population_size` and `seed` are
+    /// not used, as the population keeps its size and random generator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathslib::optimize::genetic::{
+    ///     uniform_genome, BlendCrossover, GaussianMutation, GeneticAlgorithm, Tournament,
+    /// };
+    ///
+    /// let (lower, upper) = ([-5.0, -5.0], [5.0, 5.0]);
+    /// let ga = GeneticAlgorithm::new(
+    ///     Tournament::default(),
+    ///     BlendCrossover::default(),
+    ///     GaussianMutation::new(0.2, 0.1).with_bounds(&lower, &upper),
+    /// )
+    /// .with_generations(20);
+    /// let mut population = ga.populate(uniform_genome(&lower, &upper));
+    /// let first = ga.resume(|x: &Vec<f64>| x[0] * x[0] + x[1] * x[1], &mut population);
+    /// // Move the minimum and carry on from the population bred so far.
+    /// let moved = |x: &Vec<f64>| (x[0] - 0.5).powi(2) + x[1] * x[1];
+    /// let second = ga.with_generations(80).resume(moved, &mut population);
+    /// assert!(first.fitness < 1e-2 && second.fitness < 1e-4);
+    /// ```
+    pub fn resume<G, T, F>(&self, fitness: F, population: &mut Population<G>) -> GeneticResult<G, T>
+    where
+        G: Clone,
+        T: Float,
+        F: Fn(&G) -> T,
+        S: Selection<T>,
+        C: Crossover<G>,
+        M: Mutation<G>,
+    {
+        self.evolve(
+            |genomes: &[G]| genomes.iter().map(|g| score(&fitness, g)).collect(),
+            population,
+        )
+    }
+
+    /// Like [`resume`](Self::resume), but evaluates the fitness on several
+    /// threads as [`minimize_parallel`](Self::minimize_parallel) does.
+    pub fn resume_parallel<G, T, F>(
+        &self,
+        fitness: F,
+        population: &mut Population<G>,
+    ) -> GeneticResult<G, T>
+    where
+        G: Clone + Sync,
+        T: Float + Send,
+        F: Fn(&G) -> T + Sync,
+        S: Selection<T>,
+        C: Crossover<G>,
+        M: Mutation<G>,
+    {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let fitness = &fitness;
+        self.evolve(
+            |genomes: &[G]| {
+                let chunk = genomes.len().div_ceil(threads).max(1);
+                thread::scope(|scope| {
+                    let handles: Vec<_> = genomes
+                        .chunks(chunk)
+                        .map(|chunk| {
+                            scope.spawn(move || {
+                                chunk.iter().map(|g| score(fitness, g)).collect::<Vec<_>>()
+                            })
+                        })
+                        .collect();
+                    handles
+                        .into_iter()
+                        .flat_map(|handle| handle.join().expect("fitness function panicked"))
+                        .collect()
+                })
+            },
+            population,
+        )
+    }
+
+    /// Runs the algorithm from `population` with `evaluate` giving the fitness
+    /// of a batch of genomes, in order.
+    fn evolve<G, T, E>(
+        &self,
+        mut evaluate: E,
+        population: &mut Population<G>,
+    ) -> GeneticResult<G, T>
+    where
+        G: Clone,
+        T: Float,
+        E: FnMut(&[G]) -> Vec<T>,
+        S: Selection<T>,
+        C: Crossover<G>,
+        M: Mutation<G>,
+    {
+        let Population { genomes, rng } = population;
+        let size = genomes.len();
+        let mut scores = evaluate(genomes);
+        let mut evaluations = size;
+        let best_of = |scores: &[T]| {
+            (0..scores.len())
+                .min_by(|&a, &b| {
+                    scores[a]
+                        .partial_cmp(&scores[b])
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap_or(0)
+        };
+        let mut history = vec![scores[best_of(&scores)]];
+
+        for _ in 0..self.generations {
+            let mut ranked: Vec<usize> = (0..size).collect();
+            ranked.sort_by(|&a, &b| {
+                scores[a]
+                    .partial_cmp(&scores[b])
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            let elites = self.elitism.min(size);
+            let mut next: Vec<G> = ranked[..elites]
+                .iter()
+                .map(|&i| genomes[i].clone())
+                .collect();
+            let mut next_scores: Vec<T> = ranked[..elites].iter().map(|&i| scores[i]).collect();
+            while next.len() < size {
+                let a = &genomes[self.selection.select(&scores, rng)];
+                let b = &genomes[self.selection.select(&scores, rng)];
+                let (mut c, mut d) = if rng.next_f64() < self.crossover_rate {
+                    self.crossover.crossover(a, b, rng)
+                } else {
+                    (a.clone(), b.clone())
+                };
+                for child in [&mut c, &mut d] {
+                    self.mutation.mutate(child, rng);
+                }
+                next.push(c);
+                if next.len() < size {
+                    next.push(d);
+                }
+            }
+            // Breeding only looks at the old generation, so the children can
+            // be scored together once they are all bred.
+            next_scores.extend(evaluate(&next[elites..]));
+            evaluations += size - elites;
+            *genomes = next;
+            scores = next_scores;
+            history.push(scores[best_of(&scores)]);
+        }
+
+        let best = best_of(&scores);
+        GeneticResult {
+            fitness: scores[best],
+            best: genomes[best].clone(),
+            history,
+            evaluations,
+        }
+    }
+}
+
+/// A generation of a [`GeneticAlgorithm`] run together with its random
+/// generator, from which [`GeneticAlgorithm::resume`] continues the run.
+///
+/// The population can be cloned to checkpoint a long run, or built from
+/// chosen genomes with [`new`](Self::new) to warm-start one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Population<G> {
+    genomes: Vec<G>,
+    rng: Rng,
+}
+
+impl<G> Population<G> {
+    /// A population of `genomes`, bred with a random generator seeded by
+    /// `seed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `genomes` is empty.
+    pub fn new(genomes: Vec<G>, seed: u64) -> Self {
+        assert!(
+            !genomes.is_empty(),
+            "a population needs at least one genome"
+        );
+        Population {
+            genomes,
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// The genomes of the current generation.
+    pub fn genomes(&self) -> &[G] {
+        &self.genomes
+    }
+}
+
+/// Fitness of `genome`, with NaN counted as infinitely bad.
+fn score<G, T: Float>(fitness: impl Fn(&G) -> T, genome: &G) -> T {
+    let f = fitness(genome);
+    if f.is_nan() {
+        T::INFINITY
+    } else {
+        f
+    }
+}
+
+/// Draws real-coded genomes uniformly from the box `[lower, upper]`, as the
+/// initial population of [`GeneticAlgorithm::minimize`].
+pub fn uniform_genome<'a, T: Float>(
+    lower: &'a [T],
+    upper: &'a [T],
+) -> impl FnMut(&mut Rng) -> Vec<T> + 'a {
+    move |rng| {
+        lower
+            .iter()
+            .zip(upper)
+            .map(|(&lo, &hi)| rng.uniform(lo, hi))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_a_small_travelling_salesman_problem() {
+        // Cities on a circle; the best tour visits them in angular order.
+        let n = 10;
+        let cities: Vec<(f64, f64)> = (0..n)
+            .map(|i| {
+                let angle = std::f64::consts::TAU * ((i * 7) % n) as f64 / n as f64;
+                (angle.cos(), angle.sin())
+            })
+            .collect();
+        let length = |tour: &Vec<usize>| {
+            (0..n)
+                .map(|k| {
+                    let (a, b) = (cities[tour[k]], cities[tour[(k + 1) % n]]);
+                    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+                })
+                .sum::<f64>()
+        };
+        let ga = GeneticAlgorithm::new(
+            Tournament { size: 3 },
+            OrderCrossover,
+            SwapMutation { rate: 0.3 },
+        )
+        .with_population_size(100)
+        .with_generations(300)
+        .with_seed(2);
+        let result = ga.minimize(length, |rng| {
+            let mut tour: Vec<usize> = (0..n).collect();
+            rng.shuffle(&mut tour);
+            tour
+        });
+        let optimum = 2.0 * n as f64 * (std::f64::consts::PI / n as f64).sin();
+        assert!((result.fitness - optimum).abs() < 1e-9, "{result:?}");
+        assert_eq!(result.history.len(), 301);
+        assert!(result.history.windows(2).all(|w| w[1] <= w[0]));
+        assert_eq!(result.evaluations, 100 + 300 * 99);
+    }
+
+    #[test]
+    fn parallel_evaluation_gives_the_same_run() {
+        let (lower, upper) = ([-3.0; 4], [3.0; 4]);
+        let rastrigin = |x: &Vec<f64>| {
+            x.iter()
+                .map(|xi| xi * xi - 10.0 * (std::f64::consts::TAU * xi).cos() + 10.0)
+                .sum::<f64>()
+        };
+        let ga = GeneticAlgorithm::new(
+            Tournament::default(),
+            BlendCrossover::default(),
+            GaussianMutation::new(0.3, 0.2).with_bounds(&lower, &upper),
+        )
+        .with_population_size(37)
+        .with_generations(40)
+        .with_elitism(2)
+        .with_seed(11);
+        let serial = ga.minimize(rastrigin, uniform_genome(&lower, &upper));
+        let parallel = ga.minimize_parallel(rastrigin, uniform_genome(&lower, &upper));
+        assert_eq!(parallel, serial);
+        assert_eq!(serial.evaluations, 37 + 40 * 35);
+    }
+
+    #[test]
+    fn resuming_continues_the_same_run() {
+        let (lower, upper) = ([-3.0; 3], [3.0; 3]);
+        let sphere = |x: &Vec<f64>| x.iter().map(|xi| xi * xi).sum::<f64>();
+        let ga = GeneticAlgorithm::new(
+            Tournament::default(),
+            BlendCrossover::default(),
+            GaussianMutation::new(0.3, 0.2).with_bounds(&lower, &upper),
+        )
+        .with_population_size(20)
+        .with_elitism(2)
+        .with_seed(5);
+        let whole = ga
+            .clone()
+            .with_generations(30)
+            .minimize(sphere, uniform_genome(&lower, &upper));
+        let mut population = ga.populate(uniform_genome(&lower, &upper));
+        let first = ga
+            .clone()
+            .with_generations(10)
+            .resume(sphere, &mut population);
+        let checkpoint = population.clone();
+        let second = ga
+            .clone()
+            .with_generations(20)
+            .resume(sphere, &mut population);
+        assert_eq!(second.best, whole.best);
+        assert_eq!(first.history[..], whole.history[..11]);
+        assert_eq!(second.history[..], whole.history[10..]);
+        // Resuming rescores the population it starts from.
+        assert_eq!(second.evaluations, 20 + 20 * 18);
+        assert_ne!(population, checkpoint);
+        let again = ga
+            .with_generations(20)
+            .resume_parallel(sphere, &mut checkpoint.clone());
+        assert_eq!(again, second);
+    }
+
+    #[test]
+    #[should_panic(expected = "a population needs at least one genome")]
+    fn empty_population_panics() {
+        Population::<Vec<f64>>::new(Vec::new(), 0);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::generals::random::Rng;
+use crate::generals::Float;
+
+/// Picks parents from a population by their fitness, lower being better.
+pub trait Selection<T> {
+    /// Index of the chosen individual among those with the given `fitness`.
+    fn select(&self, fitness: &[T], rng: &mut Rng) -> usize;
+}
+
+/// Combines two parent genomes into two children.
+pub trait Crossover<G> {
+    /// Children of `a` and `b`.
+    fn crossover(&self, a: &G, b: &G, rng: &mut Rng) -> (G, G);
+}
+
+/// Randomly perturbs a genome.
+pub trait Mutation<G> {
+    /// Mutates `genome` in place.
+    fn mutate(&self, genome: &mut G, rng: &mut Rng);
+}
+
+/// Tournament selection: the fittest of `size` individuals drawn at random.
+///
+/// Larger tournaments favour the fittest individuals more strongly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tournament {
+    /// Number of individuals per tournament.
+    pub size: usize,
+}
+
+impl Default for Tournament {
+    /// Binary tournaments.
+    fn default() -> Self {
+        Tournament { size: 2 }
+    }
+}
+
+impl<T: Float> Selection<T> for Tournament {
+    fn select(&self, fitness: &[T], rng: &mut Rng) -> usize {
+        let mut best = rng.below(fitness.len());
+        for _ in 1..self.size {
+            let challenger = rng.below(fitness.len());
+            if fitness[challenger] < fitness[best] {
+                best = challenger;
+            }
+        }
+        best
+    }
+}
+
+/// Blend crossover (BLX-α) for real-coded genomes: each child gene is drawn
+/// uniformly from the interval spanned by the parent genes, widened by `alpha`
+/// times its length on both sides.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlendCrossover<T> {
+    /// Relative widening of the parent interval on each side.
+    pub alpha: T,
+}
+
+impl<T: Float> Default for BlendCrossover<T> {
+    /// `α = 0.5`, which keeps the spread of the population about constant.
+    fn default() -> Self {
+        BlendCrossover {
+            alpha: T::from_f64(0.5),
+        }
+    }
+}
+
+impl<T: Float> Crossover<Vec<T>> for BlendCrossover<T> {
+    fn crossover(&self, a: &Vec<T>, b: &Vec<T>, rng: &mut Rng) -> (Vec<T>, Vec<T>) {
+        let mut child = || -> Vec<T> {
+            a.iter()
+                .zip(b)
+                .map(|(&x, &y)| {
+                    let (low, high) = (x.min(y), x.max(y));
+                    let margin = self.alpha * (high - low);
+                    rng.uniform(low - margin, high + margin)
+                })
+                .collect()
+        };
+        (child(), child())
+    }
+}
+
+/// Gaussian mutation for real-coded genomes: each gene, with probability
+/// `rate`, gets normal noise of standard deviation `sigma` added, and is then
+/// clamped into its bounds if any are set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianMutation<T> {
+    /// Probability that a gene mutates.
+    pub rate: f64,
+    /// Standard deviation of the noise.
+    pub sigma: T,
+    /// Lower and upper bounds of the genes, if any.
+    pub bounds: Option<(Vec<T>, Vec<T>)>,
+}
+
+impl<T: Float> GaussianMutation<T> {
+    /// Mutates genes with probability `rate` by noise of scale `sigma`.
+    pub fn new(rate: f64, sigma: T) -> Self {
+        GaussianMutation {
+            rate,
+            sigma,
+            bounds: None,
+        }
+    }
+
+    /// Keeps every gene `i` within `[lower[i], upper[i]]`.
+    pub fn with_bounds(mut self, lower: &[T], upper: &[T]) -> Self {
+        self.bounds = Some((lower.to_vec(), upper.to_vec()));
+        self
+    }
+}
+
+impl<T: Float> Mutation<Vec<T>> for GaussianMutation<T> {
+    fn mutate(&self, genome: &mut Vec<T>, rng: &mut Rng) {
+        for (i, gene) in genome.iter_mut().enumerate() {
+            if rng.next_f64() < self.rate {
+                *gene += self.sigma * rng.normal::<T>();
+            }
+            if let Some((lower, upper)) = &self.bounds {
+                *gene = gene.max(lower[i]).min(upper[i]);
+            }
+        }
+    }
+}
+
+/// Order crossover (OX) for permutations: each child keeps a random slice of
+/// one parent and fills the remaining positions with the missing elements in
+/// the order they appear in the other parent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OrderCrossover;
+
+impl Crossover<Vec<usize>> for OrderCrossover {
+    fn crossover(&self, a: &Vec<usize>, b: &Vec<usize>, rng: &mut Rng) -> (Vec<usize>, Vec<usize>) {
+        let n = a.len();
+        if n < 2 {
+            return (a.clone(), b.clone());
+        }
+        let (i, j) = (rng.below(n), rng.below(n));
+        let (start, end) = (i.min(j), i.max(j) + 1);
+        let child = |keep: &[usize], fill: &[usize]| -> Vec<usize> {
+            let slice = &keep[start..end];
+            let mut rest = fill.iter().filter(|e| !slice.contains(e));
+            (0..n)
+                .map(|k| {
+                    if (start..end).contains(&k) {
+                        keep[k]
+                    } else {
+                        *rest
+                            .next()
+                            .expect("parents are permutations of the same elements")
+                    }
+                })
+                .collect()
+        };
+        (child(a, b), child(b, a))
+    }
+}
+
+/// Swap mutation for permutations: with probability `rate`, two random
+/// positions exchange their elements.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwapMutation {
+    /// Probability that a genome mutates.
+    pub rate: f64,
+}
+
+impl<E> Mutation<Vec<E>> for SwapMutation {
+    fn mutate(&self, genome: &mut Vec<E>, rng: &mut Rng) {
+        if genome.len() >= 2 && rng.next_f64() < self.rate {
+            let (i, j) = (rng.below(genome.len()), rng.below(genome.len()));
+            genome.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_crossover_yields_permutations() {
+        let mut rng = Rng::new(4);
+        let a: Vec<usize> = (0..8).collect();
+        let b = vec![7, 3, 1, 0, 6, 2, 5, 4];
+        for _ in 0..50 {
+            let (c, d) = OrderCrossover.crossover(&a, &b, &mut rng);
+            for mut child in [c, d] {
+                child.sort_unstable();
+                assert_eq!(child, a);
+            }
+        }
+    }
+
+    #[test]
+    fn real_coded_operators_respect_bounds() {
+        let mut rng = Rng::new(6);
+        let mutation = GaussianMutation::new(1.0, 10.0).with_bounds(&[-1.0, 0.0], &[1.0, 2.0]);
+        let (mut c, _) =
+            BlendCrossover::default().crossover(&vec![0.0, 1.0], &vec![0.5, 1.5], &mut rng);
+        assert!((-0.25..=0.75).contains(&c[0]) && (0.75..=1.75).contains(&c[1]));
+        mutation.mutate(&mut c, &mut rng);
+        assert!((-1.0..=1.0).contains(&c[0]) && (0.0..=2.0).contains(&c[1]));
+        assert_eq!(
+            Tournament { size: 100 }.select(&[3.0, 1.0, 2.0], &mut rng),
+            1
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use crate::generals::differential_methods::gradient;
+use crate::generals::Float;
+
+/// Outcome of [`check_gradient`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientCheck<T> {
+    /// The gradient returned by the user's closure.
+    pub analytic: Vec<T>,
+    /// The central difference approximation.
+    pub numerical: Vec<T>,
+    /// Indices of the components that disagree, in increasing order.
+    pub mismatches: Vec<usize>,
+}
+
+impl<T> GradientCheck<T> {
+    /// Whether every component agreed.
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Compares the gradient `grad(x)` of `func` against central differences with
+/// step `h`, component by component.
+///
+/// Component `i` disagrees if `|aᵢ - nᵢ| > tol·max(1, |aᵢ|, |nᵢ|)` for the
+/// analytic value `aᵢ` and the numerical one `nᵢ`, or if either is NaN, or if
+/// the analytic gradient has no component `i`. Extra analytic components are
+/// reported too. Steps around `∛ε` times the scale of `x` give numerical
+/// gradients accurate to about `∛ε²`, so `tol` around `1e-6` catches wrong
+/// formulas without flagging rounding. Costs `2n` evaluations of `func` and
+/// one of `grad`.
+///
+/// ```
+/// use mathslib::optimize::check_gradient;
+///
+/// let f = |x: &[f64]| x[0] * x[0] * x[1] + x[1].sin();
+/// // The second component forgot the x² term.
+/// let wrong = |x: &[f64]| vec![2.0 * x[0] * x[1], x[1].cos()];
+/// let check = check_gradient(f, wrong, &[1.5, 0.3], 1e-5, 1e-6);
+/// assert_eq!(check.mismatches, vec![1]);
+/// assert!((check.numerical[1] - (2.25 + 0.3f64.cos())).abs() < 1e-8);
+/// ```
+pub fn check_gradient<T, F, G>(func: F, grad: G, x: &[T], h: T, tol: T) -> GradientCheck<T>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    let analytic = grad(x);
+    let numerical = gradient(func, x, h);
+    let mismatches = (0..analytic.len().max(numerical.len()))
+        .filter(|&i| match (analytic.get(i), numerical.get(i)) {
+            (Some(&a), Some(&n)) => {
+                let scale = T::ONE.max(a.abs()).max(n.abs());
+                let difference = (a - n).abs();
+                difference > tol * scale || difference.is_nan()
+            }
+            _ => true,
+        })
+        .collect();
+    GradientCheck {
+        analytic,
+        numerical,
+        mismatches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rosenbrock(x: &[f64]) -> f64 {
+        (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2)
+    }
+
+    #[test]
+    fn accepts_a_correct_gradient() {
+        let grad = |x: &[f64]| {
+            vec![
+                -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+                200.0 * (x[1] - x[0] * x[0]),
+            ]
+        };
+        for x in [[-1.2, 1.0], [3.0, -4.0], [1.0, 1.0]] {
+            let check = check_gradient(rosenbrock, grad, &x, 1e-5, 1e-6);
+            assert!(check.is_ok(), "{check:?}");
+        }
+    }
+
+    #[test]
+    fn flags_wrong_nan_and_missing_components() {
+        let sign_error = |x: &[f64]| {
+            vec![
+                -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+                -200.0 * (x[1] - x[0] * x[0]),
+            ]
+        };
+        let check = check_gradient(rosenbrock, sign_error, &[0.5, 0.5], 1e-5, 1e-6);
+        assert_eq!(check.mismatches, vec![1]);
+        assert!(!check.is_ok());
+
+        let nan = |_: &[f64]| vec![f64::NAN, 0.0];
+        assert_eq!(
+            check_gradient(rosenbrock, nan, &[1.0, 1.0], 1e-5, 1e-6).mismatches,
+            vec![0]
+        );
+        let short = |_: &[f64]| vec![0.0];
+        assert_eq!(
+            check_gradient(rosenbrock, short, &[1.0, 1.0], 1e-5, 1e-6).mismatches,
+            vec![1]
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use std::cell::Cell;
+
+use super::{LeastSquaresResult, Loss};
+use crate::generals::linalg::{add_scaled, cholesky, cholesky_solve, dot};
+use crate::generals::Float;
+use crate::optimize::multivariate::{forward_difference_jacobian, non_finite_at, VectorCriteria};
+use crate::optimize::{Budget, OptimizeError};
+
+/// Fits `residuals` from `x0` with the Levenberg–Marquardt method, using a
+/// forward difference Jacobian.
+///
+/// Each Jacobian costs `n + 1` residual evaluations, which count towards
+/// `fevals`. See [`levenberg_marquardt_with_jacobian`] for the method and errors.
+pub fn levenberg_marquardt<T, R>(
+    residuals: R,
+    x0: &[T],
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<LeastSquaresResult<T>, OptimizeError>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+{
+    let jacobian_fevals = Cell::new(0);
+    let jacobian = |x: &[T]| {
+        jacobian_fevals.set(jacobian_fevals.get() + x.len() + 1);
+        forward_difference_jacobian(&residuals, x, &residuals(x))
+    };
+    let mut result = levenberg_marquardt_with_jacobian(&residuals, jacobian, x0, criteria, budget)?;
+    result.fevals += jacobian_fevals.get();
+    result.jevals = 0;
+    Ok(result)
+}
+
+/// Fits `residuals` from `x0` with the Levenberg–Marquardt method, given the
+/// Jacobian of the residuals as a vector of rows.
+///
+/// This minimizes the plain cost `½‖r‖²`; see [`LeastSquares`] for robust
+/// losses.
+///
+/// Each iteration solves `(JᵀJ + λI)·δ = -Jᵀr`. A large damping `λ` gives a
+/// short gradient descent step, a small one the Gauss–Newton step. The step is
+/// accepted if it lowers the cost, and `λ` is then adapted to how well the
+/// local quadratic model predicted the decrease (Nielsen's update): it shrinks
+/// by up to a factor of 3 after good predictions and grows by a doubling factor
+/// after rejected steps. Starting from `10⁻³` times the largest diagonal entry
+/// of `JᵀJ`, this needs no tuning. Convergence is tested after accepted steps.
+///
+/// The search stops with `converged == false` if `λ` overflows, which happens
+/// when no step can lower the cost at machine precision. Trial points with NaN
+/// or infinite residuals are rejected like steps that raise the cost.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteAtPoint`] if the residuals or Jacobian are
+/// NaN or infinite at `x0`, or if the Jacobian is at an accepted point.
+///
+/// [`LeastSquares`]: super::LeastSquares
+pub fn levenberg_marquardt_with_jacobian<T, R, J>(
+    residuals: R,
+    jacobian: J,
+    x0: &[T],
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<LeastSquaresResult<T>, OptimizeError>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Vec<Vec<T>>,
+{
+    fit(
+        residuals,
+        jacobian,
+        Loss::Linear,
+        x0,
+        criteria.into(),
+        budget.into(),
+    )
+}
+
+/// The Levenberg–Marquardt iteration on the cost `½Σρ(rᵢ²)` of `loss`.
+///
+/// Residual `i` is weighted by `ρ'(rᵢ²)` in both the gradient `JᵀWr` and the
+/// Gauss–Newton matrix `JᵀWJ`, so the model stays positive semidefinite.
+pub(crate) fn fit<T, R, J>(
+    residuals: R,
+    jacobian: J,
+    loss: Loss<T>,
+    x0: &[T],
+    criteria: VectorCriteria<T>,
+    budget: Budget,
+) -> Result<LeastSquaresResult<T>, OptimizeError>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Vec<Vec<T>>,
+{
+    let n = x0.len();
+    let half = T::from_f64(0.5);
+    let mut x = x0.to_vec();
+    let mut r = residuals(&x);
+    if r.iter().any(|ri| !ri.is_finite()) {
+        return Err(non_finite_at(&x));
+    }
+    let mut cost = half * loss.total(&r);
+    let mut jac = finite_jacobian(&jacobian, &x, r.len())?;
+    let (mut fevals, mut jevals) = (1, 1);
+    let (mut normal, mut gradient) = normal_equations(&jac, &r, loss, n);
+    let max_diagonal = (0..n).map(|i| normal[i * n + i]).fold(T::ZERO, T::max);
+    let mut lambda = T::from_f64(1e-3) * max_diagonal.max(T::EPSILON);
+    let mut growth = T::from_f64(2.0);
+
+    let mut iterations = 0;
+    let mut converged = criteria.is_met(&x, &[T::INFINITY], cost, None, &gradient);
+    while !converged && budget.allows(iterations, fevals) {
+        if gradient.iter().all(|&g| g == T::ZERO) {
+            converged = true;
+            break;
+        }
+        if !lambda.is_finite() {
+            break;
+        }
+        iterations += 1;
+        let mut damped = normal.clone();
+        for i in 0..n {
+            damped[i * n + i] += lambda;
+        }
+        let Some(l) = cholesky(&damped, n) else {
+            lambda *= growth;
+            growth *= T::from_f64(2.0);
+            continue;
+        };
+        let minus_gradient: Vec<T> = gradient.iter().map(|&g| -g).collect();
+        let delta = cholesky_solve(&l, n, &minus_gradient);
+        let trial = add_scaled(&x, T::ONE, &delta);
+        let trial_r = residuals(&trial);
+        fevals += 1;
+        let trial_cost = half * loss.total(&trial_r);
+        // Decrease predicted by the quadratic model, ½δᵀ(λδ - Jᵀr).
+        let predicted = half * dot(&delta, &add_scaled(&minus_gradient, lambda, &delta));
+        let rho = (cost - trial_cost) / predicted;
+        if !(trial_cost.is_finite() && trial_cost < cost) {
+            lambda *= growth;
+            growth *= T::from_f64(2.0);
+            continue;
+        }
+
+        let df = cost - trial_cost;
+        (x, r, cost) = (trial, trial_r, trial_cost);
+        jac = finite_jacobian(&jacobian, &x, r.len())?;
+        jevals += 1;
+        (normal, gradient) = normal_equations(&jac, &r, loss, n);
+        let shrink = T::ONE - (T::from_f64(2.0) * rho - T::ONE).powi(3);
+        lambda *= shrink.max(T::ONE / T::from_f64(3.0));
+        growth = T::from_f64(2.0);
+        converged = criteria.is_met(&x, &delta, cost, Some(df), &gradient);
+    }
+
+    Ok(LeastSquaresResult {
+        x,
+        residuals: r,
+        cost,
+        jacobian: jac,
+        iterations,
+        fevals,
+        jevals,
+        converged,
+    })
+}
+
+/// Evaluates `jacobian` at `x`, checking it has `m` finite rows.
+fn finite_jacobian<T, J>(jacobian: J, x: &[T], m: usize) -> Result<Vec<Vec<T>>, OptimizeError>
+where
+    T: Float,
+    J: Fn(&[T]) -> Vec<Vec<T>>,
+{
+    let jac = jacobian(x);
+    let valid = jac.len() == m
+        && jac
+            .iter()
+            .all(|row| row.len() == x.len() && row.iter().all(|v| v.is_finite()));
+    if valid {
+        Ok(jac)
+    } else {
+        Err(non_finite_at(x))
+    }
+}
+
+/// `JᵀWJ`, row-major, and `JᵀWr`, with the weights `W = diag(ρ'(rᵢ²))` of
+/// `loss`.
+fn normal_equations<T: Float>(
+    jac: &[Vec<T>],
+    r: &[T],
+    loss: Loss<T>,
+    n: usize,
+) -> (Vec<T>, Vec<T>) {
+    let mut normal = vec![T::ZERO; n * n];
+    let mut gradient = vec![T::ZERO; n];
+    for (row, &ri) in jac.iter().zip(r) {
+        let weight = loss.weight(ri * ri);
+        for i in 0..n {
+            gradient[i] += row[i] * weight * ri;
+            for j in 0..=i {
+                normal[i * n + j] += row[i] * weight * row[j];
+            }
+        }
+    }
+    for i in 0..n {
+        for j in 0..i {
+            normal[j * n + i] = normal[i * n + j];
+        }
+    }
+    (normal, gradient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::ConvergenceCriteria;
+
+    fn decay_data() -> (Vec<f64>, Vec<f64>) {
+        let t: Vec<f64> = (0..20).map(|i| 0.25 * i as f64).collect();
+        let y = t.iter().map(|&t| 3.0 * (-0.7 * t).exp() + 0.5).collect();
+        (t, y)
+    }
+
+    #[test]
+    fn fits_exponential_decay() {
+        let (t, y) = decay_data();
+        let residuals = |p: &[f64]| -> Vec<f64> {
+            t.iter()
+                .zip(&y)
+                .map(|(&t, &y)| p[0] * (-p[1] * t).exp() + p[2] - y)
+                .collect()
+        };
+        let jacobian = |p: &[f64]| -> Vec<Vec<f64>> {
+            t.iter()
+                .map(|&t| {
+                    let e = (-p[1] * t).exp();
+                    vec![e, -p[0] * t * e, 1.0]
+                })
+                .collect()
+        };
+        let result = levenberg_marquardt_with_jacobian(
+            residuals,
+            jacobian,
+            &[1.0, 0.1, 0.0],
+            ConvergenceCriteria::Gradient(1e-12),
+            200,
+        )
+        .unwrap();
+        assert!(result.converged);
+        for (p, expected) in result.x.iter().zip([3.0, 0.7, 0.5]) {
+            assert!((p - expected).abs() < 1e-8, "{:?}", result.x);
+        }
+        assert!(result.cost < 1e-20);
+        assert_eq!(result.jacobian, jacobian(&result.x));
+
+        let approximate =
+            levenberg_marquardt(residuals, &[1.0, 0.1, 0.0], (1e-12, 1e-10), 200).unwrap();
+        assert!(approximate.converged);
+        assert!((approximate.x[1] - 0.7).abs() < 1e-6);
+        assert_eq!(approximate.jevals, 0);
+        assert!(approximate.fevals > 3 * approximate.iterations / 2);
+    }
+
+    #[test]
+    fn solves_rosenbrock_as_least_squares() {
+        let residuals = |x: &[f64]| vec![10.0 * (x[1] - x[0] * x[0]), 1.0 - x[0]];
+        let result = levenberg_marquardt(residuals, &[-1.2, 1.0], 1e-12, 500).unwrap();
+        assert!(result.converged);
+        assert!((result.x[0] - 1.0).abs() < 1e-8 && (result.x[1] - 1.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn rejects_non_finite_start() {
+        let residuals = |x: &[f64]| vec![x[0].sqrt()];
+        assert_eq!(
+            levenberg_marquardt(residuals, &[-1.0], 1e-8, 10),
+            Err(OptimizeError::NonFiniteAtPoint { x: vec![-1.0] })
+        );
+    }
+}
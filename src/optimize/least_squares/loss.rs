@@ -0,0 +1,332 @@
+use std::cell::Cell;
+
+use super::levenberg_marquardt::fit;
+use super::LeastSquaresResult;
+use crate::generals::Float;
+use crate::optimize::multivariate::{forward_difference_jacobian, VectorCriteria};
+use crate::optimize::{Budget, OptimizeError};
+
+/// A loss `ρ` applied to the squared residuals, giving the cost `½Σρ(rᵢ²)`.
+///
+/// The robust losses grow like `s` for small squared residuals `s` and more
+/// slowly beyond the scale `c`, so that outliers pull less on the fit. All are
+/// `c²·ρ(s / c²)` for a loss `ρ` with `ρ(z) ≈ z` near zero.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Loss<T> {
+    /// Ordinary least squares, `ρ(s) = s`.
+    #[default]
+    Linear,
+    /// Quadratic up to residuals of size `c`, linear beyond:
+    /// `ρ(z) = z` for `z ≤ 1`, `2√z - 1` otherwise.
+    Huber(T),
+    /// A smooth approximation of the absolute value, `ρ(z) = 2(√(1 + z) - 1)`.
+    SoftL1(T),
+    /// Logarithmic growth, `ρ(z) = ln(1 + z)`. Large outliers barely affect
+    /// the fit, but the cost is not convex.
+    Cauchy(T),
+}
+
+impl<T: Float> Loss<T> {
+    /// `ρ(s)` of the squared residual `s`.
+    pub fn rho(&self, s: T) -> T {
+        let two = T::from_f64(2.0);
+        match *self {
+            Loss::Linear => s,
+            Loss::Huber(c) => {
+                let c2 = c * c;
+                if s <= c2 {
+                    s
+                } else {
+                    two * c * s.sqrt() - c2
+                }
+            }
+            Loss::SoftL1(c) => {
+                let c2 = c * c;
+                two * c2 * ((T::ONE + s / c2).sqrt() - T::ONE)
+            }
+            Loss::Cauchy(c) => {
+                let c2 = c * c;
+                c2 * (T::ONE + s / c2).ln()
+            }
+        }
+    }
+
+    /// `ρ'(s)`, the weight of a residual whose square is `s` in the gradient
+    /// and the Gauss–Newton approximation. Between 0 and 1.
+    pub fn weight(&self, s: T) -> T {
+        match *self {
+            Loss::Linear => T::ONE,
+            Loss::Huber(c) => {
+                if s <= c * c {
+                    T::ONE
+                } else {
+                    c / s.sqrt()
+                }
+            }
+            Loss::SoftL1(c) => T::ONE / (T::ONE + s / (c * c)).sqrt(),
+            Loss::Cauchy(c) => T::ONE / (T::ONE + s / (c * c)),
+        }
+    }
+
+    /// `Σρ(rᵢ²)`.
+    pub(crate) fn total(&self, residuals: &[T]) -> T {
+        residuals
+            .iter()
+            .fold(T::ZERO, |sum, &r| sum + self.rho(r * r))
+    }
+}
+
+/// A least-squares objective: residuals and the [`Loss`] applied to them.
+///
+/// The cost `½Σρ(rᵢ²)` has the gradient `JᵀWr` with the weights
+/// `W = diag(ρ'(rᵢ²))`, and the solvers approximate its Hessian by `JᵀWJ`,
+/// which drops the curvature of `ρ` to stay positive semidefinite. Each solve
+/// thus reweights the residuals at every iteration, like iteratively
+/// reweighted least squares, while the step acceptance looks at the robust
+/// cost itself.
+///
+/// ```
+/// use mathslib::optimize::least_squares::{LeastSquares, Loss};
+///
+/// // A line through (t, 2t + 1), with one wild point.
+/// let t = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+/// let y = [1.0, 3.0, 5.0, 40.0, 9.0, 11.0];
+/// let residuals =
+///     |p: &[f64]| -> Vec<f64> { t.iter().zip(&y).map(|(t, y)| p[0] * t + p[1] - y).collect() };
+/// let fit = LeastSquares::new(residuals)
+///     .with_loss(Loss::Cauchy(0.5))
+///     .levenberg_marquardt(&[0.0, 0.0], 1e-10, 200)
+///     .unwrap();
+/// assert!((fit.x[0] - 2.0f64).abs() < 0.01 && (fit.x[1] - 1.0f64).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LeastSquares<T, R> {
+    residuals: R,
+    loss: Loss<T>,
+}
+
+impl<T, R> LeastSquares<T, R>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+{
+    /// The plain least-squares objective of `residuals`.
+    pub fn new(residuals: R) -> Self {
+        LeastSquares {
+            residuals,
+            loss: Loss::Linear,
+        }
+    }
+
+    /// Applies `loss` to the residuals.
+    pub fn with_loss(mut self, loss: Loss<T>) -> Self {
+        self.loss = loss;
+        self
+    }
+
+    /// The loss applied to the residuals.
+    pub fn loss(&self) -> Loss<T> {
+        self.loss
+    }
+
+    /// The residuals at `x`.
+    pub fn residuals(&self, x: &[T]) -> Vec<T> {
+        (self.residuals)(x)
+    }
+
+    /// The cost `½Σρ(rᵢ²)` at `x`.
+    pub fn cost(&self, x: &[T]) -> T {
+        T::from_f64(0.5) * self.loss.total(&(self.residuals)(x))
+    }
+
+    /// The gradient `JᵀWr` of the cost at `x`, with a forward difference
+    /// Jacobian. Costs `n + 1` residual evaluations.
+    ///
+    /// Together with [`cost`](Self::cost) this hands the objective to the
+    /// [multivariate](crate::optimize::multivariate) optimizers.
+    pub fn gradient(&self, x: &[T]) -> Vec<T> {
+        let r = (self.residuals)(x);
+        let jacobian = forward_difference_jacobian(&self.residuals, x, &r);
+        let mut gradient = vec![T::ZERO; x.len()];
+        for (row, &ri) in jacobian.iter().zip(&r) {
+            let scaled = self.loss.weight(ri * ri) * ri;
+            for (g, &j) in gradient.iter_mut().zip(row) {
+                *g += j * scaled;
+            }
+        }
+        gradient
+    }
+
+    /// Fits the residuals from `x0` with the Levenberg–Marquardt method, using
+    /// a forward difference Jacobian. As
+    /// [`levenberg_marquardt`](super::levenberg_marquardt), but for the cost
+    /// of the loss, which is also the returned `cost`.
+    ///
+    /// # Errors
+    ///
+    /// See [`levenberg_marquardt_with_jacobian`](super::levenberg_marquardt_with_jacobian).
+    pub fn levenberg_marquardt(
+        &self,
+        x0: &[T],
+        criteria: impl Into<VectorCriteria<T>>,
+        budget: impl Into<Budget>,
+    ) -> Result<LeastSquaresResult<T>, OptimizeError> {
+        let residuals = &self.residuals;
+        let jacobian_fevals = Cell::new(0);
+        let jacobian = |x: &[T]| {
+            jacobian_fevals.set(jacobian_fevals.get() + x.len() + 1);
+            forward_difference_jacobian(residuals, x, &residuals(x))
+        };
+        let mut result = fit(
+            residuals,
+            jacobian,
+            self.loss,
+            x0,
+            criteria.into(),
+            budget.into(),
+        )?;
+        result.fevals += jacobian_fevals.get();
+        result.jevals = 0;
+        Ok(result)
+    }
+
+    /// Fits the residuals from `x0` with the Levenberg–Marquardt method, given
+    /// their Jacobian as a vector of rows. As
+    /// [`levenberg_marquardt_with_jacobian`](super::levenberg_marquardt_with_jacobian),
+    /// but for the cost of the loss, which is also the returned `cost`.
+    ///
+    /// # Errors
+    ///
+    /// See [`levenberg_marquardt_with_jacobian`](super::levenberg_marquardt_with_jacobian).
+    pub fn levenberg_marquardt_with_jacobian<J>(
+        &self,
+        jacobian: J,
+        x0: &[T],
+        criteria: impl Into<VectorCriteria<T>>,
+        budget: impl Into<Budget>,
+    ) -> Result<LeastSquaresResult<T>, OptimizeError>
+    where
+        J: Fn(&[T]) -> Vec<Vec<T>>,
+    {
+        fit(
+            &self.residuals,
+            jacobian,
+            self.loss,
+            x0,
+            criteria.into(),
+            budget.into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::line_search::StrongWolfe;
+    use crate::optimize::multivariate::lbfgs_minimize;
+    use crate::optimize::ConvergenceCriteria;
+
+    #[test]
+    fn losses_agree_with_least_squares_near_zero() {
+        let losses = [Loss::Huber(1.0), Loss::SoftL1(1.0), Loss::Cauchy(1.0)];
+        for loss in losses {
+            assert!((loss.rho(1e-6) - 1e-6).abs() < 1e-11, "{loss:?}");
+            assert!((loss.weight(1e-6) - 1.0).abs() < 1e-5, "{loss:?}");
+            assert!(loss.rho(100.0) < 20.0, "{loss:?}");
+            // The weight is the derivative of ρ.
+            let s = 3.0;
+            let derivative = (loss.rho(s + 1e-6) - loss.rho(s - 1e-6)) / 2e-6;
+            assert!((derivative - loss.weight(s)).abs() < 1e-8, "{loss:?}");
+        }
+        assert_eq!(Loss::Huber(2.0).rho(9.0), 2.0 * 2.0 * 3.0 - 4.0);
+        assert_eq!(Loss::<f64>::default().rho(9.0), 9.0);
+    }
+
+    fn line_with_outliers() -> (Vec<f64>, Vec<f64>) {
+        let t: Vec<f64> = (0..30).map(|i| 0.1 * i as f64).collect();
+        let mut y: Vec<f64> = t
+            .iter()
+            .enumerate()
+            .map(|(i, &t)| 2.0 * t + 1.0 + 0.01 * ((i * 7) % 5) as f64 - 0.02)
+            .collect();
+        y[4] += 15.0;
+        y[17] -= 10.0;
+        y[25] += 20.0;
+        (t, y)
+    }
+
+    #[test]
+    fn robust_losses_ignore_outliers() {
+        let (t, y) = line_with_outliers();
+        let residuals = |p: &[f64]| -> Vec<f64> {
+            t.iter()
+                .zip(&y)
+                .map(|(&t, &y)| p[0] * t + p[1] - y)
+                .collect()
+        };
+        let jacobian = |_: &[f64]| t.iter().map(|&t| vec![t, 1.0]).collect::<Vec<_>>();
+        let plain = LeastSquares::new(residuals)
+            .levenberg_marquardt(&[0.0, 0.0], 1e-12, 200)
+            .unwrap();
+        assert!((plain.x[1] - 1.0).abs() > 0.5, "{:?}", plain.x);
+
+        for loss in [Loss::Huber(0.1), Loss::SoftL1(0.1), Loss::Cauchy(0.1)] {
+            let problem = LeastSquares::new(residuals).with_loss(loss);
+            let result = problem
+                .levenberg_marquardt_with_jacobian(
+                    jacobian,
+                    &[0.0, 0.0],
+                    ConvergenceCriteria::Gradient(1e-6),
+                    500,
+                )
+                .unwrap();
+            assert!(result.converged, "{loss:?}");
+            assert!((result.x[0] - 2.0).abs() < 0.05, "{loss:?}: {:?}", result.x);
+            assert!((result.x[1] - 1.0).abs() < 0.05, "{loss:?}: {:?}", result.x);
+            assert!((result.cost - problem.cost(&result.x)).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn gradient_matches_the_cost() {
+        let (t, y) = line_with_outliers();
+        let residuals = |p: &[f64]| -> Vec<f64> {
+            t.iter()
+                .zip(&y)
+                .map(|(&t, &y)| p[0] * (p[1] * t).sin() - y)
+                .collect()
+        };
+        let problem = LeastSquares::new(residuals).with_loss(Loss::SoftL1(0.5));
+        let x = [1.5, 0.7];
+        let gradient = problem.gradient(&x);
+        for (i, g) in gradient.iter().enumerate() {
+            let mut shifted = x;
+            shifted[i] += 1e-6;
+            let forward = problem.cost(&shifted);
+            shifted[i] -= 2e-6;
+            let central = (forward - problem.cost(&shifted)) / 2e-6;
+            assert!((g - central).abs() < 1e-5 * central.abs().max(1.0), "{i}");
+        }
+
+        // The same objective through a general optimizer.
+        let lm = problem
+            .levenberg_marquardt(&x, ConvergenceCriteria::Gradient(1e-7), 500)
+            .unwrap();
+        let lbfgs = lbfgs_minimize(
+            |p: &[f64]| problem.cost(p),
+            |p: &[f64]| problem.gradient(p),
+            &x,
+            5,
+            StrongWolfe::default(),
+            ConvergenceCriteria::Gradient(1e-7),
+            5000,
+        )
+        .unwrap();
+        assert!(
+            (lm.cost - lbfgs.fx).abs() < 1e-8,
+            "{} vs {}",
+            lm.cost,
+            lbfgs.fx
+        );
+    }
+}
@@ -0,0 +1,39 @@
+//! Nonlinear least squares: minimization of `½‖r(x)‖²` for a vector of
+//! residuals `r`, as in curve fitting.
+//!
+//! The solvers take the same [`VectorCriteria`] as the multivariate
+//! optimizers, applied to the cost `½‖r‖²` and its gradient `Jᵀr`. A
+//! [`LeastSquares`] objective replaces the squares by a robust [`Loss`] when
+//! the data has outliers.
+
+mod levenberg_marquardt;
+mod loss;
+
+pub use levenberg_marquardt::{levenberg_marquardt, levenberg_marquardt_with_jacobian};
+pub use loss::{LeastSquares, Loss};
+
+#[cfg(doc)]
+use crate::optimize::multivariate::VectorCriteria;
+
+/// Outcome of a least-squares fit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeastSquaresResult<T> {
+    /// Best parameters found.
+    pub x: Vec<T>,
+    /// Residuals at `x`.
+    pub residuals: Vec<T>,
+    /// Cost `½‖r(x)‖²`, or `½Σρ(rᵢ²)` with a robust [`Loss`].
+    pub cost: T,
+    /// Jacobian of the residuals at `x`, one row per residual. Useful for
+    /// parameter covariance estimates, `σ²·(JᵀJ)⁻¹`.
+    pub jacobian: Vec<Vec<T>>,
+    /// Number of iterations performed, including rejected steps.
+    pub iterations: usize,
+    /// Number of residual evaluations performed, including those spent on
+    /// finite difference Jacobians.
+    pub fevals: usize,
+    /// Number of analytic Jacobian evaluations performed.
+    pub jevals: usize,
+    /// Whether the convergence criterion was met before the budget ran out.
+    pub converged: bool,
+}
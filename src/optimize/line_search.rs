@@ -0,0 +1,650 @@
+//! Step length selection along a search direction, for multivariate
+//! optimizers.
+
+use crate::generals::linalg::{add_scaled, dot};
+use crate::generals::Float;
+use crate::optimize::scalar::bound_gr_minimize;
+use crate::optimize::{Budget, ConvergenceCriteria, OptimizeError};
+
+/// Outcome of a line search from `x` along `d`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineStep<T> {
+    /// Accepted step length.
+    pub alpha: T,
+    /// The new point `x + alpha * d`.
+    pub x: Vec<T>,
+    /// Objective value at the new point.
+    pub fx: T,
+    /// Gradient at the new point, if the search evaluated it.
+    pub gradient: Option<Vec<T>>,
+    /// Number of objective evaluations performed.
+    pub fevals: usize,
+    /// Number of gradient evaluations performed.
+    pub gevals: usize,
+}
+
+/// A strategy for choosing the step length along a descent direction.
+///
+/// Multivariate optimizers are generic over this trait, so the strategy can be
+/// swapped without changing the optimizer.
+pub trait LineSearch<T: Float> {
+    /// Searches from `x`, where the objective is `fx` and its gradient
+    /// `gradient`, along the direction `d`. `grad` evaluates the gradient at
+    /// other points, for strategies that need it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptimizeError::NotADescentDirection`] if `gradient · d` is not
+    /// negative, and [`OptimizeError::LineSearchFailed`] if no acceptable step
+    /// is found.
+    fn search<F, G>(
+        &self,
+        func: F,
+        grad: G,
+        x: &[T],
+        fx: T,
+        gradient: &[T],
+        d: &[T],
+    ) -> Result<LineStep<T>, OptimizeError>
+    where
+        F: Fn(&[T]) -> T,
+        G: Fn(&[T]) -> Vec<T>;
+}
+
+/// Slope of the objective along `d`, checked to be negative.
+fn descent_slope<T: Float>(gradient: &[T], d: &[T]) -> Result<T, OptimizeError> {
+    let slope = dot(gradient, d);
+    if slope >= T::ZERO || slope.is_nan() {
+        return Err(OptimizeError::NotADescentDirection);
+    }
+    Ok(slope)
+}
+
+/// Backtracking line search enforcing the Armijo sufficient decrease
+/// condition `f(x + α·d) ≤ f(x) + c1·α·∇f(x)·d`.
+///
+/// Starting from `initial_step`, the step is multiplied by `shrink` until the
+/// condition holds. Trial points where the objective is NaN or infinite count
+/// as too far and are shrunk away from. The gradient is never evaluated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backtracking<T> {
+    /// First step length tried.
+    pub initial_step: T,
+    /// Fraction of the decrease predicted by the gradient that must be achieved.
+    pub c1: T,
+    /// Factor in `(0, 1)` applied to the step after each rejection.
+    pub shrink: T,
+    /// Most step lengths tried before giving up.
+    pub max_iter: usize,
+}
+
+impl<T: Float> Default for Backtracking<T> {
+    /// Unit initial step, `c1 = 1e-4`, halving, and at most 50 trials.
+    fn default() -> Self {
+        Backtracking {
+            initial_step: T::ONE,
+            c1: T::from_f64(1e-4),
+            shrink: T::from_f64(0.5),
+            max_iter: 50,
+        }
+    }
+}
+
+impl<T: Float> Backtracking<T> {
+    /// Starts from `initial_step` instead of 1.
+    pub fn with_initial_step(mut self, initial_step: T) -> Self {
+        self.initial_step = initial_step;
+        self
+    }
+
+    /// Requires the fraction `c1` of the predicted decrease.
+    pub fn with_c1(mut self, c1: T) -> Self {
+        self.c1 = c1;
+        self
+    }
+
+    /// Multiplies rejected steps by `shrink`.
+    pub fn with_shrink(mut self, shrink: T) -> Self {
+        self.shrink = shrink;
+        self
+    }
+
+    /// Tries at most `max_iter` step lengths.
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+}
+
+impl<T: Float> LineSearch<T> for Backtracking<T> {
+    fn search<F, G>(
+        &self,
+        func: F,
+        _grad: G,
+        x: &[T],
+        fx: T,
+        gradient: &[T],
+        d: &[T],
+    ) -> Result<LineStep<T>, OptimizeError>
+    where
+        F: Fn(&[T]) -> T,
+        G: Fn(&[T]) -> Vec<T>,
+    {
+        let slope = descent_slope(gradient, d)?;
+        let mut alpha = self.initial_step;
+        for fevals in 1..=self.max_iter {
+            let trial = add_scaled(x, alpha, d);
+            let f_trial = func(&trial);
+            if f_trial.is_finite() && f_trial <= fx + self.c1 * alpha * slope {
+                return Ok(LineStep {
+                    alpha,
+                    x: trial,
+                    fx: f_trial,
+                    gradient: None,
+                    fevals,
+                    gevals: 0,
+                });
+            }
+            alpha *= self.shrink;
+        }
+        Err(OptimizeError::LineSearchFailed)
+    }
+}
+
+/// Line search enforcing the strong Wolfe conditions
+/// `f(x + α·d) ≤ f(x) + c1·α·∇f(x)·d` and `|∇f(x + α·d)·d| ≤ c2·|∇f(x)·d|`.
+///
+/// The step grows from `initial_step` until it brackets an acceptable length,
+/// which a zoom phase then narrows down with safeguarded quadratic
+/// interpolation (Nocedal and Wright, algorithms 3.5 and 3.6). The curvature
+/// condition guarantees `s·y > 0` for the step `s` and gradient change `y`,
+/// which quasi-Newton updates need to stay positive definite. Trial points
+/// where the objective is NaN or infinite count as too far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrongWolfe<T> {
+    /// First step length tried.
+    pub initial_step: T,
+    /// Sufficient decrease parameter, in `(0, c2)`.
+    pub c1: T,
+    /// Curvature parameter, in `(c1, 1)`.
+    pub c2: T,
+    /// Longest step length tried.
+    pub max_step: T,
+    /// Most step lengths tried, over both phases, before giving up.
+    pub max_iter: usize,
+}
+
+impl<T: Float> Default for StrongWolfe<T> {
+    /// Unit initial step, `c1 = 1e-4`, `c2 = 0.9` as suits quasi-Newton
+    /// methods, steps up to `1e10`, and at most 50 trials.
+    fn default() -> Self {
+        StrongWolfe {
+            initial_step: T::ONE,
+            c1: T::from_f64(1e-4),
+            c2: T::from_f64(0.9),
+            max_step: T::from_f64(1e10),
+            max_iter: 50,
+        }
+    }
+}
+
+impl<T: Float> StrongWolfe<T> {
+    /// Starts from `initial_step` instead of 1.
+    pub fn with_initial_step(mut self, initial_step: T) -> Self {
+        self.initial_step = initial_step;
+        self
+    }
+
+    /// Uses the sufficient decrease parameter `c1`.
+    pub fn with_c1(mut self, c1: T) -> Self {
+        self.c1 = c1;
+        self
+    }
+
+    /// Uses the curvature parameter `c2`; about 0.1 suits conjugate gradients.
+    pub fn with_c2(mut self, c2: T) -> Self {
+        self.c2 = c2;
+        self
+    }
+
+    /// Never tries steps longer than `max_step`.
+    pub fn with_max_step(mut self, max_step: T) -> Self {
+        self.max_step = max_step;
+        self
+    }
+
+    /// Tries at most `max_iter` step lengths.
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+}
+
+/// A trial step length with the objective, and possibly the slope, there.
+#[derive(Debug, Clone, Copy)]
+struct Trial<T> {
+    alpha: T,
+    phi: T,
+    slope: Option<T>,
+}
+
+impl<T: Float> LineSearch<T> for StrongWolfe<T> {
+    fn search<F, G>(
+        &self,
+        func: F,
+        grad: G,
+        x: &[T],
+        fx: T,
+        gradient: &[T],
+        d: &[T],
+    ) -> Result<LineStep<T>, OptimizeError>
+    where
+        F: Fn(&[T]) -> T,
+        G: Fn(&[T]) -> Vec<T>,
+    {
+        let slope0 = descent_slope(gradient, d)?;
+        let (mut fevals, mut gevals) = (0, 0);
+        let sufficient =
+            |t: &Trial<T>| t.phi.is_finite() && t.phi <= fx + self.c1 * t.alpha * slope0;
+        let flat = |slope: T| slope.abs() <= -self.c2 * slope0;
+        let accept = |alpha: T, point: Vec<T>, phi: T, g: Vec<T>, fevals, gevals| LineStep {
+            alpha,
+            x: point,
+            fx: phi,
+            gradient: Some(g),
+            fevals,
+            gevals,
+        };
+
+        // Bracketing phase: `lo` is the best acceptable-decrease step so far.
+        let mut lo = Trial {
+            alpha: T::ZERO,
+            phi: fx,
+            slope: Some(slope0),
+        };
+        let mut alpha = self.initial_step.min(self.max_step);
+        let mut hi = None;
+        while fevals < self.max_iter {
+            let point = add_scaled(x, alpha, d);
+            let phi = func(&point);
+            fevals += 1;
+            let trial = Trial {
+                alpha,
+                phi,
+                slope: None,
+            };
+            if !sufficient(&trial) || (fevals > 1 && phi >= lo.phi) {
+                hi = Some(trial);
+                break;
+            }
+            let g = grad(&point);
+            gevals += 1;
+            let slope = dot(&g, d);
+            if flat(slope) {
+                return Ok(accept(alpha, point, phi, g, fevals, gevals));
+            }
+            let trial = Trial {
+                slope: Some(slope),
+                ..trial
+            };
+            if slope >= T::ZERO {
+                hi = Some(lo);
+                lo = trial;
+                break;
+            }
+            lo = trial;
+            if alpha >= self.max_step {
+                return Err(OptimizeError::LineSearchFailed);
+            }
+            alpha = (T::from_f64(2.0) * alpha).min(self.max_step);
+        }
+        let mut hi = hi.ok_or(OptimizeError::LineSearchFailed)?;
+
+        // Zoom phase: the interval between `lo` and `hi` holds acceptable steps.
+        while fevals < self.max_iter {
+            let alpha = interpolate(&lo, &hi);
+            if alpha == lo.alpha || alpha == hi.alpha {
+                break;
+            }
+            let point = add_scaled(x, alpha, d);
+            let phi = func(&point);
+            fevals += 1;
+            let trial = Trial {
+                alpha,
+                phi,
+                slope: None,
+            };
+            if !sufficient(&trial) || phi >= lo.phi {
+                hi = trial;
+                continue;
+            }
+            let g = grad(&point);
+            gevals += 1;
+            let slope = dot(&g, d);
+            if flat(slope) {
+                return Ok(accept(alpha, point, phi, g, fevals, gevals));
+            }
+            if slope * (hi.alpha - lo.alpha) >= T::ZERO {
+                hi = lo;
+            }
+            lo = Trial {
+                slope: Some(slope),
+                ..trial
+            };
+        }
+        Err(OptimizeError::LineSearchFailed)
+    }
+}
+
+/// Minimizer of the quadratic through `lo` (value and slope) and `hi` (value),
+/// kept at least a tenth of the interval away from either end; bisection if the
+/// interpolation fails.
+fn interpolate<T: Float>(lo: &Trial<T>, hi: &Trial<T>) -> T {
+    let width = hi.alpha - lo.alpha;
+    let tenth = T::from_f64(0.1) * width;
+    let (low, high) = if width > T::ZERO {
+        (lo.alpha + tenth, hi.alpha - tenth)
+    } else {
+        (hi.alpha - tenth, lo.alpha + tenth)
+    };
+    let slope = lo.slope.unwrap_or(T::NAN);
+    let curvature = hi.phi - lo.phi - slope * width;
+    let alpha = lo.alpha - slope * width * width / (T::from_f64(2.0) * curvature);
+    if curvature > T::ZERO && alpha.is_finite() && low <= alpha && alpha <= high {
+        alpha
+    } else {
+        lo.alpha + T::from_f64(0.5) * width
+    }
+}
+
+/// "Line search" that always takes the same step length.
+///
+/// The step is taken whether or not it lowers the objective, so convergence
+/// depends on the step being small enough for the problem, as in classic
+/// gradient descent with a fixed learning rate. The gradient is never
+/// evaluated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedStep<T> {
+    /// Step length taken along every direction.
+    pub alpha: T,
+}
+
+impl<T> FixedStep<T> {
+    /// Always steps `alpha` times the search direction.
+    pub fn new(alpha: T) -> Self {
+        FixedStep { alpha }
+    }
+}
+
+impl<T: Float> LineSearch<T> for FixedStep<T> {
+    fn search<F, G>(
+        &self,
+        func: F,
+        _grad: G,
+        x: &[T],
+        _fx: T,
+        gradient: &[T],
+        d: &[T],
+    ) -> Result<LineStep<T>, OptimizeError>
+    where
+        F: Fn(&[T]) -> T,
+        G: Fn(&[T]) -> Vec<T>,
+    {
+        descent_slope(gradient, d)?;
+        let x = add_scaled(x, self.alpha, d);
+        let fx = func(&x);
+        Ok(LineStep {
+            alpha: self.alpha,
+            x,
+            fx,
+            gradient: None,
+            fevals: 1,
+            gevals: 0,
+        })
+    }
+}
+
+/// Line search that minimizes `φ(α) = f(x + α·d)` over `[0, max_step]` with
+/// golden section search ([`bound_gr_minimize`]).
+///
+/// On quadratic-like objectives this finds the optimal step length, at the cost
+/// of many more evaluations than an inexact search. `φ` must be unimodal on the
+/// interval for the step to be optimal; otherwise some local minimizer is
+/// returned. The gradient is never evaluated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExactLineSearch<T> {
+    /// Longest step length considered.
+    pub max_step: T,
+    /// Convergence criteria of the scalar search, in terms of `α`.
+    pub criteria: ConvergenceCriteria<T>,
+    /// Budget of the scalar search.
+    pub budget: Budget,
+}
+
+impl<T: Float> Default for ExactLineSearch<T> {
+    /// Steps up to 1, the default convergence criteria and 100 iterations.
+    fn default() -> Self {
+        ExactLineSearch {
+            max_step: T::ONE,
+            criteria: ConvergenceCriteria::default(),
+            budget: Budget::new(100),
+        }
+    }
+}
+
+impl<T: Float> ExactLineSearch<T> {
+    /// Considers steps up to `max_step`.
+    pub fn with_max_step(mut self, max_step: T) -> Self {
+        self.max_step = max_step;
+        self
+    }
+
+    /// Stops the scalar search once `criteria` is met.
+    pub fn with_criteria(mut self, criteria: impl Into<ConvergenceCriteria<T>>) -> Self {
+        self.criteria = criteria.into();
+        self
+    }
+
+    /// Limits the scalar search to `budget`.
+    pub fn with_budget(mut self, budget: impl Into<Budget>) -> Self {
+        self.budget = budget.into();
+        self
+    }
+}
+
+impl<T: Float> LineSearch<T> for ExactLineSearch<T> {
+    /// # Errors
+    ///
+    /// Besides the errors of [`LineSearch::search`], returns
+    /// [`OptimizeError::NonFiniteObjective`] if `φ` is NaN or infinite at an
+    /// evaluated step, and [`OptimizeError::LineSearchFailed`] if the best step
+    /// does not decrease the objective.
+    fn search<F, G>(
+        &self,
+        func: F,
+        _grad: G,
+        x: &[T],
+        fx: T,
+        gradient: &[T],
+        d: &[T],
+    ) -> Result<LineStep<T>, OptimizeError>
+    where
+        F: Fn(&[T]) -> T,
+        G: Fn(&[T]) -> Vec<T>,
+    {
+        descent_slope(gradient, d)?;
+        let phi = |alpha: T| func(&add_scaled(x, alpha, d));
+        let result = bound_gr_minimize(phi, T::ZERO, self.max_step, self.criteria, self.budget)?;
+        if result.fx >= fx {
+            return Err(OptimizeError::LineSearchFailed);
+        }
+        Ok(LineStep {
+            alpha: result.x,
+            x: add_scaled(x, result.x, d),
+            fx: result.fx,
+            gradient: None,
+            fevals: result.fevals,
+            gevals: 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rosenbrock(x: &[f64]) -> f64 {
+        (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2)
+    }
+
+    fn rosenbrock_gradient(x: &[f64]) -> Vec<f64> {
+        vec![
+            -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+            200.0 * (x[1] - x[0] * x[0]),
+        ]
+    }
+
+    #[test]
+    fn backtracking_satisfies_armijo() {
+        let x = [-1.2, 1.0];
+        let g = rosenbrock_gradient(&x);
+        let d: Vec<f64> = g.iter().map(|gi| -gi).collect();
+        let fx = rosenbrock(&x);
+        let search = Backtracking::default();
+        let step = search
+            .search(rosenbrock, rosenbrock_gradient, &x, fx, &g, &d)
+            .unwrap();
+        assert!(step.alpha < 1.0);
+        assert!(step.fx <= fx + search.c1 * step.alpha * dot(&g, &d));
+        assert_eq!(step.fx, rosenbrock(&step.x));
+        // The previous, twice as long, step was rejected.
+        let longer = add_scaled(&x, 2.0 * step.alpha, &d);
+        assert!(rosenbrock(&longer) > fx + search.c1 * 2.0 * step.alpha * dot(&g, &d));
+    }
+
+    #[test]
+    fn backtracking_steps_away_from_non_finite_values() {
+        let f = |x: &[f64]| if x[0] > 1.0 { f64::NAN } else { -x[0] };
+        let step = Backtracking::default()
+            .with_initial_step(8.0)
+            .search(f, |_: &[f64]| vec![-1.0], &[0.0], 0.0, &[-1.0], &[1.0])
+            .unwrap();
+        assert_eq!(step.alpha, 1.0);
+    }
+
+    #[test]
+    fn rejects_ascent_directions() {
+        let search = Backtracking::default();
+        let result = search.search(
+            rosenbrock,
+            rosenbrock_gradient,
+            &[0.0, 0.0],
+            1.0,
+            &[-2.0, 0.0],
+            &[-1.0, 0.0],
+        );
+        assert_eq!(result, Err(OptimizeError::NotADescentDirection));
+    }
+
+    #[test]
+    fn strong_wolfe_satisfies_both_conditions() {
+        let search = StrongWolfe::default().with_c2(0.1);
+        for x in [[-1.2, 1.0], [0.5, -0.3], [2.0, 2.0]] {
+            let g = rosenbrock_gradient(&x);
+            let d: Vec<f64> = g.iter().map(|gi| -gi).collect();
+            let fx = rosenbrock(&x);
+            let step = search
+                .search(rosenbrock, rosenbrock_gradient, &x, fx, &g, &d)
+                .unwrap();
+            let slope0 = dot(&g, &d);
+            assert!(step.fx <= fx + search.c1 * step.alpha * slope0);
+            let new_gradient = step.gradient.unwrap();
+            assert_eq!(new_gradient, rosenbrock_gradient(&step.x));
+            assert!(dot(&new_gradient, &d).abs() <= -search.c2 * slope0);
+        }
+    }
+
+    #[test]
+    fn strong_wolfe_expands_short_initial_steps() {
+        // Exact minimizer along d is at alpha = 3.
+        let f = |x: &[f64]| (x[0] - 3.0).powi(2);
+        let grad = |x: &[f64]| vec![2.0 * (x[0] - 3.0)];
+        let step = StrongWolfe::default()
+            .with_initial_step(0.01)
+            .with_c2(0.1)
+            .search(f, grad, &[0.0], 9.0, &[-6.0], &[1.0])
+            .unwrap();
+        assert!((step.alpha - 3.0).abs() < 0.3);
+        assert!(step.gevals >= 1);
+    }
+
+    #[test]
+    fn strategies_are_interchangeable() {
+        fn descend<L: LineSearch<f64>>(search: L) -> f64 {
+            let mut x = vec![-1.2, 1.0];
+            for _ in 0..200 {
+                let g = rosenbrock_gradient(&x);
+                let d: Vec<f64> = g.iter().map(|gi| -gi).collect();
+                let step = search
+                    .search(rosenbrock, rosenbrock_gradient, &x, rosenbrock(&x), &g, &d)
+                    .unwrap();
+                x = step.x;
+            }
+            rosenbrock(&x)
+        }
+        let start = rosenbrock(&[-1.2, 1.0]);
+        assert!(descend(Backtracking::default()) < start);
+        assert!(descend(StrongWolfe::default()) < start);
+    }
+
+    #[test]
+    fn exact_search_finds_optimal_step_on_quadratic() {
+        // f = (x² + 10y²) / 2; along -g the optimal step is g·g / g·Ag.
+        let f = |x: &[f64]| 0.5 * (x[0] * x[0] + 10.0 * x[1] * x[1]);
+        let grad = |x: &[f64]| vec![x[0], 10.0 * x[1]];
+        let x = [3.0, 1.0];
+        let g = grad(&x);
+        let d = [-g[0], -g[1]];
+        let optimal = (g[0] * g[0] + g[1] * g[1]) / (g[0] * g[0] + 10.0 * g[1] * g[1]);
+        let step = ExactLineSearch::default()
+            .with_criteria(1e-10)
+            .search(f, grad, &x, f(&x), &g, &d)
+            .unwrap();
+        assert!((step.alpha - optimal).abs() < 1e-8);
+        assert_eq!(step.x, add_scaled(&x, step.alpha, &d));
+        assert_eq!(step.gevals, 0);
+    }
+
+    #[test]
+    fn exact_search_fails_without_decrease() {
+        let f = |x: &[f64]| if x[0] > 0.0 { 1.0 + x[0] } else { 1.0 };
+        let result = ExactLineSearch::default().search(
+            f,
+            |_: &[f64]| vec![-1.0],
+            &[0.0],
+            1.0,
+            &[-1.0],
+            &[1.0],
+        );
+        assert_eq!(result, Err(OptimizeError::LineSearchFailed));
+    }
+
+    #[test]
+    fn fixed_step_ignores_the_objective() {
+        let f = |x: &[f64]| x[0] * x[0];
+        let step = FixedStep::new(3.0)
+            .search(
+                f,
+                |x: &[f64]| vec![2.0 * x[0]],
+                &[1.0],
+                1.0,
+                &[2.0],
+                &[-2.0],
+            )
+            .unwrap();
+        assert_eq!(
+            (step.alpha, step.x[0], step.fx, step.fevals),
+            (3.0, -5.0, 25.0, 1)
+        );
+    }
+}
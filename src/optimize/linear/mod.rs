@@ -0,0 +1,322 @@
+//! Linear programming: minimization or maximization of a linear objective
+//! subject to linear constraints on nonnegative variables.
+//!
+//! Problems are built up with [`LpProblem`], variable by variable and
+//! constraint by constraint, and solved with the simplex method. A solution
+//! can be re-optimized with the dual simplex method after constraints are
+//! added, without starting over, as branch and bound or cutting plane methods
+//! do many times.
+//!
+//! ```
+//! use mathslib::optimize::linear::{LpProblem, Relation};
+//!
+//! // Maximize 3x + 2y with x + y ≤ 4, x + 3y ≤ 6 and x ≤ 3.
+//! let mut lp = LpProblem::<f64>::maximize();
+//! let x = lp.add_variable("x", 3.0);
+//! let y = lp.add_variable("y", 2.0);
+//! lp.add_constraint(&[(x, 1.0), (y, 1.0)], Relation::LessEq, 4.0)
+//!     .add_constraint(&[(x, 1.0), (y, 3.0)], Relation::LessEq, 6.0)
+//!     .add_constraint(&[(x, 1.0)], Relation::LessEq, 3.0);
+//! let solution = lp.solve().unwrap();
+//! assert!((solution.objective - 11.0).abs() < 1e-12);
+//! assert!((solution.value(x) - 3.0).abs() < 1e-12);
+//!
+//! // Require y ≥ 1.5 and re-optimize from the previous basis.
+//! lp.add_constraint(&[(y, 1.0)], Relation::GreaterEq, 1.5);
+//! let solution = lp.resolve(&solution).unwrap();
+//! assert!((solution.value(x) - 1.5).abs() < 1e-12);
+//! assert!((solution.objective - 7.5).abs() < 1e-12);
+//! ```
+
+mod simplex;
+
+use simplex::{RowKind, Tableau};
+
+use crate::generals::Float;
+use crate::optimize::OptimizeError;
+
+/// Whether a linear program minimizes or maximizes its objective.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sense {
+    Minimize,
+    Maximize,
+}
+
+/// How the left-hand side of a constraint relates to its right-hand side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    /// `a·x ≤ b`.
+    LessEq,
+    /// `a·x ≥ b`.
+    GreaterEq,
+    /// `a·x = b`.
+    Equal,
+}
+
+/// A variable of an [`LpProblem`], as returned by
+/// [`LpProblem::add_variable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Variable(usize);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Constraint<T> {
+    terms: Vec<(Variable, T)>,
+    relation: Relation,
+    rhs: T,
+}
+
+/// A linear program over named nonnegative variables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpProblem<T> {
+    sense: Sense,
+    names: Vec<String>,
+    costs: Vec<T>,
+    constraints: Vec<Constraint<T>>,
+}
+
+/// Optimal solution of an [`LpProblem`].
+///
+/// Besides the values, a solution keeps the final simplex tableau, from which
+/// [`LpProblem::resolve`] continues.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpSolution<T> {
+    /// Values of the variables, in the order they were added.
+    pub values: Vec<T>,
+    /// Optimal objective value.
+    pub objective: T,
+    /// Number of simplex pivots performed, including those of the solutions
+    /// this one was re-optimized from.
+    pub pivots: usize,
+    tableau: Tableau<T>,
+    constraints: usize,
+}
+
+impl<T> LpSolution<T> {
+    /// Value of `variable`.
+    pub fn value(&self, variable: Variable) -> T
+    where
+        T: Copy,
+    {
+        self.values[variable.0]
+    }
+}
+
+impl<T: Float> LpProblem<T> {
+    /// An empty problem with objective sense `sense`.
+    pub fn new(sense: Sense) -> Self {
+        LpProblem {
+            sense,
+            names: Vec::new(),
+            costs: Vec::new(),
+            constraints: Vec::new(),
+        }
+    }
+
+    /// An empty minimization problem.
+    pub fn minimize() -> Self {
+        Self::new(Sense::Minimize)
+    }
+
+    /// An empty maximization problem.
+    pub fn maximize() -> Self {
+        Self::new(Sense::Maximize)
+    }
+
+    /// Adds a nonnegative variable called `name` with objective coefficient
+    /// `cost`.
+    pub fn add_variable(&mut self, name: impl Into<String>, cost: T) -> Variable {
+        self.names.push(name.into());
+        self.costs.push(cost);
+        Variable(self.costs.len() - 1)
+    }
+
+    /// The first variable called `name`, if any.
+    pub fn variable(&self, name: &str) -> Option<Variable> {
+        self.names.iter().position(|n| n == name).map(Variable)
+    }
+
+    /// Name of `variable`.
+    pub fn name(&self, variable: Variable) -> &str {
+        &self.names[variable.0]
+    }
+
+    /// Adds the constraint `Σ coefficient·variable (relation) rhs` over the
+    /// `terms`. Variables appearing more than once have their coefficients
+    /// summed.
+    pub fn add_constraint(
+        &mut self,
+        terms: &[(Variable, T)],
+        relation: Relation,
+        rhs: T,
+    ) -> &mut Self {
+        self.constraints.push(Constraint {
+            terms: terms.to_vec(),
+            relation,
+            rhs,
+        });
+        self
+    }
+
+    /// Solves the problem with the two-phase primal simplex method.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptimizeError::Infeasible`] if no point satisfies the
+    /// constraints and [`OptimizeError::Unbounded`] if the objective can be
+    /// improved without bound.
+    pub fn solve(&self) -> Result<LpSolution<T>, OptimizeError> {
+        let rows: Vec<(Vec<T>, RowKind, T)> = self
+            .constraints
+            .iter()
+            .map(|c| {
+                let kind = match c.relation {
+                    Relation::LessEq => RowKind::LessEq,
+                    Relation::GreaterEq => RowKind::GreaterEq,
+                    Relation::Equal => RowKind::Equal,
+                };
+                (self.dense(c), kind, c.rhs)
+            })
+            .collect();
+        let tableau = Tableau::solve(&self.min_costs(), &rows, self.tolerance())?;
+        Ok(self.solution(tableau))
+    }
+
+    /// Re-optimizes `previous`, a solution of this problem before the latest
+    /// constraints were added, with the dual simplex method.
+    ///
+    /// The old optimal basis stays dual feasible when constraints are added,
+    /// so the dual simplex method usually needs only a few pivots to restore
+    /// feasibility. Equality constraints are added as a pair of inequalities.
+    /// If variables were added since `previous` was found, the problem is
+    /// solved from scratch instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptimizeError::Infeasible`] if the added constraints leave no
+    /// feasible point.
+    pub fn resolve(&self, previous: &LpSolution<T>) -> Result<LpSolution<T>, OptimizeError> {
+        if previous.values.len() != self.costs.len()
+            || previous.constraints > self.constraints.len()
+        {
+            return self.solve();
+        }
+        let mut tableau = previous.tableau.clone();
+        for c in &self.constraints[previous.constraints..] {
+            let a = self.dense(c);
+            let negated: Vec<T> = a.iter().map(|&v| -v).collect();
+            match c.relation {
+                Relation::LessEq => tableau.add_row(&a, c.rhs)?,
+                Relation::GreaterEq => tableau.add_row(&negated, -c.rhs)?,
+                Relation::Equal => {
+                    tableau.add_row(&a, c.rhs)?;
+                    tableau.add_row(&negated, -c.rhs)?;
+                }
+            }
+        }
+        Ok(self.solution(tableau))
+    }
+
+    /// Coefficients of `constraint` for every variable.
+    fn dense(&self, constraint: &Constraint<T>) -> Vec<T> {
+        let mut a = vec![T::ZERO; self.costs.len()];
+        for &(Variable(j), coefficient) in &constraint.terms {
+            a[j] += coefficient;
+        }
+        a
+    }
+
+    /// Objective coefficients of the equivalent minimization problem.
+    fn min_costs(&self) -> Vec<T> {
+        match self.sense {
+            Sense::Minimize => self.costs.clone(),
+            Sense::Maximize => self.costs.iter().map(|&c| -c).collect(),
+        }
+    }
+
+    /// Threshold below which tableau entries count as zero, relative to the
+    /// largest number in the problem.
+    fn tolerance(&self) -> T {
+        let scale = self
+            .constraints
+            .iter()
+            .flat_map(|c| c.terms.iter().map(|t| t.1).chain([c.rhs]))
+            .chain(self.costs.iter().copied())
+            .fold(T::ONE, |max, v| max.max(v.abs()));
+        T::from_f64(1e3) * T::EPSILON * scale
+    }
+
+    fn solution(&self, tableau: Tableau<T>) -> LpSolution<T> {
+        let objective = match self.sense {
+            Sense::Minimize => tableau.objective(),
+            Sense::Maximize => -tableau.objective(),
+        };
+        LpSolution {
+            values: tableau.solution(self.costs.len()),
+            objective,
+            pivots: tableau.pivots,
+            tableau,
+            constraints: self.constraints.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimizes_a_diet() {
+        // Cheapest mix of two foods meeting three nutrient requirements.
+        let mut lp = LpProblem::minimize();
+        let bread = lp.add_variable("bread", 2.0);
+        let milk = lp.add_variable("milk", 3.5);
+        lp.add_constraint(&[(bread, 4.0), (milk, 8.0)], Relation::GreaterEq, 32.0)
+            .add_constraint(&[(bread, 1.0), (milk, 5.0)], Relation::GreaterEq, 10.0)
+            .add_constraint(&[(bread, 2.0), (milk, 1.0)], Relation::GreaterEq, 8.0);
+        let solution = lp.solve().unwrap();
+        assert!((solution.value(bread) - 8.0 / 3.0).abs() < 1e-12);
+        assert!((solution.value(milk) - 8.0 / 3.0).abs() < 1e-12);
+        assert!((solution.objective - 44.0 / 3.0).abs() < 1e-12);
+        assert_eq!(lp.variable("milk"), Some(milk));
+        assert_eq!(lp.name(bread), "bread");
+    }
+
+    #[test]
+    fn reports_infeasible_and_unbounded_problems() {
+        let mut lp = LpProblem::maximize();
+        let x = lp.add_variable("x", 1.0);
+        let y = lp.add_variable("y", 0.0);
+        lp.add_constraint(&[(x, 1.0), (y, -1.0)], Relation::LessEq, 1.0);
+        assert_eq!(lp.solve(), Err(OptimizeError::Unbounded));
+        lp.add_constraint(&[(y, 1.0)], Relation::Equal, 2.0);
+        let solution = lp.solve().unwrap();
+        assert!((solution.objective - 3.0).abs() < 1e-12);
+        lp.add_constraint(&[(x, 1.0), (y, 1.0)], Relation::GreaterEq, 6.0);
+        assert_eq!(lp.resolve(&solution), Err(OptimizeError::Infeasible));
+        assert_eq!(lp.solve(), Err(OptimizeError::Infeasible));
+    }
+
+    #[test]
+    fn resolve_matches_a_fresh_solve() {
+        let mut lp = LpProblem::maximize();
+        let vars: Vec<Variable> = (0..3)
+            .map(|i| lp.add_variable(format!("x{i}"), [5.0, 4.0, 3.0][i]))
+            .collect();
+        let terms = |coefficients: [f64; 3]| -> Vec<(Variable, f64)> {
+            vars.iter().copied().zip(coefficients).collect()
+        };
+        lp.add_constraint(&terms([2.0, 3.0, 1.0]), Relation::LessEq, 5.0)
+            .add_constraint(&terms([4.0, 1.0, 2.0]), Relation::LessEq, 11.0)
+            .add_constraint(&terms([3.0, 4.0, 2.0]), Relation::LessEq, 8.0);
+        let first = lp.solve().unwrap();
+        assert!((first.objective - 13.0).abs() < 1e-12);
+        lp.add_constraint(&terms([1.0, 0.0, 0.0]), Relation::LessEq, 1.5)
+            .add_constraint(&terms([0.0, 1.0, 1.0]), Relation::Equal, 1.0);
+        let warm = lp.resolve(&first).unwrap();
+        let cold = lp.solve().unwrap();
+        assert!((warm.objective - cold.objective).abs() < 1e-12);
+        for (w, c) in warm.values.iter().zip(&cold.values) {
+            assert!((w - c).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use crate::generals::Float;
+use crate::optimize::OptimizeError;
+
+/// A dense simplex tableau for minimizing `cᵀx` subject to `Ax = b`, `x ≥ 0`.
+///
+/// Row `i` holds `B⁻¹A` and `B⁻¹b` for the basis `B`, whose `i`-th column is
+/// `basis[i]`, and `reduced` the reduced costs `c - c_B·B⁻¹A`. Columns marked
+/// in `blocked`, the artificial variables once phase 1 is over, never enter
+/// the basis again.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Tableau<T> {
+    rows: Vec<Vec<T>>,
+    rhs: Vec<T>,
+    basis: Vec<usize>,
+    reduced: Vec<T>,
+    /// Minus the objective value of the current basic solution.
+    value: T,
+    blocked: Vec<bool>,
+    tolerance: T,
+    pub(crate) pivots: usize,
+}
+
+/// A row `coefficients·x (≤ | ≥ | =) rhs` of a linear program over the first
+/// columns of a tableau.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RowKind {
+    LessEq,
+    GreaterEq,
+    Equal,
+}
+
+impl<T: Float> Tableau<T> {
+    /// Solves `min cᵀx` subject to `rows` with the two-phase primal simplex
+    /// method, starting from slack and artificial variables.
+    pub(crate) fn solve(
+        costs: &[T],
+        rows: &[(Vec<T>, RowKind, T)],
+        tolerance: T,
+    ) -> Result<Self, OptimizeError> {
+        let n = costs.len();
+        // Flip rows so that every right-hand side is nonnegative.
+        let rows: Vec<(Vec<T>, RowKind, T)> = rows
+            .iter()
+            .map(|(a, kind, b)| {
+                if *b < T::ZERO {
+                    let flipped = match kind {
+                        RowKind::LessEq => RowKind::GreaterEq,
+                        RowKind::GreaterEq => RowKind::LessEq,
+                        RowKind::Equal => RowKind::Equal,
+                    };
+                    (a.iter().map(|&v| -v).collect(), flipped, -*b)
+                } else {
+                    (a.clone(), *kind, *b)
+                }
+            })
+            .collect();
+        let slacks = rows.iter().filter(|r| r.1 != RowKind::Equal).count();
+        let artificials = rows.iter().filter(|r| r.1 != RowKind::LessEq).count();
+        let columns = n + slacks + artificials;
+        let mut tableau = Tableau {
+            rows: Vec::with_capacity(rows.len()),
+            rhs: Vec::with_capacity(rows.len()),
+            basis: Vec::with_capacity(rows.len()),
+            reduced: vec![T::ZERO; columns],
+            value: T::ZERO,
+            blocked: vec![false; columns],
+            tolerance,
+            pivots: 0,
+        };
+        let (mut slack, mut artificial) = (n, n + slacks);
+        for (a, kind, b) in &rows {
+            let mut row = vec![T::ZERO; columns];
+            row[..n].copy_from_slice(a);
+            match kind {
+                RowKind::LessEq => {
+                    row[slack] = T::ONE;
+                    tableau.basis.push(slack);
+                    slack += 1;
+                }
+                RowKind::GreaterEq => {
+                    row[slack] = -T::ONE;
+                    slack += 1;
+                }
+                RowKind::Equal => {}
+            }
+            if *kind != RowKind::LessEq {
+                row[artificial] = T::ONE;
+                tableau.basis.push(artificial);
+                artificial += 1;
+            }
+            tableau.rows.push(row);
+            tableau.rhs.push(*b);
+        }
+
+        // Phase 1: minimize the sum of the artificial variables.
+        if artificials > 0 {
+            let mut phase1 = vec![T::ZERO; columns];
+            for cost in &mut phase1[n + slacks..] {
+                *cost = T::ONE;
+            }
+            tableau.set_costs(&phase1);
+            tableau.primal()?;
+            if -tableau.value > tolerance {
+                return Err(OptimizeError::Infeasible);
+            }
+            for blocked in &mut tableau.blocked[n + slacks..] {
+                *blocked = true;
+            }
+            tableau.drive_out_blocked();
+        }
+
+        let mut phase2 = costs.to_vec();
+        phase2.resize(columns, T::ZERO);
+        tableau.set_costs(&phase2);
+        tableau.primal()?;
+        Ok(tableau)
+    }
+
+    /// Values of the first `n` variables.
+    pub(crate) fn solution(&self, n: usize) -> Vec<T> {
+        let mut x = vec![T::ZERO; n];
+        for (&j, &b) in self.basis.iter().zip(&self.rhs) {
+            if j < n {
+                x[j] = b;
+            }
+        }
+        x
+    }
+
+    /// Objective value of the current basic solution.
+    pub(crate) fn objective(&self) -> T {
+        -self.value
+    }
+
+    /// Adds the constraint `a·x ≤ b` on the first `a.len()` variables with a
+    /// new basic slack variable, and restores optimality with the dual simplex
+    /// method. The tableau must be optimal, hence dual feasible, beforehand.
+    pub(crate) fn add_row(&mut self, a: &[T], b: T) -> Result<(), OptimizeError> {
+        for row in &mut self.rows {
+            row.push(T::ZERO);
+        }
+        self.reduced.push(T::ZERO);
+        self.blocked.push(false);
+        let columns = self.reduced.len();
+        let mut row = vec![T::ZERO; columns];
+        row[..a.len()].copy_from_slice(a);
+        row[columns - 1] = T::ONE;
+        let mut rhs = b;
+        // Express the row in terms of the nonbasic variables.
+        for (i, &j) in self.basis.iter().enumerate() {
+            let factor = row[j];
+            if factor != T::ZERO {
+                for (v, &r) in row.iter_mut().zip(&self.rows[i]) {
+                    *v -= factor * r;
+                }
+                rhs -= factor * self.rhs[i];
+            }
+        }
+        self.rows.push(row);
+        self.rhs.push(rhs);
+        self.basis.push(columns - 1);
+        self.dual()
+    }
+
+    /// Replaces the objective with `costs` and recomputes the reduced costs.
+    fn set_costs(&mut self, costs: &[T]) {
+        self.reduced = costs.to_vec();
+        self.value = T::ZERO;
+        for ((row, &b), &j) in self.rows.iter().zip(&self.rhs).zip(&self.basis) {
+            let cost = costs[j];
+            if cost != T::ZERO {
+                for (r, &v) in self.reduced.iter_mut().zip(row) {
+                    *r -= cost * v;
+                }
+                self.value -= cost * b;
+            }
+        }
+    }
+
+    /// Primal simplex iterations until the reduced costs are nonnegative.
+    ///
+    /// The entering column has the most negative reduced cost, except after a
+    /// degenerate pivot, when Bland's smallest index rule takes over so that
+    /// degenerate pivots cannot cycle.
+    fn primal(&mut self) -> Result<(), OptimizeError> {
+        let mut bland = false;
+        loop {
+            let candidates = (0..self.reduced.len())
+                .filter(|&j| !self.blocked[j] && self.reduced[j] < -self.tolerance);
+            let entering = if bland {
+                candidates.min()
+            } else {
+                candidates.min_by(|&a, &b| {
+                    self.reduced[a]
+                        .partial_cmp(&self.reduced[b])
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+            };
+            let Some(j) = entering else {
+                return Ok(());
+            };
+            let leaving = (0..self.rows.len())
+                .filter(|&i| self.rows[i][j] > self.tolerance)
+                .map(|i| (i, self.rhs[i] / self.rows[i][j]))
+                .min_by(|a, b| {
+                    a.1.partial_cmp(&b.1)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                        .then(self.basis[a.0].cmp(&self.basis[b.0]))
+                });
+            let Some((i, step)) = leaving else {
+                return Err(OptimizeError::Unbounded);
+            };
+            bland = step <= self.tolerance;
+            self.pivot(i, j);
+        }
+    }
+
+    /// Dual simplex iterations until the basic solution is feasible, keeping
+    /// the reduced costs nonnegative.
+    fn dual(&mut self) -> Result<(), OptimizeError> {
+        loop {
+            let leaving = (0..self.rows.len())
+                .filter(|&i| self.rhs[i] < -self.tolerance)
+                .min_by(|&a, &b| {
+                    self.rhs[a]
+                        .partial_cmp(&self.rhs[b])
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                        .then(self.basis[a].cmp(&self.basis[b]))
+                });
+            let Some(i) = leaving else {
+                return Ok(());
+            };
+            let row = &self.rows[i];
+            let entering = (0..row.len())
+                .filter(|&j| !self.blocked[j] && row[j] < -self.tolerance)
+                .map(|j| (j, self.reduced[j].max(T::ZERO) / -row[j]))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+            let Some((j, _)) = entering else {
+                return Err(OptimizeError::Infeasible);
+            };
+            self.pivot(i, j);
+        }
+    }
+
+    /// Pivots blocked columns still in the basis at zero level out of it,
+    /// deleting rows that turn out to be redundant.
+    fn drive_out_blocked(&mut self) {
+        let mut i = 0;
+        while i < self.rows.len() {
+            if !self.blocked[self.basis[i]] {
+                i += 1;
+                continue;
+            }
+            let replacement = (0..self.reduced.len())
+                .filter(|&j| !self.blocked[j])
+                .max_by(|&a, &b| {
+                    self.rows[i][a]
+                        .abs()
+                        .partial_cmp(&self.rows[i][b].abs())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .filter(|&j| self.rows[i][j].abs() > self.tolerance);
+            match replacement {
+                Some(j) => {
+                    self.pivot(i, j);
+                    i += 1;
+                }
+                None => {
+                    self.rows.remove(i);
+                    self.rhs.remove(i);
+                    self.basis.remove(i);
+                }
+            }
+        }
+    }
+
+    fn pivot(&mut self, i: usize, j: usize) {
+        self.pivots += 1;
+        let scale = self.rows[i][j];
+        for v in &mut self.rows[i] {
+            *v /= scale;
+        }
+        self.rhs[i] /= scale;
+        let pivot_row = self.rows[i].clone();
+        let pivot_rhs = self.rhs[i];
+        for (k, row) in self.rows.iter_mut().enumerate() {
+            let factor = row[j];
+            if k == i || factor == T::ZERO {
+                continue;
+            }
+            for (v, &p) in row.iter_mut().zip(&pivot_row) {
+                *v -= factor * p;
+            }
+            self.rhs[k] -= factor * pivot_rhs;
+        }
+        let factor = self.reduced[j];
+        for (r, &p) in self.reduced.iter_mut().zip(&pivot_row) {
+            *r -= factor * p;
+        }
+        self.value -= factor * pivot_rhs;
+        self.basis[i] = j;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_phases_and_a_cut() {
+        // min -x - y with x + 2y ≤ 4, 3x + y ≥ 3, x - y = 0.5 (vertex x = 5/3, y = 7/6).
+        let rows = vec![
+            (vec![1.0, 2.0], RowKind::LessEq, 4.0),
+            (vec![3.0, 1.0], RowKind::GreaterEq, 3.0),
+            (vec![1.0, -1.0], RowKind::Equal, 0.5),
+        ];
+        let mut tableau = Tableau::solve(&[-1.0, -1.0], &rows, 1e-12).unwrap();
+        let x = tableau.solution(2);
+        assert!((x[0] - 5.0 / 3.0).abs() < 1e-12 && (x[1] - 7.0 / 6.0).abs() < 1e-12);
+        assert!((tableau.objective() + 17.0 / 6.0).abs() < 1e-12);
+        // A cut x ≤ 1 moves the optimum along x - y = 0.5.
+        tableau.add_row(&[1.0, 0.0], 1.0).unwrap();
+        let x = tableau.solution(2);
+        assert!((x[0] - 1.0).abs() < 1e-12 && (x[1] - 0.5).abs() < 1e-12);
+        assert_eq!(
+            tableau.add_row(&[-1.0, 0.0], -2.0),
+            Err(OptimizeError::Infeasible)
+        );
+    }
+}
@@ -0,0 +1,44 @@
+//! Minimization of scalar and multivariate objective functions.
+//!
+//! Every optimizer takes its objective as any `Fn`, so data the objective
+//! depends on, such as measurements to fit, is passed by capturing it in a
+//! closure rather than through globals or an extra context argument:
+//!
+//! ```
+//! use mathslib::optimize::scalar::brent_minimize;
+//!
+//! // Least-squares scale factor for y ≈ k·x.
+//! let xs = [1.0, 2.0, 3.0, 4.0];
+//! let ys = [2.1, 3.9, 6.2, 7.8];
+//! let residual = |k: f64| {
+//!     xs.iter()
+//!         .zip(&ys)
+//!         .map(|(x, y)| (k * x - y).powi(2))
+//!         .sum::<f64>()
+//! };
+//! let fit = brent_minimize(residual, 0.0, 1.0, 5.0, 1e-10, 100).unwrap();
+//! assert!((fit.x - 59.7 / 30.0).abs() < 1e-8);
+//! ```
+
+mod budget;
+pub mod constrained;
+mod convergence;
+pub mod discrete;
+mod error;
+mod fallible;
+pub mod genetic;
+mod gradient_check;
+pub mod least_squares;
+pub mod line_search;
+pub mod linear;
+pub mod multivariate;
+pub mod projections;
+pub mod scalar;
+
+pub use budget::Budget;
+pub(crate) use budget::{counted, Evaluations};
+pub use convergence::ConvergenceCriteria;
+pub(crate) use convergence::Progress;
+pub use error::OptimizeError;
+pub use fallible::{fallible, Outcome};
+pub use gradient_check::{check_gradient, GradientCheck};
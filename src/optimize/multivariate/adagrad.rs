@@ -0,0 +1,121 @@
+use super::first_order::{first_order_minimize, FirstOrderOptimizer, Schedule};
+use super::{MultivariateResult, VectorCriteria};
+use crate::generals::Float;
+use crate::optimize::{Budget, OptimizeError};
+
+/// Settings of [`adagrad_minimize`], and a [`FirstOrderOptimizer`] for
+/// [`first_order_minimize`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaGrad<T> {
+    /// Initial step length.
+    pub learning_rate: T,
+    /// Added to the root sum of squares to avoid dividing by zero.
+    pub epsilon: T,
+    /// How the learning rate changes over the iterations.
+    pub schedule: Schedule<T>,
+}
+
+impl<T: Float> Default for AdaGrad<T> {
+    /// Learning rate `0.01`, `ε = 1e-8` and a constant rate.
+    fn default() -> Self {
+        AdaGrad {
+            learning_rate: T::from_f64(0.01),
+            epsilon: T::from_f64(1e-8),
+            schedule: Schedule::Constant,
+        }
+    }
+}
+
+impl<T: Float> AdaGrad<T> {
+    /// Starts from the learning rate `learning_rate`.
+    pub fn with_learning_rate(mut self, learning_rate: T) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Adds `epsilon` to the root sum of squares.
+    pub fn with_epsilon(mut self, epsilon: T) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Changes the learning rate according to `schedule`.
+    pub fn with_schedule(mut self, schedule: Schedule<T>) -> Self {
+        self.schedule = schedule;
+        self
+    }
+}
+
+impl<T: Float> FirstOrderOptimizer<T> for AdaGrad<T> {
+    /// Sums of the squared gradients.
+    type State = Vec<T>;
+
+    fn init(&self, n: usize) -> Vec<T> {
+        vec![T::ZERO; n]
+    }
+
+    fn step(&self, sums: &mut Vec<T>, iteration: usize, gradient: &[T]) -> Vec<T> {
+        let rate = self.schedule.rate(self.learning_rate, iteration);
+        sums.iter_mut()
+            .zip(gradient)
+            .map(|(s, &g)| {
+                *s += g * g;
+                -rate * g / (s.sqrt() + self.epsilon)
+            })
+            .collect()
+    }
+}
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` with AdaGrad.
+///
+/// AdaGrad sums the squares of all gradients seen so far per coordinate and
+/// moves each coordinate by `-η·g / (√Σg² + ε)`. Coordinates with steep or
+/// frequent gradients get small steps and rarely touched ones large steps, but
+/// as the sums only grow the steps shrink roughly like `1 / √k` on their own,
+/// which can stall long runs; see
+/// [`rmsprop_minimize`](super::rmsprop_minimize) for a remedy.
+///
+/// This is [`first_order_minimize`] with `settings` as the optimizer; see there
+/// for the evaluations done and the errors.
+pub fn adagrad_minimize<T, F, G>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    settings: AdaGrad<T>,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    first_order_minimize(func, grad, x0, &settings, criteria, budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_shrink_with_the_accumulated_gradient() {
+        let adagrad = AdaGrad::default().with_learning_rate(0.5);
+        let mut sums = adagrad.init(2);
+        let first = adagrad.step(&mut sums, 0, &[2.0, -0.001]);
+        assert!((first[0] + 0.5).abs() < 1e-6 && (first[1] - 0.5).abs() < 1e-4);
+        let second = adagrad.step(&mut sums, 1, &[2.0, 0.0]);
+        assert!((second[0] + 0.5 / 2f64.sqrt()).abs() < 1e-6);
+        assert_eq!(second[1], 0.0);
+    }
+
+    #[test]
+    fn adagrad_minimize_matches_first_order_minimize() {
+        let bowl = |x: &[f64]| 0.5 * (x[0] * x[0] + 10.0 * x[1] * x[1]);
+        let gradient = |x: &[f64]| vec![x[0], 10.0 * x[1]];
+        let settings = AdaGrad::default().with_learning_rate(0.5);
+        let result = adagrad_minimize(bowl, gradient, &[1.0, -1.0], settings, 1e-8, 5000).unwrap();
+        let direct = first_order_minimize(bowl, gradient, &[1.0, -1.0], &settings, 1e-8, 5000);
+        assert_eq!(result, direct.unwrap());
+        assert!(result.x.iter().all(|xi| xi.abs() < 1e-3), "{:?}", result.x);
+    }
+}
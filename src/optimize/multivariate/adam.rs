@@ -0,0 +1,193 @@
+use super::first_order::{first_order_minimize, FirstOrderOptimizer, Schedule};
+use super::{MultivariateResult, VectorCriteria};
+use crate::generals::Float;
+use crate::optimize::{Budget, OptimizeError};
+
+/// Settings of [`adam_minimize`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adam<T> {
+    /// Initial step length, roughly the largest distance a coordinate moves
+    /// per iteration.
+    pub learning_rate: T,
+    /// Decay rate of the running mean of the gradient, in `[0, 1)`.
+    pub beta1: T,
+    /// Decay rate of the running mean of the squared gradient, in `[0, 1)`.
+    pub beta2: T,
+    /// Added to the root mean square gradient to avoid dividing by zero.
+    pub epsilon: T,
+    /// How the learning rate changes over the iterations.
+    pub schedule: Schedule<T>,
+}
+
+impl<T: Float> Default for Adam<T> {
+    /// The usual learning rate `0.001`, `β1 = 0.9`, `β2 = 0.999`, `ε = 1e-8`
+    /// and a constant rate.
+    fn default() -> Self {
+        Adam {
+            learning_rate: T::from_f64(0.001),
+            beta1: T::from_f64(0.9),
+            beta2: T::from_f64(0.999),
+            epsilon: T::from_f64(1e-8),
+            schedule: Schedule::Constant,
+        }
+    }
+}
+
+impl<T: Float> Adam<T> {
+    /// Starts from the learning rate `learning_rate`.
+    pub fn with_learning_rate(mut self, learning_rate: T) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Averages the gradient with decay rate `beta1`.
+    pub fn with_beta1(mut self, beta1: T) -> Self {
+        self.beta1 = beta1;
+        self
+    }
+
+    /// Averages the squared gradient with decay rate `beta2`.
+    pub fn with_beta2(mut self, beta2: T) -> Self {
+        self.beta2 = beta2;
+        self
+    }
+
+    /// Adds `epsilon` to the root mean square gradient.
+    pub fn with_epsilon(mut self, epsilon: T) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Changes the learning rate according to `schedule`.
+    pub fn with_schedule(mut self, schedule: Schedule<T>) -> Self {
+        self.schedule = schedule;
+        self
+    }
+}
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` with Adam.
+///
+/// Adam keeps running means `m` of the gradient and `v` of its elementwise
+/// square, corrects both for their bias towards the zero start, and moves each
+/// coordinate by `-η·m̂ / (√v̂ + ε)`. Steps are thus about `η` long whatever
+/// the scale of the gradient, and averaging smooths out noise, which suits
+/// noisy or stochastic gradients better than line searches do. Adam does not
+/// settle exactly on a minimum with a constant `η`; a decaying
+/// `settings.schedule` helps when high accuracy is needed.
+///
+/// This is [`first_order_minimize`] with `settings` as the optimizer; see there
+/// for the evaluations done and the errors.
+pub fn adam_minimize<T, F, G>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    settings: Adam<T>,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    first_order_minimize(func, grad, x0, &settings, criteria, budget)
+}
+
+impl<T: Float> FirstOrderOptimizer<T> for Adam<T> {
+    /// Running means of the gradient and of its square.
+    type State = (Vec<T>, Vec<T>);
+
+    fn init(&self, n: usize) -> Self::State {
+        (vec![T::ZERO; n], vec![T::ZERO; n])
+    }
+
+    fn step(&self, state: &mut Self::State, iteration: usize, gradient: &[T]) -> Vec<T> {
+        let (beta1, beta2) = (self.beta1, self.beta2);
+        let rate = self.schedule.rate(self.learning_rate, iteration);
+        // Past i32 the corrections are one to working precision anyway.
+        let t = i32::try_from(iteration + 1).unwrap_or(i32::MAX);
+        let correction1 = T::ONE - beta1.powi(t);
+        let correction2 = T::ONE - beta2.powi(t);
+        let (mean, square) = state;
+        mean.iter_mut()
+            .zip(square.iter_mut())
+            .zip(gradient)
+            .map(|((m, v), &g)| {
+                *m = beta1 * *m + (T::ONE - beta1) * g;
+                *v = beta2 * *v + (T::ONE - beta2) * g * g;
+                -rate * (*m / correction1) / ((*v / correction2).sqrt() + self.epsilon)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::generals::random::Rng;
+    use crate::optimize::ConvergenceCriteria;
+
+    fn bowl(x: &[f64]) -> f64 {
+        0.5 * (x[0] * x[0] + 100.0 * x[1] * x[1])
+    }
+
+    fn bowl_gradient(x: &[f64]) -> Vec<f64> {
+        vec![x[0], 100.0 * x[1]]
+    }
+
+    #[test]
+    fn first_steps_have_the_learning_rate_as_length() {
+        let result = adam_minimize(
+            bowl,
+            bowl_gradient,
+            &[1.0, 1.0],
+            Adam::default().with_learning_rate(0.01),
+            ConvergenceCriteria::Gradient(0.0),
+            1,
+        )
+        .unwrap();
+        assert!((result.x[0] - 0.99).abs() < 1e-9 && (result.x[1] - 0.99).abs() < 1e-9);
+    }
+
+    #[test]
+    fn minimizes_with_noisy_gradients() {
+        let rng = RefCell::new(Rng::new(7));
+        let noisy_gradient = |x: &[f64]| {
+            let mut rng = rng.borrow_mut();
+            bowl_gradient(x)
+                .into_iter()
+                .map(|g| g + rng.uniform(-1.0, 1.0))
+                .collect()
+        };
+        let result = adam_minimize(
+            bowl,
+            noisy_gradient,
+            &[3.0, -2.0],
+            Adam::default()
+                .with_learning_rate(0.1)
+                .with_schedule(Schedule::InverseTime(0.01)),
+            ConvergenceCriteria::Gradient(0.0),
+            5000,
+        )
+        .unwrap();
+        assert!(!result.converged);
+        assert!(result.x.iter().all(|xi| xi.abs() < 0.05), "{:?}", result.x);
+        assert_eq!(result.fevals, 2);
+    }
+
+    #[test]
+    fn bias_correction_saturates_late_in_a_run() {
+        // Long after the start the corrections are one, so a first gradient
+        // seen then moves by (1 - β₁)/√(1 - β₂) ≈ 3.16 learning rates, not one.
+        let adam = Adam::default().with_learning_rate(0.01);
+        let mut state = adam.init(1);
+        let late = usize::try_from(1u64 << 32).unwrap_or(usize::MAX);
+        let step = adam.step(&mut state, late, &[2.0]);
+        assert!(
+            (step[0] + 0.01 * 0.1 / 0.001f64.sqrt()).abs() < 1e-6,
+            "{step:?}"
+        );
+    }
+}
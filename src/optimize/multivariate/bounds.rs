@@ -0,0 +1,177 @@
+use crate::generals::Float;
+
+/// A box `lower[i] ≤ x[i] ≤ upper[i]`, shared by the bound constrained
+/// optimizers.
+///
+/// Infinite bounds leave a coordinate free, and swapped bounds are put in
+/// order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bounds<T> {
+    lower: Vec<T>,
+    upper: Vec<T>,
+}
+
+impl<T: Float> Bounds<T> {
+    /// The box between `lower` and `upper`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lower` and `upper` have different lengths.
+    pub fn new(lower: Vec<T>, upper: Vec<T>) -> Self {
+        assert_eq!(
+            lower.len(),
+            upper.len(),
+            "bounds must have one entry per coordinate"
+        );
+        let (lower, upper) = lower
+            .iter()
+            .zip(&upper)
+            .map(|(&lo, &hi)| (lo.min(hi), lo.max(hi)))
+            .unzip();
+        Bounds { lower, upper }
+    }
+
+    /// No bounds on any of `n` coordinates.
+    pub fn unbounded(n: usize) -> Self {
+        Bounds {
+            lower: vec![T::NEG_INFINITY; n],
+            upper: vec![T::INFINITY; n],
+        }
+    }
+
+    /// The lower bounds.
+    pub fn lower(&self) -> &[T] {
+        &self.lower
+    }
+
+    /// The upper bounds.
+    pub fn upper(&self) -> &[T] {
+        &self.upper
+    }
+
+    /// Number of coordinates.
+    pub fn dimension(&self) -> usize {
+        self.lower.len()
+    }
+
+    /// Whether `x` lies in the box.
+    pub fn contains(&self, x: &[T]) -> bool {
+        x.len() == self.dimension()
+            && x.iter()
+                .zip(self.lower.iter().zip(&self.upper))
+                .all(|(&xi, (&lo, &hi))| lo <= xi && xi <= hi)
+    }
+
+    /// The point of the box closest to `x`.
+    pub fn clamp(&self, x: &[T]) -> Vec<T> {
+        x.iter()
+            .zip(self.lower.iter().zip(&self.upper))
+            .map(|(&xi, (&lo, &hi))| xi.max(lo).min(hi))
+            .collect()
+    }
+
+    /// `gradient` at `x` with the components pushing against an active bound
+    /// set to zero. It vanishes exactly at the stationary points of the
+    /// objective over the box.
+    pub fn projected_gradient(&self, x: &[T], gradient: &[T]) -> Vec<T> {
+        gradient
+            .iter()
+            .zip(x)
+            .zip(self.lower.iter().zip(&self.upper))
+            .map(|((&g, &xi), (&lo, &hi))| {
+                if (xi <= lo && g > T::ZERO) || (xi >= hi && g < T::ZERO) {
+                    T::ZERO
+                } else {
+                    g
+                }
+            })
+            .collect()
+    }
+
+    /// Gradient of `func` at `x`, a point of the box, from finite differences
+    /// that only evaluate `func` inside the box.
+    ///
+    /// Coordinates with room on both sides use central differences with steps
+    /// of `∛ε·max(|xᵢ|, 1)`; near a bound the difference is one-sided towards
+    /// the interior, which is less accurate, and a coordinate fixed by equal
+    /// bounds gets a zero derivative. Costs at most `2n + 1` evaluations.
+    pub fn finite_difference_gradient<F>(&self, func: F, x: &[T]) -> Vec<T>
+    where
+        F: Fn(&[T]) -> T,
+    {
+        let mut fx = None;
+        let mut point = x.to_vec();
+        (0..x.len())
+            .map(|i| {
+                let h = T::EPSILON.cbrt() * x[i].abs().max(T::ONE);
+                let (lo, hi) = (self.lower[i], self.upper[i]);
+                let (forward, backward) = (x[i] + h <= hi, x[i] - h >= lo);
+                let derivative = if forward && backward {
+                    point[i] = x[i] + h;
+                    let f_plus = func(&point);
+                    point[i] = x[i] - h;
+                    let f_minus = func(&point);
+                    (f_plus - f_minus) / (T::from_f64(2.0) * h)
+                } else {
+                    // Step as far as the box allows, up to h, towards the
+                    // roomier side.
+                    let room_up = (hi - x[i]).min(h);
+                    let room_down = (x[i] - lo).min(h);
+                    let step = if room_up >= room_down {
+                        room_up
+                    } else {
+                        -room_down
+                    };
+                    if step == T::ZERO {
+                        return T::ZERO;
+                    }
+                    point[i] = x[i] + step;
+                    let shifted = func(&point);
+                    let center = *fx.get_or_insert_with(|| func(x));
+                    (shifted - center) / step
+                };
+                point[i] = x[i];
+                derivative
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_and_clamps() {
+        let bounds = Bounds::new(vec![0.0, 3.0, f64::NEG_INFINITY], vec![1.0, -1.0, 5.0]);
+        assert_eq!(bounds.lower(), &[0.0, -1.0, f64::NEG_INFINITY]);
+        assert_eq!(bounds.upper(), &[1.0, 3.0, 5.0]);
+        assert_eq!(bounds.clamp(&[2.0, -4.0, -1e9]), vec![1.0, -1.0, -1e9]);
+        assert!(bounds.contains(&[0.5, 0.0, 0.0]));
+        assert!(!bounds.contains(&[0.5, 0.0, 6.0]));
+        assert_eq!(
+            bounds.projected_gradient(&[1.0, -1.0, 0.0], &[2.0, 3.0, 4.0]),
+            vec![2.0, 0.0, 4.0]
+        );
+        assert!(Bounds::<f64>::unbounded(2).contains(&[1e300, -1e300]));
+    }
+
+    #[test]
+    fn differences_stay_inside_the_box() {
+        let bounds = Bounds::new(vec![0.0, 0.0, 2.0], vec![1.0, 1e-9, 2.0]);
+        // sqrt is undefined below zero, so a stencil leaving the box would
+        // give NaN.
+        let f = |x: &[f64]| {
+            assert!(bounds.contains(x), "{x:?}");
+            x[0].sqrt() + (x[1] + 1.0).sqrt() + x[2] * x[2]
+        };
+        for x in [[0.0, 0.0, 2.0], [0.5, 5e-10, 2.0], [1.0, 1e-9, 2.0]] {
+            let g = bounds.finite_difference_gradient(f, &x);
+            assert!(g.iter().all(|gi| gi.is_finite()), "{g:?}");
+            assert_eq!(g[2], 0.0);
+            assert!((g[1] - 0.5).abs() < 1e-5, "{g:?}");
+        }
+        let interior = bounds.finite_difference_gradient(f, &[0.25, 0.0, 2.0]);
+        assert!((interior[0] - 1.0).abs() < 1e-9, "{interior:?}");
+    }
+}
@@ -0,0 +1,131 @@
+use std::cell::{Cell, RefCell};
+
+use super::MultivariateResult;
+use crate::generals::Float;
+use crate::optimize::OptimizeError;
+
+/// An objective that computes its value and gradient together, as adjoint
+/// methods and automatic differentiation in reverse mode do.
+///
+/// Closures `Fn(&[T]) -> (T, Vec<T>)` implement it; see
+/// [`value_and_gradient_minimize`] for running the optimizers on one.
+pub trait ObjectiveAndGradient<T> {
+    /// The value and gradient at `x`.
+    fn value_and_gradient(&self, x: &[T]) -> (T, Vec<T>);
+}
+
+impl<T, F> ObjectiveAndGradient<T> for F
+where
+    F: Fn(&[T]) -> (T, Vec<T>),
+{
+    fn value_and_gradient(&self, x: &[T]) -> (T, Vec<T>) {
+        self(x)
+    }
+}
+
+/// Minimizes an `objective` that returns its value and gradient together, by
+/// running `minimize` with separate objective and gradient closures that
+/// share its evaluations.
+///
+/// `minimize` may run any gradient-based optimizer, for example
+/// `|f, g| lbfgs_minimize(f, g, &x0, 10, StrongWolfe::default(), 1e-8, 1000)`.
+/// The last evaluation is remembered, and since the optimizers ask for the
+/// gradient at the points whose value they just computed, a line search step
+/// usually costs a single evaluation of `objective` rather than one of each.
+/// Both `fevals` and `gevals` of the result count the evaluations of
+/// `objective`.
+///
+/// # Errors
+///
+/// Returns the errors of `minimize`.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::line_search::StrongWolfe;
+/// use mathslib::optimize::multivariate::{lbfgs_minimize, value_and_gradient_minimize};
+///
+/// let rosenbrock = |x: &[f64]| {
+///     let (a, b) = (1.0 - x[0], x[1] - x[0] * x[0]);
+///     (a * a + 100.0 * b * b, vec![-2.0 * a - 400.0 * x[0] * b, 200.0 * b])
+/// };
+/// let result = value_and_gradient_minimize(rosenbrock, |f, g| {
+///     lbfgs_minimize(f, g, &[-1.2, 1.0], 10, StrongWolfe::default(), 1e-10, 1000)
+/// })
+/// .unwrap();
+/// assert!(result.converged);
+/// assert!((result.x[0] - 1.0f64).abs() < 1e-6 && (result.x[1] - 1.0f64).abs() < 1e-6);
+/// assert_eq!(result.fevals, result.gevals);
+/// ```
+pub fn value_and_gradient_minimize<T, O, M>(
+    objective: O,
+    minimize: M,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    O: ObjectiveAndGradient<T>,
+    M: FnOnce(
+        &dyn Fn(&[T]) -> T,
+        &dyn Fn(&[T]) -> Vec<T>,
+    ) -> Result<MultivariateResult<T>, OptimizeError>,
+{
+    let evaluations = Cell::new(0);
+    // The last point evaluated, with its value and gradient.
+    let last = RefCell::new(None::<(Vec<T>, T, Vec<T>)>);
+    let evaluate = |x: &[T]| -> (T, Vec<T>) {
+        if let Some((point, fx, gradient)) = &*last.borrow() {
+            if point.as_slice() == x {
+                return (*fx, gradient.clone());
+            }
+        }
+        evaluations.set(evaluations.get() + 1);
+        let (fx, gradient) = objective.value_and_gradient(x);
+        *last.borrow_mut() = Some((x.to_vec(), fx, gradient.clone()));
+        (fx, gradient)
+    };
+    let func = |x: &[T]| evaluate(x).0;
+    let grad = |x: &[T]| evaluate(x).1;
+    let mut result = minimize(&func, &grad)?;
+    result.fevals = evaluations.get();
+    result.gevals = evaluations.get();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::line_search::Backtracking;
+    use crate::optimize::multivariate::steepest_descent;
+    use crate::optimize::ConvergenceCriteria;
+
+    #[test]
+    fn shares_evaluations_between_value_and_gradient() {
+        let calls = Cell::new(0);
+        let bowl = |x: &[f64]| {
+            calls.set(calls.get() + 1);
+            (
+                x[0] * x[0] + 4.0 * x[1] * x[1],
+                vec![2.0 * x[0], 8.0 * x[1]],
+            )
+        };
+        let run = |f: &dyn Fn(&[f64]) -> f64, g: &dyn Fn(&[f64]) -> Vec<f64>| {
+            steepest_descent(
+                f,
+                g,
+                &[3.0, -2.0],
+                Backtracking::default(),
+                ConvergenceCriteria::Gradient(1e-8),
+                1000,
+            )
+        };
+        let separate = run(&|x| bowl(x).0, &|x| bowl(x).1).unwrap();
+        calls.set(0);
+        let combined = value_and_gradient_minimize(bowl, run).unwrap();
+        assert_eq!(combined.x, separate.x);
+        assert_eq!(combined.iterations, separate.iterations);
+        assert_eq!(combined.fevals, calls.get());
+        assert_eq!(combined.gevals, calls.get());
+        // Backtracking ends on the point it accepts, so each gradient is free.
+        assert_eq!(calls.get(), separate.fevals);
+    }
+}
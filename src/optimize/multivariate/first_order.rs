@@ -0,0 +1,352 @@
+use std::ops::ControlFlow;
+
+use super::{
+    evaluate, non_finite_at, Bounds, IterationState, MultivariateResult, Termination,
+    VectorCriteria,
+};
+use crate::generals::linalg::add_scaled;
+use crate::generals::Float;
+use crate::optimize::{Budget, OptimizeError};
+
+/// How the learning rate of a first-order method changes over the iterations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Schedule<T> {
+    /// The learning rate stays fixed.
+    Constant,
+    /// The learning rate is multiplied by the factor every iteration.
+    Exponential(T),
+    /// The learning rate at iteration `k` is divided by `1 + decay·k`.
+    InverseTime(T),
+    /// The learning rate is multiplied by `factor` every `every` iterations.
+    Step { every: usize, factor: T },
+}
+
+impl<T: Float> Schedule<T> {
+    /// Learning rate at `iteration`, counted from 0, for an initial rate of
+    /// `initial`.
+    pub fn rate(&self, initial: T, iteration: usize) -> T {
+        // Exponents beyond i32 saturate, where any decaying factor is spent.
+        let power = |exponent: usize| i32::try_from(exponent).unwrap_or(i32::MAX);
+        match *self {
+            Schedule::Constant => initial,
+            Schedule::Exponential(factor) => initial * factor.powi(power(iteration)),
+            Schedule::InverseTime(decay) => initial / (T::ONE + decay * T::from_usize(iteration)),
+            Schedule::Step { every, factor } => {
+                initial * factor.powi(power(iteration / every.max(1)))
+            }
+        }
+    }
+}
+
+/// A first-order method: a rule turning the gradients seen so far into steps.
+///
+/// The settings are kept apart from the state of a run, such as the velocity of
+/// [`Momentum`] or the running means of [`Adam`], so that one value can start
+/// any number of runs of [`first_order_minimize`].
+///
+/// [`Momentum`]: super::Momentum
+/// [`Adam`]: super::Adam
+pub trait FirstOrderOptimizer<T: Float> {
+    /// State carried from one step to the next.
+    type State;
+
+    /// State at the start of a run in `n` dimensions.
+    fn init(&self, n: usize) -> Self::State;
+
+    /// Step to take from a point with gradient `gradient` at `iteration`,
+    /// counted from 0.
+    fn step(&self, state: &mut Self::State, iteration: usize, gradient: &[T]) -> Vec<T>;
+}
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` by taking the steps
+/// of `optimizer`.
+///
+/// No line search is done, so any first-order method can be swapped in
+/// without changing the call. The objective is evaluated at every iterate
+/// only if `criteria` looks at objective values, and otherwise just at the
+/// start and the end; `fx` is always the value at the returned point.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteAtPoint`] if the objective or gradient is
+/// NaN or infinite at an evaluated point, which usually means the learning
+/// rate is too high.
+pub fn first_order_minimize<T, F, G, O>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    optimizer: &O,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    O: FirstOrderOptimizer<T> + ?Sized,
+{
+    first_order_minimize_with_callback(func, grad, x0, optimizer, criteria, budget, |_| {
+        ControlFlow::Continue(())
+    })
+}
+
+/// [`first_order_minimize`] calling `callback` after every iteration with the
+/// current [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`].
+/// Unless `criteria` look at objective values, the `fx` passed to the
+/// callback stays at its value at `x0`.
+///
+/// # Errors
+///
+/// As for [`first_order_minimize`].
+pub fn first_order_minimize_with_callback<T, F, G, O, C>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    optimizer: &O,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    O: FirstOrderOptimizer<T> + ?Sized,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    descend(
+        func,
+        grad,
+        x0,
+        optimizer,
+        criteria.into(),
+        budget.into(),
+        Region::Everywhere,
+        callback,
+    )
+}
+
+/// Where [`descend`] keeps its iterates.
+#[derive(Clone, Copy)]
+pub(crate) enum Region<'a, T> {
+    /// Anywhere.
+    Everywhere,
+    /// In a box.
+    Box(&'a Bounds<T>),
+    /// In the convex set onto which a closure projects.
+    Set(&'a dyn Fn(&[T]) -> Vec<T>),
+}
+
+impl<T: Float> Region<'_, T> {
+    /// The point of the region closest to `x`.
+    fn project(&self, x: Vec<T>) -> Vec<T> {
+        match self {
+            Region::Everywhere => x,
+            Region::Box(bounds) => bounds.clamp(&x),
+            Region::Set(projection) => projection(&x),
+        }
+    }
+
+    /// A measure of stationarity over the region at `x`, where the gradient
+    /// is `gradient`, that vanishes at the minima of convex objectives. In a
+    /// box it is the projected gradient; in a general set, the step
+    /// `x - P(x - gradient)` of projected gradient descent with unit rate.
+    fn stationarity(&self, x: &[T], gradient: &[T]) -> Vec<T> {
+        match self {
+            Region::Everywhere => gradient.to_vec(),
+            Region::Box(bounds) => bounds.projected_gradient(x, gradient),
+            Region::Set(projection) => {
+                add_scaled(x, -T::ONE, &projection(&add_scaled(x, -T::ONE, gradient)))
+            }
+        }
+    }
+}
+
+/// Runs `optimizer` as [`first_order_minimize`] does, projecting every
+/// iterate into `region`. Convergence is then tested on the stationarity
+/// measure of the region in place of the gradient.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn descend<T, F, G, O, C>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    optimizer: &O,
+    criteria: VectorCriteria<T>,
+    budget: Budget,
+    region: Region<'_, T>,
+    mut callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    O: FirstOrderOptimizer<T> + ?Sized,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    let project = |x: Vec<T>| region.project(x);
+    let projected_gradient = |x: &[T], gradient: &[T]| region.stationarity(x, gradient);
+    let uses_f = criteria.criteria.uses_f() || criteria.stall.is_some();
+    let mut state = optimizer.init(x0.len());
+    let mut x = project(x0.to_vec());
+    let (mut fx, mut gradient) = evaluate(&func, &grad, &x)?;
+    let (mut fevals, mut gevals) = (1, 1);
+    let mut iterations = 0;
+    let mut converged = criteria.is_met(
+        &x,
+        &[T::INFINITY],
+        fx,
+        None,
+        &projected_gradient(&x, &gradient),
+    );
+    let mut stopped = None;
+    let mut stall = criteria.stall_monitor(fx);
+    while !converged && budget.allows(iterations, fevals) {
+        let x_new = project(add_scaled(
+            &x,
+            T::ONE,
+            &optimizer.step(&mut state, iterations, &gradient),
+        ));
+        let step = add_scaled(&x_new, -T::ONE, &x);
+        x = x_new;
+        gradient = grad(&x);
+        gevals += 1;
+        let df = if uses_f {
+            let f_new = func(&x);
+            fevals += 1;
+            let df = fx - f_new;
+            fx = f_new;
+            Some(df)
+        } else {
+            None
+        };
+        if !fx.is_finite() || gradient.iter().any(|g| !g.is_finite()) {
+            return Err(non_finite_at(&x));
+        }
+        iterations += 1;
+        if callback(&IterationState {
+            iteration: iterations,
+            x: &x,
+            fx,
+            gradient: &gradient,
+            fevals,
+            gevals,
+        })
+        .is_break()
+        {
+            stopped = Some(Termination::Stopped);
+            break;
+        }
+        converged = criteria.is_met(&x, &step, fx, df, &projected_gradient(&x, &gradient));
+        if !converged && stall.is_stalled(fx) {
+            stopped = Some(Termination::Stalled);
+            break;
+        }
+    }
+    if !uses_f {
+        fx = func(&x);
+        fevals += 1;
+        if !fx.is_finite() {
+            return Err(non_finite_at(&x));
+        }
+    }
+
+    Ok(MultivariateResult {
+        x,
+        fx,
+        gradient,
+        hessian: None,
+        iterations,
+        fevals,
+        gevals,
+        converged,
+        termination: Termination::of(converged, stopped, budget.allows(iterations, fevals)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::multivariate::{AdaGrad, Adam, Momentum, RmsProp};
+    use crate::optimize::ConvergenceCriteria;
+
+    fn fit<O: FirstOrderOptimizer<f64>>(optimizer: O) -> MultivariateResult<f64> {
+        let bowl = |x: &[f64]| 0.5 * (x[0] * x[0] + 10.0 * x[1] * x[1]);
+        let gradient = |x: &[f64]| vec![x[0], 10.0 * x[1]];
+        first_order_minimize(
+            bowl,
+            gradient,
+            &[1.0, -1.0],
+            &optimizer,
+            ConvergenceCriteria::Gradient(1e-4),
+            20_000,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn optimizers_are_interchangeable() {
+        let schedule = Schedule::InverseTime(0.01);
+        let results = [
+            fit(Momentum::default()),
+            fit(Adam::default()
+                .with_learning_rate(0.05)
+                .with_schedule(schedule)),
+            fit(AdaGrad::default().with_learning_rate(0.5)),
+            fit(RmsProp::default()
+                .with_learning_rate(0.01)
+                .with_schedule(schedule)),
+        ];
+        for result in results {
+            assert!(result.converged, "{result:?}");
+            assert!(result.x.iter().all(|xi| xi.abs() < 1e-3));
+        }
+    }
+
+    #[test]
+    fn callback_can_checkpoint_the_iterates() {
+        let mut checkpoints = Vec::new();
+        let result = first_order_minimize_with_callback(
+            |x: &[f64]| x[0] * x[0],
+            |x: &[f64]| vec![2.0 * x[0]],
+            &[1.0],
+            &Momentum::default(),
+            ConvergenceCriteria::Gradient(1e-6),
+            10,
+            |state| {
+                checkpoints.push(state.x[0]);
+                ControlFlow::Continue(())
+            },
+        )
+        .unwrap();
+        assert_eq!(result.termination, Termination::BudgetExhausted);
+        assert_eq!(checkpoints.len(), 10);
+        assert_eq!(checkpoints[9], result.x[0]);
+    }
+
+    #[test]
+    fn schedules_decay_the_rate() {
+        assert_eq!(Schedule::Constant.rate(0.1, 50), 0.1);
+        assert!((Schedule::Exponential(0.5).rate(1.0, 3) - 0.125).abs() < 1e-15);
+        assert_eq!(Schedule::InverseTime(0.5).rate(1.0, 2), 0.5);
+        let step = Schedule::Step {
+            every: 10,
+            factor: 0.1,
+        };
+        assert_eq!(step.rate(1.0, 9), 1.0);
+        assert!((step.rate(1.0, 25) - 0.01).abs() < 1e-15);
+        // Past 2³¹ iterations the exponent saturates instead of wrapping.
+        let late = usize::try_from(1u64 << 32).unwrap_or(usize::MAX);
+        assert_eq!(Schedule::Exponential(0.5).rate(1.0, late), 0.0);
+        assert_eq!(
+            Schedule::Step {
+                every: 1,
+                factor: 0.5
+            }
+            .rate(1.0, late),
+            0.0
+        );
+    }
+}
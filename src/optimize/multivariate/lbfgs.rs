@@ -0,0 +1,564 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::ops::ControlFlow;
+
+use super::{
+    evaluate, non_finite_at, IterationState, MultivariateResult, Termination, VectorCriteria,
+};
+use crate::generals::linalg::{add_scaled, dot, norm};
+use crate::generals::Float;
+use crate::optimize::line_search::LineSearch;
+use crate::optimize::{Budget, OptimizeError};
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` with the limited-memory
+/// BFGS method.
+///
+/// Instead of a dense inverse Hessian approximation, only the last `memory`
+/// pairs of steps `s` and gradient changes `y` are stored, and the search
+/// direction is computed from them by the two-loop recursion. Memory use is
+/// `O(memory·n)`, so problems with thousands of variables are fine; a `memory`
+/// of 5 to 20 is usual. Pairs with `s·y ≤ 0` are skipped to keep the
+/// approximation positive definite, which a
+/// [`StrongWolfe`](crate::optimize::line_search::StrongWolfe) line search
+/// avoids in the first place.
+///
+/// If the line search fails, the history is dropped and a steepest descent step
+/// is tried; if that fails too, the search stops with `converged == false`,
+/// which usually means the objective cannot be lowered further at machine
+/// precision. A point with an exactly zero gradient counts as converged.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteAtPoint`] if the objective or gradient is
+/// NaN or infinite at `x0` or at an accepted point, and passes on line search
+/// errors other than [`OptimizeError::LineSearchFailed`].
+pub fn lbfgs_minimize<T, F, G, L>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    memory: usize,
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    L: LineSearch<T>,
+{
+    lbfgs_minimize_with_callback(
+        func,
+        grad,
+        x0,
+        memory,
+        line_search,
+        criteria,
+        budget,
+        |_| ControlFlow::Continue(()),
+    )
+}
+
+/// [`lbfgs_minimize`] calling `callback` after every iteration with the current
+/// [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`].
+///
+/// # Errors
+///
+/// As for [`lbfgs_minimize`].
+#[allow(clippy::too_many_arguments)]
+pub fn lbfgs_minimize_with_callback<T, F, G, L, C>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    memory: usize,
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    L: LineSearch<T>,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    let mut state = LbfgsState::new(x0, memory);
+    lbfgs_resume_with_callback(
+        func,
+        grad,
+        &mut state,
+        line_search,
+        criteria,
+        budget,
+        callback,
+    )
+}
+
+/// The point and curvature history of an L-BFGS search, from which
+/// [`lbfgs_resume`] continues it.
+///
+/// Resuming from the state a search ended in, for example on a budget that ran
+/// out, continues along the same iterates it would have followed with a larger
+/// budget. The state can be cloned to checkpoint a long search, and resumed on
+/// a slightly changed objective to start from a good point and a good
+/// approximation of its curvature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LbfgsState<T> {
+    x: Vec<T>,
+    memory: usize,
+    history: VecDeque<Pair<T>>,
+}
+
+impl<T: Float> LbfgsState<T> {
+    /// A search starting at `x0`, keeping the last `memory` pairs of steps and
+    /// gradient changes.
+    pub fn new(x0: &[T], memory: usize) -> Self {
+        LbfgsState {
+            x: x0.to_vec(),
+            memory,
+            history: VecDeque::with_capacity(memory),
+        }
+    }
+
+    /// The current point.
+    pub fn x(&self) -> &[T] {
+        &self.x
+    }
+
+    /// Number of pairs kept at most.
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+
+    /// Number of pairs stored so far.
+    pub fn pairs(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Forgets the curvature history, keeping the point, so that the search
+    /// restarts with a steepest descent step.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
+
+/// Continues the L-BFGS search described by `state` on `func`, whose gradient
+/// is `grad`, updating `state` as it goes.
+///
+/// Works as [`lbfgs_minimize`] from the point of `state` with its curvature
+/// history, which costs one extra evaluation of the objective and gradient
+/// there. The iterations and evaluations reported, and limited by `budget`,
+/// are those of this run only.
+///
+/// # Errors
+///
+/// As for [`lbfgs_minimize`]. On an error, `state` holds the last point
+/// accepted.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::line_search::StrongWolfe;
+/// use mathslib::optimize::multivariate::{lbfgs_resume, LbfgsState};
+/// use mathslib::optimize::ConvergenceCriteria::Gradient;
+///
+/// let f = |x: &[f64]| (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2);
+/// let grad = |x: &[f64]| {
+///     vec![
+///         -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+///         200.0 * (x[1] - x[0] * x[0]),
+///     ]
+/// };
+/// let mut state = LbfgsState::new(&[-1.2, 1.0], 10);
+/// let first = lbfgs_resume(f, grad, &mut state, StrongWolfe::default(), Gradient(1e-10), 10);
+/// assert!(!first.unwrap().converged);
+/// let checkpoint = state.clone();
+/// let second = lbfgs_resume(f, grad, &mut state, StrongWolfe::default(), Gradient(1e-10), 100);
+/// assert!(second.unwrap().converged);
+/// assert_ne!(state, checkpoint);
+/// ```
+pub fn lbfgs_resume<T, F, G, L>(
+    func: F,
+    grad: G,
+    state: &mut LbfgsState<T>,
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    L: LineSearch<T>,
+{
+    lbfgs_resume_with_callback(func, grad, state, line_search, criteria, budget, |_| {
+        ControlFlow::Continue(())
+    })
+}
+
+/// [`lbfgs_resume`] calling `callback` after every iteration with the current
+/// [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`], and the search can
+/// be resumed from `state` later.
+///
+/// # Errors
+///
+/// As for [`lbfgs_resume`].
+pub fn lbfgs_resume_with_callback<T, F, G, L, C>(
+    func: F,
+    grad: G,
+    state: &mut LbfgsState<T>,
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    mut callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    L: LineSearch<T>,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    let criteria = criteria.into();
+    let budget = budget.into();
+    let (x, memory, history) = (&mut state.x, state.memory, &mut state.history);
+    let (mut fx, mut gradient) = evaluate(&func, &grad, x)?;
+    let (mut fevals, mut gevals) = (1, 1);
+    let mut iterations = 0;
+    let mut converged = criteria.is_met(x, &[T::INFINITY], fx, None, &gradient);
+    let mut stopped = None;
+    let mut stall = criteria.stall_monitor(fx);
+    // Evaluations of a line search, which a failed one does not report.
+    let (searched_f, searched_g) = (Cell::new(0), Cell::new(0));
+    let search_func = |x: &[T]| {
+        searched_f.set(searched_f.get() + 1);
+        func(x)
+    };
+    let search_grad = |x: &[T]| {
+        searched_g.set(searched_g.get() + 1);
+        grad(x)
+    };
+    while !converged && budget.allows(iterations, fevals) {
+        if gradient.iter().all(|&g| g == T::ZERO) {
+            converged = true;
+            break;
+        }
+        let mut direction = two_loop(history, &gradient);
+        if dot(&direction, &gradient) >= T::ZERO {
+            history.clear();
+            direction = gradient.iter().map(|&g| -g).collect();
+        }
+        searched_f.set(0);
+        searched_g.set(0);
+        let search = line_search.search(&search_func, &search_grad, x, fx, &gradient, &direction);
+        let step = match search {
+            Ok(step) => step,
+            Err(OptimizeError::LineSearchFailed) => {
+                fevals += searched_f.get();
+                gevals += searched_g.get();
+                if history.is_empty() {
+                    break;
+                }
+                history.clear();
+                continue;
+            }
+            Err(error) => return Err(error),
+        };
+        fevals += step.fevals;
+        gevals += step.gevals;
+        let new_gradient = match step.gradient {
+            Some(g) => g,
+            None => {
+                gevals += 1;
+                grad(&step.x)
+            }
+        };
+        if !step.fx.is_finite() || new_gradient.iter().any(|g| !g.is_finite()) {
+            return Err(non_finite_at(&step.x));
+        }
+
+        let s = add_scaled(&step.x, -T::ONE, x);
+        let y = add_scaled(&new_gradient, -T::ONE, &gradient);
+        let sy = dot(&s, &y);
+        if sy > T::EPSILON * norm(&s) * norm(&y) && memory > 0 {
+            if history.len() == memory {
+                history.pop_front();
+            }
+            history.push_back(Pair {
+                rho: T::ONE / sy,
+                s: s.clone(),
+                y,
+            });
+        }
+
+        let df = fx - step.fx;
+        (*x, fx, gradient) = (step.x, step.fx, new_gradient);
+        iterations += 1;
+        if callback(&IterationState {
+            iteration: iterations,
+            x,
+            fx,
+            gradient: &gradient,
+            fevals,
+            gevals,
+        })
+        .is_break()
+        {
+            stopped = Some(Termination::Stopped);
+            break;
+        }
+        converged = criteria.is_met(x, &s, fx, Some(df), &gradient);
+        if !converged && stall.is_stalled(fx) {
+            stopped = Some(Termination::Stalled);
+            break;
+        }
+    }
+
+    Ok(MultivariateResult {
+        x: x.clone(),
+        fx,
+        gradient,
+        hessian: None,
+        iterations,
+        fevals,
+        gevals,
+        converged,
+        termination: Termination::of(converged, stopped, budget.allows(iterations, fevals)),
+    })
+}
+
+/// A stored step `s`, gradient change `y` and `rho = 1 / (s·y)`.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Pair<T> {
+    pub(super) s: Vec<T>,
+    pub(super) y: Vec<T>,
+    pub(super) rho: T,
+}
+
+/// Search direction `-H·gradient` for the inverse Hessian approximation `H`
+/// defined by `history`, oldest pair first, scaled initially by `s·y / y·y` of
+/// the newest pair.
+pub(super) fn two_loop<T: Float>(history: &VecDeque<Pair<T>>, gradient: &[T]) -> Vec<T> {
+    let mut q: Vec<T> = gradient.iter().map(|&g| -g).collect();
+    let mut alphas = Vec::with_capacity(history.len());
+    for pair in history.iter().rev() {
+        let alpha = pair.rho * dot(&pair.s, &q);
+        q = add_scaled(&q, -alpha, &pair.y);
+        alphas.push(alpha);
+    }
+    if let Some(newest) = history.back() {
+        let gamma = T::ONE / (newest.rho * dot(&newest.y, &newest.y));
+        q.iter_mut().for_each(|qi| *qi *= gamma);
+    }
+    for (pair, alpha) in history.iter().zip(alphas.into_iter().rev()) {
+        let beta = pair.rho * dot(&pair.y, &q);
+        q = add_scaled(&q, alpha - beta, &pair.s);
+    }
+    q
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::line_search::{Backtracking, StrongWolfe};
+    use crate::optimize::ConvergenceCriteria;
+
+    fn rosenbrock(x: &[f64]) -> f64 {
+        (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2)
+    }
+
+    fn rosenbrock_gradient(x: &[f64]) -> Vec<f64> {
+        vec![
+            -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+            200.0 * (x[1] - x[0] * x[0]),
+        ]
+    }
+
+    #[test]
+    fn minimizes_rosenbrock() {
+        let result = lbfgs_minimize(
+            rosenbrock,
+            rosenbrock_gradient,
+            &[-1.2, 1.0],
+            5,
+            StrongWolfe::default(),
+            ConvergenceCriteria::Gradient(1e-8),
+            200,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!((result.x[0] - 1.0).abs() < 1e-6 && (result.x[1] - 1.0).abs() < 1e-6);
+        assert!(result.iterations < 60, "{}", result.iterations);
+        assert!(norm(&result.gradient) < 1e-8);
+    }
+
+    #[test]
+    fn handles_many_variables() {
+        // Ill-conditioned separable quadratic with minimizer at (1, 2, ..., n).
+        let n = 1000;
+        let scale = |i: usize| 1.0 + 99.0 * i as f64 / n as f64;
+        let f = |x: &[f64]| {
+            (0..n)
+                .map(|i| 0.5 * scale(i) * (x[i] - (i + 1) as f64).powi(2))
+                .sum::<f64>()
+        };
+        let grad = |x: &[f64]| {
+            (0..n)
+                .map(|i| scale(i) * (x[i] - (i + 1) as f64))
+                .collect::<Vec<_>>()
+        };
+        let result = lbfgs_minimize(
+            f,
+            grad,
+            &vec![0.0; n],
+            10,
+            StrongWolfe::default(),
+            ConvergenceCriteria::Gradient(1e-6),
+            500,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!(result
+            .x
+            .iter()
+            .enumerate()
+            .all(|(i, &xi)| (xi - (i + 1) as f64).abs() < 1e-6));
+    }
+
+    #[test]
+    fn works_with_backtracking_and_no_memory() {
+        for memory in [0, 3] {
+            let result = lbfgs_minimize(
+                rosenbrock,
+                rosenbrock_gradient,
+                &[-1.2, 1.0],
+                memory,
+                Backtracking::default(),
+                ConvergenceCriteria::Gradient(1e-6),
+                20_000,
+            )
+            .unwrap();
+            assert!(result.converged, "memory {memory}");
+            assert!((result.x[0] - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn reports_why_it_stopped() {
+        let run = |grad: fn(&[f64]) -> Vec<f64>, budget| {
+            lbfgs_minimize(
+                rosenbrock,
+                grad,
+                &[-1.2, 1.0],
+                5,
+                StrongWolfe::default(),
+                ConvergenceCriteria::Gradient(1e-8),
+                budget,
+            )
+            .unwrap()
+        };
+        let result = run(rosenbrock_gradient, 5);
+        assert!(!result.converged);
+        assert_eq!(result.termination, Termination::BudgetExhausted);
+        assert!(result.hessian.is_none());
+        // A gradient of the wrong sign sends every line search uphill, and
+        // the evaluations of the failed searches still count.
+        let (fcalls, gcalls) = (Cell::new(0), Cell::new(0));
+        let result = lbfgs_minimize(
+            |x: &[f64]| {
+                fcalls.set(fcalls.get() + 1);
+                rosenbrock(x)
+            },
+            |x: &[f64]| {
+                gcalls.set(gcalls.get() + 1);
+                rosenbrock_gradient(x).iter().map(|g| -g).collect()
+            },
+            &[-1.2, 1.0],
+            5,
+            StrongWolfe::default(),
+            ConvergenceCriteria::Gradient(1e-8),
+            100,
+        )
+        .unwrap();
+        assert_eq!(result.termination, Termination::NoProgress);
+        assert_eq!(result.iterations, 0);
+        assert!(result.fevals > 1);
+        assert_eq!((result.fevals, result.gevals), (fcalls.get(), gcalls.get()));
+    }
+
+    #[test]
+    fn callback_sees_every_iteration_and_can_stop() {
+        let mut values = Vec::new();
+        let result = lbfgs_minimize_with_callback(
+            rosenbrock,
+            rosenbrock_gradient,
+            &[-1.2, 1.0],
+            5,
+            StrongWolfe::default(),
+            ConvergenceCriteria::Gradient(1e-8),
+            200,
+            |state| {
+                assert_eq!(state.fx, rosenbrock(state.x));
+                values.push(state.fx);
+                if state.fx < 1.0 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            },
+        )
+        .unwrap();
+        assert!(!result.converged);
+        assert_eq!(result.termination, Termination::Stopped);
+        assert_eq!(values.len(), result.iterations);
+        assert_eq!(values.last(), Some(&result.fx));
+        assert!(values.windows(2).all(|w| w[1] < w[0]));
+    }
+
+    #[test]
+    fn resuming_continues_along_the_same_iterates() {
+        let run = |state: &mut LbfgsState<f64>, budget| {
+            lbfgs_resume(
+                rosenbrock,
+                rosenbrock_gradient,
+                state,
+                StrongWolfe::default(),
+                ConvergenceCriteria::Gradient(1e-8),
+                budget,
+            )
+            .unwrap()
+        };
+        let whole = run(&mut LbfgsState::new(&[-1.2, 1.0], 5), 200);
+        let mut state = LbfgsState::new(&[-1.2, 1.0], 5);
+        let first = run(&mut state, 12);
+        assert_eq!(first.termination, Termination::BudgetExhausted);
+        assert_eq!(state.x(), first.x);
+        assert_eq!(state.pairs(), 5);
+        let second = run(&mut state.clone(), 200);
+        assert!(second.converged);
+        assert_eq!(second.x, whole.x);
+        assert_eq!(first.iterations + second.iterations, whole.iterations);
+        // Without the history, the search takes a different path.
+        state.clear_history();
+        let restarted = run(&mut state, 200);
+        assert!(restarted.converged);
+        assert_ne!(restarted.iterations, second.iterations);
+    }
+
+    #[test]
+    fn rejects_non_finite_start() {
+        let f = |x: &[f64]| x[0].ln();
+        let grad = |x: &[f64]| vec![1.0 / x[0]];
+        assert_eq!(
+            lbfgs_minimize(f, grad, &[-1.0], 5, StrongWolfe::default(), 1e-8, 10),
+            Err(OptimizeError::NonFiniteAtPoint { x: vec![-1.0] })
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::VecDeque;
+use std::ops::ControlFlow;
+
+use super::lbfgs::{two_loop, Pair};
+use super::{
+    evaluate, non_finite_at, Bounds, IterationState, MultivariateResult, Termination,
+    VectorCriteria,
+};
+use crate::generals::linalg::{add_scaled, dot, norm};
+use crate::generals::Float;
+use crate::optimize::{Budget, OptimizeError};
+
+/// Minimizes `func`, whose gradient is `grad`, over the box `bounds` from `x0`
+/// with a projected limited-memory BFGS method, in the spirit of L-BFGS-B.
+///
+/// Each iteration fixes the variables at a bound whose gradient pushes out of
+/// the box, computes the L-BFGS direction of [`lbfgs_minimize`] from the last
+/// `memory` pairs for the others, and backtracks along the projection of that
+/// direction onto the box until the Armijo condition holds, so bounds become
+/// active or inactive many at a time. `x0` is clamped into the box first, and
+/// `func` and `grad` are only evaluated inside it; for gradients by
+/// differences, [`Bounds::finite_difference_gradient`] keeps its stencils
+/// there too.
+///
+/// Convergence is tested on the projected gradient (see
+/// [`Bounds::projected_gradient`]), so a minimum on the boundary is recognized
+/// with [`ConvergenceCriteria::Gradient`]; the returned `gradient` is the full
+/// one. If the backtracking fails, the history is dropped and a projected
+/// steepest descent step is tried; if that fails too, the search stops with
+/// `converged == false`.
+///
+/// [`ConvergenceCriteria::Gradient`]: crate::optimize::ConvergenceCriteria::Gradient
+/// [`lbfgs_minimize`]: super::lbfgs_minimize
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteAtPoint`] if the objective or gradient is
+/// NaN or infinite at the clamped `x0` or at an accepted point.
+///
+/// # Panics
+///
+/// Panics if `bounds` does not have one entry per coordinate of `x0`.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::multivariate::{lbfgs_b_minimize, Bounds};
+/// use mathslib::optimize::ConvergenceCriteria::Gradient;
+///
+/// // The unconstrained minimum (1, 1) of Rosenbrock's function is cut off.
+/// let f = |x: &[f64]| (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2);
+/// let grad = |x: &[f64]| {
+///     vec![
+///         -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+///         200.0 * (x[1] - x[0] * x[0]),
+///     ]
+/// };
+/// let bounds = Bounds::new(vec![-2.0, -2.0], vec![0.5, 2.0]);
+/// let result = lbfgs_b_minimize(f, grad, &[-1.2, 1.0], &bounds, 5, Gradient(1e-6), 200).unwrap();
+/// assert!(result.converged);
+/// assert_eq!(result.x[0], 0.5);
+/// assert!((result.x[1] - 0.25f64).abs() < 1e-8);
+/// ```
+pub fn lbfgs_b_minimize<T, F, G>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    bounds: &Bounds<T>,
+    memory: usize,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    lbfgs_b_minimize_with_callback(func, grad, x0, bounds, memory, criteria, budget, |_| {
+        ControlFlow::Continue(())
+    })
+}
+
+/// [`lbfgs_b_minimize`] calling `callback` after every iteration with the
+/// current [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`].
+///
+/// # Errors
+///
+/// As for [`lbfgs_b_minimize`].
+#[allow(clippy::too_many_arguments)]
+pub fn lbfgs_b_minimize_with_callback<T, F, G, C>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    bounds: &Bounds<T>,
+    memory: usize,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    mut callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    assert_eq!(
+        bounds.dimension(),
+        x0.len(),
+        "bounds must have one entry per coordinate"
+    );
+    let criteria = criteria.into();
+    let budget = budget.into();
+    let armijo = T::from_f64(1e-4);
+    let mut x = bounds.clamp(x0);
+    let (mut fx, mut gradient) = evaluate(&func, &grad, &x)?;
+    let (mut fevals, mut gevals) = (1, 1);
+    let mut history: VecDeque<Pair<T>> = VecDeque::with_capacity(memory);
+    let mut projected = bounds.projected_gradient(&x, &gradient);
+    let mut iterations = 0;
+    let mut converged = criteria.is_met(&x, &[T::INFINITY], fx, None, &projected);
+    let mut stopped = None;
+    let mut stall = criteria.stall_monitor(fx);
+    while !converged && budget.allows(iterations, fevals) {
+        if projected.iter().all(|&g| g == T::ZERO) {
+            converged = true;
+            break;
+        }
+        // Variables held at a bound have a zero projected gradient and stay
+        // put.
+        let mut direction = two_loop(&history, &projected);
+        for (d, &g) in direction.iter_mut().zip(&projected) {
+            if g == T::ZERO {
+                *d = T::ZERO;
+            }
+        }
+        if dot(&direction, &projected) >= T::ZERO {
+            history.clear();
+            direction = projected.iter().map(|&g| -g).collect();
+        }
+        let mut alpha = if history.is_empty() {
+            T::ONE.min(T::ONE / norm(&projected))
+        } else {
+            T::ONE
+        };
+
+        let mut accepted = None;
+        for _ in 0..60 {
+            let trial = bounds.clamp(&add_scaled(&x, alpha, &direction));
+            let s = add_scaled(&trial, -T::ONE, &x);
+            if s.iter().all(|&si| si == T::ZERO) {
+                break;
+            }
+            let f_trial = func(&trial);
+            fevals += 1;
+            if f_trial.is_finite() && f_trial < fx && f_trial <= fx + armijo * dot(&gradient, &s) {
+                accepted = Some((trial, f_trial, s));
+                break;
+            }
+            alpha *= T::from_f64(0.5);
+        }
+        let Some((trial, f_trial, s)) = accepted else {
+            if history.is_empty() {
+                break;
+            }
+            history.clear();
+            continue;
+        };
+        let new_gradient = grad(&trial);
+        gevals += 1;
+        if new_gradient.iter().any(|g| !g.is_finite()) {
+            return Err(non_finite_at(&trial));
+        }
+
+        let y = add_scaled(&new_gradient, -T::ONE, &gradient);
+        let sy = dot(&s, &y);
+        if sy > T::EPSILON * norm(&s) * norm(&y) && memory > 0 {
+            if history.len() == memory {
+                history.pop_front();
+            }
+            history.push_back(Pair {
+                rho: T::ONE / sy,
+                s: s.clone(),
+                y,
+            });
+        }
+
+        let df = fx - f_trial;
+        (x, fx, gradient) = (trial, f_trial, new_gradient);
+        projected = bounds.projected_gradient(&x, &gradient);
+        iterations += 1;
+        if callback(&IterationState {
+            iteration: iterations,
+            x: &x,
+            fx,
+            gradient: &gradient,
+            fevals,
+            gevals,
+        })
+        .is_break()
+        {
+            stopped = Some(Termination::Stopped);
+            break;
+        }
+        converged = criteria.is_met(&x, &s, fx, Some(df), &projected);
+        if !converged && stall.is_stalled(fx) {
+            stopped = Some(Termination::Stalled);
+            break;
+        }
+    }
+
+    Ok(MultivariateResult {
+        x,
+        fx,
+        gradient,
+        hessian: None,
+        iterations,
+        fevals,
+        gevals,
+        converged,
+        termination: Termination::of(converged, stopped, budget.allows(iterations, fevals)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::ConvergenceCriteria;
+
+    #[test]
+    fn separable_problem_lands_on_the_clamped_minimum() {
+        // Minimizer (1, 2, ..., n) of an ill-conditioned quadratic; the box
+        // cuts off every third coordinate from above and every fifth from below.
+        let n = 200;
+        let scale = |i: usize| 1.0 + 49.0 * i as f64 / n as f64;
+        let f = |x: &[f64]| {
+            (0..n)
+                .map(|i| 0.5 * scale(i) * (x[i] - (i + 1) as f64).powi(2))
+                .sum::<f64>()
+        };
+        let grad = |x: &[f64]| {
+            (0..n)
+                .map(|i| scale(i) * (x[i] - (i + 1) as f64))
+                .collect::<Vec<_>>()
+        };
+        let lower: Vec<f64> = (0..n)
+            .map(|i| {
+                if i % 5 == 0 {
+                    i as f64 + 1.5
+                } else {
+                    f64::NEG_INFINITY
+                }
+            })
+            .collect();
+        let upper: Vec<f64> = (0..n)
+            .map(|i| if i % 3 == 0 { i as f64 + 0.5 } else { 1e3 })
+            .collect();
+        let bounds = Bounds::new(lower, upper);
+        let result = lbfgs_b_minimize(
+            f,
+            grad,
+            &vec![0.0; n],
+            &bounds,
+            10,
+            ConvergenceCriteria::Gradient(1e-6),
+            500,
+        )
+        .unwrap();
+        assert!(result.converged);
+        let expected = bounds.clamp(&(1..=n).map(|i| i as f64).collect::<Vec<_>>());
+        for (xi, ei) in result.x.iter().zip(&expected) {
+            assert!((xi - ei).abs() < 1e-6, "{xi} vs {ei}");
+        }
+        assert!(bounds.contains(&result.x));
+    }
+
+    #[test]
+    fn never_leaves_the_box() {
+        // Increasing in x[0] and undefined below zero, so the minimum is on
+        // the lower bound and a step or stencil past it would give NaN.
+        let bounds = Bounds::new(vec![0.1, -1.0], vec![10.0, 1.0]);
+        let f = |x: &[f64]| {
+            assert!(bounds.contains(x), "{x:?}");
+            x[0] + x[0].ln() + (x[1] - 0.5).powi(2)
+        };
+        let result = lbfgs_b_minimize(
+            f,
+            |x: &[f64]| bounds.finite_difference_gradient(f, x),
+            &[5.0, -0.7],
+            &bounds,
+            5,
+            ConvergenceCriteria::Gradient(1e-6),
+            200,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert_eq!(result.x[0], 0.1);
+        assert!((result.x[1] - 0.5).abs() < 1e-6);
+    }
+}
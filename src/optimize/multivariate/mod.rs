@@ -0,0 +1,420 @@
+//! Minimization of functions of several variables.
+//!
+//! The optimizers here take the same [`ConvergenceCriteria`] as the scalar ones,
+//! with norms in place of absolute values (see [`VectorCriteria`]): the x
+//! criteria compare the length of the last step with `‖x‖`, the f criteria
+//! look at the decrease of the objective over the last step, and
+//! [`ConvergenceCriteria::Gradient`] bounds `‖∇f(x)‖`. Step lengths are chosen by any
+//! [`LineSearch`](crate::optimize::line_search::LineSearch), except in the
+//! trust region methods and the first-order methods run by
+//! [`first_order_minimize`], which follow a learning rate.
+//!
+//! The gradient-based optimizers take the gradient as a closure; when none is
+//! at hand, [`numerical_gradient_minimize`] runs them on central differences,
+//! and when the value and gradient are computed together,
+//! [`value_and_gradient_minimize`] runs them on an [`ObjectiveAndGradient`].
+//! Box constraints are described by [`Bounds`] and handled by
+//! [`lbfgs_b_minimize`] and [`projected_minimize`], and other convex sets by
+//! [`projected_minimize_onto`] with a projection such as those of
+//! [`projections`](crate::optimize::projections). Problems whose variables
+//! differ in magnitude by many orders are best run in rescaled variables,
+//! which [`scaled_minimize`] sets up from a [`Scaling`].
+//!
+//! Each optimizer has a `*_with_callback` variant that passes the
+//! [`IterationState`] after every iteration to a callback, for logging or
+//! checkpointing, and stops when the callback returns
+//! [`ControlFlow::Break`](std::ops::ControlFlow::Break). An L-BFGS search
+//! can also be stopped and resumed later from its [`LbfgsState`], with
+//! [`lbfgs_resume`].
+
+mod adagrad;
+mod adam;
+mod bounds;
+mod combined;
+mod first_order;
+mod lbfgs;
+mod lbfgs_b;
+mod momentum;
+mod multistart;
+mod newton;
+mod newton_cg;
+mod numerical_gradient;
+mod projected;
+mod rmsprop;
+mod scaling;
+mod sgd;
+mod steepest_descent;
+mod trust_region;
+
+pub use adagrad::{adagrad_minimize, AdaGrad};
+pub use adam::{adam_minimize, Adam};
+pub use bounds::Bounds;
+pub use combined::{value_and_gradient_minimize, ObjectiveAndGradient};
+pub use first_order::{
+    first_order_minimize, first_order_minimize_with_callback, FirstOrderOptimizer, Schedule,
+};
+pub use lbfgs::{
+    lbfgs_minimize, lbfgs_minimize_with_callback, lbfgs_resume, lbfgs_resume_with_callback,
+    LbfgsState,
+};
+pub use lbfgs_b::{lbfgs_b_minimize, lbfgs_b_minimize_with_callback};
+pub use momentum::{momentum_descent, Momentum};
+pub use multistart::{multistart_minimize, multistart_minimize_parallel, Starts};
+pub use newton::{
+    newton_minimize, newton_minimize_with_callback, newton_minimize_with_hessian,
+    newton_minimize_with_hessian_and_callback,
+};
+pub use newton_cg::{
+    newton_cg_minimize, newton_cg_minimize_with_callback, newton_cg_minimize_with_hessp,
+    newton_cg_minimize_with_hessp_and_callback,
+};
+pub use numerical_gradient::numerical_gradient_minimize;
+pub use projected::{
+    projected_minimize, projected_minimize_onto, projected_minimize_onto_with_callback,
+    projected_minimize_with_callback,
+};
+pub use rmsprop::{rmsprop_minimize, RmsProp};
+pub use scaling::{scaled_minimize, Scaling};
+pub use sgd::{sgd_minimize, MiniBatch, SgdResult};
+pub use steepest_descent::{steepest_descent, steepest_descent_with_callback};
+pub use trust_region::{
+    trust_region_minimize, trust_region_minimize_with_callback, trust_region_minimize_with_hessian,
+    trust_region_minimize_with_hessian_and_callback, TrustRegion,
+};
+
+use crate::generals::differential_methods::{difference_jacobian, Difference};
+use crate::generals::linalg::{norm, Norm};
+use crate::generals::Float;
+use crate::optimize::{ConvergenceCriteria, OptimizeError, Progress};
+
+/// Outcome of a multivariate minimization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultivariateResult<T> {
+    /// Best point found.
+    pub x: Vec<T>,
+    /// Objective value at `x`.
+    pub fx: T,
+    /// Gradient at `x`.
+    pub gradient: Vec<T>,
+    /// The Hessian as a vector of rows, from the methods that form it: the
+    /// trust region methods give it at `x`, and Newton's method at the point
+    /// of its last step, which is close to `x` once it converges. Its inverse
+    /// approximates the covariance of `x` when the objective is a negative
+    /// log-likelihood.
+    pub hessian: Option<Vec<Vec<T>>>,
+    /// Number of iterations performed.
+    pub iterations: usize,
+    /// Number of objective evaluations performed.
+    pub fevals: usize,
+    /// Number of gradient evaluations performed.
+    pub gevals: usize,
+    /// Whether the convergence criterion was met before the budget ran out.
+    pub converged: bool,
+    /// Why the search stopped.
+    pub termination: Termination,
+}
+
+impl<T: Float> MultivariateResult<T> {
+    /// Euclidean norm of the gradient at `x`.
+    pub fn gradient_norm(&self) -> T {
+        norm(&self.gradient)
+    }
+}
+
+/// Why a multivariate minimization stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    /// The convergence criterion was met.
+    Converged,
+    /// The iteration or evaluation budget ran out first.
+    BudgetExhausted,
+    /// No acceptable step could be found, for example because a line search
+    /// failed or a trust region shrank to nothing.
+    NoProgress,
+    /// The callback of a `*_with_callback` function asked to stop.
+    Stopped,
+    /// The objective stopped improving; see [`VectorCriteria::with_stall`].
+    Stalled,
+}
+
+impl Termination {
+    /// The reason a loop `while !converged && budget.allows(..)` ended, where
+    /// `stopped` is the reason it was broken off early, if it was, and
+    /// `allowed` whether the budget still allows another iteration; a loop
+    /// left early otherwise made no progress.
+    pub(crate) fn of(converged: bool, stopped: Option<Termination>, allowed: bool) -> Self {
+        if converged {
+            Termination::Converged
+        } else if let Some(reason) = stopped {
+            reason
+        } else if allowed {
+            Termination::NoProgress
+        } else {
+            Termination::BudgetExhausted
+        }
+    }
+}
+
+/// State of a multivariate minimizer after an iteration, passed to the
+/// callbacks of the `*_with_callback` functions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IterationState<'a, T> {
+    /// Number of completed iterations.
+    pub iteration: usize,
+    /// Current point.
+    pub x: &'a [T],
+    /// Objective value at `x`.
+    pub fx: T,
+    /// Gradient at `x`.
+    pub gradient: &'a [T],
+    /// Number of objective evaluations so far.
+    pub fevals: usize,
+    /// Number of gradient evaluations so far.
+    pub gevals: usize,
+}
+
+impl<T: Float> IterationState<'_, T> {
+    /// Euclidean norm of the gradient at `x`.
+    pub fn gradient_norm(&self) -> T {
+        norm(self.gradient)
+    }
+}
+
+/// A [`ConvergenceCriteria`] applied to vectors through a [`Norm`].
+///
+/// Anything convertible into a [`ConvergenceCriteria`] converts into these
+/// criteria with the Euclidean norm. The x criteria then compare the norm of
+/// the last step with that of `x`, and [`ConvergenceCriteria::Gradient`]
+/// bounds the norm of the gradient.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VectorCriteria<T> {
+    /// The criterion, in terms of norms.
+    pub criteria: ConvergenceCriteria<T>,
+    /// The norm applied to `x`, the step and the gradient.
+    pub norm: Norm,
+    /// When to give up on a search that stopped improving, if ever.
+    pub stall: Option<Stall<T>>,
+}
+
+/// Gives up on a search once the objective has improved by at most
+/// `tolerance` over `iterations` consecutive iterations.
+///
+/// A stalled search ends with `converged == false` and
+/// [`Termination::Stalled`], instead of spending the rest of its budget on a
+/// plateau or on steps too small to matter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stall<T> {
+    /// Number of consecutive iterations without enough improvement.
+    pub iterations: usize,
+    /// Improvement of the objective that counts as progress.
+    pub tolerance: T,
+}
+
+impl<T> VectorCriteria<T> {
+    /// `criteria` in the Euclidean norm.
+    pub fn new(criteria: impl Into<ConvergenceCriteria<T>>) -> Self {
+        VectorCriteria {
+            criteria: criteria.into(),
+            norm: Norm::L2,
+            stall: None,
+        }
+    }
+
+    /// Measures vectors in `norm` instead.
+    pub fn with_norm(mut self, norm: Norm) -> Self {
+        self.norm = norm;
+        self
+    }
+
+    /// Stops the search once the objective has improved by at most
+    /// `tolerance` over `iterations` consecutive iterations.
+    pub fn with_stall(mut self, iterations: usize, tolerance: T) -> Self {
+        self.stall = Some(Stall {
+            iterations,
+            tolerance,
+        });
+        self
+    }
+}
+
+impl<T: Float> Default for VectorCriteria<T> {
+    /// The default [`ConvergenceCriteria`] in the Euclidean norm.
+    fn default() -> Self {
+        VectorCriteria::new(ConvergenceCriteria::default())
+    }
+}
+
+impl<T> From<ConvergenceCriteria<T>> for VectorCriteria<T> {
+    fn from(criteria: ConvergenceCriteria<T>) -> Self {
+        VectorCriteria::new(criteria)
+    }
+}
+
+impl<T> From<T> for VectorCriteria<T> {
+    fn from(tolerance: T) -> Self {
+        VectorCriteria::new(tolerance)
+    }
+}
+
+impl<T> From<(T, T)> for VectorCriteria<T> {
+    fn from(tolerances: (T, T)) -> Self {
+        VectorCriteria::new(tolerances)
+    }
+}
+
+impl<T: Float> VectorCriteria<T> {
+    /// A tracker of the stall rule for a search starting at the objective
+    /// value `f0`.
+    pub(crate) fn stall_monitor(&self, f0: T) -> StallMonitor<T> {
+        StallMonitor {
+            stall: self.stall,
+            reference: f0,
+            idle: 0,
+        }
+    }
+
+    /// Whether the criterion is met after a step `step` to `x`, which lowered
+    /// the objective to `fx` by `df`.
+    pub(crate) fn is_met(&self, x: &[T], step: &[T], fx: T, df: Option<T>, gradient: &[T]) -> bool {
+        self.criteria.is_met(&Progress {
+            x: self.norm.of(x),
+            dx: self.norm.of(step),
+            fx,
+            df,
+            slope: self.norm.of(gradient),
+        })
+    }
+}
+
+/// Evaluates the objective and its gradient at `x`.
+pub(crate) fn evaluate<T, F, G>(func: F, grad: G, x: &[T]) -> Result<(T, Vec<T>), OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    let fx = func(x);
+    let gradient = grad(x);
+    if fx.is_finite() && gradient.iter().all(|g| g.is_finite()) {
+        Ok((fx, gradient))
+    } else {
+        Err(non_finite_at(x))
+    }
+}
+
+/// Gradient at `x` from central differences of `func`. Costs `2n`
+/// evaluations.
+pub(crate) fn central_difference_gradient<T, F>(func: F, x: &[T]) -> Vec<T>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+{
+    let mut point = x.to_vec();
+    (0..x.len())
+        .map(|j| {
+            let step = T::EPSILON.cbrt() * x[j].abs().max(T::ONE);
+            point[j] = x[j] + step;
+            let forward = func(&point);
+            point[j] = x[j] - step;
+            let backward = func(&point);
+            point[j] = x[j];
+            (forward - backward) / (T::from_f64(2.0) * step)
+        })
+        .collect()
+}
+
+/// Hessian at `x` from central differences of the gradient, symmetrized. Costs
+/// `2n` gradient evaluations.
+pub(crate) fn finite_difference_hessian<T, G>(grad: G, x: &[T]) -> Vec<Vec<T>>
+where
+    T: Float,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    let step = |j: usize| T::EPSILON.cbrt() * x[j].abs().max(T::ONE);
+    let jacobian = difference_jacobian(grad, x, None, step, Difference::Central);
+    (0..x.len())
+        .map(|i| {
+            (0..x.len())
+                .map(|j| T::from_f64(0.5) * (jacobian[i][j] + jacobian[j][i]))
+                .collect()
+        })
+        .collect()
+}
+
+/// Applies the [`Stall`] rule of some [`VectorCriteria`] to the objective
+/// values of a search, one iteration at a time.
+pub(crate) struct StallMonitor<T> {
+    stall: Option<Stall<T>>,
+    // The last value that improved on its predecessor by more than the
+    // tolerance, and the number of iterations since.
+    reference: T,
+    idle: usize,
+}
+
+impl<T: Float> StallMonitor<T> {
+    /// Records an iteration that ended at the objective value `fx`, and
+    /// whether the search has now stalled.
+    pub(crate) fn is_stalled(&mut self, fx: T) -> bool {
+        let Some(stall) = self.stall else {
+            return false;
+        };
+        if self.reference - fx > stall.tolerance {
+            self.reference = fx;
+            self.idle = 0;
+        } else {
+            self.idle += 1;
+        }
+        self.idle >= stall.iterations
+    }
+}
+
+/// Jacobian at `x` of the vector function `func`, whose value at `x` is `fx`,
+/// from forward differences, as a vector of rows. Costs `n` evaluations.
+pub(crate) fn forward_difference_jacobian<T, R>(func: R, x: &[T], fx: &[T]) -> Vec<Vec<T>>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+{
+    let step = |j: usize| T::EPSILON.sqrt() * x[j].abs().max(T::ONE);
+    difference_jacobian(func, x, Some(fx), step, Difference::Forward)
+}
+
+/// [`OptimizeError::NonFiniteAtPoint`] at `x`.
+pub(crate) fn non_finite_at<T: Float>(x: &[T]) -> OptimizeError {
+    OptimizeError::NonFiniteAtPoint {
+        x: x.iter().map(|xi| xi.to_f64()).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn criteria_measure_vectors_in_the_chosen_norm() {
+        let gradient = [3e-7, -4e-7, 1e-7];
+        let l2 = VectorCriteria::new(ConvergenceCriteria::Gradient(5e-7));
+        let max = l2.with_norm(Norm::Infinity);
+        assert!(!l2.is_met(&[1.0; 3], &[1.0; 3], 0.0, None, &gradient));
+        assert!(max.is_met(&[1.0; 3], &[1.0; 3], 0.0, None, &gradient));
+        let step = VectorCriteria::from(1e-3).with_norm(Norm::Infinity);
+        assert!(step.is_met(&[5.0, 5.0], &[9e-4, -9e-4], 0.0, None, &[1.0, 1.0]));
+        assert_eq!(VectorCriteria::from(1e-3).norm, Norm::L2);
+    }
+
+    #[test]
+    fn stall_counts_iterations_without_enough_improvement() {
+        let mut monitor = VectorCriteria::from(1e-8)
+            .with_stall(3, 0.1)
+            .stall_monitor(10.0);
+        // Small gains add up: 10 → 9.85 clears the tolerance on the third.
+        assert!(!monitor.is_stalled(9.95));
+        assert!(!monitor.is_stalled(9.9));
+        assert!(!monitor.is_stalled(9.85));
+        assert!(!monitor.is_stalled(9.84));
+        assert!(!monitor.is_stalled(9.83));
+        assert!(monitor.is_stalled(9.8));
+        let mut never = VectorCriteria::from(1e-8).stall_monitor(10.0);
+        assert!((0..100).all(|_| !never.is_stalled(10.0)));
+    }
+}
@@ -0,0 +1,196 @@
+use super::first_order::{first_order_minimize, FirstOrderOptimizer, Schedule};
+use super::{MultivariateResult, VectorCriteria};
+use crate::generals::Float;
+use crate::optimize::{Budget, OptimizeError};
+
+/// Settings of [`momentum_descent`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Momentum<T> {
+    /// Initial step length per unit of gradient.
+    pub learning_rate: T,
+    /// Fraction of the previous step carried over, in `[0, 1)`.
+    pub momentum: T,
+    /// Whether to use Nesterov's accelerated variant.
+    pub nesterov: bool,
+    /// How the learning rate changes over the iterations.
+    pub schedule: Schedule<T>,
+}
+
+impl<T: Float> Default for Momentum<T> {
+    /// Learning rate `0.01`, momentum `0.9`, classical (heavy ball) momentum
+    /// and a constant rate.
+    fn default() -> Self {
+        Momentum {
+            learning_rate: T::from_f64(0.01),
+            momentum: T::from_f64(0.9),
+            nesterov: false,
+            schedule: Schedule::Constant,
+        }
+    }
+}
+
+impl<T: Float> Momentum<T> {
+    /// Starts from the learning rate `learning_rate`.
+    pub fn with_learning_rate(mut self, learning_rate: T) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Carries over the fraction `momentum` of the previous step.
+    pub fn with_momentum(mut self, momentum: T) -> Self {
+        self.momentum = momentum;
+        self
+    }
+
+    /// Uses Nesterov's accelerated variant.
+    pub fn nesterov(mut self) -> Self {
+        self.nesterov = true;
+        self
+    }
+
+    /// Changes the learning rate according to `schedule`.
+    pub fn with_schedule(mut self, schedule: Schedule<T>) -> Self {
+        self.schedule = schedule;
+        self
+    }
+}
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` by gradient descent
+/// with momentum.
+///
+/// The velocity `v ← μ·v - η·∇f(x)` accumulates past gradients, and each step
+/// moves `x` by `v`. Along directions where the gradient keeps its sign the
+/// steps grow to about `1 / (1 - μ)` times plain gradient descent, while
+/// oscillations across narrow valleys cancel, so poorly conditioned problems
+/// converge much faster. With [`Momentum::nesterov`] the step is `μ·v - η·∇f`,
+/// which looks ahead along the velocity and damps overshoot. No line search is
+/// done: the learning rate `η` follows `settings.schedule`.
+///
+/// This is [`first_order_minimize`] with `settings` as the optimizer; see there
+/// for the evaluations done and the errors.
+pub fn momentum_descent<T, F, G>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    settings: Momentum<T>,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    first_order_minimize(func, grad, x0, &settings, criteria, budget)
+}
+
+impl<T: Float> FirstOrderOptimizer<T> for Momentum<T> {
+    /// The velocity.
+    type State = Vec<T>;
+
+    fn init(&self, n: usize) -> Vec<T> {
+        vec![T::ZERO; n]
+    }
+
+    fn step(&self, velocity: &mut Vec<T>, iteration: usize, gradient: &[T]) -> Vec<T> {
+        let mu = self.momentum;
+        let rate = self.schedule.rate(self.learning_rate, iteration);
+        for (v, &g) in velocity.iter_mut().zip(gradient) {
+            *v = mu * *v - rate * g;
+        }
+        if self.nesterov {
+            velocity
+                .iter()
+                .zip(gradient)
+                .map(|(&v, &g)| mu * v - rate * g)
+                .collect()
+        } else {
+            velocity.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::line_search::FixedStep;
+    use crate::optimize::multivariate::steepest_descent;
+    use crate::optimize::ConvergenceCriteria;
+
+    // Condition number 100.
+    fn bowl(x: &[f64]) -> f64 {
+        0.5 * (x[0] * x[0] + 100.0 * x[1] * x[1])
+    }
+
+    fn bowl_gradient(x: &[f64]) -> Vec<f64> {
+        vec![x[0], 100.0 * x[1]]
+    }
+
+    #[test]
+    fn momentum_beats_plain_gradient_descent() {
+        let criteria = ConvergenceCriteria::Gradient(1e-6);
+        let plain = steepest_descent(
+            bowl,
+            bowl_gradient,
+            &[1.0, 1.0],
+            FixedStep::new(0.01),
+            criteria,
+            100_000,
+        )
+        .unwrap();
+        for settings in [Momentum::default(), Momentum::default().nesterov()] {
+            let result = momentum_descent(
+                bowl,
+                bowl_gradient,
+                &[1.0, 1.0],
+                settings,
+                criteria,
+                100_000,
+            )
+            .unwrap();
+            assert!(result.converged);
+            assert!(result.x.iter().all(|xi| xi.abs() < 1e-5));
+            assert!(
+                3 * result.iterations < plain.iterations,
+                "{} vs {}",
+                result.iterations,
+                plain.iterations
+            );
+            assert_eq!(result.fevals, 2);
+        }
+    }
+
+    #[test]
+    fn decaying_rate_tames_a_too_large_start() {
+        // A constant rate of 0.03 diverges along the stiff direction.
+        let settings = Momentum::default()
+            .with_learning_rate(0.03)
+            .with_schedule(Schedule::Step {
+                every: 5,
+                factor: 0.5,
+            });
+        let result = momentum_descent(
+            bowl,
+            bowl_gradient,
+            &[1.0, 1.0],
+            settings.with_momentum(0.0),
+            ConvergenceCriteria::AbsoluteF(1e-14),
+            10_000,
+        );
+        assert!(result.is_ok());
+        let diverged = momentum_descent(
+            bowl,
+            bowl_gradient,
+            &[1.0, 1.0],
+            settings
+                .with_momentum(0.0)
+                .with_schedule(Schedule::Constant),
+            ConvergenceCriteria::Gradient(1e-6),
+            10_000,
+        );
+        assert!(matches!(
+            diverged,
+            Err(OptimizeError::NonFiniteAtPoint { .. })
+        ));
+    }
+}
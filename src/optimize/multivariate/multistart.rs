@@ -0,0 +1,240 @@
+use std::cmp::Ordering;
+use std::thread;
+
+use super::MultivariateResult;
+use crate::generals::linalg::{add_scaled, norm};
+use crate::generals::random::Rng;
+use crate::generals::Float;
+use crate::optimize::OptimizeError;
+
+/// Starting points for [`multistart_minimize`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Starts<T> {
+    /// The given points.
+    Points(Vec<Vec<T>>),
+    /// `count` points drawn uniformly from the box `[lower, upper]` with a
+    /// generator seeded by `seed`.
+    Uniform {
+        lower: Vec<T>,
+        upper: Vec<T>,
+        count: usize,
+        seed: u64,
+    },
+    /// `count` points of a Latin hypercube in the box `[lower, upper]`: each
+    /// coordinate range is cut into `count` equal strata and every stratum of
+    /// every coordinate holds exactly one point, which covers the box more
+    /// evenly than uniform sampling.
+    LatinHypercube {
+        lower: Vec<T>,
+        upper: Vec<T>,
+        count: usize,
+        seed: u64,
+    },
+}
+
+impl<T: Float> Starts<T> {
+    /// The starting points.
+    pub fn points(&self) -> Vec<Vec<T>> {
+        match self {
+            Starts::Points(points) => points.clone(),
+            Starts::Uniform {
+                lower,
+                upper,
+                count,
+                seed,
+            } => {
+                let mut rng = Rng::new(*seed);
+                (0..*count)
+                    .map(|_| {
+                        lower
+                            .iter()
+                            .zip(upper)
+                            .map(|(&lo, &hi)| rng.uniform(lo, hi))
+                            .collect()
+                    })
+                    .collect()
+            }
+            Starts::LatinHypercube {
+                lower,
+                upper,
+                count,
+                seed,
+            } => {
+                let mut rng = Rng::new(*seed);
+                let strata: Vec<Vec<usize>> = lower
+                    .iter()
+                    .map(|_| {
+                        let mut order: Vec<usize> = (0..*count).collect();
+                        rng.shuffle(&mut order);
+                        order
+                    })
+                    .collect();
+                let width = T::ONE / T::from_usize(*count);
+                (0..*count)
+                    .map(|i| {
+                        strata
+                            .iter()
+                            .zip(lower.iter().zip(upper))
+                            .map(|(order, (&lo, &hi))| {
+                                let u = (T::from_usize(order[i]) + rng.uniform(T::ZERO, T::ONE))
+                                    * width;
+                                lo + u * (hi - lo)
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Runs the local optimizer `local` from each of the `starts` and returns the
+/// distinct local minima found, best first.
+///
+/// `local` receives a starting point and may run any optimizer, for example
+/// `|x0| lbfgs_minimize(f, g, x0, 10, StrongWolfe::default(), 1e-10, 500)`.
+/// Two minima closer than `distinct` in the Euclidean norm count as the same
+/// and only the lower one is kept. Starts whose local search fails are
+/// skipped. See [`multistart_minimize_parallel`] to run the starts on several
+/// threads.
+pub fn multistart_minimize<T, L>(
+    local: L,
+    starts: &Starts<T>,
+    distinct: T,
+) -> Vec<MultivariateResult<T>>
+where
+    T: Float,
+    L: Fn(&[T]) -> Result<MultivariateResult<T>, OptimizeError>,
+{
+    let results = starts
+        .points()
+        .iter()
+        .filter_map(|x0| local(x0).ok())
+        .collect();
+    distinct_minima(results, distinct)
+}
+
+/// Like [`multistart_minimize`], but spreads the starts over as many threads
+/// as the machine runs in parallel.
+///
+/// The minima found are the same as those of [`multistart_minimize`].
+pub fn multistart_minimize_parallel<T, L>(
+    local: L,
+    starts: &Starts<T>,
+    distinct: T,
+) -> Vec<MultivariateResult<T>>
+where
+    T: Float + Send + Sync,
+    L: Fn(&[T]) -> Result<MultivariateResult<T>, OptimizeError> + Sync,
+{
+    let points = starts.points();
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = points.len().div_ceil(threads).max(1);
+    let local = &local;
+    let results = thread::scope(|scope| {
+        let handles: Vec<_> = points
+            .chunks(chunk)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .filter_map(|x0| local(x0).ok())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("local optimizer panicked"))
+            .collect()
+    });
+    distinct_minima(results, distinct)
+}
+
+/// Sorts `results` by objective value and drops those within `distinct` of a
+/// better one.
+fn distinct_minima<T: Float>(
+    mut results: Vec<MultivariateResult<T>>,
+    distinct: T,
+) -> Vec<MultivariateResult<T>> {
+    results.sort_by(|a, b| a.fx.partial_cmp(&b.fx).unwrap_or(Ordering::Equal));
+    let mut minima: Vec<MultivariateResult<T>> = Vec::new();
+    for result in results {
+        if minima
+            .iter()
+            .all(|m| norm(&add_scaled(&m.x, -T::ONE, &result.x)) >= distinct)
+        {
+            minima.push(result);
+        }
+    }
+    minima
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::line_search::StrongWolfe;
+    use crate::optimize::multivariate::lbfgs_minimize;
+    use crate::optimize::ConvergenceCriteria;
+
+    // Himmelblau's function has four minima, all with value 0.
+    fn himmelblau(x: &[f64]) -> f64 {
+        (x[0] * x[0] + x[1] - 11.0).powi(2) + (x[0] + x[1] * x[1] - 7.0).powi(2)
+    }
+
+    fn himmelblau_gradient(x: &[f64]) -> Vec<f64> {
+        let a = x[0] * x[0] + x[1] - 11.0;
+        let b = x[0] + x[1] * x[1] - 7.0;
+        vec![4.0 * a * x[0] + 2.0 * b, 2.0 * a + 4.0 * b * x[1]]
+    }
+
+    #[test]
+    fn finds_all_minima_of_himmelblau() {
+        let local = |x0: &[f64]| {
+            lbfgs_minimize(
+                himmelblau,
+                himmelblau_gradient,
+                x0,
+                5,
+                StrongWolfe::default(),
+                ConvergenceCriteria::Gradient(1e-10),
+                500,
+            )
+        };
+        let starts = Starts::LatinHypercube {
+            lower: vec![-5.0, -5.0],
+            upper: vec![5.0, 5.0],
+            count: 40,
+            seed: 1,
+        };
+        let minima = multistart_minimize(local, &starts, 1e-3);
+        let minima: Vec<_> = minima.into_iter().filter(|m| m.fx < 1e-12).collect();
+        assert_eq!(minima.len(), 4);
+        assert!(minima
+            .iter()
+            .any(|m| (m.x[0] - 3.0).abs() < 1e-6 && (m.x[1] - 2.0).abs() < 1e-6));
+
+        let parallel = multistart_minimize_parallel(local, &starts, 1e-3);
+        let serial = multistart_minimize(local, &starts, 1e-3);
+        assert_eq!(parallel, serial);
+    }
+
+    #[test]
+    fn latin_hypercube_fills_every_stratum() {
+        let starts = Starts::LatinHypercube {
+            lower: vec![0.0, -1.0],
+            upper: vec![1.0, 1.0],
+            count: 10,
+            seed: 3,
+        };
+        let points = starts.points();
+        for (d, (lo, width)) in [(0.0, 0.1), (-1.0, 0.2)].into_iter().enumerate() {
+            let mut strata: Vec<usize> = points
+                .iter()
+                .map(|p| ((p[d] - lo) / width) as usize)
+                .collect();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..10).collect::<Vec<_>>());
+        }
+    }
+}
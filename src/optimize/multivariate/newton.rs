@@ -0,0 +1,380 @@
+use std::cell::Cell;
+use std::ops::ControlFlow;
+
+use super::{
+    evaluate, finite_difference_hessian, non_finite_at, IterationState, MultivariateResult,
+    Termination, VectorCriteria,
+};
+use crate::generals::linalg::{add_scaled, cholesky, cholesky_solve, dot};
+use crate::generals::Float;
+use crate::optimize::line_search::LineSearch;
+use crate::optimize::{Budget, OptimizeError};
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` with Newton's method,
+/// approximating the Hessian by central differences of the gradient.
+///
+/// Each Hessian costs `2n` gradient evaluations, which count towards `gevals`.
+/// See [`newton_minimize_with_hessian`] for the method and errors.
+pub fn newton_minimize<T, F, G, L>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    L: LineSearch<T>,
+{
+    newton_minimize_with_callback(func, grad, x0, line_search, criteria, budget, |_| {
+        ControlFlow::Continue(())
+    })
+}
+
+/// [`newton_minimize`] calling `callback` after every iteration with the
+/// current [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`].
+///
+/// # Errors
+///
+/// As for [`newton_minimize`].
+pub fn newton_minimize_with_callback<T, F, G, L, C>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    L: LineSearch<T>,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    let n = x0.len();
+    let hessian_gevals = Cell::new(0);
+    let hessian = |x: &[T]| {
+        hessian_gevals.set(hessian_gevals.get() + 2 * n);
+        finite_difference_hessian(&grad, x)
+    };
+    let mut result = newton_minimize_with_hessian_and_callback(
+        &func,
+        &grad,
+        hessian,
+        x0,
+        line_search,
+        criteria,
+        budget,
+        callback,
+    )?;
+    result.gevals += hessian_gevals.get();
+    Ok(result)
+}
+
+/// Minimizes `func` from `x0` with Newton's method, given its gradient `grad`
+/// and its Hessian `hessian` as a vector of rows.
+///
+/// Each iteration solves `(H + τI)·d = -∇f` by Cholesky factorization, where
+/// `τ = 0` if the Hessian `H` is positive definite. Otherwise `τ` starts at
+/// `10⁻³` above minus the lowest diagonal entry of `H` and doubles until
+/// `H + τI` is positive definite. Far from a minimum, where `H` is indefinite,
+/// this damping bends the step towards steepest descent; close to one the full
+/// Newton step is taken and convergence is quadratic. The step length is then chosen by
+/// `line_search`, which should try a unit step first.
+///
+/// If the line search fails, the search stops with `converged == false`. A
+/// point with an exactly zero gradient counts as converged.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteAtPoint`] if the objective, gradient or
+/// Hessian is NaN or infinite at `x0` or at an accepted point, and passes on
+/// line search errors other than [`OptimizeError::LineSearchFailed`].
+///
+/// # Panics
+///
+/// Panics if `hessian` does not return `n` rows of `n` entries, for `n` the
+/// length of `x0`.
+pub fn newton_minimize_with_hessian<T, F, G, H, L>(
+    func: F,
+    grad: G,
+    hessian: H,
+    x0: &[T],
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T]) -> Vec<Vec<T>>,
+    L: LineSearch<T>,
+{
+    newton_minimize_with_hessian_and_callback(
+        func,
+        grad,
+        hessian,
+        x0,
+        line_search,
+        criteria,
+        budget,
+        |_| ControlFlow::Continue(()),
+    )
+}
+
+/// [`newton_minimize_with_hessian`] calling `callback` after every iteration
+/// with the current [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`].
+///
+/// # Errors
+///
+/// As for [`newton_minimize_with_hessian`].
+///
+/// # Panics
+///
+/// As for [`newton_minimize_with_hessian`].
+#[allow(clippy::too_many_arguments)]
+pub fn newton_minimize_with_hessian_and_callback<T, F, G, H, L, C>(
+    func: F,
+    grad: G,
+    hessian: H,
+    x0: &[T],
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    mut callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T]) -> Vec<Vec<T>>,
+    L: LineSearch<T>,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    let criteria = criteria.into();
+    let budget = budget.into();
+    let n = x0.len();
+    let mut x = x0.to_vec();
+    let (mut fx, mut gradient) = evaluate(&func, &grad, &x)?;
+    let (mut fevals, mut gevals) = (1, 1);
+    let mut iterations = 0;
+    let mut last_hessian = None;
+    let mut converged = criteria.is_met(&x, &[T::INFINITY], fx, None, &gradient);
+    let mut stopped = None;
+    let mut stall = criteria.stall_monitor(fx);
+    while !converged && budget.allows(iterations, fevals) {
+        if gradient.iter().all(|&g| g == T::ZERO) {
+            converged = true;
+            break;
+        }
+        let rows = hessian(&x);
+        assert!(
+            rows.len() == n && rows.iter().all(|row| row.len() == n),
+            "the Hessian must have n rows of n entries"
+        );
+        let h: Vec<T> = rows.concat();
+        if h.iter().any(|v| !v.is_finite()) {
+            return Err(non_finite_at(&x));
+        }
+        last_hessian = Some(rows);
+        let direction = damped_newton_step(&h, n, &gradient);
+        let step = match line_search.search(&func, &grad, &x, fx, &gradient, &direction) {
+            Ok(step) => step,
+            Err(OptimizeError::LineSearchFailed) => break,
+            Err(error) => return Err(error),
+        };
+        fevals += step.fevals;
+        gevals += step.gevals;
+        let new_gradient = match step.gradient {
+            Some(g) => g,
+            None => {
+                gevals += 1;
+                grad(&step.x)
+            }
+        };
+        if !step.fx.is_finite() || new_gradient.iter().any(|g| !g.is_finite()) {
+            return Err(non_finite_at(&step.x));
+        }
+
+        let s = add_scaled(&step.x, -T::ONE, &x);
+        let df = fx - step.fx;
+        (x, fx, gradient) = (step.x, step.fx, new_gradient);
+        iterations += 1;
+        if callback(&IterationState {
+            iteration: iterations,
+            x: &x,
+            fx,
+            gradient: &gradient,
+            fevals,
+            gevals,
+        })
+        .is_break()
+        {
+            stopped = Some(Termination::Stopped);
+            break;
+        }
+        converged = criteria.is_met(&x, &s, fx, Some(df), &gradient);
+        if !converged && stall.is_stalled(fx) {
+            stopped = Some(Termination::Stalled);
+            break;
+        }
+    }
+
+    Ok(MultivariateResult {
+        x,
+        fx,
+        gradient,
+        hessian: last_hessian,
+        iterations,
+        fevals,
+        gevals,
+        converged,
+        termination: Termination::of(converged, stopped, budget.allows(iterations, fevals)),
+    })
+}
+
+/// Solution `d` of `(H + τI)·d = -gradient` for the smallest tried `τ` that
+/// makes `H + τI` positive definite, with `h` stored row-major.
+fn damped_newton_step<T: Float>(h: &[T], n: usize, gradient: &[T]) -> Vec<T> {
+    let beta = T::from_f64(1e-3);
+    let min_diagonal = (0..n).map(|i| h[i * n + i]).fold(T::INFINITY, T::min);
+    let mut tau = if min_diagonal > T::ZERO {
+        T::ZERO
+    } else {
+        beta - min_diagonal
+    };
+    let minus_gradient: Vec<T> = gradient.iter().map(|&g| -g).collect();
+    loop {
+        let mut damped = h.to_vec();
+        for i in 0..n {
+            damped[i * n + i] += tau;
+        }
+        if let Some(l) = cholesky(&damped, n) {
+            let d = cholesky_solve(&l, n, &minus_gradient);
+            if dot(&d, gradient) < T::ZERO {
+                return d;
+            }
+        }
+        if !tau.is_finite() {
+            return minus_gradient;
+        }
+        tau = (T::from_f64(2.0) * tau).max(beta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::line_search::Backtracking;
+    use crate::optimize::ConvergenceCriteria;
+
+    fn rosenbrock(x: &[f64]) -> f64 {
+        (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2)
+    }
+
+    fn rosenbrock_gradient(x: &[f64]) -> Vec<f64> {
+        vec![
+            -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+            200.0 * (x[1] - x[0] * x[0]),
+        ]
+    }
+
+    fn rosenbrock_hessian(x: &[f64]) -> Vec<Vec<f64>> {
+        vec![
+            vec![2.0 - 400.0 * x[1] + 1200.0 * x[0] * x[0], -400.0 * x[0]],
+            vec![-400.0 * x[0], 200.0],
+        ]
+    }
+
+    #[test]
+    fn converges_quickly_with_analytic_hessian() {
+        let result = newton_minimize_with_hessian(
+            rosenbrock,
+            rosenbrock_gradient,
+            rosenbrock_hessian,
+            &[-1.2, 1.0],
+            Backtracking::default(),
+            ConvergenceCriteria::Gradient(1e-10),
+            100,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!((result.x[0] - 1.0).abs() < 1e-8 && (result.x[1] - 1.0).abs() < 1e-8);
+        assert!(result.iterations < 30, "{}", result.iterations);
+    }
+
+    #[test]
+    fn finite_difference_hessian_matches_analytic() {
+        let analytic = newton_minimize_with_hessian(
+            rosenbrock,
+            rosenbrock_gradient,
+            rosenbrock_hessian,
+            &[-1.2, 1.0],
+            Backtracking::default(),
+            ConvergenceCriteria::Gradient(1e-8),
+            100,
+        )
+        .unwrap();
+        let approximate = newton_minimize(
+            rosenbrock,
+            rosenbrock_gradient,
+            &[-1.2, 1.0],
+            Backtracking::default(),
+            ConvergenceCriteria::Gradient(1e-8),
+            100,
+        )
+        .unwrap();
+        assert!(approximate.converged);
+        assert!((approximate.x[0] - 1.0).abs() < 1e-6);
+        assert!(approximate.iterations <= analytic.iterations + 2);
+        assert!(approximate.gevals >= 4 * approximate.iterations);
+    }
+
+    #[test]
+    fn damping_escapes_a_saddle() {
+        // Saddle at the origin, minima at (±1, 0); the Hessian is indefinite
+        // near the start.
+        let f = |x: &[f64]| x[0].powi(4) - 2.0 * x[0] * x[0] + x[1] * x[1];
+        let grad = |x: &[f64]| vec![4.0 * x[0].powi(3) - 4.0 * x[0], 2.0 * x[1]];
+        let hessian = |x: &[f64]| vec![vec![12.0 * x[0] * x[0] - 4.0, 0.0], vec![0.0, 2.0]];
+        let result = newton_minimize_with_hessian(
+            f,
+            grad,
+            hessian,
+            &[0.1, 1.0],
+            Backtracking::default(),
+            ConvergenceCriteria::Gradient(1e-10),
+            100,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!((result.x[0] - 1.0).abs() < 1e-8 && result.x[1].abs() < 1e-8);
+        assert!(result.fx < f(&[0.1, 1.0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "n rows of n entries")]
+    fn jagged_hessian_panics() {
+        let _ = newton_minimize_with_hessian(
+            rosenbrock,
+            rosenbrock_gradient,
+            |_: &[f64]| vec![vec![1.0], vec![0.0, 1.0, 0.0]],
+            &[-1.2, 1.0],
+            Backtracking::default(),
+            ConvergenceCriteria::Gradient(1e-10),
+            100,
+        );
+    }
+}
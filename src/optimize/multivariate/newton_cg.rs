@@ -0,0 +1,401 @@
+use std::cell::Cell;
+use std::ops::ControlFlow;
+
+use super::{
+    evaluate, non_finite_at, IterationState, MultivariateResult, Termination, VectorCriteria,
+};
+use crate::generals::linalg::{add_scaled, dot, norm};
+use crate::generals::Float;
+use crate::optimize::line_search::LineSearch;
+use crate::optimize::{Budget, OptimizeError};
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` with the truncated
+/// Newton (Newton–CG) method, approximating Hessian-vector products by forward
+/// differences of the gradient.
+///
+/// Each product costs one gradient evaluation, which counts towards `gevals`.
+/// See [`newton_cg_minimize_with_hessp`] for the method and errors.
+pub fn newton_cg_minimize<T, F, G, L>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    L: LineSearch<T>,
+{
+    newton_cg_minimize_with_callback(func, grad, x0, line_search, criteria, budget, |_| {
+        ControlFlow::Continue(())
+    })
+}
+
+/// [`newton_cg_minimize`] calling `callback` after every iteration with the
+/// current [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`].
+///
+/// # Errors
+///
+/// As for [`newton_cg_minimize`].
+pub fn newton_cg_minimize_with_callback<T, F, G, L, C>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    L: LineSearch<T>,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    let product_gevals = Cell::new(0);
+    // The gradient at the point of the last product or evaluation, which the
+    // products of one CG solve share; the solver always evaluates the gradient
+    // at the current point before asking for products there.
+    let base: Cell<Option<(Vec<T>, Vec<T>)>> = Cell::new(None);
+    let recorded_grad = |x: &[T]| {
+        let gradient = grad(x);
+        base.set(Some((x.to_vec(), gradient.clone())));
+        gradient
+    };
+    let hessp = |x: &[T], v: &[T]| {
+        let gradient = match base.take() {
+            Some((point, gradient)) if point == x => gradient,
+            _ => {
+                product_gevals.set(product_gevals.get() + 1);
+                grad(x)
+            }
+        };
+        let h = T::EPSILON.sqrt() * norm(x).max(T::ONE) / norm(v).max(T::MIN_POSITIVE);
+        product_gevals.set(product_gevals.get() + 1);
+        let shifted = grad(&add_scaled(x, h, v));
+        let product = shifted
+            .iter()
+            .zip(&gradient)
+            .map(|(&s, &g)| (s - g) / h)
+            .collect();
+        base.set(Some((x.to_vec(), gradient)));
+        product
+    };
+    let mut result = newton_cg_minimize_with_hessp_and_callback(
+        &func,
+        recorded_grad,
+        hessp,
+        x0,
+        line_search,
+        criteria,
+        budget,
+        callback,
+    )?;
+    result.gevals += product_gevals.get();
+    Ok(result)
+}
+
+/// Minimizes `func` from `x0` with the truncated Newton (Newton–CG) method,
+/// given its gradient `grad` and the product `hessp(x, v)` of its Hessian at
+/// `x` with a vector `v`.
+///
+/// Each iteration solves the Newton equations `H·d = -∇f` only roughly, by
+/// conjugate gradients: the inner iterations stop once the residual is below
+/// `min(½, √‖∇f‖)·‖∇f‖`, which gives superlinear convergence near a minimum
+/// while keeping early steps cheap, or on meeting a direction of nonpositive
+/// curvature, in which case the last iterate is used (steepest descent if it
+/// is the first). The step length is then chosen by `line_search`, which should
+/// try a unit step first.
+///
+/// The Hessian is never formed, only multiplied with vectors, so memory grows
+/// linearly with the dimension and `hessp` can exploit structure such as
+/// sparsity. At most `n` products are computed per iteration.
+///
+/// If the line search fails, the search stops with `converged == false`. A
+/// point with an exactly zero gradient counts as converged.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteAtPoint`] if the objective or gradient is
+/// NaN or infinite at `x0` or at an accepted point, or if a Hessian-vector
+/// product is, and passes on line search errors other than
+/// [`OptimizeError::LineSearchFailed`].
+pub fn newton_cg_minimize_with_hessp<T, F, G, H, L>(
+    func: F,
+    grad: G,
+    hessp: H,
+    x0: &[T],
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Vec<T>,
+    L: LineSearch<T>,
+{
+    newton_cg_minimize_with_hessp_and_callback(
+        func,
+        grad,
+        hessp,
+        x0,
+        line_search,
+        criteria,
+        budget,
+        |_| ControlFlow::Continue(()),
+    )
+}
+
+/// [`newton_cg_minimize_with_hessp`] calling `callback` after every iteration
+/// with the current [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`].
+///
+/// # Errors
+///
+/// As for [`newton_cg_minimize_with_hessp`].
+#[allow(clippy::too_many_arguments)]
+pub fn newton_cg_minimize_with_hessp_and_callback<T, F, G, H, L, C>(
+    func: F,
+    grad: G,
+    hessp: H,
+    x0: &[T],
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    mut callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Vec<T>,
+    L: LineSearch<T>,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    let criteria = criteria.into();
+    let budget = budget.into();
+    let mut x = x0.to_vec();
+    let (mut fx, mut gradient) = evaluate(&func, &grad, &x)?;
+    let (mut fevals, mut gevals) = (1, 1);
+    let mut iterations = 0;
+    let mut converged = criteria.is_met(&x, &[T::INFINITY], fx, None, &gradient);
+    let mut stopped = None;
+    let mut stall = criteria.stall_monitor(fx);
+    while !converged && budget.allows(iterations, fevals) {
+        if gradient.iter().all(|&g| g == T::ZERO) {
+            converged = true;
+            break;
+        }
+        let direction =
+            truncated_cg(|v| hessp(&x, v), &gradient).ok_or_else(|| non_finite_at(&x))?;
+        let step = match line_search.search(&func, &grad, &x, fx, &gradient, &direction) {
+            Ok(step) => step,
+            Err(OptimizeError::LineSearchFailed) => break,
+            Err(error) => return Err(error),
+        };
+        fevals += step.fevals;
+        gevals += step.gevals;
+        let new_gradient = match step.gradient {
+            Some(g) => g,
+            None => {
+                gevals += 1;
+                grad(&step.x)
+            }
+        };
+        if !step.fx.is_finite() || new_gradient.iter().any(|g| !g.is_finite()) {
+            return Err(non_finite_at(&step.x));
+        }
+
+        let s = add_scaled(&step.x, -T::ONE, &x);
+        let df = fx - step.fx;
+        (x, fx, gradient) = (step.x, step.fx, new_gradient);
+        iterations += 1;
+        if callback(&IterationState {
+            iteration: iterations,
+            x: &x,
+            fx,
+            gradient: &gradient,
+            fevals,
+            gevals,
+        })
+        .is_break()
+        {
+            stopped = Some(Termination::Stopped);
+            break;
+        }
+        converged = criteria.is_met(&x, &s, fx, Some(df), &gradient);
+        if !converged && stall.is_stalled(fx) {
+            stopped = Some(Termination::Stalled);
+            break;
+        }
+    }
+
+    Ok(MultivariateResult {
+        x,
+        fx,
+        gradient,
+        hessian: None,
+        iterations,
+        fevals,
+        gevals,
+        converged,
+        termination: Termination::of(converged, stopped, budget.allows(iterations, fevals)),
+    })
+}
+
+/// Approximate solution of `H·d = -gradient` by at most `n` conjugate gradient
+/// iterations, stopped early by the forcing term or on nonpositive curvature.
+/// `None` if a product is not finite.
+fn truncated_cg<T, P>(product: P, gradient: &[T]) -> Option<Vec<T>>
+where
+    T: Float,
+    P: Fn(&[T]) -> Vec<T>,
+{
+    let gradient_norm = norm(gradient);
+    let tolerance = T::from_f64(0.5).min(gradient_norm.sqrt()) * gradient_norm;
+    let mut d = vec![T::ZERO; gradient.len()];
+    let mut residual = gradient.to_vec();
+    let mut p: Vec<T> = gradient.iter().map(|&g| -g).collect();
+    let mut residual_squared = dot(&residual, &residual);
+    for j in 0..gradient.len() {
+        let hp = product(&p);
+        if hp.len() != p.len() || hp.iter().any(|v| !v.is_finite()) {
+            return None;
+        }
+        let curvature = dot(&p, &hp);
+        if curvature <= T::ZERO {
+            return Some(if j == 0 { p } else { d });
+        }
+        let alpha = residual_squared / curvature;
+        d = add_scaled(&d, alpha, &p);
+        residual = add_scaled(&residual, alpha, &hp);
+        let next_squared = dot(&residual, &residual);
+        if next_squared.sqrt() <= tolerance {
+            break;
+        }
+        p = add_scaled(
+            &residual.iter().map(|&r| -r).collect::<Vec<T>>(),
+            next_squared / residual_squared,
+            &p,
+        );
+        residual_squared = next_squared;
+    }
+    Some(d)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::optimize::line_search::{Backtracking, StrongWolfe};
+    use crate::optimize::ConvergenceCriteria;
+
+    // Σ 100(x[2i+1] - x[2i]²)² + (1 - x[2i])², in any even dimension.
+    fn rosenbrock(x: &[f64]) -> f64 {
+        x.chunks(2)
+            .map(|p| 100.0 * (p[1] - p[0] * p[0]).powi(2) + (1.0 - p[0]).powi(2))
+            .sum()
+    }
+
+    fn rosenbrock_gradient(x: &[f64]) -> Vec<f64> {
+        x.chunks(2)
+            .flat_map(|p| {
+                let t = p[1] - p[0] * p[0];
+                [-400.0 * p[0] * t - 2.0 * (1.0 - p[0]), 200.0 * t]
+            })
+            .collect()
+    }
+
+    // Block diagonal Hessian times v, without forming it.
+    fn rosenbrock_hessp(x: &[f64], v: &[f64]) -> Vec<f64> {
+        x.chunks(2)
+            .zip(v.chunks(2))
+            .flat_map(|(p, v)| {
+                let h00 = 1200.0 * p[0] * p[0] - 400.0 * p[1] + 2.0;
+                let h01 = -400.0 * p[0];
+                [h00 * v[0] + h01 * v[1], h01 * v[0] + 200.0 * v[1]]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn minimizes_a_large_problem_with_hessian_products() {
+        let x0: Vec<f64> = (0..500)
+            .map(|i| if i % 2 == 0 { -1.2 } else { 1.0 })
+            .collect();
+        let result = newton_cg_minimize_with_hessp(
+            rosenbrock,
+            rosenbrock_gradient,
+            rosenbrock_hessp,
+            &x0,
+            StrongWolfe::default(),
+            ConvergenceCriteria::Gradient(1e-8),
+            200,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!(result.x.iter().all(|xi| (xi - 1.0).abs() < 1e-8));
+        assert!(result.iterations < 100, "{}", result.iterations);
+    }
+
+    #[test]
+    fn finite_difference_products_count_as_gradient_evaluations() {
+        let products = Cell::new(0);
+        let points = RefCell::new(Vec::new());
+        let counted_gradient = |x: &[f64]| {
+            products.set(products.get() + 1);
+            points.borrow_mut().push(x.to_vec());
+            rosenbrock_gradient(x)
+        };
+        let result = newton_cg_minimize(
+            rosenbrock,
+            counted_gradient,
+            &[-1.2, 1.0, 0.5, 0.5],
+            Backtracking::default(),
+            ConvergenceCriteria::Gradient(1e-6),
+            200,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!(
+            result.x.iter().all(|xi| (xi - 1.0).abs() < 1e-6),
+            "{:?}",
+            result.x
+        );
+        assert_eq!(result.gevals, products.get());
+        // The gradient at each iterate is reused by the products there.
+        let points = points.into_inner();
+        for (i, point) in points.iter().enumerate() {
+            assert!(!points[..i].contains(point), "{point:?} evaluated twice");
+        }
+    }
+
+    #[test]
+    fn rejects_non_finite_products() {
+        let result = newton_cg_minimize_with_hessp(
+            rosenbrock,
+            rosenbrock_gradient,
+            |_: &[f64], v: &[f64]| vec![f64::NAN; v.len()],
+            &[0.0, 0.0],
+            Backtracking::default(),
+            1e-8,
+            10,
+        );
+        assert_eq!(
+            result,
+            Err(OptimizeError::NonFiniteAtPoint { x: vec![0.0, 0.0] })
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use std::cell::Cell;
+
+use super::{central_difference_gradient, MultivariateResult};
+use crate::generals::Float;
+use crate::optimize::OptimizeError;
+
+/// Minimizes `func` without an analytic gradient, by running `minimize` with
+/// central difference gradients.
+///
+/// `minimize` receives `func` and its approximate gradient, and may run any
+/// gradient-based optimizer, for example
+/// `|f, g| lbfgs_minimize(f, g, &x0, 10, StrongWolfe::default(), 1e-8, 1000)`.
+/// Each gradient costs `2n` objective evaluations with steps of
+/// `∛ε·max(|xᵢ|, 1)`, which count towards `fevals`, and `gevals` is zero. The
+/// gradients are accurate to about `∛ε²` relative to the objective's
+/// curvature, so gradient criteria much below `10⁻⁸` may not be met; an
+/// analytic gradient is both cheaper and more accurate when one is available.
+///
+/// # Errors
+///
+/// Returns the errors of `minimize`.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::line_search::StrongWolfe;
+/// use mathslib::optimize::multivariate::{lbfgs_minimize, numerical_gradient_minimize};
+///
+/// let rosenbrock = |x: &[f64]| (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2);
+/// let result = numerical_gradient_minimize(rosenbrock, |f, g| {
+///     lbfgs_minimize(f, g, &[-1.2, 1.0], 10, StrongWolfe::default(), 1e-10, 1000)
+/// })
+/// .unwrap();
+/// assert!(result.converged);
+/// assert!((result.x[0] - 1.0f64).abs() < 1e-6 && (result.x[1] - 1.0f64).abs() < 1e-6);
+/// ```
+pub fn numerical_gradient_minimize<T, F, M>(
+    func: F,
+    minimize: M,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    M: FnOnce(
+        &dyn Fn(&[T]) -> T,
+        &dyn Fn(&[T]) -> Vec<T>,
+    ) -> Result<MultivariateResult<T>, OptimizeError>,
+{
+    let gradient_fevals = Cell::new(0);
+    let gradient = |x: &[T]| {
+        gradient_fevals.set(gradient_fevals.get() + 2 * x.len());
+        central_difference_gradient(&func, x)
+    };
+    let mut result = minimize(&func, &gradient)?;
+    result.fevals += gradient_fevals.get();
+    result.gevals = 0;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::line_search::StrongWolfe;
+    use crate::optimize::multivariate::{lbfgs_minimize, trust_region_minimize, TrustRegion};
+    use crate::optimize::ConvergenceCriteria;
+
+    fn quartic(x: &[f64]) -> f64 {
+        x.iter()
+            .enumerate()
+            .map(|(i, xi)| (xi - i as f64).powi(4) + (i + 1) as f64 * (xi - i as f64).powi(2))
+            .sum()
+    }
+
+    #[test]
+    fn matches_the_analytic_gradient_run() {
+        let grad = |x: &[f64]| -> Vec<f64> {
+            x.iter()
+                .enumerate()
+                .map(|(i, xi)| {
+                    let d = xi - i as f64;
+                    4.0 * d.powi(3) + 2.0 * (i + 1) as f64 * d
+                })
+                .collect()
+        };
+        let x0 = [3.0, -2.0, 0.5, 1.0];
+        let criteria = ConvergenceCriteria::Gradient(1e-7);
+        let analytic =
+            lbfgs_minimize(quartic, grad, &x0, 5, StrongWolfe::default(), criteria, 200).unwrap();
+        let numerical = numerical_gradient_minimize(quartic, |f, g| {
+            lbfgs_minimize(f, g, &x0, 5, StrongWolfe::default(), criteria, 200)
+        })
+        .unwrap();
+        assert!(numerical.converged);
+        for (a, b) in analytic.x.iter().zip(&numerical.x) {
+            assert!(
+                (a - b).abs() < 1e-7,
+                "{:?} vs {:?}",
+                analytic.x,
+                numerical.x
+            );
+        }
+        assert_eq!(numerical.gevals, 0);
+        assert_eq!(numerical.fevals, analytic.fevals + 8 * analytic.gevals);
+    }
+
+    #[test]
+    fn drives_second_order_methods() {
+        let result = numerical_gradient_minimize(quartic, |f, g| {
+            trust_region_minimize(
+                f,
+                g,
+                &[1.0; 3],
+                TrustRegion::default(),
+                ConvergenceCriteria::Gradient(1e-6),
+                200,
+            )
+        })
+        .unwrap();
+        assert!(result.converged);
+        assert!(result
+            .x
+            .iter()
+            .enumerate()
+            .all(|(i, xi)| (xi - i as f64).abs() < 1e-6));
+    }
+}
@@ -0,0 +1,318 @@
+use std::ops::ControlFlow;
+
+use super::first_order::{descend, FirstOrderOptimizer, Region};
+use super::{Bounds, IterationState, MultivariateResult, VectorCriteria};
+use crate::generals::Float;
+use crate::optimize::{Budget, OptimizeError};
+
+/// Minimizes `func`, whose gradient is `grad`, over the box
+/// `lower[i] ≤ x[i] ≤ upper[i]` by projected gradient descent with the steps
+/// of `optimizer`.
+///
+/// Each iterate, starting with `x0`, is clamped into the box after the step,
+/// so `func` and `grad` are only evaluated inside it. Use infinite bounds for
+/// unconstrained coordinates; swapped bounds are put in order. Convergence is
+/// tested on the projected gradient, in which components pushing against an
+/// active bound count as zero, so a minimum on the boundary is recognized
+/// with [`ConvergenceCriteria::Gradient`]. The returned `gradient` is the full
+/// gradient of `func`. Otherwise this behaves like [`first_order_minimize`],
+/// including its errors.
+///
+/// [`ConvergenceCriteria::Gradient`]: crate::optimize::ConvergenceCriteria::Gradient
+/// [`first_order_minimize`]: super::first_order_minimize
+///
+/// # Panics
+///
+/// Panics if `lower` or `upper` is not as long as `x0`.
+#[allow(clippy::too_many_arguments)]
+pub fn projected_minimize<T, F, G, O>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    lower: &[T],
+    upper: &[T],
+    optimizer: &O,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    O: FirstOrderOptimizer<T> + ?Sized,
+{
+    projected_minimize_with_callback(
+        func,
+        grad,
+        x0,
+        lower,
+        upper,
+        optimizer,
+        criteria,
+        budget,
+        |_| ControlFlow::Continue(()),
+    )
+}
+
+/// [`projected_minimize`] calling `callback` after every iteration with the
+/// current [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`](super::Termination::Stopped).
+/// Unless `criteria` look at objective values, the `fx` passed to the
+/// callback stays at its value at `x0`.
+///
+/// # Errors
+///
+/// As for [`projected_minimize`].
+#[allow(clippy::too_many_arguments)]
+pub fn projected_minimize_with_callback<T, F, G, O, C>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    lower: &[T],
+    upper: &[T],
+    optimizer: &O,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    O: FirstOrderOptimizer<T> + ?Sized,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    assert!(
+        lower.len() == x0.len() && upper.len() == x0.len(),
+        "bounds must have one entry per coordinate"
+    );
+    let bounds = Bounds::new(lower.to_vec(), upper.to_vec());
+    descend(
+        func,
+        grad,
+        x0,
+        optimizer,
+        criteria.into(),
+        budget.into(),
+        Region::Box(&bounds),
+        callback,
+    )
+}
+
+/// Minimizes `func`, whose gradient is `grad`, over the closed convex set
+/// onto which `projection` projects, by projected gradient descent with the
+/// steps of `optimizer`.
+///
+/// `projection` returns the point of the set closest to its argument, such as
+/// the functions of [`projections`](crate::optimize::projections); for
+/// probability vectors, `|x: &[f64]| project_simplex(x, 1.0)`. Each iterate,
+/// starting with `x0`, is projected after the step, so `func` and `grad` are
+/// only evaluated in the set. Convergence is tested on `x - P(x - ∇f(x))`,
+/// which vanishes at the minima over the set, in place of the gradient; the
+/// returned `gradient` is the full gradient of `func`. Otherwise this behaves
+/// like [`projected_minimize`], including its errors.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::multivariate::{projected_minimize_onto, Momentum};
+/// use mathslib::optimize::projections::project_simplex;
+/// use mathslib::optimize::ConvergenceCriteria::Gradient;
+///
+/// // The probability vector closest to (0.7, 0.6, -0.1).
+/// let f = |x: &[f64]| (x[0] - 0.7).powi(2) + (x[1] - 0.6).powi(2) + (x[2] + 0.1).powi(2);
+/// let grad = |x: &[f64]| vec![2.0 * (x[0] - 0.7), 2.0 * (x[1] - 0.6), 2.0 * (x[2] + 0.1)];
+/// let result = projected_minimize_onto(
+///     f,
+///     grad,
+///     &[1.0 / 3.0; 3],
+///     |x: &[f64]| project_simplex(x, 1.0),
+///     &Momentum::default().with_learning_rate(0.1),
+///     Gradient(1e-10),
+///     10_000,
+/// )
+/// .unwrap();
+/// assert!(result.converged);
+/// assert!((result.x[0] - 0.55f64).abs() < 1e-9 && (result.x[1] - 0.45f64).abs() < 1e-9);
+/// assert_eq!(result.x[2], 0.0);
+/// ```
+pub fn projected_minimize_onto<T, F, G, P, O>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    projection: P,
+    optimizer: &O,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    P: Fn(&[T]) -> Vec<T>,
+    O: FirstOrderOptimizer<T> + ?Sized,
+{
+    projected_minimize_onto_with_callback(
+        func,
+        grad,
+        x0,
+        projection,
+        optimizer,
+        criteria,
+        budget,
+        |_| ControlFlow::Continue(()),
+    )
+}
+
+/// [`projected_minimize_onto`] calling `callback` after every iteration with
+/// the current [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`](super::Termination::Stopped).
+/// Unless `criteria` look at objective values, the `fx` passed to the
+/// callback stays at its value at `x0`.
+///
+/// # Errors
+///
+/// As for [`projected_minimize_onto`].
+#[allow(clippy::too_many_arguments)]
+pub fn projected_minimize_onto_with_callback<T, F, G, P, O, C>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    projection: P,
+    optimizer: &O,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    P: Fn(&[T]) -> Vec<T>,
+    O: FirstOrderOptimizer<T> + ?Sized,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    descend(
+        func,
+        grad,
+        x0,
+        optimizer,
+        criteria.into(),
+        budget.into(),
+        Region::Set(&projection),
+        callback,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::multivariate::{Adam, Momentum, Schedule};
+    use crate::optimize::projections::{project_ball, project_box};
+    use crate::optimize::ConvergenceCriteria;
+
+    #[test]
+    fn stops_on_the_boundary() {
+        // Unconstrained minimum at (2, -3); the box cuts off both coordinates.
+        let f = |x: &[f64]| (x[0] - 2.0).powi(2) + (x[1] + 3.0).powi(2) + x[2] * x[2];
+        let grad = |x: &[f64]| vec![2.0 * (x[0] - 2.0), 2.0 * (x[1] + 3.0), 2.0 * x[2]];
+        let lower = [-1.0, -1.0, f64::NEG_INFINITY];
+        let upper = [1.0, 1.0, f64::INFINITY];
+        let momentum = Momentum::default().with_learning_rate(0.05);
+        let adam = Adam::default()
+            .with_learning_rate(0.05)
+            .with_schedule(Schedule::InverseTime(0.01));
+        let criteria = ConvergenceCriteria::Gradient(1e-7);
+        let results = [
+            projected_minimize(
+                f,
+                grad,
+                &[0.0, 0.0, 1.0],
+                &lower,
+                &upper,
+                &momentum,
+                criteria,
+                10_000,
+            ),
+            projected_minimize(
+                f,
+                grad,
+                &[5.0, 0.5, 1.0],
+                &upper,
+                &lower,
+                &adam,
+                criteria,
+                100_000,
+            ),
+        ];
+        for result in results {
+            let result = result.unwrap();
+            assert!(result.converged);
+            assert_eq!((result.x[0], result.x[1]), (1.0, -1.0));
+            assert!(result.x[2].abs() < 1e-6);
+            assert!((result.gradient[0] + 2.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn interior_minimum_is_unaffected() {
+        let f = |x: &[f64]| x[0] * x[0] + x[1] * x[1];
+        let grad = |x: &[f64]| vec![2.0 * x[0], 2.0 * x[1]];
+        let result = projected_minimize(
+            f,
+            grad,
+            &[0.5, -0.5],
+            &[-1.0, -1.0],
+            &[1.0, 1.0],
+            &Momentum::default(),
+            ConvergenceCriteria::Gradient(1e-8),
+            10_000,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!(result.x.iter().all(|xi| xi.abs() < 1e-8));
+    }
+
+    #[test]
+    fn minimizes_over_any_convex_set() {
+        // A linear objective over the unit ball is least at -c/‖c‖.
+        let f = |x: &[f64]| 3.0 * x[0] - 4.0 * x[1];
+        let grad = |_: &[f64]| vec![3.0, -4.0];
+        let ball = |x: &[f64]| project_ball(x, &[0.0, 0.0], 1.0);
+        let result = projected_minimize_onto(
+            f,
+            grad,
+            &[0.0, 0.0],
+            ball,
+            &Momentum::default().with_learning_rate(0.05),
+            ConvergenceCriteria::Gradient(1e-10),
+            10_000,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!((result.x[0] + 0.6).abs() < 1e-9 && (result.x[1] - 0.8).abs() < 1e-9);
+        assert_eq!(result.gradient, vec![3.0, -4.0]);
+
+        // Over the box, the same as projected_minimize.
+        let bowl = |x: &[f64]| (x[0] - 2.0).powi(2) + (x[1] + 3.0).powi(2);
+        let bowl_gradient = |x: &[f64]| vec![2.0 * (x[0] - 2.0), 2.0 * (x[1] + 3.0)];
+        let (lower, upper) = ([-1.0, -1.0], [1.0, 1.0]);
+        let adam = Adam::default().with_learning_rate(0.05);
+        let onto = projected_minimize_onto(
+            bowl,
+            bowl_gradient,
+            &[0.0, 0.0],
+            |x: &[f64]| project_box(x, &lower, &upper),
+            &adam,
+            ConvergenceCriteria::Gradient(1e-8),
+            10_000,
+        )
+        .unwrap();
+        assert!(onto.converged);
+        assert_eq!(onto.x, vec![1.0, -1.0]);
+    }
+}
@@ -0,0 +1,119 @@
+use super::first_order::{first_order_minimize, FirstOrderOptimizer, Schedule};
+use super::{MultivariateResult, VectorCriteria};
+use crate::generals::Float;
+use crate::optimize::{Budget, OptimizeError};
+
+/// Settings of [`rmsprop_minimize`], and a [`FirstOrderOptimizer`] for
+/// [`first_order_minimize`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RmsProp<T> {
+    /// Initial step length.
+    pub learning_rate: T,
+    /// Decay rate `ρ` of the mean squared gradient, in `[0, 1)`.
+    pub decay: T,
+    /// Added to the root mean square gradient to avoid dividing by zero.
+    pub epsilon: T,
+    /// How the learning rate changes over the iterations.
+    pub schedule: Schedule<T>,
+}
+
+impl<T: Float> Default for RmsProp<T> {
+    /// Learning rate `0.001`, `ρ = 0.9`, `ε = 1e-8` and a constant rate.
+    fn default() -> Self {
+        RmsProp {
+            learning_rate: T::from_f64(0.001),
+            decay: T::from_f64(0.9),
+            epsilon: T::from_f64(1e-8),
+            schedule: Schedule::Constant,
+        }
+    }
+}
+
+impl<T: Float> RmsProp<T> {
+    /// Starts from the learning rate `learning_rate`.
+    pub fn with_learning_rate(mut self, learning_rate: T) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Averages the squared gradient with decay rate `decay`.
+    pub fn with_decay(mut self, decay: T) -> Self {
+        self.decay = decay;
+        self
+    }
+
+    /// Adds `epsilon` to the root mean square gradient.
+    pub fn with_epsilon(mut self, epsilon: T) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Changes the learning rate according to `schedule`.
+    pub fn with_schedule(mut self, schedule: Schedule<T>) -> Self {
+        self.schedule = schedule;
+        self
+    }
+}
+
+impl<T: Float> FirstOrderOptimizer<T> for RmsProp<T> {
+    /// Mean squared gradients.
+    type State = Vec<T>;
+
+    fn init(&self, n: usize) -> Vec<T> {
+        vec![T::ZERO; n]
+    }
+
+    fn step(&self, means: &mut Vec<T>, iteration: usize, gradient: &[T]) -> Vec<T> {
+        let rate = self.schedule.rate(self.learning_rate, iteration);
+        means
+            .iter_mut()
+            .zip(gradient)
+            .map(|(v, &g)| {
+                *v = self.decay * *v + (T::ONE - self.decay) * g * g;
+                -rate * g / (v.sqrt() + self.epsilon)
+            })
+            .collect()
+    }
+}
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` with RMSProp.
+///
+/// Like [`adagrad_minimize`](super::adagrad_minimize), RMSProp divides each
+/// coordinate of the gradient by its root mean square, `-η·g / (√v + ε)`, but
+/// `v` is an
+/// exponentially decaying mean `v ← ρ·v + (1 - ρ)·g²` that forgets old
+/// gradients, so the steps do not shrink to nothing on long runs.
+///
+/// This is [`first_order_minimize`] with `settings` as the optimizer; see there
+/// for the evaluations done and the errors.
+pub fn rmsprop_minimize<T, F, G>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    settings: RmsProp<T>,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    first_order_minimize(func, grad, x0, &settings, criteria, budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rmsprop_minimize_settles_near_the_minimum() {
+        let bowl = |x: &[f64]| 0.5 * (x[0] * x[0] + 10.0 * x[1] * x[1]);
+        let gradient = |x: &[f64]| vec![x[0], 10.0 * x[1]];
+        let settings = RmsProp::default()
+            .with_learning_rate(0.01)
+            .with_schedule(Schedule::InverseTime(0.01));
+        let result = rmsprop_minimize(bowl, gradient, &[1.0, -1.0], settings, 1e-8, 5000).unwrap();
+        assert!(result.x.iter().all(|xi| xi.abs() < 1e-2), "{:?}", result.x);
+    }
+}
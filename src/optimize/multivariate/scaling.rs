@@ -0,0 +1,296 @@
+use super::MultivariateResult;
+use crate::generals::Float;
+use crate::optimize::multivariate::Bounds;
+use crate::optimize::OptimizeError;
+
+/// A change of variables `x = offset + scale ⊙ z`, one multiplier per
+/// coordinate, that puts variables of very different magnitudes on the same
+/// footing.
+///
+/// The optimizers measure steps, gradients and trust regions with the same
+/// yardstick in every coordinate, so a problem mixing, say, a rate near
+/// `10⁻⁶` with a count near `10⁶` is badly conditioned in its natural units.
+/// In the variables `z` both are of order one when `scale` holds their
+/// typical magnitudes. The gradient becomes `scale ⊙ ∇f` and the Hessian
+/// `D·H·D` with `D = diag(scale)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scaling<T> {
+    scale: Vec<T>,
+    offset: Vec<T>,
+}
+
+impl<T: Float> Scaling<T> {
+    /// The scaling `x = scale ⊙ z`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an entry of `scale` is zero or not finite.
+    pub fn new(scale: Vec<T>) -> Self {
+        let offset = vec![T::ZERO; scale.len()];
+        Scaling::affine(scale, offset)
+    }
+
+    /// The scaling `x = offset + scale ⊙ z`, which also centers the variables
+    /// on `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` and `offset` have different lengths, or an entry of
+    /// `scale` is zero or not finite.
+    pub fn affine(scale: Vec<T>, offset: Vec<T>) -> Self {
+        assert_eq!(
+            scale.len(),
+            offset.len(),
+            "scale and offset must have one entry per coordinate"
+        );
+        assert!(
+            scale.iter().all(|&s| s != T::ZERO && s.is_finite()),
+            "scale must be finite and nonzero"
+        );
+        Scaling { scale, offset }
+    }
+
+    /// The scaling by the magnitudes `|typical|` of a typical point, with
+    /// zero coordinates left unscaled. The starting point is usually a good
+    /// choice.
+    pub fn from_typical(typical: &[T]) -> Self {
+        Scaling::new(
+            typical
+                .iter()
+                .map(|&x| {
+                    if x != T::ZERO && x.is_finite() {
+                        x.abs()
+                    } else {
+                        T::ONE
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// The multipliers.
+    pub fn scale(&self) -> &[T] {
+        &self.scale
+    }
+
+    /// The point that `z = 0` stands for.
+    pub fn offset(&self) -> &[T] {
+        &self.offset
+    }
+
+    /// Number of coordinates.
+    pub fn dimension(&self) -> usize {
+        self.scale.len()
+    }
+
+    /// The scaled variables `(x - offset) / scale` of `x`.
+    pub fn to_scaled(&self, x: &[T]) -> Vec<T> {
+        x.iter()
+            .zip(self.scale.iter().zip(&self.offset))
+            .map(|(&xi, (&s, &o))| (xi - o) / s)
+            .collect()
+    }
+
+    /// The point `offset + scale ⊙ z` of the scaled variables `z`.
+    pub fn to_original(&self, z: &[T]) -> Vec<T> {
+        z.iter()
+            .zip(self.scale.iter().zip(&self.offset))
+            .map(|(&zi, (&s, &o))| o + s * zi)
+            .collect()
+    }
+
+    /// The gradient `scale ⊙ g` with respect to the scaled variables of a
+    /// function whose gradient with respect to `x` is `g`.
+    pub fn scale_gradient(&self, g: &[T]) -> Vec<T> {
+        g.iter().zip(&self.scale).map(|(&gi, &s)| gi * s).collect()
+    }
+
+    /// The gradient `g / scale` with respect to `x` of a function whose
+    /// gradient with respect to the scaled variables is `g`.
+    pub fn unscale_gradient(&self, g: &[T]) -> Vec<T> {
+        g.iter().zip(&self.scale).map(|(&gi, &s)| gi / s).collect()
+    }
+
+    /// The box `bounds` in the scaled variables, for
+    /// [`lbfgs_b_minimize`](super::lbfgs_b_minimize) and
+    /// [`projected_minimize`](super::projected_minimize).
+    pub fn scale_bounds(&self, bounds: &Bounds<T>) -> Bounds<T> {
+        Bounds::new(
+            self.to_scaled(bounds.lower()),
+            self.to_scaled(bounds.upper()),
+        )
+    }
+}
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` by running `minimize`
+/// in the variables `z` of `scaling`.
+///
+/// `minimize` receives the objective and gradient in terms of `z` and the
+/// scaled starting point, and may run any optimizer, for example
+/// `|f, g, z0| lbfgs_minimize(f, g, z0, 10, StrongWolfe::default(), 1e-8, 1000)`.
+/// Its convergence criteria apply to the scaled problem, so a gradient
+/// tolerance bounds `‖scale ⊙ ∇f‖`, the change of `f` for relative changes
+/// of the variables of order one. Bounds have to be scaled too, with
+/// [`Scaling::scale_bounds`].
+///
+/// The result is mapped back to `x`: its point, gradient and Hessian, if any,
+/// are those of `func`. The iterations and evaluations are those of
+/// `minimize`.
+///
+/// # Errors
+///
+/// Returns the errors of `minimize`.
+///
+/// # Panics
+///
+/// Panics if `scaling` does not have one entry per coordinate of `x0`.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::line_search::StrongWolfe;
+/// use mathslib::optimize::multivariate::{lbfgs_minimize, scaled_minimize, Scaling};
+/// use mathslib::optimize::ConvergenceCriteria::Gradient;
+///
+/// // A rate near 3e-6 and a count near 2e6.
+/// let f = |x: &[f64]| ((x[0] - 3e-6) / 1e-6).powi(2) + ((x[1] - 2e6) / 1e6).powi(2);
+/// let grad = |x: &[f64]| vec![2e6 * (x[0] - 3e-6) / 1e-6, 2e-6 * (x[1] - 2e6) / 1e6];
+/// let x0 = [1e-6, 1e6];
+/// let result = scaled_minimize(f, grad, &x0, &Scaling::from_typical(&x0), |f, g, z0| {
+///     lbfgs_minimize(f, g, z0, 10, StrongWolfe::default(), Gradient(1e-10), 100)
+/// })
+/// .unwrap();
+/// assert!(result.converged);
+/// assert!((result.x[0] / 3e-6 - 1.0f64).abs() < 1e-9);
+/// assert!((result.x[1] / 2e6 - 1.0f64).abs() < 1e-9);
+/// ```
+pub fn scaled_minimize<T, F, G, M>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    scaling: &Scaling<T>,
+    minimize: M,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    M: FnOnce(
+        &dyn Fn(&[T]) -> T,
+        &dyn Fn(&[T]) -> Vec<T>,
+        &[T],
+    ) -> Result<MultivariateResult<T>, OptimizeError>,
+{
+    assert_eq!(
+        scaling.dimension(),
+        x0.len(),
+        "scaling must have one entry per coordinate"
+    );
+    let scaled = |z: &[T]| func(&scaling.to_original(z));
+    let scaled_gradient = |z: &[T]| scaling.scale_gradient(&grad(&scaling.to_original(z)));
+    let mut result = minimize(&scaled, &scaled_gradient, &scaling.to_scaled(x0))?;
+    result.x = scaling.to_original(&result.x);
+    result.gradient = scaling.unscale_gradient(&result.gradient);
+    // H_x = D⁻¹·H_z·D⁻¹.
+    if let Some(hessian) = &mut result.hessian {
+        for (row, &si) in hessian.iter_mut().zip(&scaling.scale) {
+            for (h, &sj) in row.iter_mut().zip(&scaling.scale) {
+                *h /= si * sj;
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::line_search::StrongWolfe;
+    use crate::optimize::multivariate::{
+        lbfgs_b_minimize, lbfgs_minimize, trust_region_minimize, TrustRegion,
+    };
+    use crate::optimize::ConvergenceCriteria;
+
+    // A narrow valley in units of 1e-6 and 1e6, coupling both variables.
+    fn valley(x: &[f64]) -> f64 {
+        let (u, v) = (x[0] * 1e6, x[1] * 1e-6);
+        (u - 3.0).powi(2) + (v - 2.0).powi(2) + 0.5 * (u - 3.0) * (v - 2.0)
+    }
+
+    fn valley_gradient(x: &[f64]) -> Vec<f64> {
+        let (u, v) = (x[0] * 1e6, x[1] * 1e-6);
+        vec![
+            1e6 * (2.0 * (u - 3.0) + 0.5 * (v - 2.0)),
+            1e-6 * (2.0 * (v - 2.0) + 0.5 * (u - 3.0)),
+        ]
+    }
+
+    #[test]
+    fn converges_where_the_natural_units_do_not() {
+        let x0 = [1e-6, 1e6];
+        let criteria = ConvergenceCriteria::Gradient(1e-9);
+        let run = |f: &dyn Fn(&[f64]) -> f64, g: &dyn Fn(&[f64]) -> Vec<f64>, z0: &[f64]| {
+            lbfgs_minimize(f, g, z0, 5, StrongWolfe::default(), criteria, 50)
+        };
+        let natural = run(&valley, &valley_gradient, &x0).unwrap();
+        assert!(!natural.converged);
+        let scaled = scaled_minimize(
+            valley,
+            valley_gradient,
+            &x0,
+            &Scaling::from_typical(&x0),
+            run,
+        )
+        .unwrap();
+        assert!(scaled.converged, "{scaled:?}");
+        assert!((scaled.x[0] / 3e-6 - 1.0).abs() < 1e-9);
+        assert!((scaled.x[1] / 2e6 - 1.0).abs() < 1e-9);
+        assert_eq!(scaled.gradient, valley_gradient(&scaled.x));
+    }
+
+    #[test]
+    fn maps_the_hessian_and_bounds_back() {
+        let x0 = [1e-6, 1e6];
+        let scaling = Scaling::affine(vec![1e-6, 1e6], vec![2e-6, 0.0]);
+        assert_eq!(scaling.to_scaled(&x0), vec![-1.0, 1.0]);
+        let result = scaled_minimize(valley, valley_gradient, &x0, &scaling, |f, g, z0| {
+            trust_region_minimize(
+                f,
+                g,
+                z0,
+                TrustRegion::default(),
+                ConvergenceCriteria::Gradient(1e-10),
+                100,
+            )
+        })
+        .unwrap();
+        assert!(result.converged);
+        let hessian = result.hessian.unwrap();
+        let expected = [[2e12, 0.5], [0.5, 2e-12]];
+        for (row, expected) in hessian.iter().zip(&expected) {
+            for (h, e) in row.iter().zip(expected) {
+                assert!((h / e - 1.0).abs() < 1e-4, "{hessian:?}");
+            }
+        }
+
+        // With x ≤ 2.5e-6 and x ≥ 1.5e6, the minimum sits on the first bound.
+        let bounds = Bounds::new(vec![0.0, 1.5e6], vec![2.5e-6, 1e7]);
+        let scaled_bounds = scaling.scale_bounds(&bounds);
+        let result = scaled_minimize(valley, valley_gradient, &x0, &scaling, |f, g, z0| {
+            lbfgs_b_minimize(
+                f,
+                g,
+                z0,
+                &scaled_bounds,
+                5,
+                ConvergenceCriteria::Gradient(1e-8),
+                100,
+            )
+        })
+        .unwrap();
+        assert!(result.converged);
+        assert!((result.x[0] / 2.5e-6 - 1.0).abs() < 1e-12);
+        // Minimizing over v with u = 2.5: 2(v - 2) - 0.25 = 0.
+        assert!((result.x[1] / 2.125e6 - 1.0).abs() < 1e-8);
+    }
+}
@@ -0,0 +1,191 @@
+use super::first_order::FirstOrderOptimizer;
+use super::non_finite_at;
+use crate::generals::linalg::add_scaled;
+use crate::generals::random::Rng;
+use crate::generals::Float;
+use crate::optimize::OptimizeError;
+
+/// How [`sgd_minimize`] splits the samples into mini-batches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MiniBatch {
+    /// Number of samples per batch; the last batch of an epoch may be smaller.
+    pub batch_size: usize,
+    /// Number of passes over all samples.
+    pub epochs: usize,
+    /// Whether to shuffle the samples before every epoch.
+    pub shuffle: bool,
+    /// Seed of the random generator used for shuffling.
+    pub seed: u64,
+}
+
+impl Default for MiniBatch {
+    /// Batches of 32 samples, 10 epochs, shuffled with seed 0.
+    fn default() -> Self {
+        MiniBatch {
+            batch_size: 32,
+            epochs: 10,
+            shuffle: true,
+            seed: 0,
+        }
+    }
+}
+
+impl MiniBatch {
+    /// Uses batches of `batch_size` samples.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Makes `epochs` passes over the samples.
+    pub fn with_epochs(mut self, epochs: usize) -> Self {
+        self.epochs = epochs;
+        self
+    }
+
+    /// Visits the samples in their given order every epoch.
+    pub fn without_shuffle(mut self) -> Self {
+        self.shuffle = false;
+        self
+    }
+
+    /// Shuffles with a generator seeded by `seed`.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Outcome of [`sgd_minimize`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SgdResult<T> {
+    /// Point reached after the last step.
+    pub x: Vec<T>,
+    /// Estimate of the objective for every epoch: the mean of the batch losses
+    /// at the points where the batches were used, weighted by batch size.
+    pub epoch_losses: Vec<T>,
+    /// Number of steps taken, one per batch.
+    pub steps: usize,
+    /// Number of batch loss evaluations performed.
+    pub fevals: usize,
+    /// Number of batch gradient evaluations performed.
+    pub gevals: usize,
+}
+
+/// Minimizes the mean of per-sample losses by mini-batch stochastic gradient
+/// descent, taking the steps of `optimizer`.
+///
+/// The samples are numbered `0..samples`. `loss(x, batch)` and
+/// `grad(x, batch)` return the mean loss and its gradient over the samples
+/// whose indices are in `batch`. Every epoch visits each sample once, in an
+/// order shuffled as `batches` says, and takes one step per batch; the step
+/// count is what the learning rate schedule of `optimizer` sees. The loss of
+/// each batch is evaluated just before its step, which makes the reported
+/// epoch losses a cheap, slightly stale estimate of the objective. A run is
+/// `batches.epochs` epochs long; there is no convergence test, as stochastic
+/// gradients do not vanish at the minimum.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteAtPoint`] if a batch loss or gradient is
+/// NaN or infinite, which usually means the learning rate is too high.
+pub fn sgd_minimize<T, L, G, O>(
+    loss: L,
+    grad: G,
+    samples: usize,
+    x0: &[T],
+    optimizer: &O,
+    batches: MiniBatch,
+) -> Result<SgdResult<T>, OptimizeError>
+where
+    T: Float,
+    L: Fn(&[T], &[usize]) -> T,
+    G: Fn(&[T], &[usize]) -> Vec<T>,
+    O: FirstOrderOptimizer<T> + ?Sized,
+{
+    let mut rng = Rng::new(batches.seed);
+    let mut order: Vec<usize> = (0..samples).collect();
+    let mut state = optimizer.init(x0.len());
+    let mut x = x0.to_vec();
+    let mut epoch_losses = Vec::with_capacity(batches.epochs);
+    let mut steps = 0;
+    for _ in 0..batches.epochs {
+        if batches.shuffle {
+            rng.shuffle(&mut order);
+        }
+        let mut total = T::ZERO;
+        for batch in order.chunks(batches.batch_size.max(1)) {
+            let batch_loss = loss(&x, batch);
+            let gradient = grad(&x, batch);
+            if !batch_loss.is_finite() || gradient.iter().any(|g| !g.is_finite()) {
+                return Err(non_finite_at(&x));
+            }
+            total += batch_loss * T::from_usize(batch.len());
+            let step = optimizer.step(&mut state, steps, &gradient);
+            x = add_scaled(&x, T::ONE, &step);
+            steps += 1;
+        }
+        epoch_losses.push(total / T::from_usize(samples.max(1)));
+    }
+
+    Ok(SgdResult {
+        x,
+        epoch_losses,
+        steps,
+        fevals: steps,
+        gevals: steps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::multivariate::{Adam, Momentum};
+
+    /// Noisy points around the line `y = 2x - 1` for `x` in `[-1, 1]`.
+    fn line_data() -> Vec<(f64, f64)> {
+        let mut rng = Rng::new(11);
+        (0..200)
+            .map(|i| {
+                let x = -1.0 + i as f64 / 100.0;
+                (x, 2.0 * x - 1.0 + rng.uniform(-0.1, 0.1))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fits_a_line() {
+        let data = line_data();
+        let loss = |p: &[f64], batch: &[usize]| {
+            batch
+                .iter()
+                .map(|&i| (p[0] * data[i].0 + p[1] - data[i].1).powi(2))
+                .sum::<f64>()
+                / batch.len() as f64
+        };
+        let grad = |p: &[f64], batch: &[usize]| {
+            let mut g = vec![0.0, 0.0];
+            for &i in batch {
+                let r = p[0] * data[i].0 + p[1] - data[i].1;
+                g[0] += 2.0 * r * data[i].0 / batch.len() as f64;
+                g[1] += 2.0 * r / batch.len() as f64;
+            }
+            g
+        };
+        let batches = MiniBatch::default().with_batch_size(20).with_epochs(100);
+        let result =
+            sgd_minimize(loss, grad, 200, &[0.0, 0.0], &Momentum::default(), batches).unwrap();
+        assert!((result.x[0] - 2.0).abs() < 0.05 && (result.x[1] + 1.0).abs() < 0.05);
+        assert_eq!(result.steps, 1000);
+        assert_eq!(result.epoch_losses.len(), 100);
+        assert!(result.epoch_losses[99] < 0.01 && result.epoch_losses[0] > 0.5);
+
+        let adam = Adam::default().with_learning_rate(0.05);
+        let again = sgd_minimize(loss, grad, 200, &[0.0, 0.0], &adam, batches).unwrap();
+        assert_eq!(
+            again,
+            sgd_minimize(loss, grad, 200, &[0.0, 0.0], &adam, batches).unwrap()
+        );
+        assert!((again.x[0] - 2.0).abs() < 0.05 && (again.x[1] + 1.0).abs() < 0.05);
+    }
+}
@@ -0,0 +1,213 @@
+use std::ops::ControlFlow;
+
+use super::{
+    evaluate, non_finite_at, IterationState, MultivariateResult, Termination, VectorCriteria,
+};
+use crate::generals::linalg::add_scaled;
+use crate::generals::Float;
+use crate::optimize::line_search::LineSearch;
+use crate::optimize::{Budget, OptimizeError};
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` by steepest descent.
+///
+/// Each iteration searches along `-∇f(x)` with `line_search`.
+/// [`Backtracking`](crate::optimize::line_search::Backtracking) is a good
+/// default, since it picks a step that actually lowers the objective; the
+/// classic fixed step length (learning rate) is available as
+/// [`FixedStep`](crate::optimize::line_search::FixedStep). Convergence is
+/// linear and slow on badly scaled problems, where the quasi-Newton methods do
+/// much better.
+///
+/// If the line search fails, the search stops with `converged == false`. A
+/// point with an exactly zero gradient counts as converged.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteAtPoint`] if the objective or gradient is
+/// NaN or infinite at `x0` or at an accepted point, which with a fixed step
+/// usually means the step is too long, and passes on line search errors other
+/// than [`OptimizeError::LineSearchFailed`].
+pub fn steepest_descent<T, F, G, L>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    L: LineSearch<T>,
+{
+    steepest_descent_with_callback(func, grad, x0, line_search, criteria, budget, |_| {
+        ControlFlow::Continue(())
+    })
+}
+
+/// [`steepest_descent`] calling `callback` after every iteration with the
+/// current [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`].
+///
+/// # Errors
+///
+/// As for [`steepest_descent`].
+pub fn steepest_descent_with_callback<T, F, G, L, C>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    line_search: L,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    mut callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    L: LineSearch<T>,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    let criteria = criteria.into();
+    let budget = budget.into();
+    let mut x = x0.to_vec();
+    let (mut fx, mut gradient) = evaluate(&func, &grad, &x)?;
+    let (mut fevals, mut gevals) = (1, 1);
+    let mut iterations = 0;
+    let mut converged = criteria.is_met(&x, &[T::INFINITY], fx, None, &gradient);
+    let mut stopped = None;
+    let mut stall = criteria.stall_monitor(fx);
+    while !converged && budget.allows(iterations, fevals) {
+        if gradient.iter().all(|&g| g == T::ZERO) {
+            converged = true;
+            break;
+        }
+        let direction: Vec<T> = gradient.iter().map(|&g| -g).collect();
+        let step = match line_search.search(&func, &grad, &x, fx, &gradient, &direction) {
+            Ok(step) => step,
+            Err(OptimizeError::LineSearchFailed) => break,
+            Err(error) => return Err(error),
+        };
+        fevals += step.fevals;
+        gevals += step.gevals;
+        let new_gradient = match step.gradient {
+            Some(g) => g,
+            None => {
+                gevals += 1;
+                grad(&step.x)
+            }
+        };
+        if !step.fx.is_finite() || new_gradient.iter().any(|g| !g.is_finite()) {
+            return Err(non_finite_at(&step.x));
+        }
+
+        let s = add_scaled(&step.x, -T::ONE, &x);
+        let df = fx - step.fx;
+        (x, fx, gradient) = (step.x, step.fx, new_gradient);
+        iterations += 1;
+        if callback(&IterationState {
+            iteration: iterations,
+            x: &x,
+            fx,
+            gradient: &gradient,
+            fevals,
+            gevals,
+        })
+        .is_break()
+        {
+            stopped = Some(Termination::Stopped);
+            break;
+        }
+        converged = criteria.is_met(&x, &s, fx, Some(df), &gradient);
+        if !converged && stall.is_stalled(fx) {
+            stopped = Some(Termination::Stalled);
+            break;
+        }
+    }
+
+    Ok(MultivariateResult {
+        x,
+        fx,
+        gradient,
+        hessian: None,
+        iterations,
+        fevals,
+        gevals,
+        converged,
+        termination: Termination::of(converged, stopped, budget.allows(iterations, fevals)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::line_search::{Backtracking, FixedStep};
+    use crate::optimize::ConvergenceCriteria;
+
+    fn bowl(x: &[f64]) -> f64 {
+        x[0] * x[0] + 4.0 * x[1] * x[1]
+    }
+
+    fn bowl_gradient(x: &[f64]) -> Vec<f64> {
+        vec![2.0 * x[0], 8.0 * x[1]]
+    }
+
+    #[test]
+    fn gives_up_on_a_plateau() {
+        // Far out on the flat tail of a Gaussian well, steps gain next to
+        // nothing.
+        let f = |x: &[f64]| -(-x[0] * x[0]).exp();
+        let grad = |x: &[f64]| vec![2.0 * x[0] * (-x[0] * x[0]).exp()];
+        let criteria =
+            VectorCriteria::new(ConvergenceCriteria::Gradient(1e-20)).with_stall(5, 1e-12);
+        let result =
+            steepest_descent(f, grad, &[5.0], Backtracking::default(), criteria, 10_000).unwrap();
+        assert!(!result.converged);
+        assert_eq!(result.termination, Termination::Stalled);
+        assert_eq!(result.iterations, 5);
+    }
+
+    #[test]
+    fn backtracking_picks_decreasing_steps() {
+        let result = steepest_descent(
+            bowl,
+            bowl_gradient,
+            &[3.0, -2.0],
+            Backtracking::default(),
+            ConvergenceCriteria::Gradient(1e-8),
+            1000,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!(result.x.iter().all(|xi| xi.abs() < 1e-8));
+    }
+
+    #[test]
+    fn fixed_step_converges_when_small_and_blows_up_when_large() {
+        let small = steepest_descent(
+            bowl,
+            bowl_gradient,
+            &[3.0, -2.0],
+            FixedStep::new(0.1),
+            ConvergenceCriteria::Gradient(1e-8),
+            1000,
+        )
+        .unwrap();
+        assert!(small.converged);
+        assert_eq!(small.fevals, small.iterations + 1);
+
+        // Steps beyond 2 / 8 diverge along the stiff direction.
+        let large = steepest_descent(
+            bowl,
+            bowl_gradient,
+            &[3.0, -2.0],
+            FixedStep::new(0.3),
+            ConvergenceCriteria::Gradient(1e-8),
+            5000,
+        );
+        assert!(matches!(large, Err(OptimizeError::NonFiniteAtPoint { .. })));
+    }
+}
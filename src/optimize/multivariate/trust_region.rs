@@ -0,0 +1,471 @@
+use std::cell::Cell;
+use std::ops::ControlFlow;
+
+use super::{
+    evaluate, finite_difference_hessian, non_finite_at, IterationState, MultivariateResult,
+    Termination, VectorCriteria,
+};
+use crate::generals::linalg::{add_scaled, cholesky, cholesky_solve, dot, norm};
+use crate::generals::Float;
+use crate::optimize::{Budget, OptimizeError};
+
+/// How [`trust_region_minimize`] manages the trust region radius.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrustRegion<T> {
+    /// Radius of the first trust region.
+    pub initial_radius: T,
+    /// Largest radius the region may grow to.
+    pub max_radius: T,
+    /// Smallest ratio of actual to predicted decrease for a step to be
+    /// accepted, in `[0, 1/4)`.
+    pub eta: T,
+}
+
+impl<T: Float> Default for TrustRegion<T> {
+    /// Initial radius 1, at most `1e3`, and steps accepted from a ratio of
+    /// `0.1`.
+    fn default() -> Self {
+        TrustRegion {
+            initial_radius: T::ONE,
+            max_radius: T::from_f64(1e3),
+            eta: T::from_f64(0.1),
+        }
+    }
+}
+
+impl<T: Float> TrustRegion<T> {
+    /// Starts with a region of radius `initial_radius`.
+    pub fn with_initial_radius(mut self, initial_radius: T) -> Self {
+        self.initial_radius = initial_radius;
+        self
+    }
+
+    /// Never grows the region beyond `max_radius`.
+    pub fn with_max_radius(mut self, max_radius: T) -> Self {
+        self.max_radius = max_radius;
+        self
+    }
+
+    /// Accepts steps whose actual decrease is at least `eta` times the
+    /// predicted one.
+    pub fn with_eta(mut self, eta: T) -> Self {
+        self.eta = eta;
+        self
+    }
+}
+
+/// Minimizes `func`, whose gradient is `grad`, from `x0` with a dogleg trust
+/// region method, approximating the Hessian by central differences of the
+/// gradient.
+///
+/// Each Hessian costs `2n` gradient evaluations, which count towards `gevals`.
+/// See [`trust_region_minimize_with_hessian`] for the method and errors.
+pub fn trust_region_minimize<T, F, G>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    region: TrustRegion<T>,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    trust_region_minimize_with_callback(func, grad, x0, region, criteria, budget, |_| {
+        ControlFlow::Continue(())
+    })
+}
+
+/// [`trust_region_minimize`] calling `callback` after every iteration with the
+/// current [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`].
+///
+/// # Errors
+///
+/// As for [`trust_region_minimize`].
+pub fn trust_region_minimize_with_callback<T, F, G, C>(
+    func: F,
+    grad: G,
+    x0: &[T],
+    region: TrustRegion<T>,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    let hessian_gevals = Cell::new(0);
+    let hessian = |x: &[T]| {
+        hessian_gevals.set(hessian_gevals.get() + 2 * x.len());
+        finite_difference_hessian(&grad, x)
+    };
+    let mut result = trust_region_minimize_with_hessian_and_callback(
+        &func, &grad, hessian, x0, region, criteria, budget, callback,
+    )?;
+    result.gevals += hessian_gevals.get();
+    Ok(result)
+}
+
+/// Minimizes `func` from `x0` with a dogleg trust region method, given its
+/// gradient `grad` and its Hessian `hessian` as a vector of rows.
+///
+/// Instead of searching along a line, each iteration minimizes the quadratic
+/// model of `func` within a radius of the current point: the step follows the
+/// steepest descent direction to the Cauchy point and then bends towards the
+/// Newton step, stopping at the boundary (the dogleg path). Where the Hessian
+/// is not positive definite only the Cauchy point is used. The step is
+/// accepted if the objective drops by at least `region.eta` times the decrease
+/// the model predicted; the radius shrinks after poor predictions and grows
+/// after good ones that reach the boundary. No line search is needed, which
+/// helps when the objective is noisy along lines.
+///
+/// Convergence is tested after accepted steps, and after rejected steps
+/// whose predicted decrease is within roundoff of `|f|`, which cannot be
+/// judged by the objective. The search stops with
+/// `converged == false` once the radius shrinks below machine precision
+/// relative to `x`. Trial points where the objective is NaN or infinite are
+/// rejected like poor steps. A point with an exactly zero gradient counts as
+/// converged.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteAtPoint`] if the objective, gradient or
+/// Hessian is NaN or infinite at `x0`, or if the gradient or Hessian is NaN or
+/// infinite at an accepted point.
+///
+/// # Panics
+///
+/// Panics if `hessian` does not return `n` rows of `n` entries, for `n` the
+/// length of `x0`.
+pub fn trust_region_minimize_with_hessian<T, F, G, H>(
+    func: F,
+    grad: G,
+    hessian: H,
+    x0: &[T],
+    region: TrustRegion<T>,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T]) -> Vec<Vec<T>>,
+{
+    trust_region_minimize_with_hessian_and_callback(
+        func,
+        grad,
+        hessian,
+        x0,
+        region,
+        criteria,
+        budget,
+        |_| ControlFlow::Continue(()),
+    )
+}
+
+/// [`trust_region_minimize_with_hessian`] calling `callback` after every
+/// iteration with the current [`IterationState`].
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false` and [`Termination::Stopped`].
+///
+/// # Errors
+///
+/// As for [`trust_region_minimize_with_hessian`].
+///
+/// # Panics
+///
+/// As for [`trust_region_minimize_with_hessian`].
+#[allow(clippy::too_many_arguments)]
+pub fn trust_region_minimize_with_hessian_and_callback<T, F, G, H, C>(
+    func: F,
+    grad: G,
+    hessian: H,
+    x0: &[T],
+    region: TrustRegion<T>,
+    criteria: impl Into<VectorCriteria<T>>,
+    budget: impl Into<Budget>,
+    mut callback: C,
+) -> Result<MultivariateResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T]) -> Vec<Vec<T>>,
+    C: FnMut(&IterationState<T>) -> ControlFlow<()>,
+{
+    let criteria = criteria.into();
+    let budget = budget.into();
+    let n = x0.len();
+    let quarter = T::from_f64(0.25);
+    let mut x = x0.to_vec();
+    let (mut fx, mut gradient) = evaluate(&func, &grad, &x)?;
+    let mut b = finite_hessian(&hessian, &x)?;
+    let (mut fevals, mut gevals) = (1, 1);
+    let mut radius = region.initial_radius;
+    let mut iterations = 0;
+    let mut converged = criteria.is_met(&x, &[T::INFINITY], fx, None, &gradient);
+    let mut stopped = None;
+    let mut stall = criteria.stall_monitor(fx);
+    while !converged && budget.allows(iterations, fevals) {
+        if gradient.iter().all(|&g| g == T::ZERO) {
+            converged = true;
+            break;
+        }
+        if radius <= T::EPSILON * norm(&x).max(T::ONE) {
+            break;
+        }
+        iterations += 1;
+        let step = dogleg(&b, n, &gradient, radius);
+        let bp = mat_vec(&b, n, &step);
+        let predicted = -(dot(&gradient, &step) + T::from_f64(0.5) * dot(&step, &bp));
+        let trial = add_scaled(&x, T::ONE, &step);
+        let f_trial = func(&trial);
+        fevals += 1;
+        let rho = if f_trial.is_finite() && predicted > T::ZERO {
+            (fx - f_trial) / predicted
+        } else {
+            T::NEG_INFINITY
+        };
+
+        if rho <= region.eta && predicted <= T::EPSILON * fx.abs() {
+            // Near a minimum with a nonzero value the model decrease is lost
+            // in the roundoff of `f`, so judge the step by the criterion.
+            converged = criteria.is_met(&x, &step, fx, Some(T::ZERO), &gradient);
+            if converged {
+                break;
+            }
+        }
+
+        let step_length = norm(&step);
+        if rho < quarter {
+            radius = quarter * step_length;
+        } else if rho > T::from_f64(0.75) && step_length >= T::from_f64(0.99) * radius {
+            radius = (T::from_f64(2.0) * radius).min(region.max_radius);
+        }
+        if rho <= region.eta {
+            continue;
+        }
+
+        let new_gradient = grad(&trial);
+        gevals += 1;
+        if new_gradient.iter().any(|g| !g.is_finite()) {
+            return Err(non_finite_at(&trial));
+        }
+        let df = fx - f_trial;
+        (x, fx, gradient) = (trial, f_trial, new_gradient);
+        b = finite_hessian(&hessian, &x)?;
+        if callback(&IterationState {
+            iteration: iterations,
+            x: &x,
+            fx,
+            gradient: &gradient,
+            fevals,
+            gevals,
+        })
+        .is_break()
+        {
+            stopped = Some(Termination::Stopped);
+            break;
+        }
+        converged = criteria.is_met(&x, &step, fx, Some(df), &gradient);
+        if !converged && stall.is_stalled(fx) {
+            stopped = Some(Termination::Stalled);
+            break;
+        }
+    }
+
+    Ok(MultivariateResult {
+        x,
+        fx,
+        gradient,
+        hessian: Some(b.chunks(n).map(<[T]>::to_vec).collect()),
+        iterations,
+        fevals,
+        gevals,
+        converged,
+        termination: Termination::of(converged, stopped, budget.allows(iterations, fevals)),
+    })
+}
+
+/// Evaluates `hessian` at `x` as a row-major `n × n` matrix, checking that it
+/// is finite.
+///
+/// # Panics
+///
+/// Panics if `hessian` does not return `n` rows of `n` entries.
+fn finite_hessian<T, H>(hessian: H, x: &[T]) -> Result<Vec<T>, OptimizeError>
+where
+    T: Float,
+    H: Fn(&[T]) -> Vec<Vec<T>>,
+{
+    let n = x.len();
+    let rows = hessian(x);
+    assert!(
+        rows.len() == n && rows.iter().all(|row| row.len() == n),
+        "the Hessian must have n rows of n entries"
+    );
+    let b = rows.concat();
+    if b.iter().all(|v| v.is_finite()) {
+        Ok(b)
+    } else {
+        Err(non_finite_at(x))
+    }
+}
+
+/// Product of the row-major `n × n` matrix `b` with `v`.
+fn mat_vec<T: Float>(b: &[T], n: usize, v: &[T]) -> Vec<T> {
+    (0..n).map(|i| dot(&b[i * n..(i + 1) * n], v)).collect()
+}
+
+/// Minimizer of the model `gᵀp + ½pᵀBp` along the dogleg path within
+/// `‖p‖ ≤ radius`.
+fn dogleg<T: Float>(b: &[T], n: usize, gradient: &[T], radius: T) -> Vec<T> {
+    let g_norm = norm(gradient);
+    let along_gradient =
+        |length: T| -> Vec<T> { gradient.iter().map(|&g| -length / g_norm * g).collect() };
+    let to_boundary = along_gradient(radius);
+    let curvature = dot(gradient, &mat_vec(b, n, gradient));
+    if curvature <= T::ZERO {
+        return to_boundary;
+    }
+    let cauchy_length = g_norm * g_norm * g_norm / curvature;
+    if cauchy_length >= radius {
+        return to_boundary;
+    }
+    let cauchy = along_gradient(cauchy_length);
+    let Some(l) = cholesky(b, n) else {
+        return cauchy;
+    };
+    let minus_gradient: Vec<T> = gradient.iter().map(|&g| -g).collect();
+    let newton = cholesky_solve(&l, n, &minus_gradient);
+    if norm(&newton) <= radius {
+        return newton;
+    }
+    // Solve ‖cauchy + τ·(newton - cauchy)‖ = radius for τ in [0, 1].
+    let leg = add_scaled(&newton, -T::ONE, &cauchy);
+    let a = dot(&leg, &leg);
+    let half_b = dot(&cauchy, &leg);
+    let c = dot(&cauchy, &cauchy) - radius * radius;
+    let tau = (-half_b + (half_b * half_b - a * c).max(T::ZERO).sqrt()) / a;
+    add_scaled(&cauchy, tau, &leg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::ConvergenceCriteria;
+
+    fn rosenbrock(x: &[f64]) -> f64 {
+        (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2)
+    }
+
+    fn rosenbrock_gradient(x: &[f64]) -> Vec<f64> {
+        vec![
+            -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+            200.0 * (x[1] - x[0] * x[0]),
+        ]
+    }
+
+    #[test]
+    fn minimizes_rosenbrock() {
+        let result = trust_region_minimize(
+            rosenbrock,
+            rosenbrock_gradient,
+            &[-1.2, 1.0],
+            TrustRegion::default(),
+            ConvergenceCriteria::Gradient(1e-9),
+            200,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert_eq!(result.termination, Termination::Converged);
+        assert!((result.x[0] - 1.0).abs() < 1e-7 && (result.x[1] - 1.0).abs() < 1e-7);
+        assert!(result.gradient_norm() <= 1e-9);
+        // The Hessian at the minimum (1, 1).
+        let hessian = result.hessian.unwrap();
+        for (row, expected) in hessian.iter().zip([[802.0, -400.0], [-400.0, 200.0]]) {
+            for (h, e) in row.iter().zip(expected) {
+                assert!((h - e).abs() < 1e-4, "{hessian:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn dogleg_stays_inside_the_region() {
+        let b = [2.0, 0.0, 0.0, 20.0];
+        let g = [4.0, 4.0];
+        for radius in [0.01, 0.1, 0.5, 10.0] {
+            let p = dogleg(&b, 2, &g, radius);
+            assert!(norm(&p) <= radius * (1.0 + 1e-12));
+            assert!(dot(&p, &g) < 0.0);
+        }
+        // A large region allows the full Newton step.
+        let p = dogleg(&b, 2, &g, 10.0);
+        assert!((p[0] + 2.0).abs() < 1e-12 && (p[1] + 0.2).abs() < 1e-12);
+        // Negative curvature goes straight to the boundary.
+        let p = dogleg(&[-1.0, 0.0, 0.0, -1.0], 2, &g, 0.5);
+        assert!((norm(&p) - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn escapes_a_saddle() {
+        let f = |x: &[f64]| x[0].powi(4) - 2.0 * x[0] * x[0] + x[1] * x[1];
+        let grad = |x: &[f64]| vec![4.0 * x[0].powi(3) - 4.0 * x[0], 2.0 * x[1]];
+        let hessian = |x: &[f64]| vec![vec![12.0 * x[0] * x[0] - 4.0, 0.0], vec![0.0, 2.0]];
+        let result = trust_region_minimize_with_hessian(
+            f,
+            grad,
+            hessian,
+            &[-0.05, 1.0],
+            TrustRegion::default().with_initial_radius(0.1),
+            ConvergenceCriteria::Gradient(1e-10),
+            200,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!((result.x[0] + 1.0).abs() < 1e-8 && result.x[1].abs() < 1e-8);
+    }
+
+    #[test]
+    fn converges_to_a_nonzero_minimum() {
+        let f = |x: &[f64]| (x[0] - 3.0).powi(2) + 10.0 * (x[1] + 1.0).powi(2) + 5.0;
+        let grad = |x: &[f64]| vec![2.0 * (x[0] - 3.0), 20.0 * (x[1] + 1.0)];
+        for criteria in [
+            ConvergenceCriteria::default(),
+            ConvergenceCriteria::AbsoluteX(1e-10),
+            ConvergenceCriteria::RelativeF(1e-12),
+        ] {
+            let result =
+                trust_region_minimize(f, grad, &[0.0, 0.0], TrustRegion::default(), criteria, 200)
+                    .unwrap();
+            assert!(result.converged, "{criteria:?}");
+            assert_eq!(result.termination, Termination::Converged);
+            assert!((result.x[0] - 3.0).abs() < 1e-8 && (result.x[1] + 1.0).abs() < 1e-8);
+            assert!((result.fx - 5.0).abs() < 1e-14);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "n rows of n entries")]
+    fn jagged_hessian_panics() {
+        let _ = trust_region_minimize_with_hessian(
+            rosenbrock,
+            rosenbrock_gradient,
+            |_: &[f64]| vec![vec![1.0], vec![0.0, 1.0, 0.0]],
+            &[-1.2, 1.0],
+            TrustRegion::default(),
+            ConvergenceCriteria::Gradient(1e-9),
+            200,
+        );
+    }
+}
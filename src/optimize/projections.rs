@@ -0,0 +1,151 @@
+//! Euclidean projections onto simple convex sets.
+//!
+//! Each function returns the point of its set closest to `x`. They are meant
+//! for [`projected_minimize_onto`], which keeps the iterates of a first-order
+//! method in the set by projecting after every step, as in
+//! `|x: &[f64]| project_simplex(x, 1.0)` for probability vectors.
+//!
+//! [`projected_minimize_onto`]: super::multivariate::projected_minimize_onto
+
+use crate::generals::linalg::{add_scaled, dot, norm};
+use crate::generals::Float;
+
+/// Projection of `x` onto the simplex `{y : yᵢ ≥ 0, Σyᵢ = total}`; with a
+/// `total` of one, onto the probability vectors.
+///
+/// Sorts the coordinates, so it costs `O(n log n)`.
+///
+/// # Panics
+///
+/// Panics if `total` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::optimize::projections::project_simplex;
+///
+/// assert_eq!(project_simplex(&[1.0, 0.5, 0.0], 1.0), vec![0.75, 0.25, 0.0]);
+/// assert_eq!(project_simplex(&[2.0, 1.0, 0.0], 1.0), vec![1.0, 0.0, 0.0]);
+/// ```
+pub fn project_simplex<T: Float>(x: &[T], total: T) -> Vec<T> {
+    assert!(total > T::ZERO, "the simplex total must be positive");
+    // The projection is max(xᵢ - θ, 0), for the θ that makes the positive
+    // parts sum to `total`; it is found from the coordinates in decreasing
+    // order.
+    let mut sorted = x.to_vec();
+    sorted.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+    let mut sum = T::ZERO;
+    let mut theta = T::ZERO;
+    for (k, &value) in sorted.iter().enumerate() {
+        sum += value;
+        let candidate = (sum - total) / T::from_usize(k + 1);
+        if value > candidate {
+            theta = candidate;
+        }
+    }
+    x.iter().map(|&xi| (xi - theta).max(T::ZERO)).collect()
+}
+
+/// Projection of `x` onto the ball `{y : ‖y - center‖ ≤ radius}` in the
+/// Euclidean norm.
+///
+/// # Panics
+///
+/// Panics if `center` is not as long as `x` or `radius` is negative.
+pub fn project_ball<T: Float>(x: &[T], center: &[T], radius: T) -> Vec<T> {
+    assert_eq!(x.len(), center.len(), "center must be as long as x");
+    assert!(radius >= T::ZERO, "the radius must not be negative");
+    let offset = add_scaled(x, -T::ONE, center);
+    let distance = norm(&offset);
+    if distance <= radius {
+        x.to_vec()
+    } else {
+        add_scaled(center, radius / distance, &offset)
+    }
+}
+
+/// Projection of `x` onto the box `{y : lowerᵢ ≤ yᵢ ≤ upperᵢ}`.
+///
+/// Infinite bounds leave a coordinate free, as for
+/// [`Bounds`](super::multivariate::Bounds), whose
+/// [`clamp`](super::multivariate::Bounds::clamp) this is.
+///
+/// # Panics
+///
+/// Panics if `lower` or `upper` is not as long as `x`.
+pub fn project_box<T: Float>(x: &[T], lower: &[T], upper: &[T]) -> Vec<T> {
+    assert!(
+        lower.len() == x.len() && upper.len() == x.len(),
+        "bounds must have one entry per coordinate"
+    );
+    x.iter()
+        .zip(lower.iter().zip(upper))
+        .map(|(&xi, (&lo, &hi))| xi.max(lo).min(hi))
+        .collect()
+}
+
+/// Projection of `x` onto the halfspace `{y : normal·y ≤ offset}`.
+///
+/// A zero `normal` describes all of space, or nothing if `offset` is
+/// negative; `x` is returned unchanged in both cases.
+///
+/// # Panics
+///
+/// Panics if `normal` is not as long as `x`.
+pub fn project_halfspace<T: Float>(x: &[T], normal: &[T], offset: T) -> Vec<T> {
+    assert_eq!(x.len(), normal.len(), "normal must be as long as x");
+    let excess = dot(normal, x) - offset;
+    let length2 = dot(normal, normal);
+    if excess <= T::ZERO || length2 == T::ZERO {
+        x.to_vec()
+    } else {
+        add_scaled(x, -excess / length2, normal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simplex_projection_is_closest() {
+        let x = [0.9, -0.3, 0.6, 0.2, 1.4];
+        let p = project_simplex(&x, 2.0);
+        assert!(p.iter().all(|&pi| pi >= 0.0));
+        assert!((p.iter().sum::<f64>() - 2.0).abs() < 1e-12);
+        // Optimality: x - p is constant on the support and no smaller off it.
+        let theta = x[0] - p[0];
+        for (xi, pi) in x.iter().zip(&p) {
+            if *pi > 0.0 {
+                assert!((xi - pi - theta).abs() < 1e-12);
+            } else {
+                assert!(*xi <= theta + 1e-12);
+            }
+        }
+        // Points already on the simplex stay put.
+        let q = project_simplex(&p, 2.0);
+        assert!(p.iter().zip(&q).all(|(a, b)| (a - b).abs() < 1e-15));
+    }
+
+    #[test]
+    fn ball_box_and_halfspace() {
+        let p = project_ball(&[3.0, 4.0], &[1.0, 0.0], 1.0);
+        let root5 = 5f64.sqrt();
+        assert!((p[0] - (1.0 + 1.0 / root5)).abs() < 1e-15);
+        assert!((p[1] - 2.0 / root5).abs() < 1e-15);
+        assert_eq!(project_ball(&[0.5, 0.5], &[0.0, 0.0], 1.0), vec![0.5, 0.5]);
+        assert_eq!(
+            project_box(&[-2.0, 0.5, 7.0], &[-1.0; 3], &[1.0, 1.0, f64::INFINITY]),
+            vec![-1.0, 0.5, 7.0]
+        );
+        // x + y ≤ 1.
+        assert_eq!(
+            project_halfspace(&[2.0, 1.0], &[1.0, 1.0], 1.0),
+            vec![1.0, 0.0]
+        );
+        assert_eq!(
+            project_halfspace(&[0.0, 0.0], &[1.0, 1.0], 1.0),
+            vec![0.0, 0.0]
+        );
+    }
+}
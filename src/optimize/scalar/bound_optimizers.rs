@@ -0,0 +1,376 @@
+use std::ops::ControlFlow;
+
+use super::{golden_fraction, Enclosure, IterationInfo, ScalarResult, Side};
+use crate::generals::Float;
+use crate::optimize::{counted, Budget, ConvergenceCriteria, Evaluations, OptimizeError, Progress};
+
+/// Minimizes `func` on the interval `[a, b]` by interval reduction.
+///
+/// Each iteration evaluates two interior points at `ratio` of the interval
+/// length from either end and discards the part beyond the worse one, so
+/// `ratio` must lie in `(0.5, 1)`. When the surviving interior point lines up
+/// with the next one, as it does for the golden ratio, its value is reused so
+/// the iteration costs a single evaluation. The search stops once `criteria` is met,
+/// where the x uncertainty is the width of the remaining interval and the
+/// derivative is estimated from the two interior points. The midpoint of the
+/// final interval is returned, and the interval itself as the enclosure.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteObjective`] if `func` is NaN or infinite
+/// at an evaluated point.
+pub fn bound_minimize<T, F>(
+    func: F,
+    a: T,
+    b: T,
+    ratio: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    bound_minimize_with_callback(func, a, b, ratio, criteria, budget, |_| {
+        ControlFlow::Continue(())
+    })
+}
+
+/// [`bound_minimize`] calling `callback` after every iteration with the
+/// remaining interval and the better interior point.
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false`.
+///
+/// # Errors
+///
+/// As for [`bound_minimize`].
+pub fn bound_minimize_with_callback<T, F, C>(
+    func: F,
+    a: T,
+    b: T,
+    ratio: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+    mut callback: C,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+    C: FnMut(&IterationInfo<T>) -> ControlFlow<()>,
+{
+    let criteria = criteria.into();
+    let budget = budget.into();
+    let fevals = Evaluations::new();
+    let func = counted(func, &fevals);
+    let (mut a, mut b) = if a < b { (a, b) } else { (b, a) };
+    let (mut fa, mut fb) = (None, None);
+    let mut best = T::INFINITY;
+    let mut iterations = 0;
+    let mut converged = false;
+    // Interior point carried over from the previous iteration, with its value.
+    let mut kept: Option<(Side, T, T)> = None;
+    while !converged && fevals.all_finite() && budget.allows(iterations, fevals.get()) {
+        let mut c = b - ratio * (b - a);
+        let mut d = a + ratio * (b - a);
+        let alignment = T::EPSILON.sqrt() * (b - a);
+        let (fc, fd) = match kept {
+            Some((Side::Left, x, fx)) if (x - c).abs() <= alignment => {
+                c = x;
+                (fx, func(d))
+            }
+            Some((Side::Right, x, fx)) if (x - d).abs() <= alignment => {
+                d = x;
+                (func(c), fx)
+            }
+            _ => (func(c), func(d)),
+        };
+        let (x, fx) = if fc < fd {
+            b = d;
+            fb = Some(fd);
+            kept = Some((Side::Right, c, fc));
+            (c, fc)
+        } else {
+            a = c;
+            fa = Some(fc);
+            kept = Some((Side::Left, d, fd));
+            (d, fd)
+        };
+        iterations += 1;
+        let info = IterationInfo {
+            iteration: iterations,
+            lower: a,
+            upper: b,
+            x,
+            fx,
+        };
+        if callback(&info).is_break() {
+            break;
+        }
+        converged = criteria.is_met(&Progress {
+            x,
+            dx: b - a,
+            fx,
+            df: (fx < best && best.is_finite()).then(|| best - fx),
+            slope: (fd - fc) / (d - c),
+        });
+        best = best.min(fx);
+    }
+    let x = T::from_f64(0.5) * (a + b);
+    let fx = func(x);
+    fevals.verify(Ok(ScalarResult {
+        x,
+        fx,
+        iterations,
+        fevals: fevals.get(),
+        converged,
+        enclosure: Some(Enclosure {
+            lower: a,
+            upper: b,
+            f_lower: fa,
+            f_upper: fb,
+        }),
+    }))
+}
+
+/// Golden section search on the interval `[a, b]`.
+///
+/// See [`bound_minimize`] for the stopping rule and errors.
+pub fn bound_gr_minimize<T, F>(
+    func: F,
+    a: T,
+    b: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    bound_minimize(
+        func,
+        a,
+        b,
+        T::ONE - golden_fraction::<T>(),
+        criteria,
+        budget,
+    )
+}
+
+/// Maximizes `func` by golden section search on the interval `[a, b]`.
+///
+/// The returned `fx` is the maximum of `func` itself, not of its negation.
+pub fn bound_gr_maximize<T, F>(
+    func: F,
+    a: T,
+    b: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    bound_gr_minimize(|x| -func(x), a, b, criteria, budget).map(ScalarResult::negated)
+}
+
+/// Minimizes `func` on `[a, b]` by Fibonacci search using exactly `n_evals`
+/// objective evaluations.
+///
+/// For a fixed evaluation budget this gives the smallest possible final
+/// interval, of width `(b - a) / F(n_evals)` where `F` is the Fibonacci
+/// sequence starting `1, 1, 2`, up to a small offset separating the last two
+/// points. The best evaluated point is returned with the final interval as the
+/// enclosure, and `iterations` counts the interval reductions. Budgets below 3 are raised to 3.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteObjective`] if `func` is NaN or infinite
+/// at an evaluated point.
+pub fn fibonacci_minimize<T, F>(
+    func: F,
+    a: T,
+    b: T,
+    n_evals: usize,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let fevals = Evaluations::new();
+    let func = counted(func, &fevals);
+    let n = n_evals.max(3);
+    let mut fib = vec![T::ONE, T::ONE];
+    for i in 2..=n {
+        fib.push(fib[i - 1] + fib[i - 2]);
+    }
+    // Separation of the two final points, as a fraction of the last interval.
+    let separation = T::from_f64(0.01);
+
+    let (mut a, mut b) = if a < b { (a, b) } else { (b, a) };
+    let (mut fa, mut fb) = (None, None);
+    let mut x1 = a + fib[n - 2] / fib[n] * (b - a);
+    let mut x2 = a + fib[n - 1] / fib[n] * (b - a);
+    let mut f1 = func(x1);
+    let mut f2 = func(x2);
+
+    for k in 1..=n - 2 {
+        let last = k == n - 2;
+        if f1 < f2 {
+            b = x2;
+            fb = Some(f2);
+            x2 = x1;
+            f2 = f1;
+            x1 = if last {
+                x2 - separation * (b - a)
+            } else {
+                a + fib[n - k - 2] / fib[n - k] * (b - a)
+            };
+            f1 = func(x1);
+        } else {
+            a = x1;
+            fa = Some(f1);
+            x1 = x2;
+            f1 = f2;
+            x2 = if last {
+                x1 + separation * (b - a)
+            } else {
+                a + fib[n - k - 1] / fib[n - k] * (b - a)
+            };
+            f2 = func(x2);
+        }
+    }
+
+    let (x, fx) = if f1 < f2 {
+        (b, fb) = (x2, Some(f2));
+        (x1, f1)
+    } else {
+        (a, fa) = (x1, Some(f1));
+        (x2, f2)
+    };
+    fevals.verify(Ok(ScalarResult {
+        x,
+        fx,
+        iterations: n - 1,
+        fevals: n,
+        converged: true,
+        enclosure: Some(Enclosure {
+            lower: a,
+            upper: b,
+            f_lower: fa,
+            f_upper: fb,
+        }),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn golden_bound_finds_minimum() {
+        let result =
+            bound_gr_minimize(|x: f64| (x - 0.3).powi(2) + 2.0, -1.0, 4.0, 1e-8, 200).unwrap();
+        assert!(result.converged);
+        assert!((result.x - 0.3).abs() < 1e-7);
+        assert!((result.fx - 2.0).abs() < 1e-12);
+        let enclosure = result.enclosure.unwrap();
+        // Up to rounding in f, which cannot resolve x closer than about 1e-8.
+        assert!(enclosure.lower - 1e-7 <= 0.3 && 0.3 <= enclosure.upper + 1e-7);
+        assert!(enclosure.width() < 1e-8);
+        assert_eq!(
+            enclosure.f_upper,
+            Some((enclosure.upper - 0.3).powi(2) + 2.0)
+        );
+    }
+
+    #[test]
+    fn stops_at_max_iter() {
+        let result = bound_minimize(|x: f64| x * x, -1.0, 1.0, 0.7, 1e-12, 5).unwrap();
+        assert_eq!(result.iterations, 5);
+        // A ratio of 0.7 never lines up, so every iteration evaluates twice.
+        assert_eq!(result.fevals, 11);
+        assert!(!result.converged);
+    }
+
+    #[test]
+    fn callback_sees_shrinking_interval_and_can_stop() {
+        let mut widths = Vec::new();
+        let result = bound_minimize_with_callback(
+            |x: f64| x * x,
+            -1.0,
+            1.0,
+            0.618,
+            1e-12,
+            100,
+            |info| {
+                widths.push(info.upper - info.lower);
+                if info.iteration == 10 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            },
+        )
+        .unwrap();
+        assert_eq!(result.iterations, 10);
+        assert!(!result.converged);
+        assert_eq!(widths.len(), 10);
+        assert!(widths.windows(2).all(|w| w[1] < w[0]));
+    }
+
+    #[test]
+    fn golden_section_costs_one_evaluation_per_iteration() {
+        let result = bound_gr_minimize(|x: f64| (x - 0.3).powi(2), -1.0, 4.0, 1e-6, 200).unwrap();
+        assert!(result.converged);
+        // Two initial interior points, one per further iteration, plus the midpoint.
+        assert_eq!(result.fevals, result.iterations + 2);
+    }
+
+    #[test]
+    fn evaluation_budget_stops_search() {
+        let result = bound_gr_minimize(
+            |x: f64| x * x,
+            -1.0,
+            1.0,
+            1e-12,
+            Budget::new(100).with_max_fevals(20),
+        )
+        .unwrap();
+        assert!(!result.converged);
+        assert_eq!(result.fevals, 21);
+        assert_eq!(result.iterations, 19);
+    }
+
+    #[test]
+    fn fibonacci_uses_exact_budget() {
+        use std::cell::Cell;
+        let calls = Cell::new(0);
+        let f = |x: f64| {
+            calls.set(calls.get() + 1);
+            (x - 1.234).powi(2)
+        };
+        let result = fibonacci_minimize(f, 0.0, 10.0, 30).unwrap();
+        assert_eq!(calls.get(), 30);
+        // F(30) = 1346269, so the final interval is below 1e-5 wide.
+        assert!((result.x - 1.234).abs() < 1e-5);
+        let enclosure = result.enclosure.unwrap();
+        assert!(enclosure.lower <= 1.234 && 1.234 <= enclosure.upper);
+        assert!(enclosure.width() < 1e-5);
+    }
+
+    #[test]
+    fn maximizer_reports_true_maximum() {
+        let result = bound_gr_maximize(|x: f64| x.sin(), 0.0, 3.0, 1e-8, 200).unwrap();
+        assert!((result.x - std::f64::consts::FRAC_PI_2).abs() < 1e-7);
+        assert!((result.fx - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn nan_objective_is_reported() {
+        let f = |x: f64| if x < 0.5 { x.sqrt().ln() } else { f64::NAN };
+        let error = bound_gr_minimize(f, 0.0, 1.0, 1e-8, 100).unwrap_err();
+        assert!(matches!(error, OptimizeError::NonFiniteObjective { .. }));
+        assert!(fibonacci_minimize(|_: f64| f64::NAN, 0.0, 1.0, 10).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+use std::cmp::Ordering;
+
+use super::parabola_slope;
+use crate::generals::Float;
+use crate::optimize::OptimizeError;
+
+/// Three points `left < center < right` enclosing a minimum, together with the
+/// objective values at each of them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bracket<T> {
+    pub left: T,
+    pub center: T,
+    pub right: T,
+    pub f_left: T,
+    pub f_center: T,
+    pub f_right: T,
+}
+
+/// Side of a [`Bracket`] relative to its center.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl<T: Float> Bracket<T> {
+    /// Evaluates `func` at the three points and sorts them into a bracket.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptimizeError::NonFiniteObjective`] if `func` is NaN or infinite
+    /// at one of the points, [`OptimizeError::DupeBoundForBracket`] if two of the
+    /// points coincide and [`OptimizeError::BracketNotADip`] if the middle point
+    /// is not strictly lower than both outer points.
+    pub fn new<F: Fn(T) -> T>(func: F, x1: T, x2: T, x3: T) -> Result<Self, OptimizeError> {
+        Self::from_evaluated([(x1, func(x1)), (x2, func(x2)), (x3, func(x3))])
+    }
+
+    /// Searches downhill from `x1` and `x2` for a bracket inside `[lower, upper]`.
+    ///
+    /// Starting from the lower of the two points, steps growing by the golden
+    /// ratio are taken away from the higher one until the objective rises again.
+    /// At most `max_steps` new points are evaluated.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptimizeError::BracketNotADip`] if the search reaches a bound or
+    /// runs out of steps while the objective is still decreasing, which usually
+    /// means the minimum lies on the boundary, and
+    /// [`OptimizeError::NonFiniteObjective`] if `func` returns NaN or an infinite
+    /// value along the way.
+    pub fn find<F: Fn(T) -> T>(
+        func: F,
+        x1: T,
+        x2: T,
+        lower: T,
+        upper: T,
+        max_steps: usize,
+    ) -> Result<Self, OptimizeError> {
+        let grow = T::from_f64(1.618_033_988_749_895);
+        let (f1, f2) = (finite(&func, x1)?, finite(&func, x2)?);
+        let ((mut xa, mut fa), (mut xb, mut fb)) = if f2 <= f1 {
+            ((x1, f1), (x2, f2))
+        } else {
+            ((x2, f2), (x1, f1))
+        };
+        for _ in 0..max_steps {
+            let xc = (xb + grow * (xb - xa)).max(lower).min(upper);
+            if xc == xb {
+                break;
+            }
+            let fc = finite(&func, xc)?;
+            if fc > fb {
+                if fa > fb {
+                    return Self::from_evaluated([(xa, fa), (xb, fb), (xc, fc)]);
+                }
+                // Equal starting values: look for the dip between them.
+                let xm = T::from_f64(0.5) * (xa + xb);
+                return Self::from_evaluated([(xa, fa), (xm, func(xm)), (xb, fb)]);
+            }
+            (xa, fa) = (xb, fb);
+            (xb, fb) = (xc, fc);
+        }
+        Err(OptimizeError::BracketNotADip)
+    }
+
+    fn from_evaluated(mut points: [(T, T); 3]) -> Result<Self, OptimizeError> {
+        if let Some(&(x, _)) = points.iter().find(|(_, fx)| !fx.is_finite()) {
+            return Err(OptimizeError::NonFiniteObjective { x: x.to_f64() });
+        }
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        let [(left, f_left), (center, f_center), (right, f_right)] = points;
+        if left == center || center == right {
+            return Err(OptimizeError::DupeBoundForBracket);
+        }
+        if !(f_center < f_left && f_center < f_right) {
+            return Err(OptimizeError::BracketNotADip);
+        }
+        Ok(Bracket {
+            left,
+            center,
+            right,
+            f_left,
+            f_center,
+            f_right,
+        })
+    }
+
+    /// Distance between the outer points.
+    pub fn width(&self) -> T {
+        self.right - self.left
+    }
+
+    /// Derivative at the center of the parabola through the three points.
+    pub fn slope(&self) -> T {
+        parabola_slope(
+            self.center,
+            self.f_center,
+            self.left,
+            self.f_left,
+            self.right,
+            self.f_right,
+        )
+    }
+
+    /// The side whose bound lies furthest from the center.
+    pub fn longer_bound(&self) -> Side {
+        if self.right - self.center > self.center - self.left {
+            Side::Right
+        } else {
+            Side::Left
+        }
+    }
+
+    /// Narrows the bracket with a new point `x` strictly inside it, where `fx` is
+    /// the objective value at `x`.
+    ///
+    /// If `x` improves on the center it becomes the new center, otherwise it
+    /// replaces the bound on its side.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptimizeError::DupeBoundForBracket`] if `x` coincides with one of
+    /// the existing points or falls outside the bracket, and
+    /// [`OptimizeError::NonFiniteObjective`] if `fx` is NaN or infinite.
+    pub fn update(&mut self, x: T, fx: T) -> Result<(), OptimizeError> {
+        if !fx.is_finite() {
+            return Err(OptimizeError::NonFiniteObjective { x: x.to_f64() });
+        }
+        if !(self.left < x && x < self.right) || x == self.center {
+            return Err(OptimizeError::DupeBoundForBracket);
+        }
+        let side = if x < self.center {
+            Side::Left
+        } else {
+            Side::Right
+        };
+        match (fx < self.f_center, side) {
+            (true, Side::Left) => {
+                self.right = self.center;
+                self.f_right = self.f_center;
+                self.center = x;
+                self.f_center = fx;
+            }
+            (true, Side::Right) => {
+                self.left = self.center;
+                self.f_left = self.f_center;
+                self.center = x;
+                self.f_center = fx;
+            }
+            (false, Side::Left) => {
+                self.left = x;
+                self.f_left = fx;
+            }
+            (false, Side::Right) => {
+                self.right = x;
+                self.f_right = fx;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Evaluates `func` at `x`, failing on NaN or infinite values.
+fn finite<T: Float, F: Fn(T) -> T>(func: F, x: T) -> Result<T, OptimizeError> {
+    let fx = func(x);
+    if fx.is_finite() {
+        Ok(fx)
+    } else {
+        Err(OptimizeError::NonFiniteObjective { x: x.to_f64() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_sorts_points() {
+        let bracket = Bracket::new(|x: f64| x * x, 2.5, -1.0, 0.5).unwrap();
+        assert_eq!(
+            (bracket.left, bracket.center, bracket.right),
+            (-1.0, 0.5, 2.5)
+        );
+        assert_eq!(bracket.f_center, 0.25);
+        assert_eq!(bracket.longer_bound(), Side::Right);
+        assert!((bracket.slope() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn update_replaces_center_or_bound() {
+        let f = |x: f64| x * x;
+        let mut bracket = Bracket::new(f, -1.0, 0.5, 2.5).unwrap();
+        bracket.update(0.1, f(0.1)).unwrap();
+        assert_eq!(
+            (bracket.left, bracket.center, bracket.right),
+            (-1.0, 0.1, 0.5)
+        );
+        bracket.update(-0.5, f(-0.5)).unwrap();
+        assert_eq!(
+            (bracket.left, bracket.center, bracket.right),
+            (-0.5, 0.1, 0.5)
+        );
+        assert_eq!(
+            bracket.update(0.5, f(0.5)),
+            Err(OptimizeError::DupeBoundForBracket)
+        );
+    }
+
+    #[test]
+    fn find_expands_downhill() {
+        let f = |x: f64| (x - 10.0).powi(2);
+        let bracket = Bracket::find(f, 0.0, 1.0, -100.0, 100.0, 50).unwrap();
+        assert!(bracket.left < 10.0 && 10.0 < bracket.right);
+        assert!(bracket.f_center < bracket.f_left && bracket.f_center < bracket.f_right);
+        assert_eq!(
+            Bracket::find(f, 0.0, 1.0, -100.0, 5.0, 50),
+            Err(OptimizeError::BracketNotADip)
+        );
+    }
+
+    #[test]
+    fn find_handles_symmetric_start() {
+        let bracket = Bracket::find(|x: f64| (x - 0.5).powi(2), 0.0, 1.0, -10.0, 10.0, 50).unwrap();
+        assert_eq!(bracket.center, 0.5);
+    }
+
+    #[test]
+    fn nan_values_are_rejected() {
+        let f = |x: f64| if x > 1.0 { f64::NAN } else { x * x };
+        assert_eq!(
+            Bracket::new(f, -1.0, 0.5, 2.0),
+            Err(OptimizeError::NonFiniteObjective { x: 2.0 })
+        );
+        let mut bracket = Bracket::new(f, -1.0, 0.5, 1.0).unwrap();
+        assert_eq!(
+            bracket.update(0.7, f64::NAN),
+            Err(OptimizeError::NonFiniteObjective { x: 0.7 })
+        );
+        assert_eq!(bracket.center, 0.5);
+    }
+
+    #[test]
+    fn new_rejects_non_dip() {
+        assert_eq!(
+            Bracket::new(|x: f64| x, 0.0, 1.0, 2.0),
+            Err(OptimizeError::BracketNotADip)
+        );
+    }
+}
@@ -0,0 +1,600 @@
+use std::ops::ControlFlow;
+
+use super::bracket::Bracket;
+use super::iterators::{GoldenSectionIter, ParabolicIter};
+use super::{golden_fraction, Enclosure, IterationInfo, ScalarResult};
+use crate::generals::random::Rng;
+use crate::generals::Float;
+use crate::optimize::{counted, Budget, ConvergenceCriteria, Evaluations, OptimizeError, Progress};
+
+/// Minimizes `func` by repeatedly splitting the longer half of the bracket
+/// `(x1, x2, x3)`.
+///
+/// The new point is placed at `ratio` of the way from the center towards the
+/// furthest bound, so `ratio` must lie in `(0, 1)`. The search stops once
+/// `criteria` is met, where the x uncertainty is the distance from the center
+/// to the newly evaluated point and the derivative is estimated from the
+/// parabola through the bracket. If a new point collides with an existing one
+/// the loop ends early with `converged == false`.
+///
+/// # Errors
+///
+/// Fails if the initial points do not form a valid [`Bracket`], and returns
+/// [`OptimizeError::NonFiniteObjective`] if `func` is NaN or infinite at any
+/// evaluated point.
+pub fn bracket_ratio_minimize<T, F>(
+    func: F,
+    x1: T,
+    x2: T,
+    x3: T,
+    ratio: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    bracket_ratio_minimize_with_callback(func, x1, x2, x3, ratio, criteria, budget, |_| {
+        ControlFlow::Continue(())
+    })
+}
+
+/// [`bracket_ratio_minimize`] calling `callback` after every iteration with the
+/// current bracket.
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false`.
+///
+/// # Errors
+///
+/// As for [`bracket_ratio_minimize`].
+#[allow(clippy::too_many_arguments)]
+pub fn bracket_ratio_minimize_with_callback<T, F, C>(
+    func: F,
+    x1: T,
+    x2: T,
+    x3: T,
+    ratio: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+    callback: C,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+    C: FnMut(&IterationInfo<T>) -> ControlFlow<()>,
+{
+    let fevals = Evaluations::new();
+    let func = counted(func, &fevals);
+    let bracket = Bracket::new(&func, x1, x2, x3)?;
+    fevals.verify(Ok(ratio_loop(
+        &func,
+        bracket,
+        ratio,
+        (criteria.into(), budget.into()),
+        &fevals,
+        None,
+        callback,
+    )))
+}
+
+/// [`bracket_ratio_minimize`] recovering from colliding points with random
+/// restarts.
+///
+/// Whenever a new point would coincide with an existing one, it is replaced by
+/// a point at a random fraction of the longer half of the bracket, drawn from a
+/// generator seeded with `restarts.seed`, instead of ending the search. At most
+/// `restarts.max_restarts` such replacements are made over the whole run.
+///
+/// # Errors
+///
+/// As for [`bracket_ratio_minimize`].
+#[allow(clippy::too_many_arguments)]
+pub fn bracket_ratio_minimize_with_restarts<T, F>(
+    func: F,
+    x1: T,
+    x2: T,
+    x3: T,
+    ratio: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+    restarts: Restarts,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let fevals = Evaluations::new();
+    let func = counted(func, &fevals);
+    let bracket = Bracket::new(&func, x1, x2, x3)?;
+    fevals.verify(Ok(ratio_loop(
+        &func,
+        bracket,
+        ratio,
+        (criteria.into(), budget.into()),
+        &fevals,
+        Some(restarts.start()),
+        |_| ControlFlow::Continue(()),
+    )))
+}
+
+fn ratio_loop<T, F, C>(
+    func: &F,
+    mut bracket: Bracket<T>,
+    ratio: T,
+    (criteria, budget): (ConvergenceCriteria<T>, Budget),
+    fevals: &Evaluations,
+    mut restarts: Option<Restarter>,
+    mut callback: C,
+) -> ScalarResult<T>
+where
+    T: Float,
+    F: Fn(T) -> T,
+    C: FnMut(&IterationInfo<T>) -> ControlFlow<()>,
+{
+    let mut iteration = 0;
+    let mut converged = false;
+    while !converged && budget.allows(iteration, fevals.get()) {
+        iteration += 1;
+        let previous = bracket;
+        bracket = match ratio_step(func, bracket, ratio, &mut restarts) {
+            Some(bracket) => bracket,
+            None => break,
+        };
+        if callback(&info_from(&bracket, iteration)).is_break() {
+            break;
+        }
+        converged = criteria.is_met(&progress(&previous, &bracket));
+    }
+    result_from(&bracket, iteration, fevals.get(), converged)
+}
+
+/// Golden section search over the bracket `(x1, x2, x3)`.
+///
+/// See [`bracket_ratio_minimize`] for the stopping rule and errors.
+pub fn bracket_gr_minimize<T, F>(
+    func: F,
+    x1: T,
+    x2: T,
+    x3: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    bracket_ratio_minimize(func, x1, x2, x3, golden_fraction(), criteria, budget)
+}
+
+/// Maximizes `func` by golden section search over the bracket `(x1, x2, x3)`,
+/// whose middle point must be higher than both outer points.
+///
+/// The returned `fx` is the maximum of `func` itself, not of its negation.
+pub fn bracket_gr_maximize<T, F>(
+    func: F,
+    x1: T,
+    x2: T,
+    x3: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    bracket_gr_minimize(|x| -func(x), x1, x2, x3, criteria, budget).map(ScalarResult::negated)
+}
+
+/// Minimizes `func` by successive parabolic interpolation through the three
+/// points of the bracket `(x1, x2, x3)`.
+///
+/// Converges superlinearly near a smooth minimum. Where the parabola is a poor
+/// model, the step is replaced by a golden section step as in Brent's method:
+/// whenever the parabola degenerates or proposes a point that would leave the
+/// bracket unchanged, and whenever the bracket has not halved over the last two
+/// iterations. The search stops once `criteria` is met, measured as in
+/// [`bracket_ratio_minimize`], or when the parabola's vertex lands exactly on
+/// the center. If even a golden step cannot shrink the bracket it has reached
+/// floating point resolution and the search ends with `converged == false`.
+///
+/// # Errors
+///
+/// Fails if the initial points do not form a valid [`Bracket`], and returns
+/// [`OptimizeError::NonFiniteObjective`] if `func` is NaN or infinite at any
+/// evaluated point.
+pub fn bracket_pi_minimize<T, F>(
+    func: F,
+    x1: T,
+    x2: T,
+    x3: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    bracket_pi_minimize_with_callback(func, x1, x2, x3, criteria, budget, |_| {
+        ControlFlow::Continue(())
+    })
+}
+
+/// [`bracket_pi_minimize`] calling `callback` after every iteration with the
+/// current bracket.
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false`.
+///
+/// # Errors
+///
+/// As for [`bracket_pi_minimize`].
+pub fn bracket_pi_minimize_with_callback<T, F, C>(
+    func: F,
+    x1: T,
+    x2: T,
+    x3: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+    callback: C,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+    C: FnMut(&IterationInfo<T>) -> ControlFlow<()>,
+{
+    let fevals = Evaluations::new();
+    let func = counted(func, &fevals);
+    let bracket = Bracket::new(&func, x1, x2, x3)?;
+    fevals.verify(Ok(parabolic_loop(
+        &func,
+        bracket,
+        (criteria.into(), budget.into()),
+        &fevals,
+        None,
+        callback,
+    )))
+}
+
+/// [`bracket_pi_minimize`] recovering from colliding points with random
+/// restarts.
+///
+/// When even the golden fallback step would land on an existing point, it is
+/// replaced by a point at a random fraction of the longer half of the bracket
+/// as in [`bracket_ratio_minimize_with_restarts`], instead of ending the search.
+///
+/// # Errors
+///
+/// As for [`bracket_pi_minimize`].
+pub fn bracket_pi_minimize_with_restarts<T, F>(
+    func: F,
+    x1: T,
+    x2: T,
+    x3: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+    restarts: Restarts,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let fevals = Evaluations::new();
+    let func = counted(func, &fevals);
+    let bracket = Bracket::new(&func, x1, x2, x3)?;
+    fevals.verify(Ok(parabolic_loop(
+        &func,
+        bracket,
+        (criteria.into(), budget.into()),
+        &fevals,
+        Some(restarts.start()),
+        |_| ControlFlow::Continue(()),
+    )))
+}
+
+fn parabolic_loop<T, F, C>(
+    func: &F,
+    mut bracket: Bracket<T>,
+    (criteria, budget): (ConvergenceCriteria<T>, Budget),
+    fevals: &Evaluations,
+    mut restarts: Option<Restarter>,
+    mut callback: C,
+) -> ScalarResult<T>
+where
+    T: Float,
+    F: Fn(T) -> T,
+    C: FnMut(&IterationInfo<T>) -> ControlFlow<()>,
+{
+    // Bracket widths one and two iterations ago.
+    let mut widths = (T::INFINITY, T::INFINITY);
+    let mut iteration = 0;
+    let mut converged = false;
+    while !converged && fevals.all_finite() && budget.allows(iteration, fevals.get()) {
+        iteration += 1;
+        let previous = bracket;
+        let stalled = bracket.width() > T::from_f64(0.5) * widths.1;
+        let mut parabolic = ParabolicIter::from_bracket(func, bracket);
+        let proposal = if stalled { None } else { parabolic.next() };
+        bracket = match (proposal, parabolic.error()) {
+            (Some(next), _) => next,
+            (None, None) if !stalled => {
+                converged = true;
+                break;
+            }
+            _ => match ratio_step(func, bracket, golden_fraction(), &mut restarts) {
+                Some(next) => next,
+                None => break,
+            },
+        };
+        widths = (previous.width(), widths.0);
+        if callback(&info_from(&bracket, iteration)).is_break() {
+            break;
+        }
+        converged = criteria.is_met(&progress(&previous, &bracket));
+    }
+    result_from(&bracket, iteration, fevals.get(), converged)
+}
+
+/// Maximizes `func` by parabolic interpolation over the bracket `(x1, x2, x3)`,
+/// whose middle point must be higher than both outer points.
+///
+/// The returned `fx` is the maximum of `func` itself, not of its negation.
+pub fn bracket_pi_maximize<T, F>(
+    func: F,
+    x1: T,
+    x2: T,
+    x3: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    bracket_pi_minimize(|x| -func(x), x1, x2, x3, criteria, budget).map(ScalarResult::negated)
+}
+
+/// Random restarts for [`bracket_ratio_minimize_with_restarts`] and
+/// [`bracket_pi_minimize_with_restarts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Restarts {
+    /// Most colliding points replaced over a run.
+    pub max_restarts: usize,
+    /// Seed of the generator drawing the replacement points.
+    pub seed: u64,
+}
+
+impl Restarts {
+    /// Up to `max_restarts` restarts drawn from a generator seeded with `seed`.
+    pub fn new(max_restarts: usize, seed: u64) -> Self {
+        Restarts { max_restarts, seed }
+    }
+
+    fn start(self) -> Restarter {
+        Restarter {
+            rng: Rng::new(self.seed),
+            left: self.max_restarts,
+        }
+    }
+}
+
+/// Restarts remaining in a run.
+struct Restarter {
+    rng: Rng,
+    left: usize,
+}
+
+/// Narrows `bracket` with a point at `ratio` of its longer half. If that point
+/// collides with an existing one and restarts remain, random fractions are
+/// tried instead.
+fn ratio_step<T, F>(
+    func: &F,
+    bracket: Bracket<T>,
+    ratio: T,
+    restarts: &mut Option<Restarter>,
+) -> Option<Bracket<T>>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let mut steps = GoldenSectionIter::from_bracket(func, bracket).with_ratio(ratio);
+    if let Some(next) = steps.next() {
+        return Some(next);
+    }
+    if steps.error() != Some(&OptimizeError::DupeBoundForBracket) {
+        return None;
+    }
+    let restarter = restarts.as_mut()?;
+    while restarter.left > 0 {
+        restarter.left -= 1;
+        let ratio = restarter.rng.uniform(T::from_f64(0.1), T::from_f64(0.9));
+        let mut steps = GoldenSectionIter::from_bracket(func, bracket).with_ratio(ratio);
+        if let Some(next) = steps.next() {
+            return Some(next);
+        }
+    }
+    None
+}
+
+/// Progress made by a step from `previous` to `current`.
+fn progress<T: Float>(previous: &Bracket<T>, current: &Bracket<T>) -> Progress<T> {
+    // The newly evaluated point either became the center or replaced a bound.
+    let probe = if current.center != previous.center {
+        current.center
+    } else if current.left != previous.left {
+        current.left
+    } else {
+        current.right
+    };
+    Progress {
+        x: current.center,
+        dx: (probe - previous.center).abs(),
+        fx: current.f_center,
+        df: (current.center != previous.center).then(|| previous.f_center - current.f_center),
+        slope: current.slope(),
+    }
+}
+
+fn info_from<T: Float>(bracket: &Bracket<T>, iteration: usize) -> IterationInfo<T> {
+    IterationInfo {
+        iteration,
+        lower: bracket.left,
+        upper: bracket.right,
+        x: bracket.center,
+        fx: bracket.f_center,
+    }
+}
+
+fn result_from<T: Float>(
+    bracket: &Bracket<T>,
+    iterations: usize,
+    fevals: usize,
+    converged: bool,
+) -> ScalarResult<T> {
+    ScalarResult {
+        x: bracket.center,
+        fx: bracket.f_center,
+        iterations,
+        fevals,
+        converged,
+        enclosure: Some(Enclosure {
+            lower: bracket.left,
+            upper: bracket.right,
+            f_lower: Some(bracket.f_left),
+            f_upper: Some(bracket.f_right),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn golden_bracket_finds_minimum() {
+        let result =
+            bracket_gr_minimize(|x: f64| (x - 1.5).powi(2), 0.0, 1.0, 4.0, 1e-8, 200).unwrap();
+        assert!(result.converged);
+        assert!((result.x - 1.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn bracket_steps_cost_one_evaluation() {
+        let result =
+            bracket_gr_minimize(|x: f64| (x - 1.5).powi(2), 0.0, 1.0, 4.0, 1e-8, 200).unwrap();
+        // Three evaluations set up the bracket.
+        assert_eq!(result.fevals, result.iterations + 3);
+    }
+
+    #[test]
+    fn final_bracket_encloses_the_minimizer() {
+        let f = |x: f64| (x - 1.5).powi(2);
+        let result = bracket_gr_minimize(f, 0.0, 1.0, 4.0, 1e-6, 200).unwrap();
+        let enclosure = result.enclosure.unwrap();
+        assert!(enclosure.lower < 1.5 && 1.5 < enclosure.upper);
+        assert!(enclosure.width() < 1e-5);
+        assert_eq!(enclosure.f_lower, Some(f(enclosure.lower)));
+        assert!(enclosure.f_upper.unwrap() > result.fx);
+    }
+
+    #[test]
+    fn ratio_bracket_finds_minimum() {
+        let result =
+            bracket_ratio_minimize(|x: f64| x.cosh(), -3.0, 0.5, 2.0, 0.5, 1e-8, 500).unwrap();
+        assert!(result.x.abs() < 1e-6);
+    }
+
+    #[test]
+    fn restarts_recover_from_colliding_points() {
+        let f = |x: f64| (x - 1.5).powi(2);
+        // Such a small ratio always lands on the center.
+        let stuck = bracket_ratio_minimize(f, 0.0, 1.0, 4.0, 1e-20, 1e-6, 500).unwrap();
+        assert_eq!(stuck.iterations, 1);
+        assert!(!stuck.converged);
+        let restarted = bracket_ratio_minimize_with_restarts(
+            f,
+            0.0,
+            1.0,
+            4.0,
+            1e-20,
+            1e-6,
+            500,
+            Restarts::new(500, 7),
+        )
+        .unwrap();
+        assert!((restarted.x - 1.5).abs() < 1e-5);
+        let parabolic =
+            bracket_pi_minimize_with_restarts(f, 0.0, 1.0, 4.0, 1e-10, 100, Restarts::new(5, 7))
+                .unwrap();
+        assert!((parabolic.x - 1.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn parabolic_is_exact_on_quadratics() {
+        let result =
+            bracket_pi_minimize(|x: f64| 3.0 * (x + 0.25).powi(2), -2.0, 0.0, 1.0, 1e-12, 50)
+                .unwrap();
+        assert!((result.x + 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn parabolic_falls_back_to_golden_steps() {
+        // Pure parabolic steps crawl towards the flat minimum of a quartic.
+        let result =
+            bracket_pi_minimize(|x: f64| (x - 0.3).powi(4), -1.0, 0.0, 2.0, 1e-8, 200).unwrap();
+        assert!(result.converged);
+        assert!((result.x - 0.3).abs() < 1e-6);
+        // Without a tolerance the parabola eventually stalls at rounding level.
+        let result = bracket_pi_minimize(|x: f64| x.cosh(), -1.0, 0.0, 2.0, 0.0, 2000).unwrap();
+        assert!(result.x.abs() < 1e-7);
+        assert!(result.iterations < 2000);
+    }
+
+    #[test]
+    fn f_and_gradient_criteria() {
+        let f = |x: f64| (x - 1.5).powi(2);
+        let by_f =
+            bracket_gr_minimize(f, 0.0, 1.0, 4.0, ConvergenceCriteria::AbsoluteF(1e-10), 200)
+                .unwrap();
+        assert!(by_f.converged);
+        assert!((by_f.x - 1.5).abs() < 1e-3);
+        let by_slope =
+            bracket_gr_minimize(f, 0.0, 1.0, 4.0, ConvergenceCriteria::Gradient(1e-6), 200)
+                .unwrap();
+        assert!(by_slope.converged);
+        assert!((by_slope.x - 1.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn callback_can_stop_early() {
+        let mut seen = Vec::new();
+        let result = bracket_pi_minimize_with_callback(
+            |x: f64| x.cosh(),
+            -1.0,
+            0.3,
+            2.0,
+            1e-14,
+            100,
+            |info| {
+                seen.push(info.fx);
+                ControlFlow::Break(())
+            },
+        )
+        .unwrap();
+        assert_eq!(result.iterations, 1);
+        assert!(!result.converged);
+        assert_eq!(seen, vec![result.fx]);
+    }
+
+    #[test]
+    fn maximizers_report_true_maximum() {
+        let f = |x: f64| 2.0 - (x - 0.7).powi(2);
+        let golden = bracket_gr_maximize(f, 0.0, 0.5, 2.0, 1e-8, 200).unwrap();
+        assert!((golden.x - 0.7).abs() < 1e-6);
+        assert!((golden.fx - 2.0).abs() < 1e-10);
+        let parabolic = bracket_pi_maximize(f, 0.0, 0.5, 2.0, 1e-12, 50).unwrap();
+        assert!((parabolic.x - 0.7).abs() < 1e-10);
+        assert!((parabolic.fx - 2.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,248 @@
+use std::cmp::Ordering;
+
+use super::{
+    bound_gr_minimize, minimize_scalar, Bracket, Method, MinimizeScalarOptions, ScalarResult,
+};
+use crate::generals::random::Rng;
+use crate::generals::Float;
+use crate::optimize::{counted, Budget, ConvergenceCriteria, Evaluations, OptimizeError};
+
+/// Looks for the global minimum of `func` on `[a, b]` by scanning a grid of
+/// `n_grid` equally spaced points and refining every local minimum of the grid
+/// with golden section search.
+///
+/// Each grid point that is no higher than its neighbours is refined over the
+/// interval between those neighbours with [`bound_gr_minimize`], using
+/// `criteria` and `budget` for each refinement. The best point found is
+/// returned; `iterations` and `fevals` add up all refinements and the scan, and
+/// `converged` is that of the winning refinement. Minima narrower than the grid
+/// spacing can still be missed, so `n_grid` should resolve the features of
+/// `func`. Grids of fewer than 3 points are raised to 3.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteObjective`] if `func` is NaN or infinite
+/// at an evaluated point.
+pub fn grid_refine_minimize<T, F>(
+    func: F,
+    a: T,
+    b: T,
+    n_grid: usize,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let criteria = criteria.into();
+    let budget = budget.into();
+    let n = n_grid.max(3);
+    let (a, b) = if a < b { (a, b) } else { (b, a) };
+    let step = (b - a) / T::from_usize(n - 1);
+    let xs: Vec<T> = (0..n).map(|i| a + step * T::from_usize(i)).collect();
+    let fs: Vec<T> = xs.iter().map(|&x| func(x)).collect();
+    if let Some(i) = fs.iter().position(|f| !f.is_finite()) {
+        return Err(OptimizeError::NonFiniteObjective { x: xs[i].to_f64() });
+    }
+
+    let (x, fx) =
+        fs.iter().enumerate().fold(
+            (xs[0], fs[0]),
+            |best, (i, &f)| if f < best.1 { (xs[i], f) } else { best },
+        );
+    let mut best = ScalarResult {
+        x,
+        fx,
+        iterations: 0,
+        fevals: n,
+        converged: false,
+        enclosure: None,
+    };
+    let (mut iterations, mut fevals) = (0, n);
+    for i in 0..n {
+        let left = if i > 0 { fs[i - 1] } else { T::INFINITY };
+        let right = if i + 1 < n { fs[i + 1] } else { T::INFINITY };
+        if !(fs[i] <= left && fs[i] <= right) {
+            continue;
+        }
+        let lower = xs[i.saturating_sub(1)];
+        let upper = xs[(i + 1).min(n - 1)];
+        let refined = bound_gr_minimize(&func, lower, upper, criteria, budget)?;
+        iterations += refined.iterations;
+        fevals += refined.fevals;
+        if refined.fx <= best.fx {
+            best = refined;
+        }
+    }
+    best.iterations = iterations;
+    best.fevals = fevals;
+    Ok(best)
+}
+
+/// Starting points for [`multistart_minimize`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Starts<T> {
+    /// Brackets `(x1, x2, x3)` as accepted by [`minimize_scalar`].
+    Brackets(Vec<(T, T, T)>),
+    /// `count` random points drawn uniformly from `[lower, upper]` with a
+    /// generator seeded by `seed`, each turned into a bracket with
+    /// [`Bracket::find`].
+    Random {
+        lower: T,
+        upper: T,
+        count: usize,
+        seed: u64,
+    },
+}
+
+/// Runs `method` from several starting brackets and returns the distinct local
+/// minima found, best first.
+///
+/// Two minima closer than `distinct` in x count as the same and only the lower
+/// one is kept. Starts whose local search fails, including on NaN or infinite
+/// objective values, are skipped, and so are random starts whose downhill
+/// search runs into a bound before finding a bracket. For
+/// random starts `fevals` includes the evaluations of the bracket search.
+pub fn multistart_minimize<T, F>(
+    func: F,
+    starts: Starts<T>,
+    method: Method<T>,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+    distinct: T,
+) -> Vec<ScalarResult<T>>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let criteria = criteria.into();
+    let budget = budget.into();
+    let options = |(x1, x2, x3)| MinimizeScalarOptions {
+        method,
+        bracket: (x1, x2, x3),
+        criteria,
+        budget,
+    };
+    let mut results: Vec<ScalarResult<T>> = match starts {
+        Starts::Brackets(brackets) => brackets
+            .into_iter()
+            .filter_map(|bracket| minimize_scalar(&func, options(bracket)).ok())
+            .collect(),
+        Starts::Random {
+            lower,
+            upper,
+            count,
+            seed,
+        } => {
+            let (lower, upper) = if lower < upper {
+                (lower, upper)
+            } else {
+                (upper, lower)
+            };
+            let step = T::from_f64(0.01) * (upper - lower);
+            let mut rng = Rng::new(seed);
+            (0..count)
+                .filter_map(|_| {
+                    let x = rng.uniform(lower, upper);
+                    let x2 = if x + step <= upper {
+                        x + step
+                    } else {
+                        x - step
+                    };
+                    let searched = Evaluations::new();
+                    let found = Bracket::find(counted(&func, &searched), x, x2, lower, upper, 100);
+                    let bracket = found.ok()?;
+                    let mut result = minimize_scalar(
+                        &func,
+                        options((bracket.left, bracket.center, bracket.right)),
+                    )
+                    .ok()?;
+                    result.fevals += searched.get();
+                    Some(result)
+                })
+                .collect()
+        }
+    };
+
+    results.sort_by(|a, b| a.fx.partial_cmp(&b.fx).unwrap_or(Ordering::Equal));
+    let mut minima: Vec<ScalarResult<T>> = Vec::new();
+    for result in results {
+        if minima.iter().all(|m| (m.x - result.x).abs() >= distinct) {
+            minima.push(result);
+        }
+    }
+    minima
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_global_minimum_of_multimodal_function() {
+        // Local minima near every odd multiple of π/5, the global one near 4.4.
+        let f = |x: f64| (5.0 * x).cos() + 0.1 * (x - 4.0).powi(2);
+        let result = grid_refine_minimize(f, 0.0, 10.0, 50, 1e-8, 200).unwrap();
+        let slope = -5.0 * (5.0 * result.x).sin() + 0.2 * (result.x - 4.0);
+        assert!(slope.abs() < 1e-5);
+        let dense_best = (0..=100_000)
+            .map(|i| f(i as f64 * 1e-4))
+            .fold(f64::INFINITY, f64::min);
+        assert!(result.fx <= dense_best + 1e-9);
+    }
+
+    #[test]
+    fn finds_minimum_at_the_boundary() {
+        let result = grid_refine_minimize(|x: f64| x * x, 1.0, 3.0, 5, 1e-8, 200).unwrap();
+        assert!((result.x - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn multistart_from_brackets_merges_duplicates() {
+        let f = |x: f64| (x * x - 1.0).powi(2) + 0.2 * x;
+        let starts = Starts::Brackets(vec![
+            (-2.0, -1.0, 0.0),
+            (-1.5, -0.9, 0.0),
+            (0.0, 1.0, 2.0),
+            (0.0, 0.5, 0.2),
+        ]);
+        let minima = multistart_minimize(f, starts, Method::Brent, 1e-10, 200, 1e-4);
+        assert_eq!(minima.len(), 2);
+        assert!(minima[0].x < 0.0 && minima[1].x > 0.0);
+        assert!(minima[0].fx < minima[1].fx);
+    }
+
+    #[test]
+    fn multistart_from_random_starts_finds_all_minima() {
+        let f = |x: f64| (3.0 * x).sin() + 0.05 * x * x;
+        let starts = Starts::Random {
+            lower: -6.0,
+            upper: 6.0,
+            count: 40,
+            seed: 1,
+        };
+        let minima = multistart_minimize(f, starts, Method::Golden, 1e-9, 200, 1e-3);
+        // Interior minima near -5.7, -3.6, -1.6, 0.5, 2.6 and 4.6 (approximately).
+        assert!(minima.len() >= 5);
+        assert!(minima.windows(2).all(|w| w[0].fx <= w[1].fx));
+        for m in &minima {
+            let slope = 3.0 * (3.0 * m.x).cos() + 0.1 * m.x;
+            assert!(slope.abs() < 1e-5, "{} has slope {slope}", m.x);
+        }
+        let again = multistart_minimize(
+            f,
+            Starts::Random {
+                lower: -6.0,
+                upper: 6.0,
+                count: 40,
+                seed: 1,
+            },
+            Method::Golden,
+            1e-9,
+            200,
+            1e-3,
+        );
+        assert_eq!(minima, again);
+    }
+}
@@ -0,0 +1,210 @@
+use super::bracket::{Bracket, Side};
+use super::golden_fraction;
+use crate::generals::Float;
+use crate::optimize::OptimizeError;
+
+/// Iterator over the brackets produced by golden section search.
+///
+/// Each call to `next` evaluates the objective once, at the golden section of
+/// the longer half of the bracket, and yields the narrowed bracket. Iteration
+/// ends when a new point would coincide with an existing one or the objective is
+/// not finite there; it never decides convergence, so callers apply their own
+/// stopping rule.
+#[derive(Debug, Clone)]
+pub struct GoldenSectionIter<T, F> {
+    func: F,
+    bracket: Bracket<T>,
+    ratio: T,
+    error: Option<OptimizeError>,
+}
+
+impl<T: Float, F: Fn(T) -> T> GoldenSectionIter<T, F> {
+    /// Starts from the bracket `(x1, x2, x3)`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the points do not form a valid [`Bracket`].
+    pub fn new(func: F, x1: T, x2: T, x3: T) -> Result<Self, OptimizeError> {
+        let bracket = Bracket::new(&func, x1, x2, x3)?;
+        Ok(Self::from_bracket(func, bracket))
+    }
+
+    /// Starts from an already evaluated bracket.
+    pub fn from_bracket(func: F, bracket: Bracket<T>) -> Self {
+        GoldenSectionIter {
+            func,
+            bracket,
+            ratio: golden_fraction(),
+            error: None,
+        }
+    }
+
+    /// Places new points at `ratio` of the way from the center to the furthest
+    /// bound instead of at the golden section. `ratio` must lie in `(0, 1)`.
+    pub fn with_ratio(mut self, ratio: T) -> Self {
+        self.ratio = ratio;
+        self
+    }
+
+    /// The current bracket.
+    pub fn bracket(&self) -> &Bracket<T> {
+        &self.bracket
+    }
+
+    /// Why iteration ended, if it has.
+    pub fn error(&self) -> Option<&OptimizeError> {
+        self.error.as_ref()
+    }
+}
+
+impl<T: Float, F: Fn(T) -> T> Iterator for GoldenSectionIter<T, F> {
+    type Item = Bracket<T>;
+
+    fn next(&mut self) -> Option<Bracket<T>> {
+        if self.error.is_some() {
+            return None;
+        }
+        let b = &self.bracket;
+        let x = match b.longer_bound() {
+            Side::Left => b.center - self.ratio * (b.center - b.left),
+            Side::Right => b.center + self.ratio * (b.right - b.center),
+        };
+        match self.bracket.update(x, (self.func)(x)) {
+            Ok(()) => Some(self.bracket),
+            Err(error) => {
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+}
+
+/// Iterator over the brackets produced by successive parabolic interpolation.
+///
+/// Each call to `next` evaluates the objective once, at the vertex of the
+/// parabola through the bracket, and yields the narrowed bracket. Iteration
+/// ends without an error when the vertex lands exactly on the center, with
+/// [`OptimizeError::DeadEnd`] when the parabola degenerates or would not change
+/// the bracket, and with [`OptimizeError::NonFiniteObjective`] when the
+/// objective is NaN or infinite at the vertex.
+#[derive(Debug, Clone)]
+pub struct ParabolicIter<T, F> {
+    func: F,
+    bracket: Bracket<T>,
+    finished: bool,
+    error: Option<OptimizeError>,
+}
+
+impl<T: Float, F: Fn(T) -> T> ParabolicIter<T, F> {
+    /// Starts from the bracket `(x1, x2, x3)`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the points do not form a valid [`Bracket`].
+    pub fn new(func: F, x1: T, x2: T, x3: T) -> Result<Self, OptimizeError> {
+        let bracket = Bracket::new(&func, x1, x2, x3)?;
+        Ok(Self::from_bracket(func, bracket))
+    }
+
+    /// Starts from an already evaluated bracket.
+    pub fn from_bracket(func: F, bracket: Bracket<T>) -> Self {
+        ParabolicIter {
+            func,
+            bracket,
+            finished: false,
+            error: None,
+        }
+    }
+
+    /// The current bracket.
+    pub fn bracket(&self) -> &Bracket<T> {
+        &self.bracket
+    }
+
+    /// Why iteration ended, if it ended on a failure.
+    pub fn error(&self) -> Option<&OptimizeError> {
+        self.error.as_ref()
+    }
+}
+
+impl<T: Float, F: Fn(T) -> T> Iterator for ParabolicIter<T, F> {
+    type Item = Bracket<T>;
+
+    fn next(&mut self) -> Option<Bracket<T>> {
+        if self.finished {
+            return None;
+        }
+        let step = match parabola_vertex(&self.bracket) {
+            // The parabola is centred on the current best point, which cannot be improved.
+            Some(x) if x == self.bracket.center => Ok(false),
+            Some(x) => self
+                .bracket
+                .update(x, (self.func)(x))
+                .map(|()| true)
+                .map_err(|error| match error {
+                    OptimizeError::DupeBoundForBracket => OptimizeError::DeadEnd,
+                    other => other,
+                }),
+            None => Err(OptimizeError::DeadEnd),
+        };
+        match step {
+            Ok(true) => Some(self.bracket),
+            Ok(false) => {
+                self.finished = true;
+                None
+            }
+            Err(error) => {
+                self.finished = true;
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+}
+
+/// Abscissa of the vertex of the parabola through the three bracket points.
+fn parabola_vertex<T: Float>(bracket: &Bracket<T>) -> Option<T> {
+    let (a, b, c) = (bracket.left, bracket.center, bracket.right);
+    let (fa, fb, fc) = (bracket.f_left, bracket.f_center, bracket.f_right);
+    let p = (b - a) * (b - a) * (fb - fc) - (b - c) * (b - c) * (fb - fa);
+    let q = (b - a) * (fb - fc) - (b - c) * (fb - fa);
+    if q == T::ZERO {
+        return None;
+    }
+    let x = b - T::from_f64(0.5) * p / q;
+    x.is_finite().then_some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn golden_iter_can_be_driven_manually() {
+        let iter = GoldenSectionIter::new(|x: f64| (x - 1.0).powi(2), -2.0, 0.0, 3.0).unwrap();
+        let last = iter.take_while(|b| b.width() > 1e-6).last().unwrap();
+        assert!((last.center - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn iterators_can_be_interleaved() {
+        let f = |x: f64| x.exp() - 2.0 * x;
+        let mut golden = GoldenSectionIter::new(f, 0.0, 0.5, 2.0).unwrap();
+        let mut bracket = *golden.bracket();
+        for _ in 0..5 {
+            bracket = golden.next().unwrap();
+            let mut parabolic = ParabolicIter::from_bracket(f, bracket);
+            bracket = parabolic.next().unwrap_or(bracket);
+            golden = GoldenSectionIter::from_bracket(f, bracket);
+        }
+        assert!((bracket.center - 2f64.ln()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn parabolic_iter_stops_on_exact_vertex() {
+        let mut iter = ParabolicIter::new(|x: f64| (x - 0.5).powi(2), 0.0, 0.25, 2.0).unwrap();
+        assert_eq!(iter.next().unwrap().center, 0.5);
+        assert!(iter.next().is_none());
+        assert!(iter.error().is_none());
+    }
+}
@@ -0,0 +1,338 @@
+use std::ops::ControlFlow;
+
+use super::{
+    bracket_pi_minimize_with_callback, bracket_ratio_minimize_with_callback,
+    brent_minimize_with_callback, golden_fraction, newton_raphson, IterationInfo, ScalarResult,
+};
+use crate::generals::Float;
+use crate::optimize::{Budget, ConvergenceCriteria, OptimizeError};
+
+/// Algorithm used by [`minimize_scalar`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method<T> {
+    /// Golden section search, see [`bracket_gr_minimize`](super::bracket_gr_minimize).
+    Golden,
+    /// Bracket splitting at the given ratio, see
+    /// [`bracket_ratio_minimize`](super::bracket_ratio_minimize).
+    Ratio(T),
+    /// Successive parabolic interpolation, see
+    /// [`bracket_pi_minimize`](super::bracket_pi_minimize).
+    Parabolic,
+    /// Brent's method, see [`brent_minimize`](super::brent_minimize).
+    Brent,
+    /// Newton-Raphson from the middle bracket point with finite difference
+    /// derivatives, see [`newton_raphson`].
+    Newton,
+}
+
+/// Options for [`minimize_scalar`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MinimizeScalarOptions<T> {
+    pub method: Method<T>,
+    /// Three points whose middle one is lower than the outer ones.
+    pub bracket: (T, T, T),
+    pub criteria: ConvergenceCriteria<T>,
+    pub budget: Budget,
+}
+
+impl<T: Float> MinimizeScalarOptions<T> {
+    /// Brent's method on the bracket `(x1, x2, x3)`, stopping at the default
+    /// [`ConvergenceCriteria`] or after 500 iterations.
+    pub fn new(x1: T, x2: T, x3: T) -> Self {
+        MinimizeScalarOptions {
+            method: Method::Brent,
+            bracket: (x1, x2, x3),
+            criteria: ConvergenceCriteria::default(),
+            budget: Budget::new(500),
+        }
+    }
+
+    /// Uses `method` instead of Brent's method.
+    pub fn method(mut self, method: Method<T>) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Stops at `criteria` instead, for example an `(atol, rtol)` pair.
+    pub fn criteria(mut self, criteria: impl Into<ConvergenceCriteria<T>>) -> Self {
+        self.criteria = criteria.into();
+        self
+    }
+
+    /// Uses `budget` instead of 500 iterations.
+    pub fn budget(mut self, budget: impl Into<Budget>) -> Self {
+        self.budget = budget.into();
+        self
+    }
+}
+
+/// Minimizes `func` with the algorithm selected in `options`.
+///
+/// This is a single entry point over the individual algorithm functions, which
+/// remain available for finer control.
+///
+/// # Errors
+///
+/// Passes on the errors of the selected algorithm.
+pub fn minimize_scalar<T, F>(
+    func: F,
+    options: MinimizeScalarOptions<T>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    minimize_scalar_with_callback(func, options, |_| ControlFlow::Continue(()))
+}
+
+/// Like [`minimize_scalar`], but calls `callback` after every iteration of the
+/// selected algorithm.
+///
+/// Returning [`ControlFlow::Break`] from the callback stops the search early
+/// with `converged == false`. Newton-Raphson keeps no bracket and never calls
+/// `callback`.
+///
+/// # Errors
+///
+/// Passes on the errors of the selected algorithm.
+pub fn minimize_scalar_with_callback<T, F, C>(
+    func: F,
+    options: MinimizeScalarOptions<T>,
+    callback: C,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+    C: FnMut(&IterationInfo<T>) -> ControlFlow<()>,
+{
+    let MinimizeScalarOptions {
+        method,
+        bracket: (x1, x2, x3),
+        criteria,
+        budget,
+    } = options;
+    match method {
+        Method::Golden => bracket_ratio_minimize_with_callback(
+            func,
+            x1,
+            x2,
+            x3,
+            golden_fraction(),
+            criteria,
+            budget,
+            callback,
+        ),
+        Method::Ratio(ratio) => bracket_ratio_minimize_with_callback(
+            func, x1, x2, x3, ratio, criteria, budget, callback,
+        ),
+        Method::Parabolic => {
+            bracket_pi_minimize_with_callback(func, x1, x2, x3, criteria, budget, callback)
+        }
+        Method::Brent => brent_minimize_with_callback(func, x1, x2, x3, criteria, budget, callback),
+        Method::Newton => {
+            let h = T::EPSILON.cbrt() * x2.abs().max(T::ONE);
+            newton_raphson(func, x2, h, criteria, budget)
+        }
+    }
+}
+
+/// Builder for a scalar minimization, layered over [`minimize_scalar`].
+///
+/// ```
+/// use mathslib::optimize::scalar::ScalarMinimizer;
+///
+/// let result = ScalarMinimizer::golden()
+///     .bracket(0.0, 1.0, 4.0)
+///     .tolerance(1e-8)
+///     .max_iter(500)
+///     .run(|x: f64| (x - 1.5).powi(2))
+///     .unwrap();
+/// assert!((result.x - 1.5).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScalarMinimizer<T> {
+    method: Method<T>,
+    bracket: Option<(T, T, T)>,
+    criteria: ConvergenceCriteria<T>,
+    budget: Budget,
+}
+
+impl<T: Float> ScalarMinimizer<T> {
+    /// A minimizer using `method`, with the default [`ConvergenceCriteria`] and
+    /// a budget of 500 iterations.
+    pub fn new(method: Method<T>) -> Self {
+        ScalarMinimizer {
+            method,
+            bracket: None,
+            criteria: ConvergenceCriteria::default(),
+            budget: Budget::new(500),
+        }
+    }
+
+    /// Golden section search.
+    pub fn golden() -> Self {
+        Self::new(Method::Golden)
+    }
+
+    /// Bracket splitting at `ratio`.
+    pub fn ratio(ratio: T) -> Self {
+        Self::new(Method::Ratio(ratio))
+    }
+
+    /// Successive parabolic interpolation.
+    pub fn parabolic() -> Self {
+        Self::new(Method::Parabolic)
+    }
+
+    /// Brent's method.
+    pub fn brent() -> Self {
+        Self::new(Method::Brent)
+    }
+
+    /// Newton-Raphson with finite difference derivatives.
+    pub fn newton() -> Self {
+        Self::new(Method::Newton)
+    }
+
+    /// Starts from the bracket `(x1, x2, x3)`, whose middle point must be lower
+    /// than the outer ones.
+    pub fn bracket(mut self, x1: T, x2: T, x3: T) -> Self {
+        self.bracket = Some((x1, x2, x3));
+        self
+    }
+
+    /// Stops at an absolute x tolerance of `tolerance`.
+    pub fn tolerance(mut self, tolerance: T) -> Self {
+        self.criteria = ConvergenceCriteria::AbsoluteX(tolerance);
+        self
+    }
+
+    /// Stops at `criteria` instead.
+    pub fn criteria(mut self, criteria: impl Into<ConvergenceCriteria<T>>) -> Self {
+        self.criteria = criteria.into();
+        self
+    }
+
+    /// Allows at most `max_iter` iterations.
+    pub fn max_iter(mut self, max_iter: usize) -> Self {
+        self.budget.max_iter = max_iter;
+        self
+    }
+
+    /// Allows at most about `max_fevals` objective evaluations.
+    pub fn max_fevals(mut self, max_fevals: usize) -> Self {
+        self.budget.max_fevals = Some(max_fevals);
+        self
+    }
+
+    /// Minimizes `func`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptimizeError::MissingBracket`] if no bracket was set, and
+    /// otherwise passes on the errors of the selected algorithm.
+    pub fn run<F: Fn(T) -> T>(&self, func: F) -> Result<ScalarResult<T>, OptimizeError> {
+        minimize_scalar(func, self.options()?)
+    }
+
+    /// Minimizes `func`, also returning the state after every iteration so the
+    /// convergence rate can be inspected or plotted.
+    ///
+    /// The history is empty for Newton-Raphson, which keeps no bracket.
+    ///
+    /// # Errors
+    ///
+    /// As for [`ScalarMinimizer::run`].
+    pub fn run_with_history<F: Fn(T) -> T>(
+        &self,
+        func: F,
+    ) -> Result<(ScalarResult<T>, Vec<IterationInfo<T>>), OptimizeError> {
+        let mut history = Vec::new();
+        let result = minimize_scalar_with_callback(func, self.options()?, |info| {
+            history.push(*info);
+            ControlFlow::Continue(())
+        })?;
+        Ok((result, history))
+    }
+
+    fn options(&self) -> Result<MinimizeScalarOptions<T>, OptimizeError> {
+        Ok(MinimizeScalarOptions {
+            method: self.method,
+            bracket: self.bracket.ok_or(OptimizeError::MissingBracket)?,
+            criteria: self.criteria,
+            budget: self.budget,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::scalar::bracket_pi_minimize;
+
+    #[test]
+    fn every_method_finds_the_minimum() {
+        let f = |x: f64| (x - 1.0).powi(2) + 0.1 * (x - 1.0).powi(4);
+        for method in [
+            Method::Golden,
+            Method::Ratio(0.5),
+            Method::Parabolic,
+            Method::Brent,
+            Method::Newton,
+        ] {
+            let options = MinimizeScalarOptions::new(-1.0, 0.5, 3.0)
+                .method(method)
+                .criteria((1e-10, 1e-8));
+            let result = minimize_scalar(f, options).unwrap();
+            assert!(
+                (result.x - 1.0).abs() < 1e-5,
+                "{method:?} gave {}",
+                result.x
+            );
+        }
+    }
+
+    #[test]
+    fn builder_matches_free_function() {
+        let f = |x: f64| x.cosh() - 0.5 * x;
+        let built = ScalarMinimizer::parabolic()
+            .bracket(-1.0, 0.0, 2.0)
+            .tolerance(1e-9)
+            .max_iter(100)
+            .run(f)
+            .unwrap();
+        let direct = bracket_pi_minimize(f, -1.0, 0.0, 2.0, 1e-9, 100).unwrap();
+        assert_eq!(built, direct);
+        assert_eq!(
+            ScalarMinimizer::brent().run(f),
+            Err(OptimizeError::MissingBracket)
+        );
+    }
+
+    #[test]
+    fn history_records_every_iteration() {
+        let f = |x: f64| (x - 0.3).powi(2);
+        let minimizer = ScalarMinimizer::golden()
+            .bracket(-1.0, 0.0, 2.0)
+            .tolerance(1e-8);
+        let (result, history) = minimizer.run_with_history(f).unwrap();
+        assert_eq!(result, minimizer.run(f).unwrap());
+        assert_eq!(history.len(), result.iterations);
+        assert!(history
+            .iter()
+            .enumerate()
+            .all(|(i, info)| info.iteration == i + 1));
+        // Golden section shrinks the bracket by a constant factor.
+        let rates: Vec<f64> = history
+            .windows(2)
+            .map(|w| w[1].width() / w[0].width())
+            .collect();
+        assert!(rates.iter().all(|&r| r < 0.7));
+        assert_eq!(history.last().unwrap().x, result.x);
+        let (_, newton) = ScalarMinimizer::newton()
+            .bracket(-1.0, 0.0, 2.0)
+            .run_with_history(f)
+            .unwrap();
+        assert!(newton.is_empty());
+    }
+}
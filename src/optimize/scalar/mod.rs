@@ -0,0 +1,133 @@
+//! Minimization of functions of a single variable.
+
+pub mod bound_optimizers;
+mod bracket;
+mod bracket_optimizers;
+mod brent;
+mod global;
+mod iterators;
+mod minimize;
+pub mod newtonraphson;
+mod noisy;
+mod secant;
+mod ternary;
+
+pub use crate::optimize::discrete::ternary_minimize_int;
+pub use bound_optimizers::{
+    bound_gr_maximize, bound_gr_minimize, bound_minimize, bound_minimize_with_callback,
+    fibonacci_minimize,
+};
+pub use bracket::{Bracket, Side};
+pub use bracket_optimizers::{
+    bracket_gr_maximize, bracket_gr_minimize, bracket_pi_maximize, bracket_pi_minimize,
+    bracket_pi_minimize_with_callback, bracket_pi_minimize_with_restarts, bracket_ratio_minimize,
+    bracket_ratio_minimize_with_callback, bracket_ratio_minimize_with_restarts, Restarts,
+};
+pub use brent::{bounded_minimize, brent_minimize, brent_minimize_with_callback};
+pub use global::{grid_refine_minimize, multistart_minimize, Starts};
+pub use iterators::{GoldenSectionIter, ParabolicIter};
+pub use minimize::{
+    minimize_scalar, minimize_scalar_with_callback, Method, MinimizeScalarOptions, ScalarMinimizer,
+};
+pub use newtonraphson::{
+    newton_raphson, newton_raphson_with_derivatives, safeguarded_newton,
+    safeguarded_newton_with_derivatives,
+};
+pub use noisy::{noisy_minimize, Sampling};
+pub use secant::secant_minimize;
+pub use ternary::ternary_minimize;
+
+use crate::generals::Float;
+
+/// Outcome of a scalar minimization.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScalarResult<T> {
+    /// Best abscissa found.
+    pub x: T,
+    /// Objective value at `x`.
+    pub fx: T,
+    /// Number of iterations performed.
+    pub iterations: usize,
+    /// Number of objective evaluations performed.
+    pub fevals: usize,
+    /// Whether the convergence criterion was met before the budget ran out.
+    pub converged: bool,
+    /// Final interval known to hold the minimizer, for methods that maintain
+    /// one.
+    pub enclosure: Option<Enclosure<T>>,
+}
+
+/// Interval enclosing a minimizer, with the objective at its ends where known.
+///
+/// For a unimodal objective the minimizer lies in `[lower, upper]`, so the
+/// distance from any point of the interval to the minimizer is at most
+/// [`Enclosure::width`]. This holds up to rounding in the objective: close to a
+/// smooth minimum, points within about the square root of machine precision
+/// (relative to `|x|`) have equal values and cannot be told apart. An end value is `None` when that end is an original
+/// bound the method never evaluated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Enclosure<T> {
+    pub lower: T,
+    pub upper: T,
+    pub f_lower: Option<T>,
+    pub f_upper: Option<T>,
+}
+
+impl<T: Float> Enclosure<T> {
+    /// Length of the interval.
+    pub fn width(&self) -> T {
+        self.upper - self.lower
+    }
+}
+
+/// State of a scalar minimizer after an iteration, passed to the callbacks of
+/// the `*_with_callback` functions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IterationInfo<T> {
+    /// Number of completed iterations.
+    pub iteration: usize,
+    /// Lower end of the interval known to hold the minimum.
+    pub lower: T,
+    /// Upper end of the interval known to hold the minimum.
+    pub upper: T,
+    /// Best point so far.
+    pub x: T,
+    /// Objective value at `x`.
+    pub fx: T,
+}
+
+impl<T: Float> IterationInfo<T> {
+    /// Width of the interval known to hold the minimum.
+    pub fn width(&self) -> T {
+        self.upper - self.lower
+    }
+}
+
+impl<T: Float> ScalarResult<T> {
+    /// Flips the sign of `fx` and the enclosure's end values, turning the
+    /// result of minimizing `-f` into the result of maximizing `f`.
+    pub(crate) fn negated(self) -> Self {
+        ScalarResult {
+            fx: -self.fx,
+            enclosure: self.enclosure.map(|e| Enclosure {
+                f_lower: e.f_lower.map(|f| -f),
+                f_upper: e.f_upper.map(|f| -f),
+                ..e
+            }),
+            ..self
+        }
+    }
+}
+
+/// Derivative at `x0` of the parabola through three points.
+pub(crate) fn parabola_slope<T: Float>(x0: T, f0: T, x1: T, f1: T, x2: T, f2: T) -> T {
+    let d01 = (f1 - f0) / (x1 - x0);
+    let d12 = (f2 - f1) / (x2 - x1);
+    let d012 = (d12 - d01) / (x2 - x0);
+    d01 + d012 * (x0 - x1)
+}
+
+/// Golden section fraction `(3 - sqrt(5)) / 2`, the share of an interval taken by a golden step.
+pub(crate) fn golden_fraction<T: Float>() -> T {
+    T::from_f64(0.381_966_011_250_105_1)
+}
@@ -0,0 +1,383 @@
+use super::ScalarResult;
+use crate::generals::differential_methods::{first_derivative, second_derivative};
+use crate::generals::Float;
+use crate::optimize::{counted, Budget, ConvergenceCriteria, Evaluations, OptimizeError, Progress};
+
+/// Minimizes `func` with Newton-Raphson iterations on its derivative, starting
+/// from `x0`.
+///
+/// The first and second derivatives are approximated by central differences
+/// with step `h`. Iteration stops once `criteria` is met, where the x
+/// uncertainty is the length of the last Newton step. Newton's method finds
+/// stationary points, so the result is only a minimum if `func` is convex near
+/// it.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::ZeroDerivative`] if the second derivative vanishes
+/// at an iterate, and [`OptimizeError::NonFiniteObjective`] if `func` is NaN
+/// or infinite at an evaluated point.
+pub fn newton_raphson<T, F>(
+    func: F,
+    x0: T,
+    h: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let fevals = Evaluations::new();
+    let func = counted(func, &fevals);
+    newton_loop(
+        &func,
+        |x| first_derivative(&func, x, h),
+        |x| second_derivative(&func, x, h),
+        x0,
+        Tracker::new(&func, &fevals, criteria.into(), budget.into(), x0),
+    )
+}
+
+/// Minimizes `func` with Newton-Raphson iterations using the analytic first and
+/// second derivatives `d1` and `d2`.
+///
+/// See [`newton_raphson`] for the stopping rule and errors.
+pub fn newton_raphson_with_derivatives<T, F, D1, D2>(
+    func: F,
+    d1: D1,
+    d2: D2,
+    x0: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+    D1: Fn(T) -> T,
+    D2: Fn(T) -> T,
+{
+    let fevals = Evaluations::new();
+    let func = counted(func, &fevals);
+    newton_loop(
+        &func,
+        d1,
+        d2,
+        x0,
+        Tracker::new(&func, &fevals, criteria.into(), budget.into(), x0),
+    )
+}
+
+fn newton_loop<T, F, D1, D2>(
+    func: &F,
+    d1: D1,
+    d2: D2,
+    x0: T,
+    mut tracker: Tracker<'_, T>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+    D1: Fn(T) -> T,
+    D2: Fn(T) -> T,
+{
+    let mut x = x0;
+    while tracker.allows_step() {
+        let slope = d1(x);
+        if tracker.is_met(slope) {
+            return tracker.finish(func, true);
+        }
+        let curvature = d2(x);
+        if curvature == T::ZERO {
+            return tracker.fevals.verify(Err(OptimizeError::ZeroDerivative));
+        }
+        x -= slope / curvature;
+        tracker.step_to(func, x, T::INFINITY);
+    }
+    tracker.finish(func, false)
+}
+
+/// Minimizes `func` on `[a, b]` with Newton steps safeguarded by bisection.
+///
+/// Derivatives are approximated by central differences with step `h`. See
+/// [`safeguarded_newton_with_derivatives`] for the algorithm, stopping rule and
+/// errors.
+pub fn safeguarded_newton<T, F>(
+    func: F,
+    a: T,
+    b: T,
+    h: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let fevals = Evaluations::new();
+    let func = counted(func, &fevals);
+    let (a, b) = if a < b { (a, b) } else { (b, a) };
+    safeguarded_loop(
+        &func,
+        |x| first_derivative(&func, x, h),
+        |x| second_derivative(&func, x, h),
+        a,
+        b,
+        Tracker::new(
+            &func,
+            &fevals,
+            criteria.into(),
+            budget.into(),
+            T::from_f64(0.5) * (a + b),
+        ),
+    )
+}
+
+/// Minimizes `func` on `[a, b]` with Newton steps that never leave the interval.
+///
+/// Starting from the midpoint, the sign of `d1` is used to discard the half of
+/// the interval that cannot hold the minimum. A Newton step is taken when the
+/// curvature `d2` is positive and the step lands inside the remaining interval;
+/// otherwise the interval is bisected. Iteration stops once `criteria` is met,
+/// where the x uncertainty is the shorter of the last step and the remaining
+/// interval. The objective must be unimodal on `[a, b]`.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteObjective`] if `func` is NaN or infinite
+/// at an evaluated point.
+pub fn safeguarded_newton_with_derivatives<T, F, D1, D2>(
+    func: F,
+    d1: D1,
+    d2: D2,
+    a: T,
+    b: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+    D1: Fn(T) -> T,
+    D2: Fn(T) -> T,
+{
+    let fevals = Evaluations::new();
+    let func = counted(func, &fevals);
+    let (a, b) = if a < b { (a, b) } else { (b, a) };
+    safeguarded_loop(
+        &func,
+        d1,
+        d2,
+        a,
+        b,
+        Tracker::new(
+            &func,
+            &fevals,
+            criteria.into(),
+            budget.into(),
+            T::from_f64(0.5) * (a + b),
+        ),
+    )
+}
+
+fn safeguarded_loop<T, F, D1, D2>(
+    func: &F,
+    d1: D1,
+    d2: D2,
+    mut a: T,
+    mut b: T,
+    mut tracker: Tracker<'_, T>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+    D1: Fn(T) -> T,
+    D2: Fn(T) -> T,
+{
+    let half = T::from_f64(0.5);
+    while tracker.allows_step() {
+        let x = tracker.x;
+        let slope = d1(x);
+        if slope == T::ZERO || tracker.is_met(slope) {
+            return tracker.finish(func, true);
+        }
+        if slope > T::ZERO {
+            b = x;
+        } else {
+            a = x;
+        }
+
+        let curvature = d2(x);
+        let newton = x - slope / curvature;
+        let next = if curvature > T::ZERO && a < newton && newton < b {
+            newton
+        } else {
+            half * (a + b)
+        };
+        tracker.step_to(func, next, b - a);
+    }
+    tracker.finish(func, false)
+}
+
+/// Bookkeeping shared by the Newton variants: the current iterate, the last
+/// step, objective values when the criterion needs them, and the budget.
+struct Tracker<'a, T> {
+    criteria: ConvergenceCriteria<T>,
+    budget: Budget,
+    fevals: &'a Evaluations,
+    iterations: usize,
+    x: T,
+    dx: T,
+    fx: Option<T>,
+    df: Option<T>,
+}
+
+impl<'a, T: Float> Tracker<'a, T> {
+    fn new<F: Fn(T) -> T>(
+        func: &F,
+        fevals: &'a Evaluations,
+        criteria: ConvergenceCriteria<T>,
+        budget: Budget,
+        x: T,
+    ) -> Self {
+        Tracker {
+            criteria,
+            budget,
+            fevals,
+            iterations: 0,
+            x,
+            dx: T::INFINITY,
+            fx: criteria.uses_f().then(|| func(x)),
+            df: None,
+        }
+    }
+
+    fn allows_step(&self) -> bool {
+        self.fevals.all_finite() && self.budget.allows(self.iterations, self.fevals.get())
+    }
+
+    /// Moves to `x`, recording the step as uncertainty capped at `width`.
+    fn step_to<F: Fn(T) -> T>(&mut self, func: &F, x: T, width: T) {
+        self.iterations += 1;
+        self.dx = (x - self.x).abs().min(width);
+        self.x = x;
+        if let Some(previous) = self.fx {
+            let fx = func(x);
+            self.df = Some(previous - fx);
+            self.fx = Some(fx);
+        }
+    }
+
+    fn is_met(&self, slope: T) -> bool {
+        self.criteria.is_met(&Progress {
+            x: self.x,
+            dx: self.dx,
+            fx: self.fx.unwrap_or(T::NAN),
+            df: self.df,
+            slope,
+        })
+    }
+
+    fn finish<F: Fn(T) -> T>(
+        &self,
+        func: &F,
+        converged: bool,
+    ) -> Result<ScalarResult<T>, OptimizeError> {
+        let fx = self.fx.unwrap_or_else(|| func(self.x));
+        self.fevals.verify(Ok(ScalarResult {
+            x: self.x,
+            fx,
+            iterations: self.iterations,
+            fevals: self.fevals.get(),
+            converged,
+            enclosure: None,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finite_difference_newton() {
+        let result = newton_raphson(|x: f64| x.powi(4) - 3.0 * x, 1.0, 1e-4, 1e-10, 50).unwrap();
+        let expected = (0.75f64).cbrt();
+        assert!(result.converged);
+        assert!((result.x - expected).abs() < 1e-6);
+        // Each step costs two evaluations for f' and three for f'', plus the final f.
+        assert_eq!(result.fevals, 5 * result.iterations + 3);
+    }
+
+    #[test]
+    fn analytic_derivatives_converge_quadratically() {
+        let result = newton_raphson_with_derivatives(
+            |x: f64| x.exp() - 2.0 * x,
+            |x| x.exp() - 2.0,
+            |x| x.exp(),
+            0.0,
+            1e-14,
+            50,
+        )
+        .unwrap();
+        assert!((result.x - 2f64.ln()).abs() < 1e-14);
+        assert!(result.iterations < 8);
+    }
+
+    #[test]
+    fn safeguarded_newton_stays_in_bracket_on_quartic() {
+        // Plain Newton from the flat region around 0 overshoots far outside [-1, 3].
+        let f = |x: f64| (x - 2.0).powi(4) + 0.1 * x * x;
+        let result = safeguarded_newton_with_derivatives(
+            f,
+            |x| 4.0 * (x - 2.0).powi(3) + 0.2 * x,
+            |x| 12.0 * (x - 2.0).powi(2) + 0.2,
+            -1.0,
+            3.0,
+            1e-12,
+            200,
+        )
+        .unwrap();
+        assert!(result.converged);
+        let slope = 4.0 * (result.x - 2.0).powi(3) + 0.2 * result.x;
+        assert!(slope.abs() < 1e-9);
+        assert!((-1.0..=3.0).contains(&result.x));
+    }
+
+    #[test]
+    fn safeguarded_newton_bisects_on_concave_regions() {
+        let result = safeguarded_newton(|x: f64| -x.cos(), -1.0, 4.0, 1e-4, 1e-10, 200).unwrap();
+        assert!(result.converged);
+        assert!(result.x.abs() < 1e-6);
+    }
+
+    #[test]
+    fn gradient_criterion() {
+        let result = newton_raphson(
+            |x: f64| (x - 3.0).powi(4),
+            0.0,
+            1e-3,
+            ConvergenceCriteria::Gradient(1e-6),
+            100,
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert!(4.0 * (result.x - 3.0).abs().powi(3) < 1e-6);
+    }
+
+    #[test]
+    fn flat_curvature_is_an_error() {
+        let result = newton_raphson_with_derivatives(|x: f64| x, |_| 1.0, |_| 0.0, 0.0, 1e-8, 10);
+        assert_eq!(result, Err(OptimizeError::ZeroDerivative));
+    }
+
+    #[test]
+    fn nan_objective_is_reported() {
+        let f = |x: f64| if x < 0.0 { f64::NAN } else { (x - 1.0).powi(2) };
+        // The central difference at 0 reaches to the left of the domain.
+        let error = newton_raphson(f, 0.0, 1e-3, 1e-10, 50).unwrap_err();
+        assert_eq!(error, OptimizeError::NonFiniteObjective { x: -1e-3 });
+        let error = safeguarded_newton(f, -1.0, 0.5, 1e-3, 1e-10, 50).unwrap_err();
+        assert!(matches!(error, OptimizeError::NonFiniteObjective { x } if x < 0.0));
+    }
+}
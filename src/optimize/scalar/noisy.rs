@@ -0,0 +1,235 @@
+use super::{golden_fraction, Enclosure, ScalarResult, Side};
+use crate::generals::Float;
+use crate::optimize::{counted, Budget, ConvergenceCriteria, Evaluations, OptimizeError, Progress};
+
+/// How [`noisy_minimize`] averages evaluations of a noisy objective.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sampling<T> {
+    /// Evaluations added to a point at a time.
+    pub batch: usize,
+    /// Most evaluations spent on a single point.
+    pub max_samples: usize,
+    /// Number of standard errors two averages must differ by before one is
+    /// taken to be lower.
+    pub confidence: T,
+}
+
+impl<T: Float> Default for Sampling<T> {
+    /// Batches of 4, at most 256 samples per point and a confidence of two
+    /// standard errors.
+    fn default() -> Self {
+        Sampling {
+            batch: 4,
+            max_samples: 256,
+            confidence: T::from_f64(2.0),
+        }
+    }
+}
+
+impl<T> Sampling<T> {
+    /// Adds `batch` evaluations to a point at a time.
+    pub fn with_batch(mut self, batch: usize) -> Self {
+        self.batch = batch;
+        self
+    }
+
+    /// Spends at most `max_samples` evaluations on a single point.
+    pub fn with_max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = max_samples;
+        self
+    }
+
+    /// Requires averages to differ by `confidence` standard errors.
+    pub fn with_confidence(mut self, confidence: T) -> Self {
+        self.confidence = confidence;
+        self
+    }
+}
+
+/// Minimizes a noisy `func`, such as a Monte Carlo estimate, on `[a, b]` by
+/// golden section search on averaged evaluations.
+///
+/// Each comparison of the two interior points samples both in batches until
+/// their averages differ by `sampling.confidence` standard errors, or until
+/// `sampling.max_samples` evaluations have gone into each, in which case the
+/// lower average wins. Closer to the minimum the differences shrink, so more
+/// samples are spent exactly where the noise matters. The surviving interior
+/// point keeps its samples for the next comparison. The search stops once
+/// `criteria` is met, where the x uncertainty is the width of the remaining
+/// interval; objective criteria never trigger since no improvement can be
+/// trusted. The surviving point is returned with its average as `fx`.
+///
+/// The objective must be unimodal up to noise. Its minimizer can only be
+/// resolved to where the expected difference between points drops below the
+/// averaged noise.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::NonFiniteObjective`] if `func` is NaN or infinite
+/// at an evaluated point.
+pub fn noisy_minimize<T, F>(
+    func: F,
+    a: T,
+    b: T,
+    sampling: Sampling<T>,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let criteria = criteria.into();
+    let budget = budget.into();
+    let fevals = Evaluations::new();
+    let func = counted(func, &fevals);
+    let ratio = T::ONE - golden_fraction::<T>();
+    let batch = sampling.batch.max(2);
+    let max_samples = sampling.max_samples.max(batch);
+
+    let (mut a, mut b) = if a < b { (a, b) } else { (b, a) };
+    let mut kept: Option<(Side, T, Average<T>)> = None;
+    let mut best = (T::from_f64(0.5) * (a + b), T::NAN);
+    let mut iterations = 0;
+    let mut converged = false;
+    while !converged && fevals.all_finite() && budget.allows(iterations, fevals.get()) {
+        let mut c = b - ratio * (b - a);
+        let mut d = a + ratio * (b - a);
+        let alignment = T::EPSILON.sqrt() * (b - a);
+        let (mut ac, mut ad) = match kept {
+            Some((Side::Left, x, average)) if (x - c).abs() <= alignment => {
+                c = x;
+                (average, Average::default())
+            }
+            Some((Side::Right, x, average)) if (x - d).abs() <= alignment => {
+                d = x;
+                (Average::default(), average)
+            }
+            _ => (Average::default(), Average::default()),
+        };
+
+        ac.sample(&func, c, batch.saturating_sub(ac.n));
+        ad.sample(&func, d, batch.saturating_sub(ad.n));
+        while !ac.differs_from(&ad, sampling.confidence)
+            && (ac.n < max_samples || ad.n < max_samples)
+            && fevals.all_finite()
+        {
+            if ac.n < max_samples {
+                ac.sample(&func, c, batch);
+            }
+            if ad.n < max_samples {
+                ad.sample(&func, d, batch);
+            }
+        }
+
+        let slope = (ad.mean - ac.mean) / (d - c);
+        if ac.mean < ad.mean {
+            b = d;
+            best = (c, ac.mean);
+            kept = Some((Side::Right, c, ac));
+        } else {
+            a = c;
+            best = (d, ad.mean);
+            kept = Some((Side::Left, d, ad));
+        }
+        iterations += 1;
+        converged = criteria.is_met(&Progress {
+            x: best.0,
+            dx: b - a,
+            fx: best.1,
+            df: None,
+            slope,
+        });
+    }
+
+    let (x, fx) = if best.1.is_nan() {
+        (best.0, func(best.0))
+    } else {
+        best
+    };
+    fevals.verify(Ok(ScalarResult {
+        x,
+        fx,
+        iterations,
+        fevals: fevals.get(),
+        converged,
+        enclosure: Some(Enclosure {
+            lower: a,
+            upper: b,
+            f_lower: None,
+            f_upper: None,
+        }),
+    }))
+}
+
+/// Running mean and variance of the samples at a point (Welford's method).
+#[derive(Debug, Clone, Copy)]
+struct Average<T> {
+    n: usize,
+    mean: T,
+    m2: T,
+}
+
+impl<T: Float> Default for Average<T> {
+    fn default() -> Self {
+        Average {
+            n: 0,
+            mean: T::ZERO,
+            m2: T::ZERO,
+        }
+    }
+}
+
+impl<T: Float> Average<T> {
+    fn sample<F: Fn(T) -> T>(&mut self, func: &F, x: T, count: usize) {
+        for _ in 0..count {
+            let value = func(x);
+            self.n += 1;
+            let delta = value - self.mean;
+            self.mean += delta / T::from_usize(self.n);
+            self.m2 += delta * (value - self.mean);
+        }
+    }
+
+    /// Squared standard error of the mean.
+    fn error_squared(&self) -> T {
+        self.m2 / T::from_usize(self.n * (self.n - 1))
+    }
+
+    /// Whether the two means differ by more than `confidence` standard errors.
+    fn differs_from(&self, other: &Self, confidence: T) -> bool {
+        let error = (self.error_squared() + other.error_squared()).sqrt();
+        (self.mean - other.mean).abs() > confidence * error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generals::random::Rng;
+    use std::cell::RefCell;
+
+    #[test]
+    fn finds_minimum_through_noise() {
+        let rng = RefCell::new(Rng::new(3));
+        let f = |x: f64| (x - 1.0).powi(2) + 0.05 * (rng.borrow_mut().next_f64() - 0.5);
+        let result = noisy_minimize(f, -3.0, 4.0, Sampling::default(), 1e-3, 200).unwrap();
+        assert!(result.converged);
+        assert!((result.x - 1.0).abs() < 0.1, "{}", result.x);
+        let enclosure = result.enclosure.unwrap();
+        assert!(enclosure.width() < 1e-3);
+    }
+
+    #[test]
+    fn noiseless_objective_needs_one_batch_per_point() {
+        let sampling = Sampling::default().with_batch(2);
+        let result =
+            noisy_minimize(|x: f64| (x + 0.5).powi(2), -2.0, 2.0, sampling, 1e-8, 200).unwrap();
+        assert!((result.x + 0.5).abs() < 1e-7);
+        // Two points in the first iteration and one new point in each later
+        // one, each getting a single batch of two evaluations: without noise
+        // the averages differ after the first batch, and the surviving point
+        // keeps its samples rather than being resampled.
+        assert_eq!(result.fevals, 2 * (result.iterations + 1));
+    }
+}
@@ -0,0 +1,108 @@
+use super::ScalarResult;
+use crate::generals::differential_methods::first_derivative;
+use crate::generals::Float;
+use crate::optimize::{counted, Budget, ConvergenceCriteria, Evaluations, OptimizeError, Progress};
+
+/// Minimizes `func` with the secant method applied to its derivative, starting
+/// from the two points `x0` and `x1`.
+///
+/// The second derivative is replaced by the slope of the secant through the
+/// last two derivative values, so each iteration costs a single central
+/// difference derivative (two objective evaluations) with step `h`. Iteration
+/// stops once `criteria` is met, where the x uncertainty is the length of the
+/// last step.
+///
+/// # Errors
+///
+/// Returns [`OptimizeError::ZeroDerivative`] if two consecutive derivative
+/// values are equal, leaving the secant slope undefined, and
+/// [`OptimizeError::NonFiniteObjective`] if `func` is NaN or infinite at an
+/// evaluated point.
+pub fn secant_minimize<T, F>(
+    func: F,
+    x0: T,
+    x1: T,
+    h: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let criteria = criteria.into();
+    let budget = budget.into();
+    let fevals = Evaluations::new();
+    let func = counted(func, &fevals);
+    let (mut x_prev, mut x) = (x0, x1);
+    let mut g_prev = first_derivative(&func, x_prev, h);
+    let mut dx = T::INFINITY;
+    let mut fx = criteria.uses_f().then(|| func(x));
+    let mut df = None;
+    let mut iteration = 0;
+    while fevals.all_finite() && budget.allows(iteration, fevals.get()) {
+        let g = first_derivative(&func, x, h);
+        let progress = Progress {
+            x,
+            dx,
+            fx: fx.unwrap_or(T::NAN),
+            df,
+            slope: g,
+        };
+        if criteria.is_met(&progress) {
+            let fx = fx.unwrap_or_else(|| func(x));
+            return fevals.verify(Ok(ScalarResult {
+                x,
+                fx,
+                iterations: iteration,
+                fevals: fevals.get(),
+                converged: true,
+                enclosure: None,
+            }));
+        }
+        if g == g_prev {
+            return fevals.verify(Err(OptimizeError::ZeroDerivative));
+        }
+        let step = g * (x - x_prev) / (g - g_prev);
+        x_prev = x;
+        g_prev = g;
+        x -= step;
+        dx = step.abs();
+        iteration += 1;
+        if let Some(previous) = fx {
+            let current = func(x);
+            df = Some(previous - current);
+            fx = Some(current);
+        }
+    }
+    let fx = fx.unwrap_or_else(|| func(x));
+    fevals.verify(Ok(ScalarResult {
+        x,
+        fx,
+        iterations: iteration,
+        fevals: fevals.get(),
+        converged: false,
+        enclosure: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converges_superlinearly() {
+        let result =
+            secant_minimize(|x: f64| x.cosh() + 0.5 * x, 0.0, 0.1, 1e-5, 1e-10, 50).unwrap();
+        let expected = (-0.5f64).asinh();
+        assert!(result.converged);
+        assert!((result.x - expected).abs() < 1e-8);
+        assert!(result.iterations < 12);
+    }
+
+    #[test]
+    fn equal_slopes_are_an_error() {
+        let result = secant_minimize(|x: f64| x, 0.0, 1.0, 0.5, 1e-8, 10);
+        assert_eq!(result, Err(OptimizeError::ZeroDerivative));
+    }
+}
@@ -0,0 +1,36 @@
+use super::{bound_minimize, ScalarResult};
+use crate::generals::Float;
+use crate::optimize::{Budget, ConvergenceCriteria, OptimizeError};
+
+/// Minimizes a unimodal `func` on `[a, b]` by ternary search.
+///
+/// Each iteration evaluates the two points splitting the interval into thirds
+/// and discards the outer third beyond the worse one. It needs more evaluations
+/// than golden section search but is easier to follow. This is
+/// [`bound_minimize`] with a ratio of two thirds, and shares its stopping rule
+/// and errors.
+pub fn ternary_minimize<T, F>(
+    func: F,
+    a: T,
+    b: T,
+    criteria: impl Into<ConvergenceCriteria<T>>,
+    budget: impl Into<Budget>,
+) -> Result<ScalarResult<T>, OptimizeError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    bound_minimize(func, a, b, T::from_f64(2.0 / 3.0), criteria, budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continuous_ternary_search() {
+        let result = ternary_minimize(|x: f64| (x + 1.5).powi(2), -4.0, 3.0, 1e-9, 200).unwrap();
+        assert!(result.converged);
+        assert!((result.x + 1.5).abs() < 1e-8);
+    }
+}
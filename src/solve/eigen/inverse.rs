@@ -0,0 +1,241 @@
+use super::{normalized, oriented, rayleigh, EigenOptions, EigenResult};
+use crate::generals::linalg::{LinalgError, Lu, Matrix};
+use crate::generals::Float;
+
+/// The eigenvalue of `A` nearest `target`, and an eigenvector for it, by
+/// inverse iteration from `v0`.
+///
+/// Power iteration on `(A - target·I)⁻¹`, whose dominant eigenvalue is
+/// `1/(λ - target)` for the eigenvalue `λ` of `A` nearest `target`: the
+/// other components of `v` shrink by about `|λ - target|/|μ - target|` per
+/// iteration, for the next nearest eigenvalue `μ`, so a good target
+/// converges in a few iterations. `A - target·I` is factored once, and each
+/// iteration costs a solve with the factors and a product with `A` for the
+/// Rayleigh quotient that estimates the eigenvalue. Once the eigenvalue is
+/// roughly known, [`rayleigh_quotient_iteration`] refines it faster.
+///
+/// # Errors
+///
+/// Returns [`LinalgError::Singular`] if `A - target·I` stays singular after
+/// moving `target` by a rounding error, and [`LinalgError::Breakdown`] if a
+/// non-finite value appears.
+///
+/// # Panics
+///
+/// Panics if `A` is not square, `v0` does not fit it or `v0` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::Matrix;
+/// use mathslib::solve::eigen::{inverse_iteration, EigenOptions};
+///
+/// let a: Matrix<f64> = Matrix::from_rows(&[
+///     vec![2.0, 1.0, 0.0],
+///     vec![1.0, 3.0, 1.0],
+///     vec![0.0, 1.0, 4.0],
+/// ]);
+/// // The eigenvalues are 3 - √3, 3 and 3 + √3.
+/// let result = inverse_iteration(&a, 2.9, &[1.0, 1.0, 1.0], EigenOptions::new()).unwrap();
+/// assert!(result.converged);
+/// assert!((result.value - 3.0).abs() < 1e-10);
+/// assert!(result.iterations < 15);
+/// ```
+pub fn inverse_iteration<T: Float>(
+    a: &Matrix<T>,
+    target: T,
+    v0: &[T],
+    options: EigenOptions<T>,
+) -> Result<EigenResult<T>, LinalgError> {
+    let lu = shifted_lu(a, target)?;
+    iterate(a, v0, options, |v, _| lu.solve(v))
+}
+
+/// An eigenvalue of `A` and an eigenvector for it by Rayleigh quotient
+/// iteration, from `v0` and the initial shift `target`.
+///
+/// Inverse iteration whose shift moves, after the first iteration, to the
+/// Rayleigh quotient of the current vector. Convergence is cubic for a
+/// symmetric `A` and quadratic otherwise, so a few iterations reach full
+/// precision, but `A - λ·I` is factored anew in each, and the eigenvalue
+/// found is the one `v0` and `target` are nearest in a sense hard to
+/// predict, not necessarily the one nearest `target`. It suits refining an
+/// approximate eigenpair, from [`inverse_iteration`] with a loose
+/// tolerance, say.
+///
+/// # Errors
+///
+/// Returns [`LinalgError::Singular`] if a shifted matrix stays singular
+/// after moving the shift by a rounding error, and
+/// [`LinalgError::Breakdown`] if a non-finite value appears.
+///
+/// # Panics
+///
+/// Panics if `A` is not square, `v0` does not fit it or `v0` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::Matrix;
+/// use mathslib::solve::eigen::{rayleigh_quotient_iteration, EigenOptions};
+///
+/// let a: Matrix<f64> = Matrix::from_rows(&[
+///     vec![2.0, 1.0, 0.0],
+///     vec![1.0, 3.0, 1.0],
+///     vec![0.0, 1.0, 4.0],
+/// ]);
+/// let options = EigenOptions::new().tolerance(1e-14);
+/// let result = rayleigh_quotient_iteration(&a, 4.5, &[0.0, 1.0, 2.0], options).unwrap();
+/// assert!(result.converged && result.iterations <= 5);
+/// assert!((result.value - (3.0 + 3f64.sqrt())).abs() < 1e-14);
+/// ```
+pub fn rayleigh_quotient_iteration<T: Float>(
+    a: &Matrix<T>,
+    target: T,
+    v0: &[T],
+    options: EigenOptions<T>,
+) -> Result<EigenResult<T>, LinalgError> {
+    let mut shift = target;
+    iterate(a, v0, options, |v, value| {
+        let lu = shifted_lu(a, shift)?;
+        shift = value;
+        lu.solve(v)
+    })
+}
+
+/// Inverse iteration with `solve(v, λ)` applying `(A - σ·I)⁻¹` to `v` for
+/// the shift `σ` of the iteration, given the current eigenvalue estimate
+/// `λ`.
+fn iterate<T, S>(
+    a: &Matrix<T>,
+    v0: &[T],
+    options: EigenOptions<T>,
+    mut solve: S,
+) -> Result<EigenResult<T>, LinalgError>
+where
+    T: Float,
+    S: FnMut(&[T], T) -> Result<Vec<T>, LinalgError>,
+{
+    assert!(a.is_square(), "the matrix must be square");
+    assert_eq!(v0.len(), a.cols(), "v0 must have one entry per column");
+    let target = options.tolerance * a.norm_1();
+    let mut v = normalized(v0).expect("the starting vector must not be zero");
+    let (mut value, mut residual_norm) = rayleigh(&v, &a.mul_vector(&v));
+    let mut iterations = 0;
+    loop {
+        if !residual_norm.is_finite() {
+            return Err(LinalgError::Breakdown);
+        }
+        let converged = residual_norm <= target;
+        if converged || iterations >= options.max_iter {
+            return Ok(EigenResult {
+                value,
+                vector: oriented(v),
+                residual_norm,
+                iterations,
+                converged,
+            });
+        }
+        v = normalized(&solve(&v, value)?).ok_or(LinalgError::Breakdown)?;
+        (value, residual_norm) = rayleigh(&v, &a.mul_vector(&v));
+        iterations += 1;
+    }
+}
+
+/// The factorization of `A - shift·I`, with the shift moved by a rounding
+/// error if it is exactly an eigenvalue. The factors of a nearly singular
+/// shifted matrix are what inverse iteration relies on, but an exactly
+/// singular one cannot be solved with.
+pub(super) fn shifted_lu<T: Float>(a: &Matrix<T>, shift: T) -> Result<ShiftedLu<T>, LinalgError> {
+    assert!(a.is_square(), "the matrix must be square");
+    // The size of A - shift·I, or 1 for the zero matrix at a zero shift.
+    let scale = a.norm_1() + shift.abs();
+    let scale = if scale > T::ZERO { scale } else { T::ONE };
+    let identity = Matrix::identity(a.rows());
+    let lu = Lu::new(&(a - &identity.scale(shift)));
+    if !lu.is_singular() {
+        return Ok(ShiftedLu { lu, scale });
+    }
+    let lu = Lu::new(&(a - &identity.scale(shift + T::EPSILON * scale)));
+    if lu.is_singular() {
+        Err(LinalgError::Singular)
+    } else {
+        Ok(ShiftedLu { lu, scale })
+    }
+}
+
+/// The factors of a shifted matrix `A - σ·I` from [`shifted_lu`].
+pub(super) struct ShiftedLu<T> {
+    lu: Lu<T>,
+    scale: T,
+}
+
+impl<T: Float> ShiftedLu<T> {
+    /// `(A - σ·I)⁻¹·v`, up to a positive factor. A shift within a rounding
+    /// error of an eigenvalue makes the solution up to `1/ε` times larger
+    /// than `v` divided by the size of `A - σ·I`, so for a matrix smaller
+    /// than one `v` is scaled by that size first to keep the solution finite.
+    pub(super) fn solve(&self, v: &[T]) -> Result<Vec<T>, LinalgError> {
+        let factor = self.scale.min(T::ONE);
+        let scaled: Vec<T> = v.iter().map(|&vi| vi * factor).collect();
+        Ok(self.lu.solve(&scaled)?.into_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Symmetric, with eigenvalues 1, 2, 4 and 7.
+    fn sample() -> Matrix<f64> {
+        // Q·diag(1, 2, 4, 7)·Qᵀ for the Householder reflection Q = I - 2uuᵀ,
+        // u = (1, 1, 1, 1)/2.
+        let q = Matrix::from_fn(4, 4, |i, j| if i == j { 0.5 } else { -0.5 });
+        let d = Matrix::from_diagonal(&[1.0, 2.0, 4.0, 7.0]);
+        &(&q * &d) * &q
+    }
+
+    #[test]
+    fn inverse_iteration_finds_each_eigenvalue() {
+        let a = sample();
+        let v0 = [1.0, 0.3, -0.2, 0.5];
+        for (target, expected) in [(0.0, 1.0), (2.2, 2.0), (3.6, 4.0), (100.0, 7.0)] {
+            let result = inverse_iteration(&a, target, &v0, EigenOptions::new()).unwrap();
+            assert!(result.converged, "{target}");
+            assert!((result.value - expected).abs() < 1e-9, "{target}");
+        }
+        // A target that is exactly an eigenvalue still works.
+        let exact = inverse_iteration(&a, 4.0, &v0, EigenOptions::new()).unwrap();
+        assert!(exact.converged && exact.iterations <= 2);
+        assert!((exact.value - 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn extreme_scales_normalize() {
+        for scale in [1e200, 1e-200] {
+            let a = Matrix::from_diagonal(&[scale, 2.0 * scale]);
+            let result = inverse_iteration(&a, scale, &[1.0, 1.0], EigenOptions::new()).unwrap();
+            assert!(result.converged, "{scale}");
+            assert!((result.value / scale - 1.0).abs() < 1e-15);
+            assert!((result.vector[0] - 1.0).abs() < 1e-15 && result.vector[1].abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn rayleigh_quotient_iteration_refines_a_rough_eigenpair() {
+        let a = sample();
+        let v0 = [1.0, 0.3, -0.2, 0.5];
+        let rough = EigenOptions::new().tolerance(1e-2);
+        let start = inverse_iteration(&a, 2.6, &v0, rough).unwrap();
+        let options = EigenOptions::new().tolerance(1e-14);
+        let fixed = inverse_iteration(&a, 2.6, &v0, options).unwrap();
+        let refined = rayleigh_quotient_iteration(&a, start.value, &start.vector, options).unwrap();
+        assert!(fixed.converged && refined.converged);
+        assert!(start.iterations + refined.iterations < fixed.iterations / 2);
+        assert!((refined.value - 2.0).abs() < 1e-14);
+        let av = a.mul_vector(&refined.vector);
+        for (x, v) in av.iter().zip(&refined.vector) {
+            assert!((x - refined.value * v).abs() < 1e-13);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Eigenvalues and eigenvectors of square matrices.
+//!
+//! [`power_iteration`] finds the eigenvalue of largest magnitude from
+//! products with the matrix alone, so it takes any
+//! [`LinearOperator`](crate::solve::linear::LinearOperator), sparse ones
+//! included. [`inverse_iteration`] finds the eigenvalue nearest a target
+//! instead, and [`rayleigh_quotient_iteration`] refines an approximate
+//! eigenpair very quickly; both factor a shifted dense matrix.
+//! [`eigenvalues`] finds every eigenvalue of a dense matrix at once, complex
+//! conjugate pairs included, and [`eigenpairs`] adds the eigenvectors.
+//!
+//! The iterations for a single eigenvalue stop once the residual
+//! `‖A·v - λ·v‖` of the unit vector `v` is small, as an [`EigenOptions`]
+//! sets, and report how far they got in an [`EigenResult`].
+
+mod inverse;
+mod power;
+mod qr;
+
+pub use inverse::{inverse_iteration, rayleigh_quotient_iteration};
+pub use power::power_iteration;
+pub use qr::{eigenpairs, eigenvalues, Eigenpair};
+
+use crate::generals::linalg::{dot, norm};
+use crate::generals::Float;
+
+/// Outcome of an iterative eigenvalue solver.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EigenResult<T> {
+    /// Approximate eigenvalue: the Rayleigh quotient `vᵀ·A·v` of `vector`.
+    pub value: T,
+    /// Approximate eigenvector, of unit Euclidean norm, with its entry of
+    /// largest magnitude positive.
+    pub vector: Vec<T>,
+    /// Euclidean norm of the residual `A·v - λ·v`.
+    pub residual_norm: T,
+    /// Number of iterations performed.
+    pub iterations: usize,
+    /// Whether the tolerance was met before `max_iter` iterations.
+    pub converged: bool,
+}
+
+/// Options for the iterative eigenvalue solvers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EigenOptions<T> {
+    /// Stop once `‖A·v - λ·v‖ ≤ tolerance·‖A‖`, with `‖A‖` estimated by
+    /// `|λ|` for [`power_iteration`] and the 1-norm of the matrix otherwise.
+    pub tolerance: T,
+    /// Maximum number of iterations.
+    pub max_iter: usize,
+}
+
+impl<T: Float> EigenOptions<T> {
+    /// A relative residual of `10⁻¹⁰` and at most 1000 iterations.
+    pub fn new() -> Self {
+        EigenOptions {
+            tolerance: T::from_f64(1e-10),
+            max_iter: 1000,
+        }
+    }
+
+    /// Stops at a relative residual of `tolerance` instead.
+    pub fn tolerance(mut self, tolerance: T) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Allows `max_iter` iterations instead of 1000.
+    pub fn max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+}
+
+impl<T: Float> Default for EigenOptions<T> {
+    fn default() -> Self {
+        EigenOptions::new()
+    }
+}
+
+/// Euclidean norm of `v`, scaled by its entry of largest magnitude first so
+/// that the squares neither overflow nor underflow.
+fn length<T: Float>(v: &[T]) -> T {
+    let largest = v.iter().fold(T::ZERO, |largest, vi| largest.max(vi.abs()));
+    if largest == T::ZERO || !largest.is_finite() {
+        return largest;
+    }
+    let scaled: Vec<T> = v.iter().map(|&vi| vi / largest).collect();
+    largest * norm(&scaled)
+}
+
+/// `v / ‖v‖`, or `None` if `v` is zero or its [`length`] is not finite, which
+/// takes a vector of non-finite entries or one longer than `T::MAX`.
+fn normalized<T: Float>(v: &[T]) -> Option<Vec<T>> {
+    let length = length(v);
+    if length == T::ZERO || !length.is_finite() {
+        return None;
+    }
+    Some(v.iter().map(|&vi| vi / length).collect())
+}
+
+/// The Rayleigh quotient `λ = vᵀ·A·v` of the unit vector `v`, given `A·v`,
+/// and the norm of the residual `A·v - λ·v`.
+fn rayleigh<T: Float>(v: &[T], av: &[T]) -> (T, T) {
+    let value = dot(v, av);
+    let residual: Vec<T> = av
+        .iter()
+        .zip(v)
+        .map(|(&avi, &vi)| avi - value * vi)
+        .collect();
+    (value, length(&residual))
+}
+
+/// Flips `v` so that its entry of largest magnitude is positive, since an
+/// eigenvector is only determined up to sign.
+fn oriented<T: Float>(mut v: Vec<T>) -> Vec<T> {
+    let largest = v.iter().fold(
+        T::ZERO,
+        |best, &vi| if vi.abs() > best.abs() { vi } else { best },
+    );
+    if largest < T::ZERO {
+        for vi in &mut v {
+            *vi = -*vi;
+        }
+    }
+    v
+}
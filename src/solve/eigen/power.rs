@@ -0,0 +1,126 @@
+use super::{normalized, oriented, rayleigh, EigenOptions, EigenResult};
+use crate::generals::linalg::LinalgError;
+use crate::generals::Float;
+use crate::solve::linear::LinearOperator;
+
+/// The eigenvalue of `A` of largest magnitude, and an eigenvector for it,
+/// by power iteration from `v0`.
+///
+/// Repeated products `A·v`, normalized, turn `v` toward the dominant
+/// eigenvector, reducing the other components by about `|λ₂/λ₁|` per
+/// iteration for the two eigenvalues of largest magnitude; the eigenvalue
+/// is estimated by the Rayleigh quotient, whose error is about the square
+/// of that of `v` for a symmetric `A`. Only products with `A` are needed,
+/// one per iteration. The iteration does not converge when `|λ₂| = |λ₁|`
+/// with `λ₂ ≠ λ₁`, as for a complex conjugate pair or for `±λ`, nor when
+/// `v0` has no component along the dominant eigenvector, though rounding
+/// usually introduces one.
+///
+/// # Errors
+///
+/// Returns [`LinalgError::Breakdown`] if a non-finite value appears.
+///
+/// # Panics
+///
+/// Panics if `A` is not square, `v0` does not fit it or `v0` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::Matrix;
+/// use mathslib::solve::eigen::{power_iteration, EigenOptions};
+///
+/// let a: Matrix<f64> = Matrix::from_rows(&[
+///     vec![2.0, 1.0, 0.0],
+///     vec![1.0, 3.0, 1.0],
+///     vec![0.0, 1.0, 4.0],
+/// ]);
+/// let result = power_iteration(&a, &[1.0, 1.0, 1.0], EigenOptions::new()).unwrap();
+/// assert!(result.converged);
+/// assert!((result.value - (3.0 + 3f64.sqrt())).abs() < 1e-10);
+/// let av = a.mul_vector(&result.vector);
+/// assert!(av.iter().zip(&result.vector).all(|(x, v)| (x - result.value * v).abs() < 1e-8));
+/// ```
+pub fn power_iteration<T, A>(
+    a: &A,
+    v0: &[T],
+    options: EigenOptions<T>,
+) -> Result<EigenResult<T>, LinalgError>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+{
+    assert_eq!(v0.len(), a.dim(), "v0 must have one entry per column");
+    let mut v = normalized(v0).expect("the starting vector must not be zero");
+    let mut av = a.apply(&v);
+    let (mut value, mut residual_norm) = rayleigh(&v, &av);
+    let mut iterations = 0;
+    loop {
+        if !residual_norm.is_finite() {
+            return Err(LinalgError::Breakdown);
+        }
+        let converged = residual_norm <= options.tolerance * value.abs();
+        if converged || iterations >= options.max_iter {
+            return Ok(EigenResult {
+                value,
+                vector: oriented(v),
+                residual_norm,
+                iterations,
+                converged,
+            });
+        }
+        // A·v is nonzero here, or the residual would be zero.
+        v = normalized(&av).ok_or(LinalgError::Breakdown)?;
+        av = a.apply(&v);
+        (value, residual_norm) = rayleigh(&v, &av);
+        iterations += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generals::linalg::{CsrMatrix, Matrix};
+
+    #[test]
+    fn finds_the_dominant_eigenpair_of_a_sparse_laplacian() {
+        // The eigenvalues of the 1-D Laplacian are 2 - 2·cos(kπ/(n + 1)).
+        let n = 20;
+        let mut triplets = Vec::new();
+        for i in 0..n {
+            triplets.push((i, i, 2.0));
+            if i > 0 {
+                triplets.push((i, i - 1, -1.0));
+                triplets.push((i - 1, i, -1.0));
+            }
+        }
+        let a = CsrMatrix::from_triplets(n, n, &triplets);
+        let v0: Vec<f64> = (0..n).map(|i| 1.0 + i as f64).collect();
+        let options = EigenOptions::new().tolerance(1e-8).max_iter(10_000);
+        let result = power_iteration(&a, &v0, options).unwrap();
+        assert!(result.converged);
+        let theta = std::f64::consts::PI / (n + 1) as f64;
+        let expected = 2.0 - 2.0 * (n as f64 * theta).cos();
+        assert!((result.value - expected).abs() < 1e-10);
+        for (i, vi) in result.vector.iter().enumerate() {
+            let exact = ((i + 1) as f64 * n as f64 * theta).sin().abs();
+            assert!((vi.abs() - exact / (n as f64 / 2.0 + 0.5).sqrt()).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn negative_and_stagnating_eigenvalues() {
+        let a = Matrix::from_rows(&[vec![-5.0, 1.0], vec![0.0, 2.0]]);
+        let result = power_iteration(&a, &[1.0, 1.0], EigenOptions::new()).unwrap();
+        assert!(result.converged);
+        assert!((result.value + 5.0).abs() < 1e-9);
+        assert_eq!(result.vector[0].signum(), 1.0);
+
+        // Eigenvalues ±1: v alternates instead of converging.
+        let flip = Matrix::from_rows(&[vec![0.0, 1.0], vec![1.0, 0.0]]);
+        let options = EigenOptions::new().max_iter(50);
+        let result = power_iteration(&flip, &[1.0, 0.0], options).unwrap();
+        assert!(!result.converged);
+        assert_eq!(result.iterations, 50);
+    }
+}
@@ -0,0 +1,490 @@
+use super::inverse::shifted_lu;
+use super::normalized;
+use crate::generals::linalg::{dot, norm, LinalgError, Matrix};
+use crate::generals::{Complex, Float};
+
+/// Iteration limit per eigenvalue of the QR algorithm; it usually needs
+/// two or three.
+const MAX_ITERATIONS: usize = 30;
+
+/// Inverse iteration steps taken for each eigenvector. The eigenvalue is
+/// accurate, so one step usually suffices and a second makes sure.
+const INVERSE_STEPS: usize = 2;
+
+/// An eigenvalue of a real matrix and an eigenvector for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Eigenpair<T> {
+    /// The eigenvalue.
+    pub value: Complex<T>,
+    /// An eigenvector, of unit Euclidean norm, scaled so that its entry of
+    /// largest modulus is real and positive.
+    pub vector: Vec<Complex<T>>,
+}
+
+/// All eigenvalues of the square matrix `A`, repeated as often as their
+/// algebraic multiplicity, ordered by real part and then imaginary part.
+///
+/// `A` is first reduced to upper Hessenberg form by Householder
+/// reflections, which keeps its eigenvalues, and the Francis double-shift
+/// QR algorithm then drives the subdiagonal to zero, leaving `1 × 1` blocks
+/// for the real eigenvalues and `2 × 2` ones for the complex conjugate
+/// pairs. Each QR step costs `O(n²)` on the Hessenberg form, and a couple of
+/// steps per eigenvalue usually suffice, for `O(n³)` in all. The eigenvalues
+/// come out with errors of about `ε·‖A‖` times their condition numbers, so
+/// well-separated eigenvalues of a symmetric matrix to full precision, and
+/// those of badly nonnormal matrices much less accurately. Real
+/// eigenvalues have an imaginary part of exactly zero.
+///
+/// # Errors
+///
+/// Returns [`LinalgError::NoConvergence`] if an eigenvalue is not found
+/// within 30 iterations, which only contrived matrices cause.
+///
+/// # Panics
+///
+/// Panics if `A` is not square.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::Matrix;
+/// use mathslib::solve::eigen::eigenvalues;
+///
+/// // x'' + 0.2·x' + x = 0 as a first order system: a damped oscillator,
+/// // stable because both eigenvalues lie in the left half-plane.
+/// let a: Matrix<f64> = Matrix::from_rows(&[vec![0.0, 1.0], vec![-1.0, -0.2]]);
+/// let values = eigenvalues(&a).unwrap();
+/// assert!(values.iter().all(|z| z.re < 0.0));
+/// assert!((values[0].re + 0.1).abs() < 1e-15);
+/// assert!((values[1].im - 0.99f64.sqrt()).abs() < 1e-15);
+/// assert_eq!(values[0], values[1].conj());
+/// ```
+pub fn eigenvalues<T: Float>(a: &Matrix<T>) -> Result<Vec<Complex<T>>, LinalgError> {
+    assert!(a.is_square(), "the matrix must be square");
+    let mut values = francis(hessenberg(a), a.rows())?;
+    values.sort_by(|a, b| {
+        (a.re, a.im)
+            .partial_cmp(&(b.re, b.im))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    Ok(values)
+}
+
+/// All eigenvalues of the square matrix `A`, as [`eigenvalues`] orders
+/// them, each with an eigenvector.
+///
+/// The eigenvectors come from inverse iteration with the eigenvalues as
+/// shifts, which costs a factorization of `A - λ·I` per eigenvalue, carried
+/// out in real arithmetic of twice the size for complex eigenvalues. The
+/// eigenvectors of a conjugate pair are conjugate too. A repeated eigenvalue
+/// gets the same eigenvector each time, even when its eigenspace has more
+/// dimensions.
+///
+/// # Errors
+///
+/// Returns [`LinalgError::NoConvergence`] as [`eigenvalues`] does, and
+/// [`LinalgError::Singular`] in the unlikely case that a shifted matrix
+/// stays singular after moving the shift by a rounding error.
+///
+/// # Panics
+///
+/// Panics if `A` is not square.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::Matrix;
+/// use mathslib::solve::eigen::eigenpairs;
+///
+/// // A rotation by 90° in the plane, and a stretch along the third axis.
+/// let a: Matrix<f64> = Matrix::from_rows(&[
+///     vec![0.0, -1.0, 0.0],
+///     vec![1.0, 0.0, 0.0],
+///     vec![0.0, 0.0, 2.0],
+/// ]);
+/// let pairs = eigenpairs(&a).unwrap();
+/// assert_eq!(pairs.len(), 3);
+/// let (rotation, stretch) = (&pairs[1], &pairs[2]);
+/// assert!((rotation.value.im - 1.0).abs() < 1e-15 && stretch.value.re == 2.0);
+/// // (1, -i, 0)/√2 for the eigenvalue i.
+/// let v = &rotation.vector;
+/// assert!((v[0].re - 0.5f64.sqrt()).abs() < 1e-14 && (v[1].im + 0.5f64.sqrt()).abs() < 1e-14);
+/// assert!((stretch.vector[2].re - 1.0).abs() < 1e-14);
+/// ```
+pub fn eigenpairs<T: Float>(a: &Matrix<T>) -> Result<Vec<Eigenpair<T>>, LinalgError> {
+    eigenvalues(a)?
+        .into_iter()
+        .map(|value| {
+            Ok(Eigenpair {
+                value,
+                vector: eigenvector(a, value)?,
+            })
+        })
+        .collect()
+}
+
+/// The upper Hessenberg matrix similar to `a`, by Householder reflections,
+/// row-major.
+fn hessenberg<T: Float>(a: &Matrix<T>) -> Vec<T> {
+    let n = a.rows();
+    let mut h = a.as_slice().to_vec();
+    for k in 0..n.saturating_sub(2) {
+        let x: Vec<T> = (k + 1..n).map(|i| h[i * n + k]).collect();
+        let length = norm(&x);
+        if length == T::ZERO {
+            continue;
+        }
+        let alpha = if x[0] > T::ZERO { -length } else { length };
+        let mut v = x;
+        v[0] -= alpha;
+        let scale = T::from_f64(2.0) / dot(&v, &v);
+        // H ← P·H·P for P = I - 2vvᵀ/(vᵀv) acting on rows and columns k + 1...
+        for j in k..n {
+            let s = scale * (k + 1..n).map(|i| v[i - k - 1] * h[i * n + j]).sum::<T>();
+            for i in k + 1..n {
+                h[i * n + j] -= s * v[i - k - 1];
+            }
+        }
+        for i in 0..n {
+            let s = scale * (k + 1..n).map(|j| h[i * n + j] * v[j - k - 1]).sum::<T>();
+            for j in k + 1..n {
+                h[i * n + j] -= s * v[j - k - 1];
+            }
+        }
+        h[(k + 1) * n + k] = alpha;
+        for i in k + 2..n {
+            h[i * n + k] = T::ZERO;
+        }
+    }
+    h
+}
+
+/// The eigenvalues of the `n × n` upper Hessenberg matrix `h`, row-major,
+/// by the Francis double-shift QR algorithm, in the order they deflate.
+fn francis<T: Float>(mut h: Vec<T>, n: usize) -> Result<Vec<Complex<T>>, LinalgError> {
+    let at = |i: usize, j: usize| i * n + j;
+    let scale = (0..n)
+        .flat_map(|i| (i.saturating_sub(1)..n).map(move |j| (i, j)))
+        .map(|(i, j)| h[at(i, j)].abs())
+        .sum::<T>();
+    let half = T::from_f64(0.5);
+    let mut values = Vec::with_capacity(n);
+    // The active block is rows and columns low..=last, and the exceptional
+    // shifts taken so far add up to `shifted`.
+    let mut end = n;
+    let mut shifted = T::ZERO;
+    let mut iterations = 0;
+    while end > 0 {
+        let last = end - 1;
+        let mut low = last;
+        while low > 0 {
+            let mut s = h[at(low - 1, low - 1)].abs() + h[at(low, low)].abs();
+            if s == T::ZERO {
+                s = scale;
+            }
+            if h[at(low, low - 1)].abs() <= T::EPSILON * s {
+                h[at(low, low - 1)] = T::ZERO;
+                break;
+            }
+            low -= 1;
+        }
+        let x = h[at(last, last)];
+        if low == last {
+            values.push(Complex::real(x + shifted));
+            end -= 1;
+            iterations = 0;
+            continue;
+        }
+        let y = h[at(last - 1, last - 1)];
+        let w = h[at(last, last - 1)] * h[at(last - 1, last)];
+        if low == last - 1 {
+            // The eigenvalues of the trailing 2 × 2 block.
+            let p = half * (y - x);
+            let q = p * p + w;
+            let z = q.abs().sqrt();
+            let x = x + shifted;
+            if q >= T::ZERO {
+                let z = p + z.copysign(p);
+                let other = if z == T::ZERO { x + z } else { x - w / z };
+                values.push(Complex::real(x + z));
+                values.push(Complex::real(other));
+            } else {
+                values.push(Complex::new(x + p, z));
+                values.push(Complex::new(x + p, -z));
+            }
+            end -= 2;
+            iterations = 0;
+            continue;
+        }
+        if iterations == MAX_ITERATIONS {
+            return Err(LinalgError::NoConvergence { iterations });
+        }
+        // The shifts are the eigenvalues of the trailing 2 × 2 block, with
+        // sum x + y and product x·y - w, except for ad hoc ones when
+        // convergence is slow.
+        let (mut x, mut y, mut w) = (x, y, w);
+        if iterations == 10 || iterations == 20 {
+            shifted += x;
+            for i in 0..end {
+                h[at(i, i)] -= x;
+            }
+            let s = h[at(last, last - 1)].abs() + h[at(last - 1, last - 2)].abs();
+            x = T::from_f64(0.75) * s;
+            y = x;
+            w = T::from_f64(-0.4375) * s * s;
+        }
+        iterations += 1;
+        // Start the step at the lowest row where the first column of the
+        // double-shift polynomial makes the subdiagonal entry negligible.
+        let mut m = last - 2;
+        let (mut p, mut q, mut r);
+        loop {
+            let z = h[at(m, m)];
+            let (rr, ss) = (x - z, y - z);
+            p = (rr * ss - w) / h[at(m + 1, m)] + h[at(m, m + 1)];
+            q = h[at(m + 1, m + 1)] - z - rr - ss;
+            r = h[at(m + 2, m + 1)];
+            let s = p.abs() + q.abs() + r.abs();
+            (p, q, r) = (p / s, q / s, r / s);
+            if m == low {
+                break;
+            }
+            let u = h[at(m, m - 1)].abs() * (q.abs() + r.abs());
+            let v = p.abs() * (h[at(m - 1, m - 1)].abs() + z.abs() + h[at(m + 1, m + 1)].abs());
+            if u <= T::EPSILON * v {
+                break;
+            }
+            m -= 1;
+        }
+        for i in m + 2..end {
+            h[at(i, i - 2)] = T::ZERO;
+            if i != m + 2 {
+                h[at(i, i - 3)] = T::ZERO;
+            }
+        }
+        // Chase the bulge down with 3 × 3 Householder reflections.
+        for k in m..last {
+            let mut norm_pqr = T::ONE;
+            if k != m {
+                p = h[at(k, k - 1)];
+                q = h[at(k + 1, k - 1)];
+                r = if k != last - 1 {
+                    h[at(k + 2, k - 1)]
+                } else {
+                    T::ZERO
+                };
+                norm_pqr = p.abs() + q.abs() + r.abs();
+                if norm_pqr != T::ZERO {
+                    (p, q, r) = (p / norm_pqr, q / norm_pqr, r / norm_pqr);
+                }
+            }
+            let s = (p * p + q * q + r * r).sqrt().copysign(p);
+            if s == T::ZERO {
+                continue;
+            }
+            if k == m {
+                if low != m {
+                    h[at(k, k - 1)] = -h[at(k, k - 1)];
+                }
+            } else {
+                h[at(k, k - 1)] = -s * norm_pqr;
+            }
+            p += s;
+            let (vx, vy, vz) = (p / s, q / s, r / s);
+            q /= p;
+            r /= p;
+            for j in k..end {
+                let mut t = h[at(k, j)] + q * h[at(k + 1, j)];
+                if k != last - 1 {
+                    t += r * h[at(k + 2, j)];
+                    h[at(k + 2, j)] -= t * vz;
+                }
+                h[at(k + 1, j)] -= t * vy;
+                h[at(k, j)] -= t * vx;
+            }
+            for i in low..=last.min(k + 3) {
+                let mut t = vx * h[at(i, k)] + vy * h[at(i, k + 1)];
+                if k != last - 1 {
+                    t += vz * h[at(i, k + 2)];
+                    h[at(i, k + 2)] -= t * r;
+                }
+                h[at(i, k + 1)] -= t * q;
+                h[at(i, k)] -= t;
+            }
+        }
+    }
+    Ok(values)
+}
+
+/// A unit eigenvector of `a` for its eigenvalue `value`, by inverse
+/// iteration.
+fn eigenvector<T: Float>(a: &Matrix<T>, value: Complex<T>) -> Result<Vec<Complex<T>>, LinalgError> {
+    let n = a.rows();
+    if value.im == T::ZERO {
+        let lu = shifted_lu(a, value.re)?;
+        let mut v = start(n);
+        for _ in 0..INVERSE_STEPS {
+            v = normalized(&lu.solve(&v)?).ok_or(LinalgError::Breakdown)?;
+        }
+        return Ok(oriented(v.into_iter().map(Complex::real).collect()));
+    }
+    // (A - (α + iβ)·I)·(x + iy) = 0 splits into the real system
+    // [A - α·I, β·I; -β·I, A - α·I]·[x; y] = 0 of twice the size.
+    let beta = value.im;
+    let block = Matrix::from_fn(2 * n, 2 * n, |i, j| match (i < n, j < n) {
+        (true, true) => a[(i, j)],
+        (false, false) => a[(i - n, j - n)],
+        (true, false) if i + n == j => beta,
+        (false, true) if i == j + n => -beta,
+        _ => T::ZERO,
+    });
+    let lu = shifted_lu(&block, value.re)?;
+    let mut v = start(2 * n);
+    for _ in 0..INVERSE_STEPS {
+        v = normalized(&lu.solve(&v)?).ok_or(LinalgError::Breakdown)?;
+    }
+    Ok(oriented(
+        (0..n).map(|i| Complex::new(v[i], v[i + n])).collect(),
+    ))
+}
+
+/// A starting vector for inverse iteration, with no pattern that could make
+/// it orthogonal to an eigenvector of a structured matrix.
+fn start<T: Float>(n: usize) -> Vec<T> {
+    const GOLDEN: f64 = 0.618_033_988_749_895;
+    (1..=n)
+        .map(|i| T::from_f64(1.0 + (i as f64 * GOLDEN).fract()))
+        .collect()
+}
+
+/// `v` normalized to unit norm and rotated so that its entry of largest
+/// modulus is real and positive.
+fn oriented<T: Float>(v: Vec<Complex<T>>) -> Vec<Complex<T>> {
+    let length = v.iter().map(|z| z.norm().powi(2)).sum::<T>().sqrt();
+    let largest = v.iter().fold(Complex::default(), |best: Complex<T>, &z| {
+        if z.norm() > best.norm() {
+            z
+        } else {
+            best
+        }
+    });
+    let factor = largest.conj() * (T::ONE / (largest.norm() * length));
+    v.into_iter().map(|z| z * factor).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn residual(a: &Matrix<f64>, pair: &Eigenpair<f64>) -> f64 {
+        let n = a.rows();
+        (0..n)
+            .map(|i| {
+                let av = (0..n).fold(Complex::default(), |sum, j| {
+                    sum + pair.vector[j] * a[(i, j)]
+                });
+                (av - pair.value * pair.vector[i]).norm().powi(2)
+            })
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    #[test]
+    fn hessenberg_form_keeps_the_eigenvalues() {
+        let a = Matrix::from_fn(6, 6, |i, j| ((i * 7 + j * 3) % 11) as f64 - 5.0);
+        let h = Matrix::new(6, 6, hessenberg(&a));
+        for i in 2..6 {
+            for j in 0..i - 1 {
+                assert_eq!(h[(i, j)], 0.0);
+            }
+        }
+        // Similar matrices share trace, Frobenius norm (orthogonal
+        // similarity) and determinant.
+        let trace = |m: &Matrix<f64>| m.diagonal().iter().sum::<f64>();
+        assert!((trace(&h) - trace(&a)).abs() < 1e-12);
+        assert!((h.frobenius_norm() - a.frobenius_norm()).abs() < 1e-12);
+        assert!((h.det() - a.det()).abs() < 1e-9 * a.det().abs());
+    }
+
+    #[test]
+    fn finds_real_and_complex_eigenvalues() {
+        // Block diagonal in a rotated basis: eigenvalues 3, -1, 2 ± 5i, 0.5.
+        let d = Matrix::from_rows(&[
+            vec![3.0, 1.0, 0.0, 0.0, 0.0],
+            vec![0.0, -1.0, 0.0, 0.0, 0.0],
+            vec![0.0, 0.0, 2.0, 5.0, 0.0],
+            vec![0.0, 0.0, -5.0, 2.0, 4.0],
+            vec![0.0, 0.0, 0.0, 0.0, 0.5],
+        ]);
+        let p = Matrix::from_fn(5, 5, |i, j| {
+            if i == j {
+                2.0
+            } else {
+                1.0 / (1 + i + j) as f64
+            }
+        });
+        let a = &(&p * &d) * &p.inverse().unwrap();
+        let values = eigenvalues(&a).unwrap();
+        let expected = [
+            Complex::real(-1.0),
+            Complex::real(0.5),
+            Complex::new(2.0, -5.0),
+            Complex::new(2.0, 5.0),
+            Complex::real(3.0),
+        ];
+        for (value, expected) in values.iter().zip(expected) {
+            assert!((*value - expected).norm() < 1e-12, "{value}");
+        }
+        assert_eq!(values[2].im, -values[3].im);
+        for pair in eigenpairs(&a).unwrap() {
+            assert!(residual(&a, &pair) < 1e-12, "{}", pair.value);
+            let length = pair.vector.iter().map(|z| z.norm().powi(2)).sum::<f64>();
+            assert!((length - 1.0).abs() < 1e-14);
+        }
+        assert!(eigenvalues(&Matrix::<f64>::zeros(0, 0)).unwrap().is_empty());
+        assert_eq!(
+            eigenvalues(&Matrix::from_rows(&[vec![4.0]])).unwrap(),
+            vec![Complex::real(4.0)]
+        );
+    }
+
+    #[test]
+    fn symmetric_eigenvalues_match_the_closed_form() {
+        // The 1-D Laplacian, whose eigenvalues are 2 - 2·cos(kπ/(n + 1)).
+        let n = 30;
+        let a = Matrix::from_fn(n, n, |i, j| match i.abs_diff(j) {
+            0 => 2.0,
+            1 => -1.0,
+            _ => 0.0,
+        });
+        let values = eigenvalues(&a).unwrap();
+        for (k, value) in values.iter().enumerate() {
+            let exact = 2.0 - 2.0 * ((k + 1) as f64 * std::f64::consts::PI / (n + 1) as f64).cos();
+            assert!((value.re - exact).abs() < 1e-13 && value.im == 0.0);
+        }
+        for pair in eigenpairs(&a).unwrap() {
+            assert!(residual(&a, &pair) < 1e-12);
+            assert!(pair.vector.iter().all(|z| z.im == 0.0));
+        }
+    }
+
+    #[test]
+    fn eigenvectors_of_degenerate_and_extreme_matrices() {
+        let cases = [
+            Matrix::from_rows(&[vec![0.0]]),
+            Matrix::zeros(3, 3),
+            Matrix::from_diagonal(&[1e200, 1e200]),
+            Matrix::from_diagonal(&[1e-200, 1e-200]),
+            Matrix::from_diagonal(&[1e-300, 1e-300]),
+            Matrix::from_rows(&[vec![1e300, 1e300], vec![1e-300, 1.0]]),
+        ];
+        for a in &cases {
+            for pair in eigenpairs(a).unwrap() {
+                let length = pair.vector.iter().map(|z| z.norm().powi(2)).sum::<f64>();
+                assert!((length - 1.0).abs() < 1e-14, "{a:?}");
+                let scale = a.norm_1().max(f64::MIN_POSITIVE);
+                assert!(residual(a, &pair) <= 1e-14 * scale, "{a:?}");
+            }
+        }
+    }
+}
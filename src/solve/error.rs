@@ -0,0 +1,48 @@
+use std::error::Error;
+use std::fmt;
+
+/// Errors reported by the solvers in [`crate::solve`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    /// The function has the same sign at both ends of the interval, so the
+    /// interval is not known to hold a root.
+    NotBracketed,
+    /// A derivative used as a divisor evaluated to zero.
+    ZeroDerivative,
+    /// The function returned NaN or an infinite value at `x`.
+    NonFiniteValue { x: f64 },
+    /// The function of a system of equations, or its Jacobian, returned NaN
+    /// or an infinite value at the point `x`.
+    NonFiniteAtPoint { x: Vec<f64> },
+    /// The right-hand side of a differential equation returned NaN or an
+    /// infinite value at time `t` and state `y`.
+    NonFiniteDerivative { t: f64, y: Vec<f64> },
+    /// An adaptive integrator needed a step too small to advance time `t`
+    /// in floating point, as near a singularity of the solution.
+    StepSizeTooSmall { t: f64 },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::NotBracketed => {
+                write!(f, "function has the same sign at both ends of the interval")
+            }
+            SolveError::ZeroDerivative => write!(f, "derivative evaluated to zero"),
+            SolveError::NonFiniteValue { x } => {
+                write!(f, "function value is not finite at x = {x}")
+            }
+            SolveError::NonFiniteAtPoint { x } => {
+                write!(f, "function or Jacobian is not finite at x = {x:?}")
+            }
+            SolveError::NonFiniteDerivative { t, y } => {
+                write!(f, "derivative is not finite at t = {t}, y = {y:?}")
+            }
+            SolveError::StepSizeTooSmall { t } => {
+                write!(f, "step size became too small at t = {t}")
+            }
+        }
+    }
+}
+
+impl Error for SolveError {}
@@ -0,0 +1,260 @@
+use super::{
+    check_dimensions, residual, IterativeOptions, IterativeResult, LinearOperator, Preconditioner,
+};
+use crate::generals::linalg::{dot, norm, LinalgError};
+use crate::generals::Float;
+
+/// Solves `A·x = b` from `x0` by the restarted generalized minimal residual
+/// method, GMRES(`restart`), for any nonsingular `A`.
+///
+/// Each cycle builds an orthonormal basis of the Krylov subspace spanned by
+/// `r, A·r, A²·r, …` for the current residual `r`, by Arnoldi's process with
+/// modified Gram–Schmidt, and moves `x` to minimize `‖b - A·x‖` over it,
+/// through Givens rotations that keep that least-squares problem
+/// triangular as the basis grows. An iteration costs one product with `A`
+/// and `O(k·n)` work for the `k`-th basis vector, and storage grows by one
+/// vector per iteration, so after `restart` iterations the cycle ends and a
+/// new one starts from the improved `x`. Larger `restart` values converge in
+/// fewer iterations, up to full GMRES, which never stagnates, at the price
+/// of memory and orthogonalization; 20 to 50 is common.
+/// [`preconditioned_gmres`] cuts the iterations needed on hard systems.
+///
+/// # Errors
+///
+/// Returns [`LinalgError::Breakdown`] if the least-squares problem of a
+/// cycle becomes singular, which happens when `A` is singular, or a
+/// non-finite value appears.
+///
+/// # Panics
+///
+/// Panics if `A` is not square, `b` or `x0` does not fit it, or `restart`
+/// is zero.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::CsrMatrix;
+/// use mathslib::solve::linear::{gmres, IterativeOptions};
+///
+/// // Convection–diffusion: the upwinded convection term makes it nonsymmetric.
+/// let n = 200;
+/// let mut triplets = Vec::new();
+/// for i in 0..n {
+///     triplets.push((i, i, 3.0));
+///     if i > 0 {
+///         triplets.push((i, i - 1, -2.0));
+///         triplets.push((i - 1, i, -0.5));
+///     }
+/// }
+/// let a: CsrMatrix<f64> = CsrMatrix::from_triplets(n, n, &triplets);
+/// let b = vec![1.0; n];
+/// let result = gmres(&a, &b, &vec![0.0; n], 30, IterativeOptions::new()).unwrap();
+/// assert!(result.converged && result.iterations < n);
+/// let ax = a.mul_vector(&result.x);
+/// assert!(ax.iter().all(|axi| (axi - 1.0).abs() < 1e-9));
+/// ```
+pub fn gmres<T, A>(
+    a: &A,
+    b: &[T],
+    x0: &[T],
+    restart: usize,
+    options: IterativeOptions<T>,
+) -> Result<IterativeResult<T>, LinalgError>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+{
+    preconditioned_gmres(a, b, x0, restart, &|r: &[T]| r.to_vec(), options)
+}
+
+/// Solves `A·x = b` from `x0` by GMRES(`restart`) with a [`Preconditioner`]
+/// applied on the right.
+///
+/// The iteration runs on `A·M⁻¹·u = b` with `x = M⁻¹·u`, so the residual it
+/// minimizes, and checks against the tolerance, is still that of
+/// `A·x = b`, and `M` need not be symmetric: an [`Ilu0`](super::Ilu0)
+/// factorization suits it well. Each iteration costs one application of
+/// `M⁻¹` more than [`gmres`].
+///
+/// # Errors
+///
+/// Returns [`LinalgError::Breakdown`] if the least-squares problem of a
+/// cycle becomes singular, which happens when `A` or `M` is singular, or a
+/// non-finite value appears.
+///
+/// # Panics
+///
+/// Panics if `A` is not square, `b` or `x0` does not fit it, `restart` is
+/// zero, or the preconditioner does not return one value per unknown.
+pub fn preconditioned_gmres<T, A, P>(
+    a: &A,
+    b: &[T],
+    x0: &[T],
+    restart: usize,
+    preconditioner: &P,
+    options: IterativeOptions<T>,
+) -> Result<IterativeResult<T>, LinalgError>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+    P: Preconditioner<T> + ?Sized,
+{
+    check_dimensions(a, b, x0);
+    assert!(restart > 0, "the restart length must be positive");
+    let target = options.tolerance * norm(b);
+    let mut x = x0.to_vec();
+    let mut iterations = 0;
+    loop {
+        let (r, residual_norm) = residual(a, b, &x);
+        if !residual_norm.is_finite() {
+            return Err(LinalgError::Breakdown);
+        }
+        let converged = residual_norm <= target;
+        if converged || iterations >= options.max_iter {
+            return Ok(IterativeResult {
+                x,
+                residual_norm,
+                iterations,
+                converged,
+            });
+        }
+        let steps = restart.min(options.max_iter - iterations);
+        let (step, taken) = cycle(a, preconditioner, r, residual_norm, steps, target)?;
+        iterations += taken;
+        for (xi, si) in x.iter_mut().zip(step) {
+            *xi += si;
+        }
+    }
+}
+
+/// One GMRES cycle of at most `steps` iterations from the residual `r` of
+/// norm `beta`: the correction to add to `x`, and the iterations taken.
+fn cycle<T, A, P>(
+    a: &A,
+    preconditioner: &P,
+    r: Vec<T>,
+    beta: T,
+    steps: usize,
+    target: T,
+) -> Result<(Vec<T>, usize), LinalgError>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+    P: Preconditioner<T> + ?Sized,
+{
+    let mut basis = vec![r.into_iter().map(|ri| ri / beta).collect::<Vec<T>>()];
+    // Columns of the Hessenberg matrix, rotated into upper triangular form,
+    // and the rotated right-hand side β·e₁.
+    let mut columns: Vec<Vec<T>> = Vec::with_capacity(steps);
+    let mut rotations: Vec<(T, T)> = Vec::with_capacity(steps);
+    let mut g = vec![beta];
+    for j in 0..steps {
+        let mut w = a.apply(&preconditioner.apply(&basis[j]));
+        let mut h = Vec::with_capacity(j + 2);
+        for v in &basis {
+            let hij = dot(&w, v);
+            for (wi, &vi) in w.iter_mut().zip(v) {
+                *wi -= hij * vi;
+            }
+            h.push(hij);
+        }
+        let subdiagonal = norm(&w);
+        h.push(subdiagonal);
+        for (i, &(c, s)) in rotations.iter().enumerate() {
+            let (upper, lower) = (h[i], h[i + 1]);
+            h[i] = c * upper + s * lower;
+            h[i + 1] = c * lower - s * upper;
+        }
+        let length = h[j].hypot(h[j + 1]);
+        if length == T::ZERO || !length.is_finite() {
+            return Err(LinalgError::Breakdown);
+        }
+        let (c, s) = (h[j] / length, h[j + 1] / length);
+        h[j] = length;
+        h.pop();
+        rotations.push((c, s));
+        g.push(-s * g[j]);
+        g[j] = c * g[j];
+        columns.push(h);
+        // |g[j + 1]| is the residual norm of the minimizer so far; a zero
+        // subdiagonal means the subspace holds the solution.
+        let done = g[j + 1].abs() <= target || subdiagonal == T::ZERO;
+        if done || j + 1 == steps {
+            break;
+        }
+        basis.push(w.into_iter().map(|wi| wi / subdiagonal).collect());
+    }
+
+    let k = columns.len();
+    let mut y = vec![T::ZERO; k];
+    for i in (0..k).rev() {
+        let sum = (i + 1..k).map(|l| columns[l][i] * y[l]).sum::<T>();
+        y[i] = (g[i] - sum) / columns[i][i];
+    }
+    let mut step = vec![T::ZERO; basis[0].len()];
+    for (v, &yi) in basis.iter().zip(&y) {
+        for (si, &vi) in step.iter_mut().zip(v) {
+            *si += yi * vi;
+        }
+    }
+    Ok((preconditioner.apply(&step), k))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generals::linalg::{CsrMatrix, Matrix};
+
+    fn convection_diffusion(n: usize, wind: f64) -> CsrMatrix<f64> {
+        let mut triplets = Vec::new();
+        for i in 0..n {
+            triplets.push((i, i, 2.0 + wind));
+            if i > 0 {
+                triplets.push((i, i - 1, -1.0 - wind));
+                triplets.push((i - 1, i, -1.0));
+            }
+        }
+        CsrMatrix::from_triplets(n, n, &triplets)
+    }
+
+    #[test]
+    fn solves_nonsymmetric_systems_with_and_without_restarts() {
+        let n = 60;
+        let a = convection_diffusion(n, 4.0);
+        let expected: Vec<f64> = (0..n).map(|i| (i as f64 / 7.0).cos()).collect();
+        let b = a.mul_vector(&expected);
+        let options = IterativeOptions::new().tolerance(1e-12).max_iter(5000);
+        let full = gmres(&a, &b, &vec![0.0; n], n, options).unwrap();
+        let restarted = gmres(&a, &b, &vec![0.0; n], 10, options).unwrap();
+        for result in [&full, &restarted] {
+            assert!(result.converged);
+            for (xi, ei) in result.x.iter().zip(&expected) {
+                assert!((xi - ei).abs() < 1e-9);
+            }
+        }
+        assert!(full.iterations <= n);
+        assert!(restarted.iterations > full.iterations);
+    }
+
+    #[test]
+    fn finds_exact_solutions_in_small_subspaces() {
+        // b is an eigenvector, so the Krylov subspace is one-dimensional.
+        let a = Matrix::from_rows(&[vec![2.0, 1.0], vec![0.0, 3.0]]);
+        let result = gmres(&a, &[1.0, 0.0], &[0.0; 2], 5, IterativeOptions::new()).unwrap();
+        assert!(result.converged);
+        assert_eq!(result.iterations, 1);
+        assert_eq!(result.x, vec![0.5, 0.0]);
+
+        let singular = Matrix::from_rows(&[vec![0.0, 1.0], vec![0.0, 0.0]]);
+        assert_eq!(
+            gmres(
+                &singular,
+                &[1.0, 0.0],
+                &[0.0; 2],
+                5,
+                IterativeOptions::new()
+            ),
+            Err(LinalgError::Breakdown)
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use super::{check_dimensions, residual, IterativeOptions, IterativeResult};
+use crate::generals::linalg::{norm, CsrMatrix, LinalgError};
+use crate::generals::Float;
+
+/// Solves `A·x = b` from `x0` by the Jacobi method.
+///
+/// Every iteration solves each equation for its own unknown, holding the
+/// others at their previous values: `x ← x + D⁻¹·(b - A·x)` with `D` the
+/// diagonal of `A`. An iteration costs one product with `A`, and all
+/// unknowns are updated independently, but convergence is slow, by a
+/// constant factor per iteration that tends to one as the system grows.
+///
+/// Convergence is guaranteed when `A` is diagonally dominant, and only then
+/// is the method tried: every row must have `|aᵢᵢ| ≥ Σⱼ≠ᵢ |aᵢⱼ|`, at least
+/// one strictly. For a weakly dominant `A`, the graph of its nonzeros must
+/// also be connected, which is not checked.
+///
+/// # Errors
+///
+/// Returns [`LinalgError::NotDiagonallyDominant`] if `A` is not diagonally
+/// dominant, or has a zero diagonal entry.
+///
+/// # Panics
+///
+/// Panics if `A` is not square, or `b` or `x0` does not fit it.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::CsrMatrix;
+/// use mathslib::solve::linear::{jacobi, IterativeOptions};
+///
+/// let a: CsrMatrix<f64> = CsrMatrix::from_triplets(
+///     3,
+///     3,
+///     &[(0, 0, 4.0), (0, 1, -1.0), (1, 0, -1.0), (1, 1, 4.0), (1, 2, -1.0), (2, 1, -1.0), (2, 2, 4.0)],
+/// );
+/// let result = jacobi(&a, &[2.0, 4.0, 10.0], &[0.0; 3], IterativeOptions::new()).unwrap();
+/// assert!(result.converged);
+/// for (xi, expected) in result.x.iter().zip([1.0, 2.0, 3.0]) {
+///     assert!((xi - expected).abs() < 1e-9);
+/// }
+/// ```
+pub fn jacobi<T: Float>(
+    a: &CsrMatrix<T>,
+    b: &[T],
+    x0: &[T],
+    options: IterativeOptions<T>,
+) -> Result<IterativeResult<T>, LinalgError> {
+    check_dimensions(a, b, x0);
+    check_diagonal_dominance(a)?;
+    let diagonal = a.diagonal();
+    let target = options.tolerance * norm(b);
+    let mut x = x0.to_vec();
+    let mut iterations = 0;
+    loop {
+        let (r, residual_norm) = residual(a, b, &x);
+        let converged = residual_norm <= target;
+        if converged || iterations >= options.max_iter {
+            return Ok(IterativeResult {
+                x,
+                residual_norm,
+                iterations,
+                converged,
+            });
+        }
+        for ((xi, ri), &d) in x.iter_mut().zip(r).zip(diagonal.iter()) {
+            *xi += ri / d;
+        }
+        iterations += 1;
+    }
+}
+
+/// `Ok` if every row of `a` is weakly diagonally dominant with a nonzero
+/// diagonal, and some row strictly.
+fn check_diagonal_dominance<T: Float>(a: &CsrMatrix<T>) -> Result<(), LinalgError> {
+    let mut strict = a.rows() == 0;
+    for i in 0..a.rows() {
+        let (columns, values) = a.row(i);
+        let (mut diagonal, mut others) = (T::ZERO, T::ZERO);
+        for (&j, &v) in columns.iter().zip(values) {
+            if j == i {
+                diagonal = v.abs();
+            } else {
+                others += v.abs();
+            }
+        }
+        if diagonal == T::ZERO || diagonal < others || diagonal.is_nan() {
+            return Err(LinalgError::NotDiagonallyDominant);
+        }
+        strict |= diagonal > others;
+    }
+    if strict {
+        Ok(())
+    } else {
+        Err(LinalgError::NotDiagonallyDominant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The 1-D Laplacian with Dirichlet boundaries: weakly dominant inside,
+    /// strictly at the ends.
+    fn laplacian(n: usize) -> CsrMatrix<f64> {
+        let mut triplets = Vec::new();
+        for i in 0..n {
+            triplets.push((i, i, 2.0));
+            if i > 0 {
+                triplets.push((i, i - 1, -1.0));
+                triplets.push((i - 1, i, -1.0));
+            }
+        }
+        CsrMatrix::from_triplets(n, n, &triplets)
+    }
+
+    #[test]
+    fn converges_on_weakly_dominant_systems() {
+        let a = laplacian(10);
+        let expected: Vec<f64> = (0..10).map(|i| (i as f64 * 0.3).cos()).collect();
+        let b = a.mul_vector(&expected);
+        let options = IterativeOptions::new().tolerance(1e-12).max_iter(5000);
+        let result = jacobi(&a, &b, &[0.0; 10], options).unwrap();
+        assert!(result.converged);
+        assert!(result.residual_norm <= 1e-12 * norm(&b));
+        for (xi, ei) in result.x.iter().zip(&expected) {
+            assert!((xi - ei).abs() < 1e-9);
+        }
+        // The error shrinks by cos(π/11) per iteration, so it takes hundreds.
+        assert!(result.iterations > 300, "{}", result.iterations);
+
+        let short = jacobi(&a, &b, &[0.0; 10], options.max_iter(10)).unwrap();
+        assert!(!short.converged);
+        assert_eq!(short.iterations, 10);
+        assert!(short.residual_norm > result.residual_norm);
+    }
+
+    #[test]
+    fn systems_without_dominance_are_rejected() {
+        let a = CsrMatrix::from_triplets(2, 2, &[(0, 0, 1.0), (0, 1, 2.0), (1, 1, 3.0)]);
+        assert_eq!(
+            jacobi(&a, &[1.0, 1.0], &[0.0; 2], IterativeOptions::new()),
+            Err(LinalgError::NotDiagonallyDominant)
+        );
+        let rotation =
+            CsrMatrix::from_triplets(2, 2, &[(0, 0, 1.0), (0, 1, -1.0), (1, 0, 1.0), (1, 1, 1.0)]);
+        assert_eq!(
+            jacobi(&rotation, &[1.0, 1.0], &[0.0; 2], IterativeOptions::new()),
+            Err(LinalgError::NotDiagonallyDominant)
+        );
+        let zero_diagonal = CsrMatrix::from_triplets(2, 2, &[(0, 0, 1.0), (1, 0, 0.0)]);
+        assert_eq!(
+            jacobi(
+                &zero_diagonal,
+                &[1.0, 1.0],
+                &[0.0; 2],
+                IterativeOptions::new()
+            ),
+            Err(LinalgError::NotDiagonallyDominant)
+        );
+    }
+}
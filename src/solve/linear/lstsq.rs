@@ -0,0 +1,318 @@
+use crate::generals::linalg::{cholesky, cholesky_solve, norm, LinalgError, Matrix, Qr};
+use crate::generals::Float;
+
+/// How [`lstsq_with_options`] solves a least-squares problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LstsqMethod {
+    /// Householder QR with column pivoting, see [`Qr`]. Stable, and copes
+    /// with rank deficient matrices.
+    #[default]
+    Qr,
+    /// A Cholesky factorization of `AᵀA`. For tall `m × n` matrices it takes
+    /// about half the flops of QR, `m·n²` against `2m·n²`, but it squares the
+    /// condition number of `A`, losing twice as many digits, and fails on rank
+    /// deficient matrices.
+    NormalEquations,
+}
+
+/// Options for [`lstsq_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LstsqOptions {
+    pub method: LstsqMethod,
+    /// Whether to compute [`LstsqResult::covariance`].
+    pub covariance: bool,
+}
+
+impl LstsqOptions {
+    /// QR, without the covariance factor.
+    pub fn new() -> Self {
+        LstsqOptions::default()
+    }
+
+    /// Uses `method` instead of QR.
+    pub fn method(mut self, method: LstsqMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Also computes the covariance factor `(AᵀA)⁻¹`.
+    pub fn covariance(mut self, covariance: bool) -> Self {
+        self.covariance = covariance;
+        self
+    }
+}
+
+/// Outcome of a linear least-squares solve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LstsqResult<T> {
+    /// The coefficients minimizing `‖b - A·x‖`.
+    pub x: Vec<T>,
+    /// Euclidean norm of the residual `b - A·x`.
+    pub residual_norm: T,
+    /// Numerical rank of `A`.
+    pub rank: usize,
+    /// `(AᵀA)⁻¹`, when requested and `A` has full column rank. Scaled by
+    /// the residual variance `σ² = ‖b - A·x‖²/(m - n)`, it is the covariance
+    /// of `x` for independent errors of equal variance in `b`.
+    pub covariance: Option<Matrix<T>>,
+}
+
+/// The `x` minimizing `‖b - A·x‖`, by QR with column pivoting.
+///
+/// See [`lstsq_with_options`] for the normal equations and the covariance
+/// factor.
+///
+/// # Errors
+///
+/// Never, as QR copes with every matrix; the `Result` matches
+/// [`lstsq_with_options`].
+///
+/// # Panics
+///
+/// Panics if `b` does not have one entry per row of `A`.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::Matrix;
+/// use mathslib::solve::linear::lstsq;
+///
+/// // Fit y = c0 + c1·t to noisy points around y = 1 + 2t.
+/// let t = [0.0f64, 1.0, 2.0, 3.0, 4.0];
+/// let y: [f64; 5] = [1.1, 2.9, 5.1, 6.9, 9.0];
+/// let a: Matrix<f64> = Matrix::from_fn(5, 2, |i, j| t[i].powi(j as i32));
+/// let fit = lstsq(&a, &y).unwrap();
+/// assert_eq!(fit.rank, 2);
+/// assert!((fit.x[0] - 1.04).abs() < 1e-12 && (fit.x[1] - 1.98).abs() < 1e-12);
+/// assert!((fit.residual_norm - 0.036f64.sqrt()).abs() < 1e-12);
+/// ```
+pub fn lstsq<T: Float>(a: &Matrix<T>, b: &[T]) -> Result<LstsqResult<T>, LinalgError> {
+    lstsq_with_options(a, b, LstsqOptions::new())
+}
+
+/// The `x` minimizing `‖b - A·x‖` for the `m × n` matrix `A`, by the method
+/// of `options`, with the covariance factor `(AᵀA)⁻¹` if requested.
+///
+/// With QR, a rank deficient `A`, which has many minimizers, gives the
+/// basic one, with the entries of `x` for the `n - rank` columns found to
+/// depend on the others set to zero, and no covariance factor. This
+/// includes every underdetermined system, where `m < n`.
+///
+/// # Errors
+///
+/// With the normal equations, returns [`LinalgError::Singular`] if `AᵀA`
+/// is numerically singular, which happens when the columns of `A` are
+/// nearly dependent.
+///
+/// # Panics
+///
+/// Panics if `b` does not have one entry per row of `A`.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::Matrix;
+/// use mathslib::solve::linear::{lstsq_with_options, LstsqMethod, LstsqOptions};
+///
+/// // The mean of four measurements, and its standard error.
+/// let a: Matrix<f64> = Matrix::from_fn(4, 1, |_, _| 1.0);
+/// let b = [9.8, 10.1, 10.3, 9.8];
+/// let options = LstsqOptions::new().method(LstsqMethod::NormalEquations).covariance(true);
+/// let fit = lstsq_with_options(&a, &b, options).unwrap();
+/// assert!((fit.x[0] - 10.0).abs() < 1e-12);
+/// let variance = fit.residual_norm.powi(2) / 3.0;
+/// let standard_error = (variance * fit.covariance.unwrap()[(0, 0)]).sqrt();
+/// assert!((standard_error - 0.015f64.sqrt()).abs() < 1e-12);
+/// ```
+pub fn lstsq_with_options<T: Float>(
+    a: &Matrix<T>,
+    b: &[T],
+    options: LstsqOptions,
+) -> Result<LstsqResult<T>, LinalgError> {
+    assert_eq!(b.len(), a.rows(), "b must have one entry per row");
+    match options.method {
+        LstsqMethod::Qr => Ok(by_qr(a, b, options.covariance)),
+        LstsqMethod::NormalEquations => by_normal_equations(a, b, options.covariance),
+    }
+}
+
+/// The basic solution and, for full column rank, `(AᵀA)⁻¹` if `covariance`
+/// is set, from `A·P = Q·R`.
+fn by_qr<T: Float>(a: &Matrix<T>, b: &[T], covariance: bool) -> LstsqResult<T> {
+    let qr = Qr::with_pivoting(a);
+    let rank = qr.rank();
+    let n = a.cols();
+    let x = qr.solve(b);
+    if !covariance || rank < n {
+        return result(a, b, x, rank, None);
+    }
+    // AᵀA = P·RᵀR·Pᵀ, so (AᵀA)⁻¹ = P·R⁻¹R⁻ᵀ·Pᵀ, with R⁻¹ upper triangular.
+    let r = |i: usize, j: usize| qr.factors[i * n + j];
+    let mut inverse = Matrix::zeros(n, n);
+    for j in 0..n {
+        inverse[(j, j)] = T::ONE / r(j, j);
+        for i in (0..j).rev() {
+            let sum = (i + 1..=j).map(|k| r(i, k) * inverse[(k, j)]).sum::<T>();
+            inverse[(i, j)] = -sum / r(i, i);
+        }
+    }
+    let product = &inverse * &inverse.transpose();
+    let p = &qr.permutation;
+    let mut factor = Matrix::zeros(n, n);
+    for i in 0..n {
+        for j in 0..n {
+            factor[(p[i], p[j])] = product[(i, j)];
+        }
+    }
+    result(a, b, x, rank, Some(factor))
+}
+
+/// The solution of `AᵀA·x = Aᵀb` by Cholesky, and `(AᵀA)⁻¹` if
+/// `covariance` is set.
+fn by_normal_equations<T: Float>(
+    a: &Matrix<T>,
+    b: &[T],
+    covariance: bool,
+) -> Result<LstsqResult<T>, LinalgError> {
+    let n = a.cols();
+    let gram = &a.transpose() * a;
+    let l = cholesky(gram.as_slice(), n).ok_or(LinalgError::Singular)?;
+    let x = cholesky_solve(&l, n, &a.transpose_mul_vector(b));
+    let factor = covariance.then(|| {
+        let columns: Vec<Vec<T>> = (0..n)
+            .map(|j| {
+                let unit: Vec<T> = (0..n)
+                    .map(|i| if i == j { T::ONE } else { T::ZERO })
+                    .collect();
+                cholesky_solve(&l, n, &unit)
+            })
+            .collect();
+        Matrix::from_fn(n, n, |i, j| columns[j][i])
+    });
+    Ok(result(a, b, x, n, factor))
+}
+
+/// The result for the solution `x`, with its residual norm.
+fn result<T: Float>(
+    a: &Matrix<T>,
+    b: &[T],
+    x: Vec<T>,
+    rank: usize,
+    covariance: Option<Matrix<T>>,
+) -> LstsqResult<T> {
+    let residual: Vec<T> = a
+        .mul_vector(&x)
+        .iter()
+        .zip(b)
+        .map(|(&ax, &bi)| bi - ax)
+        .collect();
+    LstsqResult {
+        x,
+        residual_norm: norm(&residual),
+        rank,
+        covariance,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A cubic fit on 20 points of a smooth function.
+    fn cubic_fit() -> (Matrix<f64>, Vec<f64>) {
+        let t: Vec<f64> = (0..20).map(|i| f64::from(i) / 19.0).collect();
+        let a = Matrix::from_fn(20, 4, |i, j| t[i].powi(j as i32));
+        let b = t.iter().map(|&ti| (2.0 * ti).exp()).collect();
+        (a, b)
+    }
+
+    #[test]
+    fn both_methods_agree_on_full_rank_problems() {
+        let (a, b) = cubic_fit();
+        let options = LstsqOptions::new().covariance(true);
+        let qr = lstsq_with_options(&a, &b, options).unwrap();
+        let normal =
+            lstsq_with_options(&a, &b, options.method(LstsqMethod::NormalEquations)).unwrap();
+        assert_eq!((qr.rank, normal.rank), (4, 4));
+        for (x, y) in qr.x.iter().zip(&normal.x) {
+            assert!((x - y).abs() < 1e-8);
+        }
+        assert!((qr.residual_norm - normal.residual_norm).abs() < 1e-10);
+        // The residual is orthogonal to the columns of A.
+        let residual: Vec<f64> = a
+            .mul_vector(&qr.x)
+            .iter()
+            .zip(&b)
+            .map(|(ax, bi)| bi - ax)
+            .collect();
+        assert!(a
+            .transpose_mul_vector(&residual)
+            .iter()
+            .all(|g| g.abs() < 1e-12));
+        let expected = (&a.transpose() * &a).inverse().unwrap();
+        for factor in [qr.covariance.unwrap(), normal.covariance.unwrap()] {
+            assert!((&factor - &expected).frobenius_norm() < 1e-8 * expected.frobenius_norm());
+        }
+        assert_eq!(lstsq(&a, &b).unwrap().covariance, None);
+    }
+
+    #[test]
+    fn rank_deficient_problems() {
+        // The third column is the sum of the first two.
+        let a = Matrix::from_rows(&[
+            vec![1.0, 0.0, 1.0],
+            vec![0.0, 1.0, 1.0],
+            vec![1.0, 1.0, 2.0],
+            vec![2.0, -1.0, 1.0],
+        ]);
+        let b = [1.0, 2.0, 3.0, 0.5];
+        let result = lstsq_with_options(&a, &b, LstsqOptions::new().covariance(true)).unwrap();
+        assert_eq!(result.rank, 2);
+        assert_eq!(result.covariance, None);
+        assert_eq!(result.x.iter().filter(|&&xi| xi == 0.0).count(), 1);
+        let residual: Vec<f64> = a
+            .mul_vector(&result.x)
+            .iter()
+            .zip(&b)
+            .map(|(ax, bi)| bi - ax)
+            .collect();
+        assert!(a
+            .transpose_mul_vector(&residual)
+            .iter()
+            .all(|g| g.abs() < 1e-12));
+        assert_eq!(
+            lstsq_with_options(
+                &a,
+                &b,
+                LstsqOptions::new().method(LstsqMethod::NormalEquations)
+            ),
+            Err(LinalgError::Singular)
+        );
+    }
+
+    #[test]
+    fn fits_many_more_rows_than_columns() {
+        // A quadratic through 50000 points, with deterministic noise of zero
+        // mean, so the fit recovers the coefficients closely.
+        let m = 50_000;
+        let a = Matrix::from_fn(m, 3, |i, j| (i as f64 / m as f64).powi(j as i32));
+        let b: Vec<f64> = (0..m)
+            .map(|i| {
+                let t = i as f64 / m as f64;
+                1.0 - 2.0 * t + 3.0 * t * t + if i % 2 == 0 { 1e-3 } else { -1e-3 }
+            })
+            .collect();
+        let qr = lstsq(&a, &b).unwrap();
+        let normal = lstsq_with_options(
+            &a,
+            &b,
+            LstsqOptions::new().method(LstsqMethod::NormalEquations),
+        )
+        .unwrap();
+        assert_eq!(qr.rank, 3);
+        for ((x, y), expected) in qr.x.iter().zip(&normal.x).zip([1.0, -2.0, 3.0]) {
+            assert!((x - expected).abs() < 1e-3 && (x - y).abs() < 1e-8);
+        }
+        assert!((qr.residual_norm - 1e-3 * (m as f64).sqrt()).abs() < 1e-4);
+    }
+}
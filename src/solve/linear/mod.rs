@@ -0,0 +1,151 @@
+//! Systems of linear equations `A·x = b` with structure worth exploiting.
+//!
+//! Dense systems are solved through the factorizations of
+//! [`crate::generals::linalg`]; the solvers here take the matrix in a compact
+//! form instead and report a
+//! [`LinalgError`](crate::generals::linalg::LinalgError) when it cannot be
+//! solved. Overdetermined systems are solved in the least-squares sense by
+//! [`lstsq`].
+//!
+//! The iterative solvers refine a starting guess `x0` until the residual
+//! `b - A·x` is small, as an [`IterativeOptions`] sets, and report how far
+//! they got in an [`IterativeResult`]. The Krylov solvers take the matrix as
+//! a [`LinearOperator`], and have variants accepting a [`Preconditioner`]
+//! that makes realistic problems converge in far fewer iterations.
+
+mod bicgstab;
+mod conjugate_gradient;
+mod gauss_seidel;
+mod gmres;
+mod jacobi;
+mod lstsq;
+mod preconditioner;
+mod thomas;
+
+pub use bicgstab::{bicgstab, preconditioned_bicgstab};
+pub use conjugate_gradient::{conjugate_gradient, preconditioned_conjugate_gradient};
+pub use gauss_seidel::{gauss_seidel, sor, Relaxation};
+pub use gmres::{gmres, preconditioned_gmres};
+pub use jacobi::jacobi;
+pub use lstsq::{lstsq, lstsq_with_options, LstsqMethod, LstsqOptions, LstsqResult};
+pub use preconditioner::{Ilu0, JacobiPreconditioner, Preconditioner};
+pub use thomas::thomas;
+
+use crate::generals::linalg::{norm, BandedMatrix, CsrMatrix, Matrix};
+use crate::generals::Float;
+
+/// Outcome of an iterative linear solver.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IterativeResult<T> {
+    /// Best approximation of the solution.
+    pub x: Vec<T>,
+    /// Euclidean norm of the residual `b - A·x`.
+    pub residual_norm: T,
+    /// Number of iterations performed.
+    pub iterations: usize,
+    /// Whether the tolerance was met before `max_iter` iterations.
+    pub converged: bool,
+}
+
+/// Options for the iterative linear solvers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IterativeOptions<T> {
+    /// Stop once `‖b - A·x‖ ≤ tolerance·‖b‖`, in the Euclidean norm.
+    pub tolerance: T,
+    /// Maximum number of iterations.
+    pub max_iter: usize,
+}
+
+impl<T: Float> IterativeOptions<T> {
+    /// A relative residual of `10⁻¹⁰` and at most 1000 iterations.
+    pub fn new() -> Self {
+        IterativeOptions {
+            tolerance: T::from_f64(1e-10),
+            max_iter: 1000,
+        }
+    }
+
+    /// Stops at a relative residual of `tolerance` instead.
+    pub fn tolerance(mut self, tolerance: T) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Allows `max_iter` iterations instead of 1000.
+    pub fn max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+}
+
+impl<T: Float> Default for IterativeOptions<T> {
+    fn default() -> Self {
+        IterativeOptions::new()
+    }
+}
+
+/// A square matrix, or anything that acts like one on vectors.
+///
+/// The Krylov solvers only need products `A·x`, so they accept the dense,
+/// banded and sparse matrices of [`crate::generals::linalg`] alike, and any
+/// other type that can apply a linear map.
+pub trait LinearOperator<T> {
+    /// Number of rows and columns.
+    fn dim(&self) -> usize;
+
+    /// The product `A·x`.
+    fn apply(&self, x: &[T]) -> Vec<T>;
+}
+
+impl<T: Float> LinearOperator<T> for Matrix<T> {
+    fn dim(&self) -> usize {
+        assert!(self.is_square(), "the matrix must be square");
+        self.rows()
+    }
+
+    fn apply(&self, x: &[T]) -> Vec<T> {
+        self.mul_vector(x).into_vec()
+    }
+}
+
+impl<T: Float> LinearOperator<T> for CsrMatrix<T> {
+    fn dim(&self) -> usize {
+        assert_eq!(self.rows(), self.cols(), "the matrix must be square");
+        self.rows()
+    }
+
+    fn apply(&self, x: &[T]) -> Vec<T> {
+        self.mul_vector(x).into_vec()
+    }
+}
+
+impl<T: Float> LinearOperator<T> for BandedMatrix<T> {
+    fn dim(&self) -> usize {
+        self.n()
+    }
+
+    fn apply(&self, x: &[T]) -> Vec<T> {
+        self.mul_vector(x).into_vec()
+    }
+}
+
+/// Checks that `a` is square and `b` and `x0` fit it.
+fn check_dimensions<T, A>(a: &A, b: &[T], x0: &[T])
+where
+    A: LinearOperator<T> + ?Sized,
+{
+    let n = a.dim();
+    assert_eq!(b.len(), n, "b must have one entry per row");
+    assert_eq!(x0.len(), n, "x0 must have one entry per column");
+}
+
+/// `b - A·x` and its Euclidean norm.
+fn residual<T, A>(a: &A, b: &[T], x: &[T]) -> (Vec<T>, T)
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+{
+    let r: Vec<T> = a.apply(x).iter().zip(b).map(|(&ax, &bi)| bi - ax).collect();
+    let size = norm(&r);
+    (r, size)
+}
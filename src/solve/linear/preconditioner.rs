@@ -0,0 +1,284 @@
+use crate::generals::linalg::{CsrMatrix, LinalgError};
+use crate::generals::Float;
+
+/// An approximation `M` of a matrix `A` that is cheap to invert, to speed up
+/// the Krylov solvers of this module.
+///
+/// The solvers work on `M⁻¹·A` or `A·M⁻¹`, whose eigenvalues cluster much
+/// better than those of `A` for a good `M`. Any `Fn(&[T]) -> Vec<T>`
+/// applying `M⁻¹` is a preconditioner, as are [`JacobiPreconditioner`] and
+/// [`Ilu0`].
+pub trait Preconditioner<T> {
+    /// `M⁻¹·r`.
+    fn apply(&self, r: &[T]) -> Vec<T>;
+}
+
+impl<T, F> Preconditioner<T> for F
+where
+    F: Fn(&[T]) -> Vec<T>,
+{
+    fn apply(&self, r: &[T]) -> Vec<T> {
+        self(r)
+    }
+}
+
+/// The diagonal, or Jacobi, preconditioner: `M` is the diagonal of `A`.
+///
+/// It costs next to nothing to build and apply, and is the natural choice
+/// for [`preconditioned_conjugate_gradient`](super::preconditioned_conjugate_gradient)
+/// when the rows of `A` differ widely in scale; it does little for the
+/// Laplacian-like matrices of uniform grids.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JacobiPreconditioner<T> {
+    inverse_diagonal: Vec<T>,
+}
+
+impl<T: Float> JacobiPreconditioner<T> {
+    /// The Jacobi preconditioner of `a`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LinalgError::ZeroDiagonal`] if a diagonal entry of `a` is
+    /// zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` is not square.
+    pub fn new(a: &CsrMatrix<T>) -> Result<Self, LinalgError> {
+        assert_eq!(a.rows(), a.cols(), "the matrix must be square");
+        let diagonal = a.diagonal();
+        match diagonal.iter().position(|&d| d == T::ZERO) {
+            Some(row) => Err(LinalgError::ZeroDiagonal { row }),
+            None => Ok(JacobiPreconditioner {
+                inverse_diagonal: diagonal.iter().map(|&d| T::ONE / d).collect(),
+            }),
+        }
+    }
+}
+
+impl<T: Float> Preconditioner<T> for JacobiPreconditioner<T> {
+    fn apply(&self, r: &[T]) -> Vec<T> {
+        r.iter()
+            .zip(&self.inverse_diagonal)
+            .map(|(&ri, &d)| ri * d)
+            .collect()
+    }
+}
+
+/// The incomplete LU factorization with no fill-in, ILU(0): `M = L·U` with
+/// `L` and `U` restricted to the sparsity pattern of `A`.
+///
+/// Gaussian elimination on `A` discards every update that would create an
+/// entry outside the pattern, so the factors take no more memory than `A`
+/// and applying `M⁻¹` costs about one product with `A`. It typically cuts
+/// Krylov iterations several times over on discretized PDEs, for
+/// [`preconditioned_gmres`](super::preconditioned_gmres) and
+/// [`preconditioned_bicgstab`](super::preconditioned_bicgstab). For a
+/// symmetric `A` it is not symmetric, so it does not suit the conjugate
+/// gradient method.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::linalg::CsrMatrix;
+/// use mathslib::solve::linear::{gmres, preconditioned_gmres, Ilu0, IterativeOptions};
+///
+/// // The 5-point Laplacian on a 20 × 20 grid, with a convection term.
+/// let m = 20;
+/// let mut triplets = Vec::new();
+/// for i in 0..m {
+///     for j in 0..m {
+///         let k = i * m + j;
+///         triplets.push((k, k, 4.5));
+///         if i > 0 { triplets.push((k, k - m, -1.5)); }
+///         if i + 1 < m { triplets.push((k, k + m, -1.0)); }
+///         if j > 0 { triplets.push((k, k - 1, -1.0)); }
+///         if j + 1 < m { triplets.push((k, k + 1, -1.0)); }
+///     }
+/// }
+/// let a: CsrMatrix<f64> = CsrMatrix::from_triplets(m * m, m * m, &triplets);
+/// let b = vec![1.0; m * m];
+/// let x0 = vec![0.0; m * m];
+/// let plain = gmres(&a, &b, &x0, 30, IterativeOptions::new()).unwrap();
+/// let ilu = Ilu0::new(&a).unwrap();
+/// let preconditioned = preconditioned_gmres(&a, &b, &x0, 30, &ilu, IterativeOptions::new()).unwrap();
+/// assert!(plain.converged && preconditioned.converged);
+/// assert!(preconditioned.iterations * 2 < plain.iterations);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ilu0<T> {
+    /// The pattern of `A`, row `i` at `row_starts[i]..row_starts[i + 1]`,
+    /// holding `L` without its unit diagonal left of the diagonal and `U`
+    /// from it on.
+    row_starts: Vec<usize>,
+    columns: Vec<usize>,
+    values: Vec<T>,
+    /// Where the diagonal entry of each row is stored.
+    diagonal: Vec<usize>,
+}
+
+impl<T: Float> Ilu0<T> {
+    /// The ILU(0) factorization of `a`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LinalgError::ZeroDiagonal`] if a pivot is zero, or not
+    /// stored at all.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` is not square.
+    pub fn new(a: &CsrMatrix<T>) -> Result<Self, LinalgError> {
+        assert_eq!(a.rows(), a.cols(), "the matrix must be square");
+        let n = a.rows();
+        let mut row_starts = Vec::with_capacity(n + 1);
+        let mut columns = Vec::with_capacity(a.nnz());
+        let mut values = Vec::with_capacity(a.nnz());
+        let mut diagonal = Vec::with_capacity(n);
+        row_starts.push(0);
+        for i in 0..n {
+            let (row_columns, row_values) = a.row(i);
+            let Ok(position) = row_columns.binary_search(&i) else {
+                return Err(LinalgError::ZeroDiagonal { row: i });
+            };
+            diagonal.push(columns.len() + position);
+            columns.extend_from_slice(row_columns);
+            values.extend_from_slice(row_values);
+            row_starts.push(columns.len());
+        }
+
+        // Where each column of the current row is stored, if it is.
+        let mut stored = vec![None; n];
+        for i in 0..n {
+            let row = row_starts[i]..row_starts[i + 1];
+            for position in row.clone() {
+                stored[columns[position]] = Some(position);
+            }
+            for position in row_starts[i]..diagonal[i] {
+                let k = columns[position];
+                let pivot = values[diagonal[k]];
+                if pivot == T::ZERO {
+                    return Err(LinalgError::ZeroDiagonal { row: k });
+                }
+                let factor = values[position] / pivot;
+                values[position] = factor;
+                for upper in diagonal[k] + 1..row_starts[k + 1] {
+                    if let Some(target) = stored[columns[upper]] {
+                        let u = values[upper];
+                        values[target] -= factor * u;
+                    }
+                }
+            }
+            if values[diagonal[i]] == T::ZERO {
+                return Err(LinalgError::ZeroDiagonal { row: i });
+            }
+            for position in row {
+                stored[columns[position]] = None;
+            }
+        }
+        Ok(Ilu0 {
+            row_starts,
+            columns,
+            values,
+            diagonal,
+        })
+    }
+}
+
+impl<T: Float> Preconditioner<T> for Ilu0<T> {
+    fn apply(&self, r: &[T]) -> Vec<T> {
+        let n = self.diagonal.len();
+        let mut z = r.to_vec();
+        for i in 0..n {
+            let sum = (self.row_starts[i]..self.diagonal[i])
+                .map(|position| self.values[position] * z[self.columns[position]])
+                .sum::<T>();
+            z[i] -= sum;
+        }
+        for i in (0..n).rev() {
+            let sum = (self.diagonal[i] + 1..self.row_starts[i + 1])
+                .map(|position| self.values[position] * z[self.columns[position]])
+                .sum::<T>();
+            z[i] = (z[i] - sum) / self.values[self.diagonal[i]];
+        }
+        z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generals::linalg::{Lu, Matrix};
+
+    #[test]
+    fn ilu0_is_exact_without_fill_in() {
+        // A tridiagonal matrix has no fill-in, so ILU(0) is its LU.
+        let a = CsrMatrix::from_dense(&Matrix::from_rows(&[
+            vec![4.0, -1.0, 0.0, 0.0],
+            vec![-2.0, 5.0, 1.0, 0.0],
+            vec![0.0, 1.0, 3.0, -1.0],
+            vec![0.0, 0.0, -1.0, 2.0],
+        ]));
+        let ilu = Ilu0::new(&a).unwrap();
+        let b = [1.0, -2.0, 0.5, 3.0];
+        let exact = Lu::new(&a.to_dense()).solve(&b).unwrap();
+        for (zi, xi) in ilu.apply(&b).iter().zip(exact.iter()) {
+            assert!((zi - xi).abs() < 1e-14);
+        }
+    }
+
+    #[test]
+    fn ilu0_drops_fill_in_outside_the_pattern() {
+        // Eliminating (1, 0) would fill in (1, 2), which is not stored.
+        let dense = Matrix::from_rows(&[
+            vec![2.0, 0.0, 1.0],
+            vec![1.0, 3.0, 0.0],
+            vec![0.0, 1.0, 4.0],
+        ]);
+        let ilu = Ilu0::new(&CsrMatrix::from_dense(&dense)).unwrap();
+        // L = [1, 0, 0; ½, 1, 0; 0, ⅓, 1] and U = [2, 0, 1; 0, 3, 0; 0, 0, 4].
+        assert_eq!(ilu.values, vec![2.0, 1.0, 0.5, 3.0, 1.0 / 3.0, 4.0]);
+        let r = [1.0, 1.0, 1.0];
+        let z = ilu.apply(&r);
+        let l = Matrix::from_rows(&[
+            vec![1.0, 0.0, 0.0],
+            vec![0.5, 1.0, 0.0],
+            vec![0.0, 1.0 / 3.0, 1.0],
+        ]);
+        let u = Matrix::from_rows(&[
+            vec![2.0, 0.0, 1.0],
+            vec![0.0, 3.0, 0.0],
+            vec![0.0, 0.0, 4.0],
+        ]);
+        let back = (&l * &u).mul_vector(&z);
+        for (bi, ri) in back.iter().zip(r) {
+            assert!((bi - ri).abs() < 1e-14);
+        }
+    }
+
+    #[test]
+    fn zero_pivots_are_reported() {
+        let missing = CsrMatrix::from_triplets(2, 2, &[(0, 0, 1.0), (0, 1, 1.0), (1, 0, 1.0)]);
+        assert_eq!(
+            Ilu0::new(&missing),
+            Err(LinalgError::ZeroDiagonal { row: 1 })
+        );
+        assert_eq!(
+            JacobiPreconditioner::new(&missing),
+            Err(LinalgError::ZeroDiagonal { row: 1 })
+        );
+        let eliminated =
+            CsrMatrix::from_triplets(2, 2, &[(0, 0, 1.0), (0, 1, 1.0), (1, 0, 1.0), (1, 1, 1.0)]);
+        assert_eq!(
+            Ilu0::new(&eliminated),
+            Err(LinalgError::ZeroDiagonal { row: 1 })
+        );
+        let jacobi = JacobiPreconditioner::new(&CsrMatrix::from_triplets(
+            2,
+            2,
+            &[(0, 0, 2.0), (1, 1, -4.0)],
+        ))
+        .unwrap();
+        assert_eq!(jacobi.apply(&[1.0, 1.0]), vec![0.5, -0.25]);
+    }
+}
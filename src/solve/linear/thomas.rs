@@ -0,0 +1,105 @@
+use crate::generals::linalg::LinalgError;
+use crate::generals::Float;
+
+/// Solves the tridiagonal system `A·x = d` by the Thomas algorithm, in
+/// `O(n)` operations.
+///
+/// Row `i` of `A` reads `a[i - 1]·x[i - 1] + b[i]·x[i] + c[i]·x[i + 1]`: `b`
+/// is the diagonal, `a` the subdiagonal and `c` the superdiagonal, each one
+/// entry shorter than `b`. This is Gaussian elimination without pivoting,
+/// which is stable when `A` is diagonally dominant or symmetric positive
+/// definite, as the matrices of cubic splines and of implicit schemes for
+/// diffusion are. For others, a zero pivot may appear although `A` is
+/// nonsingular.
+///
+/// # Errors
+///
+/// Returns [`LinalgError::Singular`] if elimination meets a zero or
+/// non-finite pivot.
+///
+/// # Panics
+///
+/// Panics if `a` or `c` does not have one entry fewer than `b`, or `d` does
+/// not have as many as `b`.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::linear::thomas;
+///
+/// // The second difference matrix with -2 on the diagonal.
+/// let x: Vec<f64> = thomas(&[1.0; 3], &[-2.0; 4], &[1.0; 3], &[-1.0, 0.0, 0.0, -1.0]).unwrap();
+/// assert!(x.iter().all(|xi| (xi - 1.0).abs() < 1e-15));
+/// ```
+pub fn thomas<T: Float>(a: &[T], b: &[T], c: &[T], d: &[T]) -> Result<Vec<T>, LinalgError> {
+    let n = b.len();
+    assert_eq!(d.len(), n, "d must have one entry per row");
+    assert!(
+        a.len() + 1 == n.max(1) && c.len() + 1 == n.max(1),
+        "a and c must have one entry fewer than b"
+    );
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    // Forward sweep: eliminate the subdiagonal, keeping the modified
+    // superdiagonal in `upper` and right-hand side in `x`.
+    let mut upper = vec![T::ZERO; n];
+    let mut x = vec![T::ZERO; n];
+    let mut pivot = b[0];
+    for i in 0..n {
+        if i > 0 {
+            pivot = b[i] - a[i - 1] * upper[i - 1];
+        }
+        if pivot == T::ZERO || !pivot.is_finite() {
+            return Err(LinalgError::Singular);
+        }
+        if i + 1 < n {
+            upper[i] = c[i] / pivot;
+        }
+        let previous = if i > 0 { a[i - 1] * x[i - 1] } else { T::ZERO };
+        x[i] = (d[i] - previous) / pivot;
+    }
+    for i in (0..n - 1).rev() {
+        let next = x[i + 1];
+        x[i] -= upper[i] * next;
+    }
+    Ok(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_dense_solution() {
+        let a = [1.0, -0.5, 2.0, 0.25];
+        let b = [4.0, 3.0, 5.0, 6.0, 2.0];
+        let c = [-1.0, 1.0, 0.5, -0.5];
+        let expected = [1.0, -2.0, 0.5, 3.0, -1.0];
+        let d: Vec<f64> = (0..5)
+            .map(|i| {
+                let left = if i > 0 {
+                    a[i - 1] * expected[i - 1]
+                } else {
+                    0.0
+                };
+                let right = if i < 4 { c[i] * expected[i + 1] } else { 0.0 };
+                left + b[i] * expected[i] + right
+            })
+            .collect();
+        let x = thomas(&a, &b, &c, &d).unwrap();
+        for (xi, ei) in x.iter().zip(expected) {
+            assert!((xi - ei).abs() < 1e-14);
+        }
+        assert_eq!(thomas(&[], &[2.0], &[], &[3.0]).unwrap(), vec![1.5]);
+        assert!(thomas::<f64>(&[], &[], &[], &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn zero_pivots_are_reported() {
+        assert_eq!(
+            thomas(&[1.0], &[1.0, 1.0], &[1.0], &[1.0, 2.0]),
+            Err(LinalgError::Singular)
+        );
+    }
+}
@@ -0,0 +1,21 @@
+//! Solution of equations: roots of functions of a single variable and of
+//! polynomials, structured linear systems, systems of nonlinear equations,
+//! eigenvalue problems, and initial value problems for ordinary differential
+//! equations.
+//!
+//! The solvers take the function as any `Fn`, like the optimizers of
+//! [`crate::optimize`], and share their [`Budget`](crate::optimize::Budget)
+//! for limiting the work done. How close to a root is close enough is a
+//! [`Tolerance`].
+
+pub mod eigen;
+mod error;
+pub mod linear;
+pub mod ode;
+pub mod polynomial;
+pub mod scalar;
+pub mod system;
+mod tolerance;
+
+pub use error::SolveError;
+pub use tolerance::Tolerance;
@@ -0,0 +1,104 @@
+//! Initial value problems for ordinary differential equations
+//! `y' = f(t, y)`, `y(t₀) = y₀`, with a vector state `y`.
+//!
+//! The right-hand side `f` is any `FnMut(T, &[T]) -> Vec<T>`, called with
+//! the time and the state. Higher order equations are rewritten as first
+//! order systems, as in `y'' = -y` becoming `(y, v)' = (v, -y)`. A
+//! [`Record`] chooses whether a solver keeps every step of the trajectory
+//! or only the final state, and the outcome is an [`OdeSolution`].
+//!
+//! [`rk4`] takes steps of a fixed size. [`rk45`] adapts the step size to
+//! meet a tolerance, and is the one to try first.
+
+mod rk4;
+mod rk45;
+
+pub use rk4::rk4;
+pub use rk45::{rk45, Rk45Options};
+
+use crate::generals::Float;
+use crate::solve::SolveError;
+
+/// Which states an integrator keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Record {
+    /// The initial state and the state after every step.
+    #[default]
+    Trajectory,
+    /// Only the state at the end of the interval.
+    FinalState,
+}
+
+/// Outcome of an integrator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OdeSolution<T> {
+    /// Times at which the state was recorded, in order of integration.
+    pub t: Vec<T>,
+    /// The state at each time of `t`.
+    pub y: Vec<Vec<T>>,
+    /// Number of steps taken.
+    pub steps: usize,
+    /// Number of steps attempted and rejected as too inaccurate, by the
+    /// adaptive integrators.
+    pub rejected: usize,
+    /// Number of evaluations of the right-hand side.
+    pub fevals: usize,
+    /// Whether the end of the interval was reached before the budget ran
+    /// out.
+    pub completed: bool,
+}
+
+impl<T: Float> OdeSolution<T> {
+    /// The last time reached and the state there.
+    pub fn final_state(&self) -> (T, &[T]) {
+        let last = self.t.len() - 1;
+        (self.t[last], &self.y[last])
+    }
+}
+
+/// `f` wrapped to count its evaluations and reject non-finite derivatives.
+struct Derivative<F> {
+    f: F,
+    fevals: usize,
+}
+
+impl<F> Derivative<F> {
+    fn new(f: F) -> Self {
+        Derivative { f, fevals: 0 }
+    }
+
+    /// `f(t, y)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` does not return one value per component of `y`.
+    fn at<T>(&mut self, t: T, y: &[T]) -> Result<Vec<T>, SolveError>
+    where
+        T: Float,
+        F: FnMut(T, &[T]) -> Vec<T>,
+    {
+        self.fevals += 1;
+        let dy = (self.f)(t, y);
+        assert_eq!(
+            dy.len(),
+            y.len(),
+            "the derivative must have one entry per component of the state"
+        );
+        if dy.iter().all(|d| d.is_finite()) {
+            Ok(dy)
+        } else {
+            Err(SolveError::NonFiniteDerivative {
+                t: t.to_f64(),
+                y: y.iter().map(|yi| yi.to_f64()).collect(),
+            })
+        }
+    }
+}
+
+/// `y + h·Σ cᵢ·kᵢ` for the pairs `(cᵢ, kᵢ)` of `terms`.
+fn combine<T: Float>(y: &[T], h: T, terms: &[(T, &[T])]) -> Vec<T> {
+    y.iter()
+        .enumerate()
+        .map(|(i, &yi)| yi + h * terms.iter().map(|&(c, k)| c * k[i]).sum::<T>())
+        .collect()
+}
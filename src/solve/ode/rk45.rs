@@ -0,0 +1,523 @@
+use super::{combine, Derivative, OdeSolution, Record};
+use crate::generals::Float;
+use crate::optimize::Budget;
+use crate::solve::SolveError;
+
+/// Nodes `c`, coupling coefficients `a` row by row, and the weights of the
+/// error estimate: the fifth-order solution minus the embedded fourth-order
+/// one, of the Dormand–Prince pair. The last row of `a` holds the
+/// fifth-order weights, so the last stage is the first of the next step.
+const C: [f64; 6] = [1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+const A: [&[f64]; 6] = [
+    &[1.0 / 5.0],
+    &[3.0 / 40.0, 9.0 / 40.0],
+    &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+    &[
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+    ],
+    &[
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+    ],
+    &[
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+const E: [f64; 7] = [
+    71.0 / 57600.0,
+    0.0,
+    -71.0 / 16695.0,
+    71.0 / 1920.0,
+    -17253.0 / 339200.0,
+    22.0 / 525.0,
+    -1.0 / 40.0,
+];
+
+/// Step size changes are scaled by this, so that the next step is likely
+/// to pass, and kept within these bounds.
+const SAFETY: f64 = 0.9;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 10.0;
+
+/// Options for [`rk45`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rk45Options<T> {
+    /// Relative tolerances on the local error, a single one for every
+    /// component or one per component.
+    pub rtol: Vec<T>,
+    /// Absolute tolerances on the local error, a single one for every
+    /// component or one per component.
+    pub atol: Vec<T>,
+    /// Size of the first step; chosen from `f` at the start if `None`.
+    pub first_step: Option<T>,
+    /// Largest step size allowed.
+    pub max_step: T,
+    /// Limits on the number of steps attempted, counting rejected ones, and
+    /// on evaluations of `f`.
+    pub budget: Budget,
+    pub record: Record,
+}
+
+impl<T: Float> Rk45Options<T> {
+    /// A relative tolerance of `10⁻³` and an absolute one of `10⁻⁶`, an
+    /// automatic first step, no limit on the step size, at most 10000
+    /// steps, and the whole trajectory recorded.
+    pub fn new() -> Self {
+        Rk45Options {
+            rtol: vec![T::from_f64(1e-3)],
+            atol: vec![T::from_f64(1e-6)],
+            first_step: None,
+            max_step: T::INFINITY,
+            budget: Budget::new(10_000),
+            record: Record::Trajectory,
+        }
+    }
+
+    /// Uses the relative tolerance `rtol` for every component instead.
+    pub fn rtol(mut self, rtol: T) -> Self {
+        self.rtol = vec![rtol];
+        self
+    }
+
+    /// Uses the absolute tolerance `atol` for every component instead.
+    pub fn atol(mut self, atol: T) -> Self {
+        self.atol = vec![atol];
+        self
+    }
+
+    /// Uses the relative tolerance `rtol[i]` for component `i` instead.
+    pub fn component_rtol(mut self, rtol: Vec<T>) -> Self {
+        self.rtol = rtol;
+        self
+    }
+
+    /// Uses the absolute tolerance `atol[i]` for component `i` instead,
+    /// typically to match components of very different scales.
+    pub fn component_atol(mut self, atol: Vec<T>) -> Self {
+        self.atol = atol;
+        self
+    }
+
+    /// Starts with a step of size `first_step`.
+    pub fn first_step(mut self, first_step: T) -> Self {
+        self.first_step = Some(first_step);
+        self
+    }
+
+    /// Takes no step longer than `max_step`.
+    pub fn max_step(mut self, max_step: T) -> Self {
+        self.max_step = max_step;
+        self
+    }
+
+    /// Uses `budget` instead of 10000 steps.
+    pub fn budget(mut self, budget: impl Into<Budget>) -> Self {
+        self.budget = budget.into();
+        self
+    }
+
+    /// Keeps the states `record` selects instead of the whole trajectory.
+    pub fn record(mut self, record: Record) -> Self {
+        self.record = record;
+        self
+    }
+}
+
+impl<T: Float> Default for Rk45Options<T> {
+    fn default() -> Self {
+        Rk45Options::new()
+    }
+}
+
+/// Integrates `y' = f(t, y)` from `y(t0) = y0` over `t_span = (t0, t1)` by
+/// the adaptive Dormand–Prince Runge–Kutta method, RK45.
+///
+/// Each step computes a fifth-order solution and an embedded fourth-order
+/// one from the same six evaluations of `f`, the last of which also starts
+/// the next step, and takes their difference as an estimate of the local
+/// error. The step is accepted when the root mean square over the
+/// components of that error, each divided by `atol + rtol·|y|`, is at most
+/// one, and the solution advances with the fifth-order values. With `atol`
+/// zero the error is purely relative, and a component that stays exactly
+/// zero over a step does not count. Either way
+/// the next step size follows from the error, with a safety factor, and
+/// changes at most tenfold, so steps stay long where the solution is smooth
+/// and shrink where it varies quickly. Tolerances bound the error of each
+/// step, not the global error, which can accumulate beyond them over long
+/// intervals. `t1` may lie before `t0`, to integrate backward. Stiff
+/// problems make the steps tiny, and call for an implicit method instead.
+///
+/// When the budget runs out first, the solution reached so far is returned
+/// with [`completed`](OdeSolution::completed) unset.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NonFiniteDerivative`] if `f` returns NaN or an
+/// infinite value, and [`SolveError::StepSizeTooSmall`] if the step size
+/// falls to the rounding level of `t`, as when the solution blows up.
+///
+/// # Panics
+///
+/// Panics if a tolerance vector has neither one entry nor one per
+/// component, `max_step` or `first_step` is not positive, or `f` does not
+/// return one value per component of the state.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::ode::{rk45, Record, Rk45Options};
+///
+/// // Kepler orbit of eccentricity 0.5, over one period 2π: the steps must
+/// // shrink near the periapsis, where the body moves fastest.
+/// let e: f64 = 0.5;
+/// let kepler = |_: f64, y: &[f64]| {
+///     let r3 = y[0].hypot(y[1]).powi(3);
+///     vec![y[2], y[3], -y[0] / r3, -y[1] / r3]
+/// };
+/// let y0 = [1.0 - e, 0.0, 0.0, ((1.0 + e) / (1.0 - e)).sqrt()];
+/// let options = Rk45Options::new().rtol(1e-9).atol(1e-12).record(Record::FinalState);
+/// let solution = rk45(kepler, (0.0, 2.0 * std::f64::consts::PI), &y0, options).unwrap();
+/// assert!(solution.completed);
+/// let (_, y) = solution.final_state();
+/// assert!(y.iter().zip(&y0).all(|(a, b)| (a - b).abs() < 1e-6));
+/// assert!(solution.fevals < 6 * (solution.steps + solution.rejected) + 3);
+/// ```
+pub fn rk45<T, F>(
+    f: F,
+    t_span: (T, T),
+    y0: &[T],
+    options: Rk45Options<T>,
+) -> Result<OdeSolution<T>, SolveError>
+where
+    T: Float,
+    F: FnMut(T, &[T]) -> Vec<T>,
+{
+    let n = y0.len();
+    let per_component = |tolerances: &[T], name: &str| -> Vec<T> {
+        assert!(
+            tolerances.len() == 1 || tolerances.len() == n,
+            "{name} must have one entry or one per component"
+        );
+        (0..n)
+            .map(|i| tolerances[i.min(tolerances.len() - 1)])
+            .collect()
+    };
+    let rtol = per_component(&options.rtol, "rtol");
+    let atol = per_component(&options.atol, "atol");
+    assert!(
+        options.max_step > T::ZERO,
+        "the largest step must be positive"
+    );
+    let (t0, t1) = t_span;
+    let direction = if t1 >= t0 { T::ONE } else { -T::ONE };
+    let length = (t1 - t0).abs();
+    let mut f = Derivative::new(f);
+    let mut t = t0;
+    let mut y = y0.to_vec();
+    let mut solution = OdeSolution {
+        t: vec![t0],
+        y: vec![y.clone()],
+        steps: 0,
+        rejected: 0,
+        fevals: 0,
+        completed: length == T::ZERO,
+    };
+    if solution.completed {
+        return Ok(finish(solution, f.fevals, t, y, options.record));
+    }
+    let mut k_first = f.at(t, &y)?;
+    let mut h = match options.first_step {
+        Some(first_step) => {
+            assert!(first_step > T::ZERO, "the first step must be positive");
+            first_step
+        }
+        None => initial_step(&mut f, t, &y, &k_first, direction, &rtol, &atol)?,
+    };
+    let (safety, min_factor, max_factor) = (
+        T::from_f64(SAFETY),
+        T::from_f64(MIN_FACTOR),
+        T::from_f64(MAX_FACTOR),
+    );
+    let exponent = T::from_f64(-0.2);
+    // Whether the step being attempted follows a rejection, in which case it
+    // may not grow.
+    let mut retry = false;
+    while options
+        .budget
+        .allows(solution.steps + solution.rejected, f.fevals)
+    {
+        let remaining = (t1 - t).abs();
+        h = h.min(options.max_step).min(remaining);
+        let last = h == remaining;
+        let minimum = T::from_f64(10.0) * T::EPSILON * t.abs().max(length);
+        if h < minimum {
+            return Err(SolveError::StepSizeTooSmall { t: t.to_f64() });
+        }
+        let step = direction * h;
+        let mut k = vec![k_first.clone()];
+        let mut y_next = y.clone();
+        for (&c, a) in C.iter().zip(A) {
+            let terms: Vec<(T, &[T])> = a
+                .iter()
+                .zip(&k)
+                .map(|(&aij, kj)| (T::from_f64(aij), kj.as_slice()))
+                .collect();
+            y_next = combine(&y, step, &terms);
+            let stage_t = if c == 1.0 && last {
+                t1
+            } else {
+                t + T::from_f64(c) * step
+            };
+            k.push(f.at(stage_t, &y_next)?);
+        }
+        let error = (0..n)
+            .map(|i| {
+                let estimate = step
+                    * E.iter()
+                        .zip(&k)
+                        .map(|(&e, kj)| T::from_f64(e) * kj[i])
+                        .sum::<T>();
+                let scale = atol[i] + rtol[i] * y[i].abs().max(y_next[i].abs());
+                ratio(estimate, scale).powi(2)
+            })
+            .sum::<T>();
+        let error = if n == 0 {
+            T::ZERO
+        } else {
+            (error / T::from_usize(n)).sqrt()
+        };
+        if error > T::ONE || error.is_nan() {
+            solution.rejected += 1;
+            retry = true;
+            let factor = if error.is_nan() {
+                min_factor
+            } else {
+                (safety * error.powf(exponent)).max(min_factor)
+            };
+            h *= factor;
+            continue;
+        }
+        let factor = if error == T::ZERO {
+            max_factor
+        } else {
+            (safety * error.powf(exponent)).min(max_factor)
+        };
+        h = if retry {
+            h * factor.min(T::ONE)
+        } else {
+            h * factor
+        };
+        retry = false;
+        t = if last { t1 } else { t + step };
+        y = y_next;
+        k_first = k.pop().unwrap_or_default();
+        solution.steps += 1;
+        if options.record == Record::Trajectory {
+            solution.t.push(t);
+            solution.y.push(y.clone());
+        }
+        if last {
+            solution.completed = true;
+            break;
+        }
+    }
+    Ok(finish(solution, f.fevals, t, y, options.record))
+}
+
+/// `solution` with its evaluation count, and only the final state `(t, y)`
+/// if that is all `record` keeps.
+fn finish<T: Float>(
+    mut solution: OdeSolution<T>,
+    fevals: usize,
+    t: T,
+    y: Vec<T>,
+    record: Record,
+) -> OdeSolution<T> {
+    solution.fevals = fevals;
+    if record == Record::FinalState {
+        solution.t = vec![t];
+        solution.y = vec![y];
+    }
+    solution
+}
+
+/// `value / scale`, or zero if both are zero, as when a component with no
+/// absolute tolerance stays exactly zero over a step.
+fn ratio<T: Float>(value: T, scale: T) -> T {
+    if value == T::ZERO {
+        T::ZERO
+    } else {
+        value / scale
+    }
+}
+
+/// A first step size for the fifth-order method, from the sizes of `y`,
+/// `f(t, y) = dy` and an estimate of the second derivative, as proposed by
+/// Hairer, Nørsett and Wanner. Costs one evaluation of `f`. Components whose
+/// tolerance scale is zero, with no absolute tolerance and a zero value, are
+/// left out of the estimate.
+fn initial_step<T, F>(
+    f: &mut Derivative<F>,
+    t: T,
+    y: &[T],
+    dy: &[T],
+    direction: T,
+    rtol: &[T],
+    atol: &[T],
+) -> Result<T, SolveError>
+where
+    T: Float,
+    F: FnMut(T, &[T]) -> Vec<T>,
+{
+    let n = y.len();
+    if n == 0 {
+        return Ok(T::INFINITY);
+    }
+    let scale: Vec<T> = (0..n).map(|i| atol[i] + rtol[i] * y[i].abs()).collect();
+    let rms = |v: &[T]| {
+        (v.iter()
+            .zip(&scale)
+            .map(|(&vi, &si)| {
+                if si > T::ZERO {
+                    (vi / si).powi(2)
+                } else {
+                    T::ZERO
+                }
+            })
+            .sum::<T>()
+            / T::from_usize(n))
+        .sqrt()
+    };
+    let (d0, d1) = (rms(y), rms(dy));
+    let small = T::from_f64(1e-5);
+    let h0 = if d0 < small || d1 < small {
+        T::from_f64(1e-6)
+    } else {
+        T::from_f64(0.01) * d0 / d1
+    };
+    let y1 = combine(y, direction * h0, &[(T::ONE, dy)]);
+    let dy1 = f.at(t + direction * h0, &y1)?;
+    let change: Vec<T> = dy1.iter().zip(dy).map(|(&a, &b)| a - b).collect();
+    let d2 = rms(&change) / h0;
+    let tiny = T::from_f64(1e-15);
+    let h1 = if d1 <= tiny && d2 <= tiny {
+        (h0 * T::from_f64(1e-3)).max(T::from_f64(1e-6))
+    } else {
+        (T::from_f64(0.01) / d1.max(d2)).powf(T::from_f64(0.2))
+    };
+    Ok((T::from_f64(100.0) * h0).min(h1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meets_the_tolerance_on_a_smooth_problem() {
+        // y' = y·cos(t), y(0) = 1, with solution exp(sin t).
+        let exact = |t: f64| t.sin().exp();
+        for rtol in [1e-4, 1e-7, 1e-10] {
+            let options = Rk45Options::new().rtol(rtol).atol(rtol * 1e-3);
+            let solution = rk45(|t, y| vec![y[0] * t.cos()], (0.0, 10.0), &[1.0], options).unwrap();
+            assert!(solution.completed);
+            assert_eq!(*solution.t.last().unwrap(), 10.0);
+            assert_eq!(solution.t.len(), solution.steps + 1);
+            for (t, y) in solution.t.iter().zip(&solution.y) {
+                assert!(
+                    (y[0] - exact(*t)).abs() < 50.0 * rtol * exact(*t),
+                    "{rtol} {t}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn adapts_to_fast_transients_and_rejects_steps() {
+        // y' = -50(y - cos t): a fast initial decay onto a slow solution.
+        let options = Rk45Options::new().rtol(1e-6).atol(1e-9);
+        let solution = rk45(
+            |t, y| vec![-50.0 * (y[0] - t.cos())],
+            (0.0, 2.0),
+            &[0.0],
+            options,
+        )
+        .unwrap();
+        assert!(solution.completed);
+        let sizes: Vec<f64> = solution.t.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(sizes[0] < 0.01);
+        assert!(solution.rejected > 0);
+        assert_eq!(
+            solution.fevals,
+            2 + 6 * (solution.steps + solution.rejected)
+        );
+        // Per-component tolerances, backward in time, and a budget.
+        let options = Rk45Options::new()
+            .component_rtol(vec![1e-8, 1e-3])
+            .component_atol(vec![1e-10, 1e-3])
+            .record(Record::FinalState);
+        let backward = rk45(
+            |_, y| vec![y[1], -y[0]],
+            (1.0, -1.0),
+            &[1f64.cos(), -1f64.sin()],
+            options.clone(),
+        )
+        .unwrap();
+        let (t, y) = backward.final_state();
+        assert_eq!(t, -1.0);
+        assert!((y[0] - (-1f64).cos()).abs() < 1e-7);
+        let short = rk45(
+            |_, y| vec![y[1], -y[0]],
+            (0.0, 100.0),
+            &[1.0, 0.0],
+            options.budget(5),
+        )
+        .unwrap();
+        assert!(!short.completed);
+        assert_eq!(short.steps + short.rejected, 5);
+        assert!(short.final_state().0 < 100.0);
+    }
+
+    #[test]
+    fn blow_up_is_reported() {
+        // y' = y², y(0) = 1 blows up at t = 1.
+        let result = rk45(
+            |_, y| vec![y[0] * y[0]],
+            (0.0, 2.0),
+            &[1.0],
+            Rk45Options::new(),
+        );
+        assert!(matches!(
+            result,
+            Err(SolveError::StepSizeTooSmall { .. } | SolveError::NonFiniteDerivative { .. })
+        ));
+    }
+
+    #[test]
+    fn purely_relative_tolerances_with_zero_components() {
+        // sin and cos from sin(0) = 0, and a component that stays zero.
+        let options = Rk45Options::new().rtol(1e-8).atol(0.0);
+        let solution = rk45(
+            |_, y| vec![y[1], -y[0], 0.0],
+            (0.0, 1.0),
+            &[0.0, 1.0, 0.0],
+            options.record(Record::FinalState),
+        )
+        .unwrap();
+        assert!(solution.completed);
+        let (t, y) = solution.final_state();
+        assert_eq!(t, 1.0);
+        assert!((y[0] - 1f64.sin()).abs() < 1e-7 && (y[1] - 1f64.cos()).abs() < 1e-7);
+        assert_eq!(y[2], 0.0);
+    }
+}
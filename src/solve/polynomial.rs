@@ -0,0 +1,664 @@
+//! Roots of polynomials of low degree in closed form.
+//!
+//! [`solve_quadratic`], [`solve_cubic`] and [`solve_quartic`] take the
+//! coefficients from the leading one down, as in `a·x² + b·x + c`, and return
+//! every root once with its multiplicity, real and complex alike. The
+//! formulas are arranged to avoid cancellation, and simple roots are polished
+//! with Newton steps on the polynomial itself, so they come out accurate to
+//! about machine precision relative to their size.
+//!
+//! A double root is only determined to about half the working precision by
+//! the coefficients, and roots of higher multiplicity to less, as rounding
+//! the coefficients splits them that far apart. Roots that the coefficients
+//! cannot tell apart in this way are therefore reported as one repeated
+//! root, while close roots that they do resolve, however small relative to
+//! the others, are kept apart. For polynomials of any degree, see
+//! [`Polynomial::roots`] or [`companion_roots`].
+
+use crate::generals::linalg::{LinalgError, Matrix};
+use crate::generals::{Complex, Float, Polynomial};
+use crate::solve::eigen::eigenvalues;
+
+/// A root of a polynomial and the number of times it is repeated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolynomialRoot<T> {
+    /// The root.
+    pub value: Complex<T>,
+    /// Its multiplicity, at least one.
+    pub multiplicity: usize,
+}
+
+impl<T: Float> PolynomialRoot<T> {
+    fn new(value: Complex<T>, multiplicity: usize) -> Self {
+        PolynomialRoot {
+            value,
+            multiplicity,
+        }
+    }
+
+    fn simple(value: Complex<T>) -> Self {
+        PolynomialRoot::new(value, 1)
+    }
+
+    /// Whether the root is real, that is its imaginary part is exactly zero.
+    /// The solvers return real roots of real polynomials with a zero
+    /// imaginary part.
+    pub fn is_real(&self) -> bool {
+        self.value.im == T::ZERO
+    }
+}
+
+/// The roots of `a·x² + b·x + c`.
+///
+/// Uses the form of the quadratic formula that does not subtract nearly
+/// equal numbers, so both roots are accurate even when one is much smaller
+/// than the other. With `a` zero, the roots of `b·x + c`. The zero polynomial
+/// is given no roots.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::polynomial::solve_quadratic;
+///
+/// // The textbook formula loses every digit of the small root here.
+/// let roots = solve_quadratic(1.0, 1e9, 1.0);
+/// assert_eq!(roots[0].value.re, -1e9);
+/// assert_eq!(roots[1].value.re, -1e-9);
+///
+/// let double = solve_quadratic(1.0, -2.0, 1.0);
+/// assert_eq!(double.len(), 1);
+/// assert_eq!(double[0].multiplicity, 2);
+/// ```
+pub fn solve_quadratic<T: Float>(a: T, b: T, c: T) -> Vec<PolynomialRoot<T>> {
+    if a == T::ZERO {
+        return linear(b, c);
+    }
+    // Normalizing by a power of two keeps b² from overflowing, without
+    // rounding.
+    let largest = a.abs().max(b.abs()).max(c.abs()).to_f64();
+    let scale = T::from_f64(2f64.powi(largest.log2().floor() as i32));
+    sorted(quadratic(a / scale, b / scale, c / scale))
+}
+
+/// The roots of `a·x³ + b·x² + c·x + d`.
+///
+/// Cardano's formula when there is one real root and the trigonometric form
+/// when there are three, after the substitution that removes the quadratic
+/// term. With `a` zero, the roots of the quadratic
+/// ([`solve_quadratic`]).
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::polynomial::solve_cubic;
+///
+/// // (x - 1)²(x + 2)
+/// let roots = solve_cubic(1.0, 0.0, -3.0, 2.0);
+/// assert_eq!(roots.len(), 2);
+/// assert_eq!((roots[0].value.re, roots[0].multiplicity), (-2.0, 1));
+/// assert_eq!((roots[1].value.re, roots[1].multiplicity), (1.0, 2));
+///
+/// // x³ - 1 has 1 and (-1 ± i√3)/2.
+/// let roots = solve_cubic(1.0, 0.0, 0.0, -1.0);
+/// assert!(roots.iter().all(|r| r.multiplicity == 1));
+/// assert!((roots[1].value.im - 3f64.sqrt() / 2.0).abs() < 1e-15);
+/// assert_eq!(roots[2].value.re, 1.0);
+/// ```
+pub fn solve_cubic<T: Float>(a: T, b: T, c: T, d: T) -> Vec<PolynomialRoot<T>> {
+    if a == T::ZERO {
+        return solve_quadratic(b, c, d);
+    }
+    if d == T::ZERO {
+        let mut roots = solve_quadratic(a, b, c);
+        roots.push(PolynomialRoot::simple(Complex::default()));
+        return sorted(merge(&[d, c, b, a], roots));
+    }
+    let monic = [d / a, c / a, b / a, T::ONE];
+    sorted(polished(&monic, cubic(monic[2], monic[1], monic[0])))
+}
+
+/// The roots of `a·x⁴ + b·x³ + c·x² + d·x + e`.
+///
+/// Ferrari's method: after the substitution that removes the cubic term, a
+/// root of the resolvent cubic splits the quartic into two quadratics. With
+/// `a` zero, the roots of the cubic ([`solve_cubic`]).
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::polynomial::solve_quartic;
+///
+/// // (x² + 1)(x - 2)² = x⁴ - 4x³ + 5x² - 4x + 4
+/// let roots = solve_quartic(1.0, -4.0, 5.0, -4.0, 4.0);
+/// assert_eq!(roots.len(), 3);
+/// assert!((roots[0].value.im + 1.0f64).abs() < 1e-15 && roots[0].value.re.abs() < 1e-15);
+/// assert!((roots[1].value.im - 1.0f64).abs() < 1e-15);
+/// assert!((roots[2].value.re - 2.0f64).abs() < 1e-7 && roots[2].multiplicity == 2);
+/// ```
+pub fn solve_quartic<T: Float>(a: T, b: T, c: T, d: T, e: T) -> Vec<PolynomialRoot<T>> {
+    if a == T::ZERO {
+        return solve_cubic(b, c, d, e);
+    }
+    if e == T::ZERO {
+        let mut roots = solve_cubic(a, b, c, d);
+        roots.push(PolynomialRoot::simple(Complex::default()));
+        return sorted(merge(&[e, d, c, b, a], roots));
+    }
+    let monic = [e / a, d / a, c / a, b / a, T::ONE];
+    sorted(polished(
+        &monic,
+        quartic(monic[3], monic[2], monic[1], monic[0]),
+    ))
+}
+
+/// All complex roots of `p`, repeated as often as their multiplicity,
+/// ordered by real part and then imaginary part, as the eigenvalues of its
+/// [`companion_matrix`](Polynomial::companion_matrix).
+///
+/// The QR algorithm of [`eigenvalues`] is backward stable for the companion
+/// matrix, which makes this the standard way to find every root at once,
+/// and unlike an iteration on the polynomial itself it needs no starting
+/// values. The roots are exact for a slightly perturbed companion matrix
+/// rather than for slightly perturbed coefficients, so they lose accuracy
+/// when the coefficients vary widely in size; [`Polynomial::roots`], which
+/// polishes on the polynomial, is then the more accurate. The zero
+/// polynomial and the nonzero constants have no roots.
+///
+/// # Errors
+///
+/// Returns [`LinalgError::NoConvergence`] if the QR algorithm fails to
+/// converge, which only contrived polynomials cause.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::generals::Polynomial;
+/// use mathslib::solve::polynomial::companion_roots;
+///
+/// // x⁵ - 1: the fifth roots of unity.
+/// let p: Polynomial<f64> = Polynomial::new(vec![-1.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+/// let roots = companion_roots(&p).unwrap();
+/// assert_eq!(roots.len(), 5);
+/// assert!(roots.iter().all(|z| (z.norm() - 1.0).abs() < 1e-14));
+/// assert!((roots[4].re - 1.0).abs() < 1e-14 && roots[4].im == 0.0);
+/// ```
+pub fn companion_roots<T: Float>(p: &Polynomial<T>) -> Result<Vec<Complex<T>>, LinalgError> {
+    eigenvalues(&Matrix::from_rows(&p.companion_matrix()))
+}
+
+/// The root of `b·x + c`, if `b` is not zero.
+fn linear<T: Float>(b: T, c: T) -> Vec<PolynomialRoot<T>> {
+    if b == T::ZERO {
+        Vec::new()
+    } else {
+        vec![PolynomialRoot::simple(Complex::real(-c / b))]
+    }
+}
+
+/// The roots of `a·x² + b·x + c` with `a` not zero.
+fn quadratic<T: Float>(a: T, b: T, c: T) -> Vec<PolynomialRoot<T>> {
+    let (two, four) = (T::from_f64(2.0), T::from_f64(4.0));
+    let discriminant = b * b - four * a * c;
+    // Below its rounding error, the discriminant is taken to be zero.
+    if discriminant.abs() <= four * T::EPSILON * (b * b + four * (a * c).abs()) {
+        return vec![PolynomialRoot::new(Complex::real(-b / (two * a)), 2)];
+    }
+    if discriminant > T::ZERO {
+        // a·x² + b·x + c = a·(x - q/a)·(x - c/q), with no cancellation in q.
+        let q = -(b + discriminant.sqrt().copysign(b)) / two;
+        vec![
+            PolynomialRoot::simple(Complex::real(q / a)),
+            PolynomialRoot::simple(Complex::real(c / q)),
+        ]
+    } else {
+        let re = -b / (two * a);
+        let im = (-discriminant).sqrt() / (two * a).abs();
+        vec![
+            PolynomialRoot::simple(Complex::new(re, -im)),
+            PolynomialRoot::simple(Complex::new(re, im)),
+        ]
+    }
+}
+
+/// The roots of the monic `x³ + a·x² + b·x + c`, with `c` not zero.
+fn cubic<T: Float>(a: T, b: T, c: T) -> Vec<PolynomialRoot<T>> {
+    let third = T::ONE / T::from_f64(3.0);
+    let shift = a * third;
+    // x = t - a/3 gives t³ + p·t + q, whose discriminant is -108·Δ.
+    let p = b - a * shift;
+    let q = (T::from_f64(2.0 / 27.0) * a * a - b * third) * a + c;
+    let half_q = q / T::from_f64(2.0);
+    let delta = half_q * half_q + (p * third).powi(3);
+    // Rounding errors of p, q and then Δ.
+    let p_error = T::EPSILON * (b.abs() + (a * shift).abs());
+    let q_error =
+        T::EPSILON * (T::from_f64(2.0 / 27.0) * a.abs().powi(3) + (a * b).abs() * third + c.abs());
+    let delta_error = T::from_f64(8.0) * (half_q.abs() * q_error + p * p * third * third * p_error);
+
+    if delta.abs() <= delta_error {
+        if p.abs() <= T::from_f64(8.0) * p_error {
+            return vec![PolynomialRoot::new(Complex::real(-shift), 3)];
+        }
+        // A simple root t = 3q/p and a double one at -t/2.
+        let simple = T::from_f64(3.0) * q / p - shift;
+        let double = -(a + simple) / T::from_f64(2.0);
+        return vec![
+            PolynomialRoot::simple(Complex::real(simple)),
+            PolynomialRoot::new(Complex::real(double), 2),
+        ];
+    }
+    if delta > T::ZERO {
+        // Cardano, taking the cube root of the larger of -q/2 ± √Δ.
+        let u = (-half_q - delta.sqrt().copysign(half_q)).cbrt();
+        let v = if u == T::ZERO {
+            T::ZERO
+        } else {
+            -p * third / u
+        };
+        let root = u + v - shift;
+        let root = newton(&[c, b, a, T::ONE], Complex::real(root)).re;
+        // Deflating leaves x² + e₁·x + e₀, with e₀ = -c / root from the
+        // product of the roots.
+        let e1 = a + root;
+        let mut roots = quadratic(T::ONE, e1, -c / root);
+        roots.push(PolynomialRoot::simple(Complex::real(root)));
+        return roots;
+    }
+    // Three real roots, 2·√(-p/3)·cos((φ - 2πk)/3) with cos φ = (3q/2p)·√(-3/p).
+    let r = (-p * third).sqrt();
+    let cos = (-half_q / (r * r * r)).max(-T::ONE).min(T::ONE);
+    let phi = (T::ONE - cos * cos).sqrt().atan2(cos);
+    let turn = T::from_f64(std::f64::consts::TAU);
+    (0..3)
+        .map(|k| {
+            let angle = (phi - turn * T::from_usize(k)) * third;
+            PolynomialRoot::simple(Complex::real(T::from_f64(2.0) * r * angle.cos() - shift))
+        })
+        .collect()
+}
+
+/// The roots of the monic `x⁴ + a·x³ + b·x² + c·x + d`, with `d` not zero.
+fn quartic<T: Float>(a: T, b: T, c: T, d: T) -> Vec<PolynomialRoot<T>> {
+    let (two, four) = (T::from_f64(2.0), T::from_f64(4.0));
+    let shift = a / four;
+    // x = y - a/4 gives y⁴ + p·y² + q·y + r.
+    let a2 = a * a;
+    let p = b - T::from_f64(3.0 / 8.0) * a2;
+    let q = c - a * b / two + a2 * a / T::from_f64(8.0);
+    let r = d - a * c / four + a2 * b / T::from_f64(16.0) - T::from_f64(3.0 / 256.0) * a2 * a2;
+    let q_error = T::from_f64(8.0)
+        * T::EPSILON
+        * (c.abs() + (a * b).abs() / two + (a2 * a).abs() / T::from_f64(8.0));
+
+    let roots = if q.abs() <= q_error {
+        // Biquadratic: y² = z for each root z of z² + p·z + r.
+        let mut roots = Vec::with_capacity(4);
+        for z in quadratic(T::ONE, p, r) {
+            if z.value == Complex::default() {
+                roots.push(PolynomialRoot::new(z.value, 2 * z.multiplicity));
+            } else {
+                let y = z.value.sqrt();
+                roots.push(PolynomialRoot::new(y, z.multiplicity));
+                roots.push(PolynomialRoot::new(-y, z.multiplicity));
+            }
+        }
+        roots
+    } else {
+        // For a root m > 0 of the resolvent m³ + p·m² + (p²/4 - r)·m - q²/8,
+        // the quartic is (y² + s·y + p/2 + m - q/2s)(y² - s·y + p/2 + m + q/2s)
+        // with s = √(2m).
+        let resolvent = cubic(p, p * p / four - r, -q * q / T::from_f64(8.0));
+        let m = resolvent
+            .iter()
+            .filter(|root| root.is_real())
+            .map(|root| root.value.re)
+            .fold(T::NEG_INFINITY, T::max);
+        let s = (two * m).sqrt();
+        let (base, tilt) = (p / two + m, q / (two * s));
+        let mut roots = quadratic(T::ONE, s, base - tilt);
+        roots.extend(quadratic(T::ONE, -s, base + tilt));
+        roots
+    };
+    merge(
+        &[d, c, b, a, T::ONE],
+        roots
+            .into_iter()
+            .map(|root| PolynomialRoot::new(root.value + -shift, root.multiplicity))
+            .collect(),
+    )
+}
+
+/// The value and derivative at `z` of the polynomial with `coefficients`,
+/// constant term first.
+fn horner<T: Float>(coefficients: &[T], z: Complex<T>) -> (Complex<T>, Complex<T>) {
+    coefficients
+        .iter()
+        .rev()
+        .fold((Complex::default(), Complex::default()), |(p, dp), &c| {
+            (p * z + c, dp * z + p)
+        })
+}
+
+/// `z` after up to two Newton steps on the polynomial with `coefficients`,
+/// constant term first, each kept only if it lowers the modulus there.
+fn newton<T: Float>(coefficients: &[T], mut z: Complex<T>) -> Complex<T> {
+    for _ in 0..2 {
+        let (p, dp) = horner(coefficients, z);
+        let next = z - p / dp;
+        if !next.is_finite() || horner(coefficients, next).0.norm() >= p.norm() {
+            break;
+        }
+        z = next;
+    }
+    z
+}
+
+/// Most sweeps of [`polished`]. Two close roots that start at roundoff
+/// distance from each other move apart about threefold per sweep.
+const POLISH_SWEEPS: usize = 40;
+
+/// `roots` of the polynomial with `coefficients`, constant term first, with
+/// the simple ones polished by Newton steps corrected for the other roots
+/// (the Aberth–Ehrlich method), until they stop moving.
+///
+/// Unlike plain Newton steps, which stall between two close roots where the
+/// derivative nearly vanishes, the correction pushes them apart onto the
+/// roots that the coefficients resolve. Real roots stay real.
+fn polished<T: Float>(
+    coefficients: &[T],
+    mut roots: Vec<PolynomialRoot<T>>,
+) -> Vec<PolynomialRoot<T>> {
+    for _ in 0..POLISH_SWEEPS {
+        let mut moved = false;
+        for i in 0..roots.len() {
+            if roots[i].multiplicity != 1 {
+                continue;
+            }
+            let z = roots[i].value;
+            let (p, dp) = horner(coefficients, z);
+            let others = roots.iter().enumerate().filter(|&(j, _)| j != i).fold(
+                Complex::default(),
+                |sum, (_, other)| {
+                    sum + Complex::real(T::from_usize(other.multiplicity)) / (z - other.value)
+                },
+            );
+            let ratio = p / dp;
+            let mut next = z - ratio / (Complex::real(T::ONE) - ratio * others);
+            if roots[i].is_real() {
+                next = Complex::real(next.re);
+            }
+            if next.is_finite() && (next - z).norm() > T::EPSILON * z.norm() {
+                roots[i].value = next;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+    roots
+}
+
+/// `roots` of the polynomial with `coefficients`, constant term first, with
+/// the ones it cannot tell apart combined into one repeated root at their
+/// mean.
+///
+/// Each root is combined with as many of its nearest neighbours as form an
+/// `m`-fold root of the coefficients perturbed at roundoff: the polynomial
+/// and its first `m - 1` derivatives vanish at their mean to within the
+/// rounding error of evaluating them there, and the roots lie within the
+/// distance such a perturbation can split the mean by. Close roots that the
+/// coefficients do resolve stay apart.
+fn merge<T: Float>(coefficients: &[T], roots: Vec<PolynomialRoot<T>>) -> Vec<PolynomialRoot<T>> {
+    let magnitudes: Vec<T> = coefficients.iter().map(|c| c.abs()).collect();
+    let tolerance = T::from_f64(16.0) * T::from_usize(coefficients.len()) * T::EPSILON;
+    let combined = |cluster: &[PolynomialRoot<T>]| -> Option<PolynomialRoot<T>> {
+        let multiplicity: usize = cluster.iter().map(|root| root.multiplicity).sum();
+        let weight = |m: usize| T::from_usize(m) / T::from_usize(multiplicity);
+        let value = cluster.iter().fold(Complex::default(), |sum, root| {
+            sum + root.value * weight(root.multiplicity)
+        });
+        let terms = taylor(coefficients, value, multiplicity + 1);
+        let bounds = taylor(&magnitudes, Complex::real(value.norm()), multiplicity + 1);
+        let mut radius = T::ZERO;
+        for k in 0..multiplicity {
+            let error = tolerance * bounds[k].re;
+            if !error.is_finite() || terms[k].norm() > error {
+                return None;
+            }
+            // Perturbing t_k by its error splits an m-fold root of
+            // t_m·h^m + ... + t_k·h^k by up to about this much.
+            let exponent = T::ONE / T::from_usize(multiplicity - k);
+            radius = radius.max((error / terms[multiplicity].norm()).powf(exponent));
+        }
+        let spread = cluster.iter().fold(T::ZERO, |spread, root| {
+            spread.max((root.value - value).norm())
+        });
+        (spread <= T::from_f64(2.0) * radius).then_some(PolynomialRoot::new(value, multiplicity))
+    };
+    let mut pending = roots;
+    let mut merged = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let first = pending.remove(0);
+        let distance = |root: &PolynomialRoot<T>| (root.value - first.value).norm();
+        pending.sort_by(|a, b| {
+            distance(a)
+                .partial_cmp(&distance(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let (size, root) = (1..=pending.len())
+            .rev()
+            .find_map(|size| {
+                let mut cluster = vec![first];
+                cluster.extend_from_slice(&pending[..size]);
+                combined(&cluster).map(|root| (size, root))
+            })
+            .unwrap_or((0, first));
+        pending.drain(..size);
+        merged.push(root);
+    }
+    merged
+}
+
+/// The first `count` Taylor coefficients `p⁽ᵏ⁾(z)/k!` of the polynomial with
+/// `coefficients`, constant term first, by repeated synthetic division.
+fn taylor<T: Float>(coefficients: &[T], z: Complex<T>, count: usize) -> Vec<Complex<T>> {
+    let mut quotient: Vec<Complex<T>> = coefficients.iter().map(|&c| Complex::real(c)).collect();
+    (0..count)
+        .map(|_| {
+            let mut acc = Complex::default();
+            for c in quotient.iter_mut().rev() {
+                acc = acc * z + *c;
+                *c = acc;
+            }
+            quotient.remove(0)
+        })
+        .collect()
+}
+
+/// `roots` in order of real part and then imaginary part.
+fn sorted<T: Float>(mut roots: Vec<PolynomialRoot<T>>) -> Vec<PolynomialRoot<T>> {
+    roots.sort_by(|a, b| {
+        (a.value.re, a.value.im)
+            .partial_cmp(&(b.value.re, b.value.im))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The roots against `expected` values and multiplicities, to within
+    /// `tol` relative to their size.
+    fn assert_roots(roots: &[PolynomialRoot<f64>], expected: &[(f64, f64, usize)], tol: f64) {
+        assert_eq!(roots.len(), expected.len(), "{roots:?}");
+        for (root, &(re, im, multiplicity)) in roots.iter().zip(expected) {
+            let error = (root.value - Complex::new(re, im)).norm();
+            assert!(error <= tol * re.hypot(im).max(1e-300), "{roots:?}");
+            assert_eq!(root.multiplicity, multiplicity, "{roots:?}");
+            assert_eq!(root.is_real(), im == 0.0, "{roots:?}");
+        }
+    }
+
+    #[test]
+    fn quadratics_keep_both_roots_accurate() {
+        assert_roots(
+            &solve_quadratic(2.0, -2.0, -12.0),
+            &[(-2.0, 0.0, 1), (3.0, 0.0, 1)],
+            0.0,
+        );
+        assert_roots(
+            &solve_quadratic(1.0, 2.0, 5.0),
+            &[(-1.0, -2.0, 1), (-1.0, 2.0, 1)],
+            0.0,
+        );
+        assert_roots(
+            &solve_quadratic(1.0, -1e8, 1.0),
+            &[(1e-8, 0.0, 1), (1e8, 0.0, 1)],
+            1e-15,
+        );
+        assert_roots(
+            &solve_quadratic(1e300, 2e300, 1e300),
+            &[(-1.0, 0.0, 2)],
+            0.0,
+        );
+        assert_roots(
+            &solve_quadratic(1.0, 3.0, 0.0),
+            &[(-3.0, 0.0, 1), (0.0, 0.0, 1)],
+            0.0,
+        );
+        assert_roots(&solve_quadratic(0.0, 2.0, 1.0), &[(-0.5, 0.0, 1)], 0.0);
+        assert!(solve_quadratic(0.0, 0.0, 1.0).is_empty());
+    }
+
+    #[test]
+    fn cubics_in_every_case() {
+        let cubic = |roots: &[f64]| {
+            let c = Polynomial::from_roots(roots).coefficients().to_vec();
+            solve_cubic(c[3], c[2], c[1], c[0])
+        };
+        // Three real roots, spread over orders of magnitude.
+        assert_roots(
+            &cubic(&[-3.0, 1e-4, 250.0]),
+            &[(-3.0, 0.0, 1), (1e-4, 0.0, 1), (250.0, 0.0, 1)],
+            1e-14,
+        );
+        // One real root and a complex pair: 2(x - 1)(x² - x + 4).
+        let im = 15f64.sqrt() / 2.0;
+        assert_roots(
+            &solve_cubic(2.0, -4.0, 10.0, -8.0),
+            &[(0.5, -im, 1), (0.5, im, 1), (1.0, 0.0, 1)],
+            1e-15,
+        );
+        assert_roots(
+            &cubic(&[2.0, 2.0, -5.0]),
+            &[(-5.0, 0.0, 1), (2.0, 0.0, 2)],
+            1e-15,
+        );
+        assert_roots(&cubic(&[0.5, 0.5, 0.5]), &[(0.5, 0.0, 3)], 1e-15);
+        assert_roots(
+            &cubic(&[0.0, 0.0, 4.0]),
+            &[(0.0, 0.0, 2), (4.0, 0.0, 1)],
+            0.0,
+        );
+        // Small roots next to a large one are resolved, not merged.
+        assert_roots(
+            &solve_cubic(1.0, -(1e6 + 1e-3), 1e3, 0.0),
+            &[(0.0, 0.0, 1), (1e-3, 0.0, 1), (1e6, 0.0, 1)],
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn quartics_in_every_case() {
+        let quartic = |roots: &[f64]| {
+            let c = Polynomial::from_roots(roots).coefficients().to_vec();
+            solve_quartic(c[4], c[3], c[2], c[1], c[0])
+        };
+        assert_roots(
+            &quartic(&[-7.0, -0.5, 1e-3, 30.0]),
+            &[
+                (-7.0, 0.0, 1),
+                (-0.5, 0.0, 1),
+                (1e-3, 0.0, 1),
+                (30.0, 0.0, 1),
+            ],
+            1e-13,
+        );
+        // (x² + 2x + 5)(x² - 4x + 13), roots -1 ± 2i and 2 ± 3i.
+        assert_roots(
+            &solve_quartic(1.0, -2.0, 10.0, 6.0, 65.0),
+            &[
+                (-1.0, -2.0, 1),
+                (-1.0, 2.0, 1),
+                (2.0, -3.0, 1),
+                (2.0, 3.0, 1),
+            ],
+            1e-14,
+        );
+        // Biquadratic (x² - 1)(x² - 4) and a double pair.
+        assert_roots(
+            &quartic(&[-2.0, -1.0, 1.0, 2.0]),
+            &[(-2.0, 0.0, 1), (-1.0, 0.0, 1), (1.0, 0.0, 1), (2.0, 0.0, 1)],
+            1e-15,
+        );
+        assert_roots(
+            &quartic(&[1.0, 1.0, 3.0, 3.0]),
+            &[(1.0, 0.0, 2), (3.0, 0.0, 2)],
+            1e-7,
+        );
+        assert_roots(
+            &quartic(&[-1.0, 2.0, 2.0, 5.0]),
+            &[(-1.0, 0.0, 1), (2.0, 0.0, 2), (5.0, 0.0, 1)],
+            1e-7,
+        );
+        assert_roots(&quartic(&[1.5; 4]), &[(1.5, 0.0, 4)], 1e-3);
+        assert_roots(
+            &quartic(&[0.0, 3.0, 3.0, 3.0]),
+            &[(0.0, 0.0, 1), (3.0, 0.0, 3)],
+            1e-4,
+        );
+        // Close roots that the coefficients resolve stay apart.
+        assert_roots(
+            &solve_quartic(1.0, -(1e6 + 1e-3), 1e3, 0.0, 0.0),
+            &[(0.0, 0.0, 2), (1e-3, 0.0, 1), (1e6, 0.0, 1)],
+            1e-12,
+        );
+        let c = (&Polynomial::from_roots(&[1.0, 1.00001]) * &Polynomial::new(vec![-1e6, 0.0, 1.0]))
+            .coefficients()
+            .to_vec();
+        assert_roots(
+            &solve_quartic(c[4], c[3], c[2], c[1], c[0]),
+            &[
+                (-1e3, 0.0, 1),
+                (1.0, 0.0, 1),
+                (1.00001, 0.0, 1),
+                (1e3, 0.0, 1),
+            ],
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn companion_roots_agree_with_the_polynomial_roots() {
+        // Roots 1..=8 and ±2i.
+        let roots: Vec<f64> = (1..=8).map(f64::from).collect();
+        let p = &Polynomial::from_roots(&roots) * &Polynomial::new(vec![4.0, 0.0, 1.0]);
+        let found = companion_roots(&p).unwrap();
+        assert_eq!(found.len(), 10);
+        assert!((found[0] - Complex::new(0.0, -2.0)).norm() < 1e-9);
+        assert!((found[1] - Complex::new(0.0, 2.0)).norm() < 1e-9);
+        for (z, k) in found[2..].iter().zip(1..) {
+            assert!((z.re - f64::from(k)).abs() < 1e-8 && z.im == 0.0, "{z}");
+        }
+        let reference = p.roots();
+        for z in &found {
+            assert!(reference.iter().any(|&w| (*z - w).norm() < 1e-8), "{z}");
+        }
+        assert!(companion_roots(&Polynomial::constant(2.0))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+use super::{ordered, start_bracket, Checked, RootResult, Start};
+use crate::generals::Float;
+use crate::optimize::Budget;
+use crate::solve::{SolveError, Tolerance};
+
+/// Finds a root of `func` in `[a, b]` by Brent's method.
+///
+/// `func(a)` and `func(b)` must differ in sign. Each iteration tries inverse
+/// quadratic interpolation through the last three points, or the secant step
+/// when only two are distinct, and falls back to bisection whenever the
+/// interpolated point would leave the bracket or shrink it too slowly. So the
+/// root stays enclosed, convergence is superlinear on smooth functions, and
+/// it is never much slower than bisection on any continuous one. This is the
+/// method to reach for first.
+///
+/// The search stops once the bracket is at most `xtol + rtol·|x|` wide, or
+/// the function is small enough; steps shorter than half that tolerance are
+/// lengthened to it.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotBracketed`] if `func` has the same sign at `a`
+/// and `b`, and [`SolveError::NonFiniteValue`] if it is NaN or infinite at an
+/// evaluated point.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::scalar::brentq;
+///
+/// // The Dottie number, cos(x) = x.
+/// let result = brentq(|x: f64| x.cos() - x, 0.0, 1.0, 1e-14, 100).unwrap();
+/// assert!(result.converged);
+/// assert!((result.x - 0.739_085_133_215_160_6).abs() < 1e-14);
+/// ```
+pub fn brentq<T, F>(
+    func: F,
+    a: T,
+    b: T,
+    tolerance: impl Into<Tolerance<T>>,
+    budget: impl Into<Budget>,
+) -> Result<RootResult<T>, SolveError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let tolerance = tolerance.into();
+    let budget = budget.into();
+    let mut func = Checked::new(func);
+    let (mut fa, mut fb) = match start_bracket(&mut func, a, b)? {
+        Start::Root(result) => return Ok(result),
+        Start::Values(fa, fb) => (fa, fb),
+    };
+    let (two, three, half) = (T::from_f64(2.0), T::from_f64(3.0), T::from_f64(0.5));
+    // `b` is the best point and `c` the other end of the bracket; `a` is the
+    // previous `b`. `d` is the last step and `e` the one before.
+    let (mut a, mut b) = (a, b);
+    let (mut c, mut fc) = (a, fa);
+    let mut d = b - a;
+    let mut e = d;
+    let mut iterations = 0;
+    let converged = loop {
+        if fc.abs() < fb.abs() {
+            (a, b, c) = (b, c, b);
+            (fa, fb, fc) = (fb, fc, fb);
+        }
+        let m = half * (c - b);
+        if tolerance.x_met(b, two * m) || tolerance.f_met(fb) {
+            break true;
+        }
+        if !budget.allows(iterations, func.fevals()) {
+            break false;
+        }
+        let tol = half * (tolerance.xtol + tolerance.rtol * b.abs());
+        if e.abs() < tol || fa.abs() <= fb.abs() {
+            (d, e) = (m, m);
+        } else {
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (two * m * s, T::ONE - s)
+            } else {
+                let (q, r) = (fa / fc, fb / fc);
+                (
+                    s * (two * m * q * (q - r) - (b - a) * (r - T::ONE)),
+                    (q - T::ONE) * (r - T::ONE) * (s - T::ONE),
+                )
+            };
+            if p > T::ZERO {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            // Accept the interpolation only if it lands well inside the
+            // bracket and shrinks faster than the step before last.
+            if two * p < three * m * q - (tol * q).abs() && p < (half * e * q).abs() {
+                e = d;
+                d = p / q;
+            } else {
+                (d, e) = (m, m);
+            }
+        }
+        (a, fa) = (b, fb);
+        b += if d.abs() > tol { d } else { tol.copysign(m) };
+        fb = func.at(b)?;
+        iterations += 1;
+        if (fb > T::ZERO) == (fc > T::ZERO) {
+            (c, fc) = (a, fa);
+            d = b - a;
+            e = d;
+        }
+    };
+
+    Ok(RootResult {
+        x: b,
+        fx: fb,
+        iterations,
+        fevals: func.fevals(),
+        converged,
+        bracket: Some(if fb == T::ZERO { (b, b) } else { ordered(b, c) }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve::scalar::{regula_falsi, Modification};
+
+    #[test]
+    fn converges_superlinearly_on_smooth_functions() {
+        let f = |x: f64| x.exp() - 10.0;
+        let brent = brentq(f, 0.0, 5.0, 1e-13, 100).unwrap();
+        let illinois = regula_falsi(f, 0.0, 5.0, Modification::Illinois, 1e-13, 100).unwrap();
+        assert!(brent.converged);
+        assert!((brent.x - 10f64.ln()).abs() < 1e-13);
+        assert!(brent.fevals < illinois.fevals, "{brent:?}");
+        let (lower, upper) = brent.bracket.unwrap();
+        assert!(lower <= 10f64.ln() && 10f64.ln() <= upper);
+    }
+
+    #[test]
+    fn is_never_much_slower_than_bisection() {
+        // Flat near the root, where interpolation crawls, and a jump with no
+        // root at all, where only the sign change matters.
+        let bisection_steps = (4.0f64 / 1e-10).log2().ceil() as usize;
+        let flat = brentq(|x: f64| (x - 1.0).powi(9), -1.0, 3.0, 1e-10, 200).unwrap();
+        assert!(flat.converged && (flat.x - 1.0).abs() < 1e-3);
+        assert!(flat.iterations <= 3 * bisection_steps, "{flat:?}");
+        let step = |x: f64| if x < 0.3 { -1.0 } else { 1.0 };
+        let jump = brentq(step, -1.0, 3.0, 1e-10, 200).unwrap();
+        assert!(jump.converged && (jump.x - 0.3).abs() <= 1e-10);
+        assert!(jump.iterations <= 3 * bisection_steps, "{jump:?}");
+        let (lower, upper) = jump.bracket.unwrap();
+        assert!(step(lower) < 0.0 && step(upper) > 0.0);
+    }
+
+    #[test]
+    fn reports_a_bad_bracket_and_an_exhausted_budget() {
+        assert_eq!(
+            brentq(|x: f64| x * x + 1.0, -1.0, 1.0, 1e-12, 100),
+            Err(SolveError::NotBracketed)
+        );
+        let result = brentq(|x: f64| x.cos() - x, 0.0, 1.0, 1e-14, 2).unwrap();
+        assert!(!result.converged);
+        assert_eq!(result.iterations, 2);
+        assert_eq!(result.fevals, 4);
+    }
+}
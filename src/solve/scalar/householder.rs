@@ -0,0 +1,253 @@
+use super::RootResult;
+use crate::generals::Float;
+use crate::optimize::Budget;
+use crate::solve::{SolveError, Tolerance};
+
+/// Finds a root of `func` near `x0` by Halley's method, with derivatives from
+/// central differences with step `h`.
+///
+/// Each iteration costs three evaluations of `func`, at `x` and `x ± h`. See
+/// [`halley_with_derivatives`] for the method, stopping rule and errors.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::scalar::halley;
+///
+/// let result = halley(|x: f64| x.exp() - 2.0 * x - 1.0, 2.0, 1e-5, 1e-12, 50).unwrap();
+/// assert!(result.converged);
+/// assert!((result.x - 1.256_431_208_626_17).abs() < 1e-10);
+/// ```
+pub fn halley<T, F>(
+    func: F,
+    x0: T,
+    h: T,
+    tolerance: impl Into<Tolerance<T>>,
+    budget: impl Into<Budget>,
+) -> Result<RootResult<T>, SolveError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let two = T::from_f64(2.0);
+    let derivatives = |x: T| {
+        let (f, forward, backward) = (func(x), func(x + h), func(x - h));
+        (
+            vec![
+                f,
+                (forward - backward) / (two * h),
+                (forward - two * f + backward) / (h * h),
+            ],
+            3,
+        )
+    };
+    iterate(derivatives, x0, 2, tolerance.into(), budget.into())
+}
+
+/// Finds a root of `func` near `x0` by Halley's method, with the analytic
+/// first and second derivatives `d1` and `d2`.
+///
+/// Each iteration steps from `x` to `x - 2·f·f' / (2·f'² - f·f'')`, which
+/// converges cubically to a simple root, against quadratically for Newton's
+/// method; on smooth transcendental equations that often saves an iteration
+/// or two. The iteration is not safeguarded, so it needs a start close enough
+/// to the root. It stops once the last step is within `tolerance`, or the
+/// function is small enough.
+///
+/// # Errors
+///
+/// Returns [`SolveError::ZeroDerivative`] if the denominator of a step
+/// vanishes, and [`SolveError::NonFiniteValue`] if the function or a
+/// derivative is NaN or infinite at an iterate.
+pub fn halley_with_derivatives<T, F, D1, D2>(
+    func: F,
+    d1: D1,
+    d2: D2,
+    x0: T,
+    tolerance: impl Into<Tolerance<T>>,
+    budget: impl Into<Budget>,
+) -> Result<RootResult<T>, SolveError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+    D1: Fn(T) -> T,
+    D2: Fn(T) -> T,
+{
+    let derivatives = |x: T| (vec![func(x), d1(x), d2(x)], 1);
+    iterate(derivatives, x0, 2, tolerance.into(), budget.into())
+}
+
+/// Finds a root of a function near `x0` by Householder's method of order
+/// `order`, given `derivatives` returning the function and its first `order`
+/// derivatives, `[f, f', …, f⁽ᵒʳᵈᵉʳ⁾]`.
+///
+/// Each iteration steps by `order·(1/f)⁽ᵒʳᵈᵉʳ⁻¹⁾ / (1/f)⁽ᵒʳᵈᵉʳ⁾`, which
+/// converges with order `order + 1` to a simple root. Order 1 is Newton's
+/// method and order 2 Halley's method. The iteration is not safeguarded, and
+/// stops as [`halley_with_derivatives`] does. `fevals` counts the calls of
+/// `derivatives`.
+///
+/// # Errors
+///
+/// As for [`halley_with_derivatives`].
+///
+/// # Panics
+///
+/// Panics if `order` is zero or `derivatives` returns fewer than `order + 1`
+/// values.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::scalar::householder;
+///
+/// // The cube root of 5, with x³ - 5 and its derivatives.
+/// let derivatives = |x: f64| vec![x.powi(3) - 5.0, 3.0 * x * x, 6.0 * x, 6.0];
+/// let result = householder(derivatives, 2.0, 3, 1e-14, 20).unwrap();
+/// assert!(result.converged);
+/// assert!((result.x - 5f64.cbrt()).abs() < 1e-14);
+/// ```
+pub fn householder<T, D>(
+    derivatives: D,
+    x0: T,
+    order: usize,
+    tolerance: impl Into<Tolerance<T>>,
+    budget: impl Into<Budget>,
+) -> Result<RootResult<T>, SolveError>
+where
+    T: Float,
+    D: Fn(T) -> Vec<T>,
+{
+    assert!(order > 0, "the order must be at least one");
+    let derivatives = |x: T| {
+        let values = derivatives(x);
+        assert!(
+            values.len() > order,
+            "derivatives must return the function and its first `order` derivatives"
+        );
+        (values, 1)
+    };
+    iterate(derivatives, x0, order, tolerance.into(), budget.into())
+}
+
+/// Runs Householder's iteration of order `order` from `x0`, where
+/// `derivatives(x)` gives the function and its derivatives at `x` along with
+/// the number of function evaluations that took.
+fn iterate<T, D>(
+    derivatives: D,
+    x0: T,
+    order: usize,
+    tolerance: Tolerance<T>,
+    budget: Budget,
+) -> Result<RootResult<T>, SolveError>
+where
+    T: Float,
+    D: Fn(T) -> (Vec<T>, usize),
+{
+    let evaluate = |x: T| {
+        let (values, cost) = derivatives(x);
+        if values[..=order].iter().all(|v| v.is_finite()) {
+            Ok((values, cost))
+        } else {
+            Err(SolveError::NonFiniteValue { x: x.to_f64() })
+        }
+    };
+    let mut x = x0;
+    let (mut values, mut fevals) = evaluate(x)?;
+    let mut iterations = 0;
+    let mut converged = tolerance.f_met(values[0]);
+    while !converged && budget.allows(iterations, fevals) {
+        let step = householder_step(&values, order)?;
+        x += step;
+        let cost;
+        (values, cost) = evaluate(x)?;
+        fevals += cost;
+        iterations += 1;
+        converged = tolerance.x_met(x, step) || tolerance.f_met(values[0]);
+    }
+
+    Ok(RootResult {
+        x,
+        fx: values[0],
+        iterations,
+        fevals,
+        converged,
+        bracket: None,
+    })
+}
+
+/// The step `order·(1/f)⁽ᵒʳᵈᵉʳ⁻¹⁾ / (1/f)⁽ᵒʳᵈᵉʳ⁾` for the function and
+/// derivatives `values`, with `f` nonzero.
+fn householder_step<T: Float>(values: &[T], order: usize) -> Result<T, SolveError> {
+    // u[n] = f·(1/f)⁽ⁿ⁾, from the Leibniz rule for f·(1/f) = 1:
+    // u[n] = -Σₖ C(n, k)·f⁽ᵏ⁾·u[n - k] / f for k = 1..=n.
+    let f = values[0];
+    let mut u = vec![T::ONE];
+    for n in 1..=order {
+        let mut binomial = T::ONE;
+        let mut sum = T::ZERO;
+        for k in 1..=n {
+            binomial = binomial * T::from_usize(n + 1 - k) / T::from_usize(k);
+            sum += binomial * values[k] * u[n - k];
+        }
+        u.push(-sum / f);
+    }
+    if u[order] == T::ZERO {
+        return Err(SolveError::ZeroDerivative);
+    }
+    Ok(T::from_usize(order) * u[order - 1] / u[order])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn higher_orders_take_fewer_iterations() {
+        // cos(x) = x from a start far enough out to tell the orders apart.
+        let derivatives = |x: f64| vec![x.cos() - x, -x.sin() - 1.0, -x.cos(), x.sin(), x.cos()];
+        let dottie = 0.739_085_133_215_160_6;
+        let iterations: Vec<usize> = (1..=4)
+            .map(|order| {
+                let result = householder(derivatives, 3.0, order, 1e-15, 50).unwrap();
+                assert!(result.converged, "order {order}");
+                assert!((result.x - dottie).abs() < 1e-15, "order {order}");
+                result.iterations
+            })
+            .collect();
+        assert!(
+            iterations.windows(2).all(|w| w[1] <= w[0]),
+            "{iterations:?}"
+        );
+        assert!(iterations[0] > iterations[3], "{iterations:?}");
+
+        let halley = halley_with_derivatives(
+            |x: f64| x.cos() - x,
+            |x: f64| -x.sin() - 1.0,
+            |x: f64| -x.cos(),
+            3.0,
+            1e-15,
+            50,
+        )
+        .unwrap();
+        assert_eq!(halley.iterations, iterations[1]);
+        assert_eq!(halley.fevals, halley.iterations + 1);
+    }
+
+    #[test]
+    fn reports_a_flat_spot_and_an_exact_root() {
+        // f' = f'' = 0 at the start leaves no step to take.
+        let result = halley_with_derivatives(
+            |x: f64| x.powi(3) - 1.0,
+            |x: f64| 3.0 * x * x,
+            |x: f64| 6.0 * x,
+            0.0,
+            1e-12,
+            50,
+        );
+        assert_eq!(result, Err(SolveError::ZeroDerivative));
+        let result = halley(|x: f64| x * x - 4.0, 2.0, 1e-5, 1e-12, 50).unwrap();
+        assert_eq!((result.x, result.iterations, result.fevals), (2.0, 0, 3));
+        assert!(result.converged);
+    }
+}
@@ -0,0 +1,123 @@
+//! Roots of functions of a single variable.
+//!
+//! The bracketing methods start from an interval `[a, b]` over which the
+//! function changes sign, and keep a root enclosed as they shrink it, so they
+//! cannot fail on a continuous function. Of these, [`brentq`] is the one to
+//! try first; [`root`] selects any of them by a [`Method`], and
+//! [`find_brackets`] locates brackets by scanning an interval, which
+//! [`all_roots`] then refines.
+//!
+//! Starting from a single point close to a root instead, [`halley`] and
+//! [`householder`] converge with higher order using second and higher
+//! derivatives, but without a safeguard.
+
+mod brent;
+mod householder;
+mod regula_falsi;
+mod ridder;
+mod root;
+mod scan;
+
+pub use brent::brentq;
+pub use householder::{halley, halley_with_derivatives, householder};
+pub use regula_falsi::{regula_falsi, Modification};
+pub use ridder::ridder;
+pub use root::{root, root_with_options, Method, RootOptions};
+pub use scan::{all_roots, find_brackets};
+
+use crate::generals::Float;
+use crate::solve::SolveError;
+
+/// Outcome of a scalar root finder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RootResult<T> {
+    /// Best approximation of the root.
+    pub x: T,
+    /// Function value at `x`.
+    pub fx: T,
+    /// Number of iterations performed.
+    pub iterations: usize,
+    /// Number of function evaluations performed.
+    pub fevals: usize,
+    /// Whether the tolerance was met before the budget ran out.
+    pub converged: bool,
+    /// Final interval known to hold the root, for the bracketing methods.
+    pub bracket: Option<(T, T)>,
+}
+
+/// `func` wrapped to count its evaluations and reject non-finite values.
+pub(crate) struct Checked<F> {
+    func: F,
+    fevals: usize,
+}
+
+impl<F> Checked<F> {
+    pub(crate) fn new(func: F) -> Self {
+        Checked { func, fevals: 0 }
+    }
+
+    /// Number of evaluations so far.
+    pub(crate) fn fevals(&self) -> usize {
+        self.fevals
+    }
+
+    /// The value at `x`.
+    pub(crate) fn at<T>(&mut self, x: T) -> Result<T, SolveError>
+    where
+        T: Float,
+        F: FnMut(T) -> T,
+    {
+        self.fevals += 1;
+        let fx = (self.func)(x);
+        if fx.is_finite() {
+            Ok(fx)
+        } else {
+            Err(SolveError::NonFiniteValue { x: x.to_f64() })
+        }
+    }
+}
+
+/// How a bracket `[a, b]` starts: with a root at one of its ends, or with the
+/// function values `(f(a), f(b))` of opposite signs.
+pub(crate) enum Start<T> {
+    Root(RootResult<T>),
+    Values(T, T),
+}
+
+/// Evaluates `func` at both ends of `[a, b]`.
+pub(crate) fn start_bracket<T, F>(func: &mut Checked<F>, a: T, b: T) -> Result<Start<T>, SolveError>
+where
+    T: Float,
+    F: FnMut(T) -> T,
+{
+    let fa = func.at(a)?;
+    let fb = func.at(b)?;
+    let root = |x, fx| {
+        Start::Root(RootResult {
+            x,
+            fx,
+            iterations: 0,
+            fevals: 2,
+            converged: true,
+            bracket: Some((x, x)),
+        })
+    };
+    if fa == T::ZERO {
+        Ok(root(a, fa))
+    } else if fb == T::ZERO {
+        Ok(root(b, fb))
+    } else if (fa > T::ZERO) == (fb > T::ZERO) {
+        Err(SolveError::NotBracketed)
+    } else {
+        Ok(Start::Values(fa, fb))
+    }
+}
+
+/// The interval between `a` and `b`, in order.
+pub(crate) fn ordered<T: Float>(a: T, b: T) -> (T, T) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
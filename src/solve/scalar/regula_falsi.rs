@@ -0,0 +1,154 @@
+use super::{ordered, start_bracket, Checked, RootResult, Start};
+use crate::generals::Float;
+use crate::optimize::Budget;
+use crate::solve::{SolveError, Tolerance};
+
+/// How [`regula_falsi`] keeps an end of the bracket from getting stuck.
+///
+/// Plain false position on a convex or concave function moves only one end of
+/// the bracket, which then shrinks no faster than linearly. Both
+/// modifications scale down the function value at an end that was kept twice
+/// in a row, which pulls the next secant point towards it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Modification {
+    /// Halves the kept value, for convergence of order about 1.44.
+    #[default]
+    Illinois,
+    /// Scales the kept value by `1 - f(c)/f(b)`, the ratio of the last two
+    /// values at the moving end, falling back to a half when that is not
+    /// positive. Usually faster than [`Modification::Illinois`], at order
+    /// about 1.7.
+    AndersonBjorck,
+}
+
+/// Finds a root of `func` in `[a, b]` by false position, modified as
+/// `modification` says.
+///
+/// `func(a)` and `func(b)` must differ in sign. Each iteration evaluates
+/// `func` at the point where the secant through the ends of the bracket
+/// crosses zero, and replaces the end of the same sign with it, so a root
+/// stays enclosed. The search stops once the bracket is narrower than
+/// `tolerance` allows, or the function is small enough there. The result is
+/// the newest point, usually the best.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotBracketed`] if `func` has the same sign at `a`
+/// and `b`, and [`SolveError::NonFiniteValue`] if it is NaN or infinite at an
+/// evaluated point.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::scalar::{regula_falsi, Modification};
+///
+/// let result = regula_falsi(|x: f64| x.powi(3) - 2.0, 0.0, 2.0, Modification::Illinois, 1e-12, 100)
+///     .unwrap();
+/// assert!(result.converged);
+/// assert!((result.x - 2f64.cbrt()).abs() < 1e-12);
+/// ```
+pub fn regula_falsi<T, F>(
+    func: F,
+    a: T,
+    b: T,
+    modification: Modification,
+    tolerance: impl Into<Tolerance<T>>,
+    budget: impl Into<Budget>,
+) -> Result<RootResult<T>, SolveError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let tolerance = tolerance.into();
+    let budget = budget.into();
+    let mut func = Checked::new(func);
+    let (mut fa, mut fb) = match start_bracket(&mut func, a, b)? {
+        Start::Root(result) => return Ok(result),
+        Start::Values(fa, fb) => (fa, fb),
+    };
+    // `b` is the newest point and `a` the other end; `weight` scales the
+    // value at `a` for the secant.
+    let (mut a, mut b) = (a, b);
+    let mut weight = T::ONE;
+    let mut iterations = 0;
+    let mut converged = tolerance.x_met(b, b - a) || tolerance.f_met(fb);
+    while !converged && budget.allows(iterations, func.fevals()) {
+        let weighted = weight * fa;
+        let c = b - fb * (b - a) / (fb - weighted);
+        let fc = func.at(c)?;
+        if (fc > T::ZERO) != (fb > T::ZERO) {
+            (a, fa, weight) = (b, fb, T::ONE);
+        } else {
+            weight *= match modification {
+                Modification::Illinois => T::from_f64(0.5),
+                Modification::AndersonBjorck => {
+                    let m = T::ONE - fc / fb;
+                    if m > T::ZERO {
+                        m
+                    } else {
+                        T::from_f64(0.5)
+                    }
+                }
+            };
+        }
+        (b, fb) = (c, fc);
+        iterations += 1;
+        converged = tolerance.x_met(b, b - a) || tolerance.f_met(fb);
+    }
+
+    Ok(RootResult {
+        x: b,
+        fx: fb,
+        iterations,
+        fevals: func.fevals(),
+        converged,
+        bracket: Some(if fb == T::ZERO { (b, b) } else { ordered(a, b) }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifications_beat_stagnation() {
+        // Convex on the bracket, where plain false position keeps the right
+        // end forever.
+        let f = |x: f64| x.exp() - 10.0;
+        for modification in [Modification::Illinois, Modification::AndersonBjorck] {
+            let result = regula_falsi(f, 0.0, 5.0, modification, 1e-12, 100).unwrap();
+            assert!(result.converged, "{modification:?}");
+            assert!((result.x - 10f64.ln()).abs() < 1e-12);
+            let (lower, upper) = result.bracket.unwrap();
+            assert!(lower <= 10f64.ln() && 10f64.ln() <= upper && upper - lower <= 1e-12);
+            assert!(result.iterations < 20, "{result:?}");
+        }
+    }
+
+    #[test]
+    fn checks_the_bracket() {
+        let f = |x: f64| x * x - 1.0;
+        assert_eq!(
+            regula_falsi(f, -2.0, 2.0, Modification::Illinois, 1e-12, 100),
+            Err(SolveError::NotBracketed)
+        );
+        let at_end = regula_falsi(f, 1.0, 3.0, Modification::Illinois, 1e-12, 100).unwrap();
+        assert_eq!((at_end.x, at_end.fevals, at_end.iterations), (1.0, 2, 0));
+        assert_eq!(
+            regula_falsi(
+                |x: f64| x.ln(),
+                -1.0,
+                2.0,
+                Modification::Illinois,
+                1e-12,
+                100
+            ),
+            Err(SolveError::NonFiniteValue { x: -1.0 })
+        );
+        // Stops early on a small enough value, with the bracket reversed.
+        let loose = Tolerance::new(1e-12).with_ftol(1e-3);
+        let result = regula_falsi(f, 3.0, 0.0, Modification::AndersonBjorck, loose, 100).unwrap();
+        assert!(result.converged && result.fx.abs() <= 1e-3);
+        assert!(result.iterations < 10);
+    }
+}
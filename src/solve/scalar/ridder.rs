@@ -0,0 +1,117 @@
+use super::{ordered, start_bracket, Checked, RootResult, Start};
+use crate::generals::Float;
+use crate::optimize::Budget;
+use crate::solve::{SolveError, Tolerance};
+
+/// Finds a root of `func` in `[a, b]` by Ridder's method.
+///
+/// `func(a)` and `func(b)` must differ in sign. Each iteration evaluates
+/// `func` at the midpoint `m` of the bracket and then at
+/// `m + (m - a)·sign(f(a) - f(b))·f(m) / √(f(m)² - f(a)·f(b))`, the root of
+/// the exponential-weighted secant, which always lies in the bracket. The
+/// bracket is cut down to the closest pair of points of opposite signs. That
+/// takes two evaluations per iteration, for quadratic convergence per
+/// iteration, and the state is just the bracket. The search stops once the
+/// bracket is narrower than `tolerance` allows, or the function is small
+/// enough.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotBracketed`] if `func` has the same sign at `a`
+/// and `b`, and [`SolveError::NonFiniteValue`] if it is NaN or infinite at an
+/// evaluated point.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::scalar::ridder;
+///
+/// let result = ridder(|x: f64| x * x.exp() - 1.0, 0.0, 1.0, 1e-13, 50).unwrap();
+/// assert!(result.converged);
+/// assert!((result.x - 0.567_143_290_409_783_8).abs() < 1e-13);
+/// ```
+pub fn ridder<T, F>(
+    func: F,
+    a: T,
+    b: T,
+    tolerance: impl Into<Tolerance<T>>,
+    budget: impl Into<Budget>,
+) -> Result<RootResult<T>, SolveError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let tolerance = tolerance.into();
+    let budget = budget.into();
+    let mut func = Checked::new(func);
+    let (mut fa, mut fb) = match start_bracket(&mut func, a, b)? {
+        Start::Root(result) => return Ok(result),
+        Start::Values(fa, fb) => (fa, fb),
+    };
+    let (mut a, mut b) = (a, b);
+    let (mut x, mut fx) = if fa.abs() < fb.abs() {
+        (a, fa)
+    } else {
+        (b, fb)
+    };
+    let mut iterations = 0;
+    let mut converged = tolerance.x_met(x, b - a) || tolerance.f_met(fx);
+    while !converged && budget.allows(iterations, func.fevals()) {
+        let m = T::from_f64(0.5) * (a + b);
+        let fm = func.at(m)?;
+        let s = (fm * fm - fa * fb).sqrt();
+        (x, fx) = if fm == T::ZERO {
+            (m, fm)
+        } else {
+            let next = m + (m - a) * (fa - fb).signum() * fm / s;
+            (next, func.at(next)?)
+        };
+        // Keep the closest pair of points of opposite signs.
+        if (fm > T::ZERO) != (fx > T::ZERO) {
+            (a, fa, b, fb) = (m, fm, x, fx);
+        } else if (fa > T::ZERO) != (fx > T::ZERO) {
+            (b, fb) = (x, fx);
+        } else {
+            (a, fa) = (x, fx);
+        }
+        iterations += 1;
+        converged = tolerance.x_met(x, b - a) || tolerance.f_met(fx);
+    }
+
+    Ok(RootResult {
+        x,
+        fx,
+        iterations,
+        fevals: func.fevals(),
+        converged,
+        bracket: Some(if fx == T::ZERO { (x, x) } else { ordered(a, b) }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shrinks_the_bracket_quadratically() {
+        let f = |x: f64| x.exp() - 10.0;
+        let result = ridder(f, 0.0, 5.0, 1e-13, 100).unwrap();
+        assert!(result.converged);
+        assert!((result.x - 10f64.ln()).abs() < 1e-13);
+        assert!(result.iterations <= 8, "{result:?}");
+        assert_eq!(result.fevals, 2 + 2 * result.iterations);
+        let (lower, upper) = result.bracket.unwrap();
+        assert!(lower <= 10f64.ln() && 10f64.ln() <= upper);
+    }
+
+    #[test]
+    fn stops_on_an_exact_zero_at_the_midpoint() {
+        let result = ridder(|x: f64| x - 1.0, 0.0, 2.0, 1e-13, 100).unwrap();
+        assert_eq!((result.x, result.fx, result.fevals), (1.0, 0.0, 3));
+        assert_eq!(result.bracket, Some((1.0, 1.0)));
+        assert_eq!(
+            ridder(|x: f64| x * x + 1.0, -1.0, 1.0, 1e-12, 100),
+            Err(SolveError::NotBracketed)
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use super::{brentq, regula_falsi, ridder, Modification, RootResult};
+use crate::generals::Float;
+use crate::optimize::Budget;
+use crate::solve::{SolveError, Tolerance};
+
+/// Bracketing algorithm used by [`root`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Method {
+    /// Brent's method, see [`brentq`].
+    #[default]
+    Brent,
+    /// Ridder's method, see [`ridder`].
+    Ridder,
+    /// False position with the given modification, see [`regula_falsi`].
+    RegulaFalsi(Modification),
+}
+
+/// Options for [`root_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RootOptions<T> {
+    /// Bracketing algorithm to run.
+    pub method: Method,
+    /// Interval over which the function changes sign.
+    pub bracket: (T, T),
+    /// When the bracket is narrow enough to stop.
+    pub tolerance: Tolerance<T>,
+    /// Limits on the iterations and function evaluations.
+    pub budget: Budget,
+}
+
+impl<T: Float> RootOptions<T> {
+    /// Brent's method on the bracket `(a, b)`, stopping at the default
+    /// [`Tolerance`] or after 500 iterations.
+    pub fn new(a: T, b: T) -> Self {
+        RootOptions {
+            method: Method::Brent,
+            bracket: (a, b),
+            tolerance: Tolerance::default(),
+            budget: Budget::new(500),
+        }
+    }
+
+    /// Uses `method` instead of Brent's method.
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Stops at `tolerance` instead, for example an `(xtol, rtol)` pair.
+    pub fn tolerance(mut self, tolerance: impl Into<Tolerance<T>>) -> Self {
+        self.tolerance = tolerance.into();
+        self
+    }
+
+    /// Uses `budget` instead of 500 iterations.
+    pub fn budget(mut self, budget: impl Into<Budget>) -> Self {
+        self.budget = budget.into();
+        self
+    }
+}
+
+/// Finds a root of `func` in `bracket` with `method`, stopping at the default
+/// [`Tolerance`] or after 500 iterations.
+///
+/// # Errors
+///
+/// Passes on the errors of the selected algorithm.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::scalar::{root, Method};
+///
+/// let result = root(|x: f64| x * x - 2.0, (0.0, 2.0), Method::Ridder).unwrap();
+/// assert!((result.x - 2f64.sqrt()).abs() < 1e-12);
+/// ```
+pub fn root<T, F>(func: F, bracket: (T, T), method: Method) -> Result<RootResult<T>, SolveError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    root_with_options(func, RootOptions::new(bracket.0, bracket.1).method(method))
+}
+
+/// Finds a root of `func` with the algorithm, bracket, tolerance and budget
+/// selected in `options`.
+///
+/// This is a single entry point over the individual algorithm functions, which
+/// remain available for finer control.
+///
+/// # Errors
+///
+/// Passes on the errors of the selected algorithm.
+pub fn root_with_options<T, F>(
+    func: F,
+    options: RootOptions<T>,
+) -> Result<RootResult<T>, SolveError>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let RootOptions {
+        method,
+        bracket: (a, b),
+        tolerance,
+        budget,
+    } = options;
+    match method {
+        Method::Brent => brentq(func, a, b, tolerance, budget),
+        Method::Ridder => ridder(func, a, b, tolerance, budget),
+        Method::RegulaFalsi(modification) => {
+            regula_falsi(func, a, b, modification, tolerance, budget)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_method_finds_the_root() {
+        let f = |x: f64| x.powi(3) - x - 1.0;
+        let plastic = 1.324_717_957_244_746;
+        for method in [
+            Method::Brent,
+            Method::Ridder,
+            Method::RegulaFalsi(Modification::Illinois),
+            Method::RegulaFalsi(Modification::AndersonBjorck),
+        ] {
+            let options = RootOptions::new(1.0, 2.0).method(method).tolerance(1e-14);
+            let result = root_with_options(f, options).unwrap();
+            assert!(result.converged, "{method:?}");
+            assert!((result.x - plastic).abs() < 1e-14, "{method:?}");
+        }
+        let starved = RootOptions::new(1.0, 2.0).budget(1);
+        assert!(!root_with_options(f, starved).unwrap().converged);
+    }
+}
@@ -0,0 +1,163 @@
+use super::brentq;
+use crate::generals::Float;
+use crate::solve::Tolerance;
+
+/// Scans `[a, b]` on a grid of `n` equal steps for sign changes of `func`,
+/// returning the subintervals over which it changes sign, in increasing
+/// order.
+///
+/// Each interval brackets a root of a continuous `func`, ready for
+/// [`brentq`](super::brentq) or any other bracketing method. A grid point
+/// where `func` is exactly zero is returned as the degenerate interval
+/// `(x, x)`. Roots closer together than the grid step, such as a double
+/// root or a pair of nearby roots, can be missed, since the sign may not
+/// change between neighbouring grid points; so can roots in a step next to a
+/// grid point where `func` is zero or NaN, since that point has no sign.
+/// Costs `n + 1` evaluations.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::scalar::{brentq, find_brackets};
+///
+/// let brackets = find_brackets(|x: f64| x.sin(), 1.0, 10.0, 30);
+/// assert_eq!(brackets.len(), 3);
+/// let roots: Vec<f64> = brackets
+///     .iter()
+///     .map(|&(a, b)| brentq(|x: f64| x.sin(), a, b, 1e-12, 100).unwrap().x)
+///     .collect();
+/// assert!((roots[2] - 3.0 * std::f64::consts::PI).abs() < 1e-12);
+/// ```
+pub fn find_brackets<T, F>(func: F, a: T, b: T, n: usize) -> Vec<(T, T)>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let (a, b) = if a <= b { (a, b) } else { (b, a) };
+    let n = n.max(1);
+    let step = (b - a) / T::from_usize(n);
+    let point = |i: usize| {
+        if i == n {
+            b
+        } else {
+            a + T::from_usize(i) * step
+        }
+    };
+    let mut brackets = Vec::new();
+    let (mut x0, mut f0) = (a, func(a));
+    for i in 1..=n {
+        let x1 = point(i);
+        let f1 = func(x1);
+        if f0 == T::ZERO {
+            brackets.push((x0, x0));
+        } else if f1 != T::ZERO && !f0.is_nan() && !f1.is_nan() && (f0 > T::ZERO) != (f1 > T::ZERO)
+        {
+            brackets.push((x0, x1));
+        }
+        (x0, f0) = (x1, f1);
+    }
+    if f0 == T::ZERO {
+        brackets.push((x0, x0));
+    }
+    brackets
+}
+
+/// Finds the roots of `func` in `[a, b]` at which it changes sign, in
+/// increasing order.
+///
+/// The interval is scanned on `grid` equal steps by [`find_brackets`], and
+/// the root in each bracket is refined by [`brentq`] to `tolerance`, with a
+/// budget of 500 iterations. Roots within twice the tolerance of the previous
+/// one are dropped as duplicates, as happens when a root lies on a grid
+/// point. Roots missed by the scan are missed here too, so the grid should be
+/// finer than the spacing of the roots; brackets over which `func` is not
+/// finite everywhere, or whose refinement runs out of budget, are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::scalar::all_roots;
+///
+/// // The first positive solutions of tan(x) = x, where the branches of tan
+/// // cross the line; the poles change sign too but are not roots.
+/// let f = |x: f64| x.sin() - x * x.cos();
+/// let roots = all_roots(f, 1.0, 11.0, 100, 1e-12);
+/// assert_eq!(roots.len(), 3);
+/// assert!((roots[0] - 4.493_409_457_909_064).abs() < 1e-12);
+/// assert!((roots[2] - 10.904_121_659_428_9).abs() < 1e-12);
+/// ```
+pub fn all_roots<T, F>(
+    func: F,
+    a: T,
+    b: T,
+    grid: usize,
+    tolerance: impl Into<Tolerance<T>>,
+) -> Vec<T>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    let tolerance = tolerance.into();
+    let mut roots: Vec<T> = Vec::new();
+    for (lower, upper) in find_brackets(&func, a, b, grid) {
+        let root = if lower == upper {
+            lower
+        } else {
+            match brentq(&func, lower, upper, tolerance, 500) {
+                Ok(result) if result.converged => result.x,
+                _ => continue,
+            }
+        };
+        let duplicate = roots
+            .last()
+            .is_some_and(|&last| tolerance.x_met(root, (root - last) / T::from_f64(2.0)));
+        if !duplicate {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_every_sign_change_and_exact_zero() {
+        let f = |x: f64| (x + 2.0) * (x - 0.5) * (x - 3.0);
+        let brackets = find_brackets(f, 4.0, -4.0, 16);
+        assert_eq!(brackets, vec![(-2.0, -2.0), (0.5, 0.5), (3.0, 3.0)]);
+        let brackets = find_brackets(f, -4.0, 4.0, 5);
+        assert_eq!(brackets.len(), 3);
+        for ((a, b), root) in brackets.into_iter().zip([-2.0, 0.5, 3.0]) {
+            assert!(a < root && root < b && b - a < 1.6 + 1e-12);
+        }
+        // A double root does not change sign and goes unnoticed.
+        assert!(find_brackets(|x: f64| (x - 1.0).powi(2), 0.0, 3.0, 7).is_empty());
+        // The upper end is the exact bound, not a rounded multiple of the step.
+        let brackets = find_brackets(|x: f64| x - 0.95, 0.0, 1.0, 3);
+        assert_eq!(brackets.len(), 1);
+        assert_eq!(brackets[0].1, 1.0);
+    }
+
+    #[test]
+    fn refines_and_deduplicates_the_roots() {
+        // Roots at the multiples of π/3.
+        let f = |x: f64| (3.0 * x).sin();
+        let pi = std::f64::consts::PI;
+        let roots = all_roots(f, 0.5, 12.0, 48, 1e-13);
+        assert_eq!(roots.len(), 11);
+        for (k, root) in roots.iter().enumerate() {
+            assert!(
+                (root - (k + 1) as f64 * pi / 3.0).abs() < 1e-12,
+                "{roots:?}"
+            );
+        }
+        // Roots on the grid are taken as they are.
+        let g = |x: f64| (x - 0.25) * (x - 0.5) * (x - 0.9);
+        let roots = all_roots(g, 0.0, 1.0, 4, 1e-13);
+        assert_eq!(roots[..2], [0.25, 0.5]);
+        assert!((roots[2] - 0.9).abs() < 1e-13);
+        assert!(all_roots(|x: f64| x * x + 1.0, -3.0, 3.0, 10, 1e-12).is_empty());
+    }
+}
@@ -0,0 +1,294 @@
+use super::{finite_at, is_met, non_finite_at, SystemOptions, SystemResult};
+use crate::generals::linalg::{add_scaled, dot, norm, Norm, Qr};
+use crate::generals::Float;
+use crate::optimize::multivariate::forward_difference_jacobian;
+use crate::solve::SolveError;
+
+/// Most step halvings a line search tries before the Jacobian is
+/// recomputed.
+const MAX_BACKTRACKS: usize = 10;
+
+/// Rank-one update of the approximate inverse Jacobian used by [`broyden`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BroydenUpdate {
+    /// Broyden's first, "good" method: the Jacobian approximation changes
+    /// as little as possible, in the Frobenius norm, to match the secant
+    /// condition. The usual choice.
+    #[default]
+    Good,
+    /// Broyden's second, "bad" method: the inverse changes as little as
+    /// possible instead. Cheaper to update but usually less robust.
+    Bad,
+}
+
+/// Solves `residual(x) = 0` from `x0` by Broyden's quasi-Newton method.
+///
+/// The Jacobian is computed once by forward differences and inverted; every
+/// iteration then takes the step `δ = -H·F` with the approximate inverse
+/// `H`, and corrects `H` by a rank-one `update` that makes it map the change
+/// in `F` to the step just taken. An iteration thus costs a single residual
+/// evaluation and `O(n²)` work, against `n + 1` evaluations and a
+/// factorization for [`newton_system`](super::newton_system), at the price
+/// of superlinear rather than quadratic convergence.
+///
+/// With the line search of `options`, a step that does not lower `‖F‖` is
+/// halved a few times. If that fails, or the update degenerates, the
+/// approximation has degraded and the Jacobian is recomputed at the current
+/// point, which costs another `n` evaluations. The search stops with
+/// `converged == false` if even a freshly computed Jacobian gives no
+/// acceptable step. Without the line search, every step is taken in full.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NonFiniteAtPoint`] if the residual is NaN or
+/// infinite at `x0` or at an accepted point, or the Jacobian when it is
+/// computed.
+///
+/// # Panics
+///
+/// Panics if `residual` does not return one value per unknown.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::system::{broyden, BroydenUpdate, SystemOptions};
+///
+/// // Broyden's tridiagonal problem in 50 unknowns.
+/// let residual = |x: &[f64]| {
+///     let n = x.len();
+///     (0..n)
+///         .map(|i| {
+///             let left = if i > 0 { x[i - 1] } else { 0.0 };
+///             let right = if i + 1 < n { x[i + 1] } else { 0.0 };
+///             (3.0 - 2.0 * x[i]) * x[i] - left - 2.0 * right + 1.0
+///         })
+///         .collect()
+/// };
+/// let result = broyden(residual, &[-1.0; 50], BroydenUpdate::Good, SystemOptions::new()).unwrap();
+/// assert!(result.converged);
+/// assert!(result.residuals.iter().all(|r| r.abs() < 1e-10));
+/// ```
+pub fn broyden<T, R>(
+    residual: R,
+    x0: &[T],
+    update: BroydenUpdate,
+    options: SystemOptions<T>,
+) -> Result<SystemResult<T>, SolveError>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+{
+    let SystemOptions {
+        tolerance,
+        budget,
+        line_search,
+    } = options;
+    let n = x0.len();
+    let mut x = x0.to_vec();
+    let mut f = finite_at(residual(&x), &x)?;
+    assert_eq!(f.len(), n, "the system must have one equation per unknown");
+    let mut fevals = 1;
+    let mut iterations = 0;
+    let mut converged = tolerance.f_met(Norm::Infinity.of(&f));
+    if converged {
+        return Ok(SystemResult {
+            x,
+            residuals: f,
+            iterations,
+            fevals,
+            jevals: 0,
+            converged,
+        });
+    }
+    let mut h = inverse_jacobian(&residual, &x, &f)?;
+    fevals += n;
+    // Whether `h` was just computed rather than updated.
+    let mut fresh = true;
+    while !converged && budget.allows(iterations, fevals) {
+        let step: Vec<T> = (0..n).map(|i| -dot(&h[i * n..(i + 1) * n], &f)).collect();
+        let accepted = if line_search {
+            backtrack(&residual, &x, &f, &step, &mut fevals)
+        } else {
+            let next = add_scaled(&x, T::ONE, &step);
+            fevals += 1;
+            let next_f = residual(&next);
+            if next_f.iter().any(|v| !v.is_finite()) {
+                return Err(non_finite_at(&next));
+            }
+            Some((next, next_f))
+        };
+        let Some((next, next_f)) = accepted else {
+            if fresh {
+                break;
+            }
+            h = inverse_jacobian(&residual, &x, &f)?;
+            fevals += n;
+            fresh = true;
+            continue;
+        };
+
+        let s = add_scaled(&next, -T::ONE, &x);
+        let y = add_scaled(&next_f, -T::ONE, &f);
+        iterations += 1;
+        converged = is_met(&tolerance, &next, &s, &next_f);
+        (x, f) = (next, next_f);
+        if converged {
+            break;
+        }
+        fresh = false;
+        if !update_inverse(&mut h, &s, &y, update) {
+            h = inverse_jacobian(&residual, &x, &f)?;
+            fevals += n;
+            fresh = true;
+        }
+    }
+
+    Ok(SystemResult {
+        x,
+        residuals: f,
+        iterations,
+        fevals,
+        jevals: 0,
+        converged,
+    })
+}
+
+/// The inverse, row-major, of the forward difference Jacobian at `x`, where
+/// the residual is `f`; the basic least-squares inverse if it is singular.
+fn inverse_jacobian<T, R>(residual: R, x: &[T], f: &[T]) -> Result<Vec<T>, SolveError>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+{
+    let n = x.len();
+    let jacobian = finite_at(forward_difference_jacobian(residual, x, f).concat(), x)?;
+    let qr = Qr::factor(&jacobian, n, n, true);
+    let mut inverse = vec![T::ZERO; n * n];
+    let mut unit = vec![T::ZERO; n];
+    for j in 0..n {
+        unit[j] = T::ONE;
+        for (i, value) in qr.solve(&unit).into_iter().enumerate() {
+            inverse[i * n + j] = value;
+        }
+        unit[j] = T::ZERO;
+    }
+    Ok(inverse)
+}
+
+/// Applies the rank-one `update` to the approximate inverse `h` for the step
+/// `s` and residual change `y`; `false`, leaving `h` alone, if its
+/// denominator is too small for the update to be trusted.
+fn update_inverse<T: Float>(h: &mut [T], s: &[T], y: &[T], update: BroydenUpdate) -> bool {
+    let n = s.len();
+    let hy: Vec<T> = (0..n).map(|i| dot(&h[i * n..(i + 1) * n], y)).collect();
+    let correction = add_scaled(s, -T::ONE, &hy);
+    // H ← H + (s - H·y)·vᵀ / (vᵀ·y), with v = Hᵀ·s for the good update and
+    // v = y for the bad one.
+    let v: Vec<T> = match update {
+        BroydenUpdate::Good => (0..n)
+            .map(|j| (0..n).map(|i| s[i] * h[i * n + j]).sum::<T>())
+            .collect(),
+        BroydenUpdate::Bad => y.to_vec(),
+    };
+    let denominator = dot(&v, y);
+    if denominator.abs() <= T::EPSILON.sqrt() * norm(&v) * norm(y) || denominator.is_nan() {
+        return false;
+    }
+    for i in 0..n {
+        let factor = correction[i] / denominator;
+        for j in 0..n {
+            h[i * n + j] += factor * v[j];
+        }
+    }
+    true
+}
+
+/// The point `x + α·step` and its residuals for the first `α` of 1, ½, ¼, …
+/// that lowers `‖F‖` by a fraction of `α`; `None` if there is none.
+fn backtrack<T, R>(
+    residual: R,
+    x: &[T],
+    f: &[T],
+    step: &[T],
+    fevals: &mut usize,
+) -> Option<(Vec<T>, Vec<T>)>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+{
+    let size = norm(f);
+    let mut alpha = T::ONE;
+    for _ in 0..MAX_BACKTRACKS {
+        let trial = add_scaled(x, alpha, step);
+        let trial_f = residual(&trial);
+        *fevals += 1;
+        let trial_size = norm(&trial_f);
+        if trial_size.is_finite() && trial_size <= (T::ONE - T::from_f64(1e-4) * alpha) * size {
+            return Some((trial, trial_f));
+        }
+        alpha *= T::from_f64(0.5);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve::system::newton_system;
+
+    fn tridiagonal(x: &[f64]) -> Vec<f64> {
+        let n = x.len();
+        (0..n)
+            .map(|i| {
+                let left = if i > 0 { x[i - 1] } else { 0.0 };
+                let right = if i + 1 < n { x[i + 1] } else { 0.0 };
+                (3.0 - 2.0 * x[i]) * x[i] - left - 2.0 * right + 1.0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn both_updates_beat_newton_on_evaluations() {
+        let x0 = [-1.0; 30];
+        let options = SystemOptions::new().tolerance(1e-12);
+        let newton = newton_system(tridiagonal, &x0, options).unwrap();
+        for update in [BroydenUpdate::Good, BroydenUpdate::Bad] {
+            let result = broyden(tridiagonal, &x0, update, options).unwrap();
+            assert!(result.converged, "{update:?}");
+            assert!(
+                result.residuals.iter().all(|r| r.abs() < 1e-10),
+                "{update:?}"
+            );
+            assert!(result.fevals < newton.fevals / 2, "{update:?}: {result:?}");
+            for (a, b) in result.x.iter().zip(&newton.x) {
+                assert!((a - b).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn recovers_when_secant_steps_mislead() {
+        // Rosenbrock's valley: early secant steps overshoot along the curve
+        // and are cut back, or the Jacobian recomputed.
+        let residual = |v: &[f64]| vec![10.0 * (v[1] - v[0] * v[0]), 1.0 - v[0]];
+        for update in [BroydenUpdate::Good, BroydenUpdate::Bad] {
+            let result = broyden(residual, &[-1.2, 1.0], update, SystemOptions::new()).unwrap();
+            assert!(result.converged, "{update:?}");
+            assert!(
+                result.x.iter().all(|xi| (xi - 1.0).abs() < 1e-12),
+                "{update:?}"
+            );
+            assert!(result.fevals > 1 + 2 + result.iterations, "{update:?}");
+        }
+
+        assert!(matches!(
+            broyden(
+                |v: &[f64]| vec![v[0].ln()],
+                &[-1.0],
+                BroydenUpdate::Bad,
+                SystemOptions::new()
+            ),
+            Err(SolveError::NonFiniteAtPoint { .. })
+        ));
+    }
+}
@@ -0,0 +1,328 @@
+use super::{finite_at, is_met, SystemOptions, SystemResult};
+use crate::generals::linalg::{add_scaled, dot, norm, Norm, Qr};
+use crate::generals::Float;
+use crate::optimize::multivariate::forward_difference_jacobian;
+use crate::solve::SolveError;
+
+/// Solves `residual(x) = 0` from `x0` by Powell's hybrid method, using a
+/// forward difference Jacobian.
+///
+/// Each Jacobian costs `n` residual evaluations, which count towards
+/// `fevals`. See [`hybrid_with_jacobian`] for the method and errors.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::system::{hybrid, SystemOptions};
+///
+/// // Powell's badly scaled problem, whose solution has x ≈ 1.1·10⁻⁵.
+/// let residual = |v: &[f64]| {
+///     vec![1e4 * v[0] * v[1] - 1.0, (-v[0]).exp() + (-v[1]).exp() - 1.0001]
+/// };
+/// let result = hybrid(residual, &[0.0, 1.0], SystemOptions::new()).unwrap();
+/// assert!(result.converged);
+/// assert!((result.x[0] / 1.098159e-5 - 1.0f64).abs() < 1e-6);
+/// ```
+pub fn hybrid<T, R>(
+    residual: R,
+    x0: &[T],
+    options: SystemOptions<T>,
+) -> Result<SystemResult<T>, SolveError>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+{
+    let jacobian = |x: &[T], fx: &[T]| forward_difference_jacobian(&residual, x, fx);
+    iterate(&residual, jacobian, (x0.len(), 0), x0, options)
+}
+
+/// Solves `residual(x) = 0` from `x0` by Powell's hybrid method, given the
+/// Jacobian of the residual as a vector of rows.
+///
+/// This is the trust region method of MINPACK's `hybrd` and `hybrj`, behind
+/// SciPy's `fsolve`. Each iteration takes a dogleg step within the trust
+/// region: the Newton step `J·δ = -F` if it fits, else a combination of it
+/// and the steepest descent step on `½‖F‖²` that reaches the boundary. The
+/// region grows while a linear model of `F` predicts the decrease of `‖F‖`
+/// well and shrinks when it does not, so the method blends the fast local
+/// convergence of Newton's method with the robustness of steepest descent.
+/// Between Jacobian evaluations, `J` is corrected by Broyden's rank-one
+/// update after every step, and it is only recomputed after two rejected
+/// steps in a row. The line search of `options` is not used.
+///
+/// The search stops with `converged == false` if the trust region shrinks
+/// below the tolerance without reaching a solution, as it does near a local
+/// minimum of `‖F‖` that is not one. `iterations` counts rejected steps too.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NonFiniteAtPoint`] if the residual or Jacobian is
+/// NaN or infinite at `x0` or at an accepted point. Trial points with
+/// non-finite residuals are rejected.
+///
+/// # Panics
+///
+/// Panics if `residual` does not return one value per unknown.
+pub fn hybrid_with_jacobian<T, R, J>(
+    residual: R,
+    jacobian: J,
+    x0: &[T],
+    options: SystemOptions<T>,
+) -> Result<SystemResult<T>, SolveError>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Vec<Vec<T>>,
+{
+    iterate(
+        residual,
+        |x: &[T], _: &[T]| jacobian(x),
+        (0, 1),
+        x0,
+        options,
+    )
+}
+
+/// The hybrid method with `jacobian(x, F(x))` costing `cost` residual and
+/// Jacobian evaluations.
+fn iterate<T, R, J>(
+    residual: R,
+    jacobian: J,
+    cost: (usize, usize),
+    x0: &[T],
+    options: SystemOptions<T>,
+) -> Result<SystemResult<T>, SolveError>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T], &[T]) -> Vec<Vec<T>>,
+{
+    let SystemOptions {
+        tolerance, budget, ..
+    } = options;
+    let n = x0.len();
+    let mut x = x0.to_vec();
+    let mut f = finite_at(residual(&x), &x)?;
+    assert_eq!(f.len(), n, "the system must have one equation per unknown");
+    let (mut fevals, mut jevals) = (1, 0);
+    let mut iterations = 0;
+    let mut converged = tolerance.f_met(Norm::Infinity.of(&f));
+    // As in MINPACK, the region starts 100 times as large as x.
+    let mut radius = T::from_f64(100.0) * norm(&x);
+    if radius == T::ZERO {
+        radius = T::from_f64(100.0);
+    }
+    let mut jac = Vec::new();
+    let mut fresh_jacobian_needed = true;
+    let mut failures = 0;
+    let mut successes = 0;
+    while !converged && budget.allows(iterations, fevals) {
+        if fresh_jacobian_needed {
+            jac = finite_at(jacobian(&x, &f).concat(), &x)?;
+            fevals += cost.0;
+            jevals += cost.1;
+            fresh_jacobian_needed = false;
+            failures = 0;
+        }
+        let step = dogleg(&jac, &f, radius);
+        let step_norm = norm(&step);
+        let trial = add_scaled(&x, T::ONE, &step);
+        let trial_f = residual(&trial);
+        fevals += 1;
+        iterations += 1;
+
+        // Reductions of ‖F‖², actual and predicted by F + J·δ, relative to
+        // ‖F‖².
+        let size = norm(&f);
+        let predicted_f: Vec<T> = (0..n)
+            .map(|i| f[i] + dot(&jac[i * n..(i + 1) * n], &step))
+            .collect();
+        let predicted = T::ONE - (norm(&predicted_f) / size).powi(2);
+        let trial_size = norm(&trial_f);
+        let actual = if trial_size.is_finite() {
+            T::ONE - (trial_size / size).powi(2)
+        } else {
+            T::NEG_INFINITY
+        };
+        let ratio = if predicted > T::ZERO {
+            actual / predicted
+        } else {
+            T::ZERO
+        };
+
+        if ratio < T::from_f64(0.1) {
+            radius = T::from_f64(0.5) * radius;
+            successes = 0;
+            failures += 1;
+        } else {
+            failures = 0;
+            successes += 1;
+            if ratio >= T::from_f64(0.5) || successes > 1 {
+                radius = radius.max(T::from_f64(2.0) * step_norm);
+            }
+            if (ratio - T::ONE).abs() <= T::from_f64(0.1) {
+                radius = T::from_f64(2.0) * step_norm;
+            }
+        }
+
+        if ratio >= T::from_f64(1e-4) {
+            converged = is_met(&tolerance, &trial, &step, &trial_f);
+            let y = add_scaled(&trial_f, -T::ONE, &f);
+            (x, f) = (trial, trial_f);
+            broyden_update(&mut jac, &step, &y);
+        } else if trial_size.is_finite() {
+            // A rejected step still tells how F changes along it.
+            let y = add_scaled(&trial_f, -T::ONE, &f);
+            broyden_update(&mut jac, &step, &y);
+        }
+        if converged {
+            break;
+        }
+        if tolerance.x_met(Norm::Infinity.of(&x), radius) {
+            // The region has collapsed without reaching a root.
+            break;
+        }
+        if failures == 2 {
+            fresh_jacobian_needed = true;
+        }
+    }
+
+    Ok(SystemResult {
+        x,
+        residuals: f,
+        iterations,
+        fevals,
+        jevals,
+        converged,
+    })
+}
+
+/// The dogleg step for the row-major Jacobian `jac` and residual `f` within
+/// a trust region of `radius`.
+fn dogleg<T: Float>(jac: &[T], f: &[T], radius: T) -> Vec<T> {
+    let n = f.len();
+    let minus_f: Vec<T> = f.iter().map(|&fi| -fi).collect();
+    let newton = Qr::factor(jac, n, n, true).solve(&minus_f);
+    let newton_norm = norm(&newton);
+    if newton_norm <= radius && newton.iter().any(|&s| s != T::ZERO) {
+        return newton;
+    }
+    // The gradient Jᵀ·F of ½‖F‖², and the minimizer of the linear model
+    // along it.
+    let gradient: Vec<T> = (0..n)
+        .map(|j| (0..n).map(|i| jac[i * n + j] * f[i]).sum::<T>())
+        .collect();
+    let gradient_norm = norm(&gradient);
+    if gradient_norm == T::ZERO {
+        return newton;
+    }
+    let j_gradient: Vec<T> = (0..n)
+        .map(|i| dot(&jac[i * n..(i + 1) * n], &gradient))
+        .collect();
+    let curvature = dot(&j_gradient, &j_gradient);
+    let cauchy_length = if curvature > T::ZERO {
+        gradient_norm * gradient_norm / curvature
+    } else {
+        T::INFINITY
+    };
+    let cauchy: Vec<T> = gradient.iter().map(|&g| -cauchy_length * g).collect();
+    let cauchy_norm = cauchy_length * gradient_norm;
+    if cauchy_norm >= radius || newton.iter().all(|&s| s == T::ZERO) {
+        return gradient
+            .iter()
+            .map(|&g| -radius / gradient_norm * g)
+            .collect();
+    }
+    // cauchy + τ·(newton - cauchy) on the boundary, τ ∈ [0, 1].
+    let d = add_scaled(&newton, -T::ONE, &cauchy);
+    let (a, b, c) = (
+        dot(&d, &d),
+        dot(&cauchy, &d),
+        cauchy_norm * cauchy_norm - radius * radius,
+    );
+    let tau = (-b + (b * b - a * c).sqrt()) / a;
+    add_scaled(&cauchy, tau, &d)
+}
+
+/// Broyden's rank-one update `J ← J + (y - J·s)·sᵀ / sᵀs` of the row-major
+/// Jacobian `jac`, for the step `s` and residual change `y`.
+fn broyden_update<T: Float>(jac: &mut [T], s: &[T], y: &[T]) {
+    let n = s.len();
+    let ss = dot(s, s);
+    if ss == T::ZERO {
+        return;
+    }
+    for i in 0..n {
+        let row = &mut jac[i * n..(i + 1) * n];
+        let factor = (y[i] - dot(row, s)) / ss;
+        for (entry, &sj) in row.iter_mut().zip(s) {
+            *entry += factor * sj;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve::system::newton_system;
+
+    #[test]
+    fn dogleg_interpolates_between_descent_and_newton() {
+        // J = diag(1, 10) and F = (1, 1): the Newton step is (-1, -0.1).
+        let jac = [1.0, 0.0, 0.0, 10.0];
+        let f = [1.0, 1.0];
+        assert_eq!(dogleg(&jac, &f, 2.0), vec![-1.0, -0.1]);
+        // The gradient is (1, 10) and the Cauchy step 101/10001 of it.
+        let short = dogleg(&jac, &f, 0.05);
+        assert!((norm(&short) - 0.05).abs() < 1e-15);
+        assert!((short[1] / short[0] - 10.0).abs() < 1e-12);
+        let middle = dogleg(&jac, &f, 0.5);
+        assert!((norm(&middle) - 0.5).abs() < 1e-15);
+        assert!(middle[0] < 0.0 && middle[1] < 0.0);
+    }
+
+    #[test]
+    fn solves_hard_problems_with_and_without_a_jacobian() {
+        // Helical valley, with a singular-looking start for Newton.
+        let theta = |x: f64, y: f64| y.atan2(x) / std::f64::consts::TAU;
+        let residual = |v: &[f64]| {
+            vec![
+                10.0 * (v[2] - 10.0 * theta(v[0], v[1])),
+                10.0 * (v[0].hypot(v[1]) - 1.0),
+                v[2],
+            ]
+        };
+        let options = SystemOptions::new().tolerance(1e-12).budget(500);
+        let result = hybrid(residual, &[-1.0, 0.0, 0.0], options).unwrap();
+        assert!(result.converged, "{result:?}");
+        assert!((result.x[0] - 1.0).abs() < 1e-10 && result.x[1].abs() < 1e-10);
+
+        // Rosenbrock's valley, with its Jacobian.
+        let residual = |v: &[f64]| vec![10.0 * (v[1] - v[0] * v[0]), 1.0 - v[0]];
+        let jacobian = |v: &[f64]| vec![vec![-20.0 * v[0], 10.0], vec![-1.0, 0.0]];
+        let result = hybrid_with_jacobian(residual, jacobian, &[-1.2, 1.0], options).unwrap();
+        assert!(result.converged);
+        assert!(result.x.iter().all(|xi| (xi - 1.0).abs() < 1e-12));
+        assert!(result.jevals < result.iterations);
+    }
+
+    #[test]
+    fn stops_at_a_local_minimum_of_the_residual() {
+        // Freudenstein and Roth: from this start, ‖F‖ has a local minimum
+        // near (11.41, -0.8968) that is not a solution.
+        let residual = |v: &[f64]| {
+            vec![
+                -13.0 + v[0] + ((5.0 - v[1]) * v[1] - 2.0) * v[1],
+                -29.0 + v[0] + ((v[1] + 1.0) * v[1] - 14.0) * v[1],
+            ]
+        };
+        let result = hybrid(residual, &[0.5, -2.0], SystemOptions::new().budget(1000)).unwrap();
+        assert!(!result.converged);
+        assert!((result.x[0] - 11.41).abs() < 0.01 && (result.x[1] + 0.8968).abs() < 1e-3);
+        assert!(
+            newton_system(residual, &[6.0, 3.0], SystemOptions::new())
+                .unwrap()
+                .converged
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! Systems of nonlinear equations `F(x) = 0`, with as many equations as
+//! unknowns.
+//!
+//! The residual `F` is any `Fn(&[T]) -> Vec<T>`, and its Jacobian, where one
+//! is given, a vector of rows like the Jacobians of
+//! [`crate::optimize::least_squares`]. A [`SystemOptions`] sets how closely
+//! to solve the system and how much work to spend: the [`Tolerance`] applies
+//! to the largest component of the last step, relative to the largest
+//! component of `x`, and its `ftol` to the largest residual.
+
+mod broyden;
+mod hybrid;
+mod newton;
+
+pub use broyden::{broyden, BroydenUpdate};
+pub use hybrid::{hybrid, hybrid_with_jacobian};
+pub use newton::{newton_system, newton_system_with_jacobian};
+
+use crate::generals::linalg::Norm;
+use crate::generals::Float;
+use crate::optimize::Budget;
+use crate::solve::{SolveError, Tolerance};
+
+/// Outcome of a solver for a system of equations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemResult<T> {
+    /// Best approximation of the solution.
+    pub x: Vec<T>,
+    /// Residuals `F(x)`.
+    pub residuals: Vec<T>,
+    /// Number of iterations performed.
+    pub iterations: usize,
+    /// Number of residual evaluations performed, including those spent on
+    /// finite difference Jacobians.
+    pub fevals: usize,
+    /// Number of analytic Jacobian evaluations performed.
+    pub jevals: usize,
+    /// Whether the tolerance was met before the budget ran out.
+    pub converged: bool,
+}
+
+/// Options for the solvers of systems of equations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SystemOptions<T> {
+    pub tolerance: Tolerance<T>,
+    pub budget: Budget,
+    /// Whether to backtrack along steps that do not lower `½‖F‖²` enough,
+    /// which makes the solvers much more robust far from a solution.
+    pub line_search: bool,
+}
+
+impl<T: Float> SystemOptions<T> {
+    /// The default [`Tolerance`], 100 iterations and a line search.
+    pub fn new() -> Self {
+        SystemOptions {
+            tolerance: Tolerance::default(),
+            budget: Budget::new(100),
+            line_search: true,
+        }
+    }
+
+    /// Stops at `tolerance` instead, for example an `(xtol, rtol)` pair.
+    pub fn tolerance(mut self, tolerance: impl Into<Tolerance<T>>) -> Self {
+        self.tolerance = tolerance.into();
+        self
+    }
+
+    /// Uses `budget` instead of 100 iterations.
+    pub fn budget(mut self, budget: impl Into<Budget>) -> Self {
+        self.budget = budget.into();
+        self
+    }
+
+    /// Turns the line search on or off. Without it, every step is taken in
+    /// full.
+    pub fn line_search(mut self, line_search: bool) -> Self {
+        self.line_search = line_search;
+        self
+    }
+}
+
+impl<T: Float> Default for SystemOptions<T> {
+    fn default() -> Self {
+        SystemOptions::new()
+    }
+}
+
+/// Whether a step of `step` to `x` with residuals `f` meets `tolerance`.
+pub(crate) fn is_met<T: Float>(tolerance: &Tolerance<T>, x: &[T], step: &[T], f: &[T]) -> bool {
+    tolerance.f_met(Norm::Infinity.of(f))
+        || tolerance.x_met(Norm::Infinity.of(x), Norm::Infinity.of(step))
+}
+
+/// `values` of a residual or Jacobian at `x`, if they are all finite.
+pub(crate) fn finite_at<T: Float>(values: Vec<T>, x: &[T]) -> Result<Vec<T>, SolveError> {
+    if values.iter().all(|v| v.is_finite()) {
+        Ok(values)
+    } else {
+        Err(non_finite_at(x))
+    }
+}
+
+/// [`SolveError::NonFiniteAtPoint`] at `x`.
+pub(crate) fn non_finite_at<T: Float>(x: &[T]) -> SolveError {
+    SolveError::NonFiniteAtPoint {
+        x: x.iter().map(|xi| xi.to_f64()).collect(),
+    }
+}
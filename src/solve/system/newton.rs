@@ -0,0 +1,262 @@
+use super::{finite_at, is_met, non_finite_at, SystemOptions, SystemResult};
+use crate::generals::linalg::{add_scaled, dot, Norm, Qr};
+use crate::generals::Float;
+use crate::optimize::multivariate::forward_difference_jacobian;
+use crate::solve::SolveError;
+
+/// Most step halvings a line search tries before giving up.
+const MAX_BACKTRACKS: usize = 40;
+
+/// Solves `residual(x) = 0` from `x0` by Newton's method, using a forward
+/// difference Jacobian.
+///
+/// Each Jacobian costs `n` residual evaluations, which count towards
+/// `fevals`. See [`newton_system_with_jacobian`] for the method and errors.
+///
+/// # Examples
+///
+/// ```
+/// use mathslib::solve::system::{newton_system, SystemOptions};
+///
+/// // Where the circle x² + y² = 4 meets the curve y = eˣ - 1.
+/// let residual = |v: &[f64]| vec![v[0] * v[0] + v[1] * v[1] - 4.0, v[0].exp() - 1.0 - v[1]];
+/// let result = newton_system(residual, &[1.0, 1.0], SystemOptions::new()).unwrap();
+/// assert!(result.converged);
+/// assert!(result.residuals.iter().all(|r| r.abs() < 1e-12));
+/// ```
+pub fn newton_system<T, R>(
+    residual: R,
+    x0: &[T],
+    options: SystemOptions<T>,
+) -> Result<SystemResult<T>, SolveError>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+{
+    let jacobian = |x: &[T], fx: &[T]| forward_difference_jacobian(&residual, x, fx);
+    iterate(&residual, jacobian, (x0.len(), 0), x0, options)
+}
+
+/// Solves `residual(x) = 0` from `x0` by Newton's method, given the Jacobian
+/// of the residual as a vector of rows.
+///
+/// Each iteration solves `J·δ = -F` by a QR factorization with column
+/// pivoting. Where `J` is singular, `δ` is the basic least-squares solution
+/// instead, which still points downhill for `½‖F‖²`. With the line search of
+/// `options`, the step `x + α·δ` backtracks from `α = 1` by quadratic
+/// interpolation until `½‖F‖²` falls by a fraction of the decrease its slope
+/// predicts; near a solution, the full step is taken and convergence is
+/// quadratic.
+///
+/// The search stops with `converged == false` if the line search finds no
+/// acceptable step, which happens near a local minimum of `½‖F‖²` that is not
+/// a solution, or if the Newton step vanishes without a solution.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NonFiniteAtPoint`] if the residual or Jacobian is
+/// NaN or infinite at `x0` or at an accepted point. Trial points of the line
+/// search with non-finite residuals are merely rejected.
+///
+/// # Panics
+///
+/// Panics if `residual` does not return one value per unknown.
+pub fn newton_system_with_jacobian<T, R, J>(
+    residual: R,
+    jacobian: J,
+    x0: &[T],
+    options: SystemOptions<T>,
+) -> Result<SystemResult<T>, SolveError>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Vec<Vec<T>>,
+{
+    iterate(
+        residual,
+        |x: &[T], _: &[T]| jacobian(x),
+        (0, 1),
+        x0,
+        options,
+    )
+}
+
+/// Newton's method with `jacobian(x, F(x))` costing `cost` residual and
+/// Jacobian evaluations.
+fn iterate<T, R, J>(
+    residual: R,
+    jacobian: J,
+    cost: (usize, usize),
+    x0: &[T],
+    options: SystemOptions<T>,
+) -> Result<SystemResult<T>, SolveError>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T], &[T]) -> Vec<Vec<T>>,
+{
+    let SystemOptions {
+        tolerance,
+        budget,
+        line_search,
+    } = options;
+    let n = x0.len();
+    let mut x = x0.to_vec();
+    let mut f = finite_at(residual(&x), &x)?;
+    assert_eq!(f.len(), n, "the system must have one equation per unknown");
+    let (mut fevals, mut jevals) = (1, 0);
+    let mut iterations = 0;
+    let mut converged = tolerance.f_met(Norm::Infinity.of(&f));
+    while !converged && budget.allows(iterations, fevals) {
+        let jac = finite_at(jacobian(&x, &f).concat(), &x)?;
+        fevals += cost.0;
+        jevals += cost.1;
+        let minus_f: Vec<T> = f.iter().map(|&fi| -fi).collect();
+        let step = Qr::factor(&jac, n, n, true).solve(&minus_f);
+        if step.iter().all(|&s| s == T::ZERO) {
+            break;
+        }
+
+        let (next, next_f) = if line_search {
+            // The slope of ½‖F‖² along the step is Fᵀ·J·δ.
+            let slope = -(0..n)
+                .map(|i| minus_f[i] * dot(&jac[i * n..(i + 1) * n], &step))
+                .sum::<T>();
+            match backtrack(&residual, &x, &f, &step, slope, &mut fevals) {
+                Some(accepted) => accepted,
+                None => break,
+            }
+        } else {
+            let next = add_scaled(&x, T::ONE, &step);
+            fevals += 1;
+            let next_f = residual(&next);
+            if next_f.iter().any(|v| !v.is_finite()) {
+                return Err(non_finite_at(&next));
+            }
+            (next, next_f)
+        };
+        iterations += 1;
+        converged = is_met(&tolerance, &next, &step, &next_f);
+        (x, f) = (next, next_f);
+    }
+
+    Ok(SystemResult {
+        x,
+        residuals: f,
+        iterations,
+        fevals,
+        jevals,
+        converged,
+    })
+}
+
+/// The point `x + α·step` and its residuals for the first `α` in a
+/// backtracking search from one that lowers `½‖F‖²` sufficiently, given its
+/// `slope` along `step`; `None` if there is none.
+pub(crate) fn backtrack<T, R>(
+    residual: R,
+    x: &[T],
+    f: &[T],
+    step: &[T],
+    slope: T,
+    fevals: &mut usize,
+) -> Option<(Vec<T>, Vec<T>)>
+where
+    T: Float,
+    R: Fn(&[T]) -> Vec<T>,
+{
+    if slope >= T::ZERO || slope.is_nan() {
+        return None;
+    }
+    let half = T::from_f64(0.5);
+    let merit = half * dot(f, f);
+    let mut alpha = T::ONE;
+    for _ in 0..MAX_BACKTRACKS {
+        let trial = add_scaled(x, alpha, step);
+        let trial_f = residual(&trial);
+        *fevals += 1;
+        let trial_merit = half * dot(&trial_f, &trial_f);
+        if trial_merit.is_finite() && trial_merit <= merit + T::from_f64(1e-4) * alpha * slope {
+            return Some((trial, trial_f));
+        }
+        // Minimizer of the parabola through the merit at 0 and α with the
+        // slope at 0, kept within [α/10, α/2]; NaN and infinite merits give
+        // the smallest factor.
+        let interpolated =
+            -slope * alpha * alpha / (T::from_f64(2.0) * (trial_merit - merit - slope * alpha));
+        alpha = interpolated.max(T::from_f64(0.1) * alpha).min(half * alpha);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn analytic_and_finite_difference_jacobians_agree() {
+        let residual = |v: &[f64]| {
+            vec![
+                v[0] * v[0] + v[1] * v[1] + v[2] * v[2] - 3.0,
+                v[0] * v[1] - v[2],
+                v[0] - v[1] * v[1],
+            ]
+        };
+        let jacobian = |v: &[f64]| {
+            vec![
+                vec![2.0 * v[0], 2.0 * v[1], 2.0 * v[2]],
+                vec![v[1], v[0], -1.0],
+                vec![1.0, -2.0 * v[1], 0.0],
+            ]
+        };
+        let x0 = [2.0, 0.5, 0.5];
+        let options = SystemOptions::new().tolerance(1e-13);
+        let analytic = newton_system_with_jacobian(residual, jacobian, &x0, options).unwrap();
+        let numeric = newton_system(residual, &x0, options).unwrap();
+        for result in [&analytic, &numeric] {
+            assert!(result.converged);
+            assert!(
+                result.x.iter().all(|xi| (xi - 1.0).abs() < 1e-12),
+                "{result:?}"
+            );
+        }
+        assert_eq!(analytic.jevals, analytic.iterations);
+        assert_eq!(numeric.jevals, 0);
+        // Each iteration costs n differences and at least one trial point.
+        assert!(numeric.fevals > 4 * numeric.iterations);
+    }
+
+    #[test]
+    fn line_search_rescues_a_diverging_start() {
+        // Newton on arctan overshoots further every step from |x| > 1.39.
+        let residual = |v: &[f64]| vec![v[0].atan()];
+        let jacobian = |v: &[f64]| vec![vec![1.0 / (1.0 + v[0] * v[0])]];
+        let plain = SystemOptions::new().line_search(false).budget(6);
+        let diverged = newton_system_with_jacobian(residual, jacobian, &[2.0], plain).unwrap();
+        assert!(!diverged.converged);
+        assert!(diverged.x[0].abs() > 1e3);
+        let result =
+            newton_system_with_jacobian(residual, jacobian, &[2.0], SystemOptions::new()).unwrap();
+        assert!(result.converged);
+        assert!(result.x[0].abs() < 1e-12);
+    }
+
+    #[test]
+    fn reports_non_finite_residuals_and_local_minima() {
+        let residual = |v: &[f64]| vec![v[0].ln()];
+        assert!(matches!(
+            newton_system(residual, &[-1.0], SystemOptions::new()),
+            Err(SolveError::NonFiniteAtPoint { .. })
+        ));
+        // x² + 1 has no real root; the search stalls at the minimum of its
+        // square instead.
+        let result = newton_system(
+            |v: &[f64]| vec![v[0] * v[0] + 1.0],
+            &[0.7],
+            SystemOptions::new(),
+        )
+        .unwrap();
+        assert!(!result.converged);
+        assert!(result.x[0].abs() < 1e-3);
+    }
+}
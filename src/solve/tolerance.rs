@@ -0,0 +1,91 @@
+use crate::generals::Float;
+
+/// When a root finder may stop: once the root is known to within
+/// `xtol + rtol·|x|`, or the function is at most `ftol` in magnitude.
+///
+/// Solvers accept anything convertible into a tolerance. A bare number sets
+/// `xtol` and a pair sets `(xtol, rtol)`; `rtol` defaults to four machine
+/// epsilons, about as closely as a root can be located in floating point, and
+/// `ftol` to zero, so only an exact zero of the function stops the search
+/// early.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance<T> {
+    /// Absolute tolerance on the root.
+    pub xtol: T,
+    /// Tolerance on the root relative to its magnitude.
+    pub rtol: T,
+    /// Tolerance on the magnitude of the function.
+    pub ftol: T,
+}
+
+impl<T: Float> Tolerance<T> {
+    /// An absolute tolerance of `xtol` on the root, with the default `rtol`
+    /// and `ftol`.
+    pub fn new(xtol: T) -> Self {
+        Tolerance {
+            xtol,
+            rtol: T::from_f64(4.0) * T::EPSILON,
+            ftol: T::ZERO,
+        }
+    }
+
+    /// Also accepts a root known to within `rtol·|x|`.
+    pub fn with_rtol(mut self, rtol: T) -> Self {
+        self.rtol = rtol;
+        self
+    }
+
+    /// Also accepts any point where the function is at most `ftol` in
+    /// magnitude.
+    pub fn with_ftol(mut self, ftol: T) -> Self {
+        self.ftol = ftol;
+        self
+    }
+
+    /// Whether a root near `x`, known to within `dx`, is located closely
+    /// enough.
+    pub(crate) fn x_met(&self, x: T, dx: T) -> bool {
+        dx.abs() <= self.xtol + self.rtol * x.abs()
+    }
+
+    /// Whether the function value `fx` is small enough to stop at.
+    pub(crate) fn f_met(&self, fx: T) -> bool {
+        fx.abs() <= self.ftol
+    }
+}
+
+impl<T: Float> Default for Tolerance<T> {
+    /// An absolute tolerance of `2·10⁻¹²` on the root.
+    fn default() -> Self {
+        Tolerance::new(T::from_f64(2e-12))
+    }
+}
+
+impl<T: Float> From<T> for Tolerance<T> {
+    fn from(xtol: T) -> Self {
+        Tolerance::new(xtol)
+    }
+}
+
+impl<T: Float> From<(T, T)> for Tolerance<T> {
+    fn from((xtol, rtol): (T, T)) -> Self {
+        Tolerance::new(xtol).with_rtol(rtol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combines_absolute_and_relative_parts() {
+        let tolerance = Tolerance::from((1e-6, 1e-3));
+        assert!(tolerance.x_met(0.0, 5e-7));
+        assert!(!tolerance.x_met(0.0, 5e-6));
+        assert!(tolerance.x_met(100.0, -0.09));
+        assert!(!tolerance.f_met(1e-300));
+        assert!(tolerance.f_met(0.0));
+        assert!(Tolerance::from(1e-6).with_ftol(1e-8).f_met(-1e-9));
+        assert_eq!(Tolerance::from(1e-6).rtol, 4.0 * f64::EPSILON);
+    }
+}